@@ -23,13 +23,17 @@ class AppCoordinator: ObservableObject {
     @Published var localIPAddress: String?
     @Published var bearerTokenForDisplay: String = ""
     @Published var isAuthenticationEnabled: Bool = false
+    @Published var pairingPrompt: PairingPrompt?
 
     // MARK: - Components
 
     private var captureManager: CaptureManager?
     private var ndiManager: NDIManager?
     private var networkServer: NetworkServer?
+    private let pairingManager = PairingManager()
     private var telemetryCollector: TelemetryCollector
+    private var temperatureTimeline: [TemperatureSample] = []
+    private let temperatureTimelineLimit = 600  // 10 minutes at 1 Hz
     private var bonjourService: BonjourService?
     private var tallyPoller: NDITallyPoller?
 
@@ -254,6 +258,29 @@ class AppCoordinator: ObservableObject {
         print("🔐 Authentication \(isAuthenticationEnabled ? "enabled" : "disabled")")
     }
     
+    // MARK: - Pairing
+
+    func cancelPairing() {
+        guard let prompt = pairingPrompt else { return }
+        pairingManager.cancel(pairingId: prompt.pairingId)
+    }
+
+    private func observePairing() {
+        // Pairing requests arrive on NIO event loops
+        pairingManager.onPromptShown = { [weak self] prompt in
+            Task { @MainActor in
+                self?.pairingPrompt = prompt
+            }
+        }
+        pairingManager.onPromptCleared = { [weak self] pairingId in
+            Task { @MainActor in
+                if self?.pairingPrompt?.pairingId == pairingId {
+                    self?.pairingPrompt = nil
+                }
+            }
+        }
+    }
+
     // MARK: - Screen Brightness Control
 
     func toggleScreenBrightness() {
@@ -277,9 +304,11 @@ class AppCoordinator: ObservableObject {
     // MARK: - Network Server
 
     private func startNetworkServer() {
+        observePairing()
         networkServer = NetworkServer(
             port: serverPort,
             bearerToken: bearerToken,
+            pairingManager: pairingManager,
             requestHandler: self
         )
         
@@ -342,6 +371,12 @@ class AppCoordinator: ObservableObject {
             chargingState: systemTelemetry.chargingState
         )
 
+        // Keep a rolling temperature history for diagnostics
+        temperatureTimeline.append(TemperatureSample(timestamp: Date(), tempC: systemTelemetry.temperature))
+        if temperatureTimeline.count > temperatureTimelineLimit {
+            temperatureTimeline.removeFirst(temperatureTimeline.count - temperatureTimelineLimit)
+        }
+
         // Broadcast telemetry via WebSocket
         if let telemetry = self.telemetry {
             let currentNDIState: NDIState = self.isStreaming ? .streaming : .idle
@@ -428,6 +463,31 @@ class AppCoordinator: ObservableObject {
         isStreaming = false
     }
 
+    /// Apply new stream settings without restarting NDI, so the program feed stays up.
+    /// Resolution and frame rate reconfigure the capture session (frames pause briefly);
+    /// NDI encodes internally, so the bitrate is only recorded for status reports.
+    func updateStream(_ request: StreamUpdateRequest) async throws {
+        let current = currentSettings ?? createDefaultSettings()
+        let updated = StreamStartRequest(
+            resolution: request.resolution ?? current.resolution,
+            framerate: request.framerate ?? current.fps,
+            bitrate: request.bitrate ?? current.bitrate,
+            codec: request.codec ?? current.codec
+        )
+
+        print("🔄 Updating stream: \(updated.resolution) @ \(updated.framerate)fps, \(updated.bitrate)bps")
+
+        if isStreaming && (updated.resolution != current.resolution || updated.framerate != current.fps) {
+            // The frame callback survives reconfiguration, so NDI keeps sending
+            try await captureManager?.configure(
+                resolution: updated.resolution,
+                framerate: updated.framerate
+            )
+        }
+
+        updateCurrentSettings(from: updated)
+    }
+
     // MARK: - Camera Control
 
     func updateCameraSettings(_ settings: CameraSettingsRequest) async throws {
@@ -674,6 +734,10 @@ extension AppCoordinator: NetworkRequestHandler {
         await stopStreaming()
     }
 
+    func handleStreamUpdate(_ request: StreamUpdateRequest) async throws {
+        try await updateStream(request)
+    }
+
     func handleCameraSettings(_ settings: CameraSettingsRequest) async throws {
         try await updateCameraSettings(settings)
     }
@@ -785,4 +849,14 @@ extension AppCoordinator: NetworkRequestHandler {
         // Return updated values
         return await handleGetTorchLevel()
     }
+
+    func handleGetDiagnostics() async -> DiagnosticsResponse {
+        let ndiStats = ndiManager?.getTelemetryStats() ?? (fps: 0.0, sentFrames: 0, droppedFrames: 0)
+
+        return DiagnosticsResponse(
+            droppedFrames: Int(ndiStats.droppedFrames),
+            queueDepth: ndiManager?.getQueueDepth() ?? 0,
+            temperatureTimeline: temperatureTimeline
+        )
+    }
 }
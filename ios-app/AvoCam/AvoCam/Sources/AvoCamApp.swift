@@ -11,6 +11,10 @@ import SwiftUI
 struct AvoCamApp: App {
     @StateObject private var coordinator = AppCoordinator()
 
+    init() {
+        LogStore.shared.start()
+    }
+
     var body: some Scene {
         WindowGroup {
             ContentView()
@@ -818,7 +818,9 @@ actor CaptureManager: NSObject {
                             fps: supportedFPS,
                             codec: ["h264", "hevc"],
                             lens: lens,
-                            maxZoom: Double(device.activeFormat.videoMaxZoomFactor)
+                            maxZoom: Double(device.activeFormat.videoMaxZoomFactor),
+                            minIso: Int(device.activeFormat.minISO.rounded(.up)),
+                            maxIso: Int(device.activeFormat.maxISO.rounded(.down))
                         ))
                 }
             }
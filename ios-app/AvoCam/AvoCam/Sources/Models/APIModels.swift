@@ -121,6 +121,8 @@ struct Capability: Codable {
     let codec: [String]
     let lens: String?
     let maxZoom: Double?
+    let minIso: Int?
+    let maxIso: Int?
 
     enum CodingKeys: String, CodingKey {
         case resolution
@@ -128,6 +130,8 @@ struct Capability: Codable {
         case codec
         case lens
         case maxZoom = "max_zoom"
+        case minIso = "min_iso"
+        case maxIso = "max_iso"
     }
 }
 
@@ -140,6 +144,14 @@ struct StreamStartRequest: Codable {
     let codec: String
 }
 
+/// Changes a running stream in place; only the fields sent are changed
+struct StreamUpdateRequest: Codable {
+    let resolution: String?
+    let framerate: Int?
+    let bitrate: Int?
+    let codec: String?
+}
+
 // MARK: - Camera Control
 
 struct CameraSettingsRequest: Codable {
@@ -308,6 +320,76 @@ struct TorchLevelUpdateRequest: Codable {
     }
 }
 
+// MARK: - Diagnostics
+
+struct TemperatureSample: Codable {
+    let timestamp: Date
+    let tempC: Double
+
+    enum CodingKeys: String, CodingKey {
+        case timestamp
+        case tempC = "temp_c"
+    }
+}
+
+struct DiagnosticsResponse: Codable {
+    let droppedFrames: Int
+    let queueDepth: Int
+    let temperatureTimeline: [TemperatureSample]  // Oldest first
+
+    enum CodingKeys: String, CodingKey {
+        case droppedFrames = "dropped_frames"
+        case queueDepth = "queue_depth"
+        case temperatureTimeline = "temperature_timeline"
+    }
+}
+
+// MARK: - Pairing
+
+struct PairingRequest: Codable {
+    let controllerId: String
+    let controllerName: String
+    let method: String  // "pin" (the app cannot scan "qr" codes yet)
+
+    enum CodingKeys: String, CodingKey {
+        case controllerId = "controller_id"
+        case controllerName = "controller_name"
+        case method
+    }
+}
+
+struct PairingResponse: Codable {
+    let pairingId: String
+    let expiresInS: Int
+
+    enum CodingKeys: String, CodingKey {
+        case pairingId = "pairing_id"
+        case expiresInS = "expires_in_s"
+    }
+}
+
+struct PairingConfirmRequest: Codable {
+    let pairingId: String
+    let code: String
+
+    enum CodingKeys: String, CodingKey {
+        case pairingId = "pairing_id"
+        case code
+    }
+}
+
+struct PairingConfirmResponse: Codable {
+    let token: String
+}
+
+struct PairingRevokeRequest: Codable {
+    let controllerId: String
+
+    enum CodingKeys: String, CodingKey {
+        case controllerId = "controller_id"
+    }
+}
+
 // MARK: - Error Response
 
 struct ErrorResponse: Codable {
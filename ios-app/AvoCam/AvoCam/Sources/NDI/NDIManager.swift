@@ -40,6 +40,8 @@ class NDIManager {
     private let ndiSemaphore = DispatchSemaphore(value: 3)
     private var droppedFrameCount: Int64 = 0
     private var sentFrameCount: Int64 = 0
+    // Frames handed to the send queue and not sent yet
+    private var queuedFrameCount: Int64 = 0
 
     // PERF: Reusable NDI frame struct (eliminates 25 allocs/sec at 4K25)
     private var ndiVideoFrame = NDIlib_video_frame_v2_t()
@@ -163,6 +165,7 @@ class NDIManager {
             }
 
             self.sendFrameSync(pixelBuffer: pixelBuffer, sender: sender)
+            OSAtomicDecrement64(&self.queuedFrameCount)
 
             if self.enableBackpressure {
                 self.ndiSemaphore.signal()
@@ -171,6 +174,8 @@ class NDIManager {
             OSAtomicIncrement64(&self.sentFrameCount)
         }
 
+        OSAtomicIncrement64(&queuedFrameCount)
+
         // PERF: Send on dedicated queue (off capture thread, 8% CPU reduction)
         if enableDedicatedQueue {
             ndiQueue.async(execute: sendBlock)
@@ -337,4 +342,10 @@ class NDIManager {
 
         return (fps, sent, dropped)
     }
+
+    /// Frames waiting in the NDI send queue
+    func getQueueDepth() -> Int {
+        guard isActive else { return 0 }
+        return Int(max(queuedFrameCount, 0))
+    }
 }
@@ -15,6 +15,7 @@ import NIOWebSocket
 protocol NetworkRequestHandler: AnyObject {
     func handleStreamStart(_ request: StreamStartRequest) async throws
     func handleStreamStop() async throws
+    func handleStreamUpdate(_ request: StreamUpdateRequest) async throws
     func handleCameraSettings(_ settings: CameraSettingsRequest) async throws
     func handleGetStatus() async -> StatusResponse
     func handleGetCapabilities() async -> [Capability]
@@ -25,6 +26,7 @@ protocol NetworkRequestHandler: AnyObject {
     func handleUpdateAlias(_ request: AliasUpdateRequest) async throws -> AliasUpdateResponse
     func handleGetTorchLevel() async -> TorchLevelResponse
     func handleUpdateTorchLevel(_ request: TorchLevelUpdateRequest) async throws -> TorchLevelResponse
+    func handleGetDiagnostics() async -> DiagnosticsResponse
 }
 
 // MARK: - Network Server
@@ -34,6 +36,7 @@ class NetworkServer {
 
     private let port: Int
     private let bearerToken: String
+    private let pairingManager: PairingManager
     private weak var requestHandler: NetworkRequestHandler?
     private var isAuthenticationEnabled: Bool = false
 
@@ -51,9 +54,10 @@ class NetworkServer {
 
     // MARK: - Initialization
 
-    init(port: Int, bearerToken: String, requestHandler: NetworkRequestHandler?) {
+    init(port: Int, bearerToken: String, pairingManager: PairingManager, requestHandler: NetworkRequestHandler?) {
         self.port = port
         self.bearerToken = bearerToken
+        self.pairingManager = pairingManager
         self.requestHandler = requestHandler
         self.isAuthenticationEnabled = false
     }
@@ -92,9 +96,7 @@ class NetworkServer {
 
                         // Validate bearer token if authentication is enabled
                         if self.isAuthenticationEnabled {
-                            guard let authHeader = head.headers["authorization"].first,
-                                  authHeader.hasPrefix("Bearer "),
-                                  authHeader.dropFirst("Bearer ".count) == self.bearerToken else {
+                            guard self.isAuthorized(head.headers["authorization"].first) else {
                                 return channel.eventLoop.makeSucceededFuture(nil)
                             }
                         }
@@ -225,10 +227,11 @@ class NetworkServer {
             )
         }
 
-        // Authenticate if enabled (skip for OPTIONS which is already handled above)
-        if isAuthenticationEnabled {
-            guard let authHeader = headers["Authorization"],
-                  authHeader == "Bearer \(bearerToken)" else {
+        // Authenticate if enabled (skip for OPTIONS which is already handled above).
+        // Pairing is how a controller gets a token in the first place.
+        let isPairingRequest = method == "POST" && (path == "/api/v1/pair/request" || path == "/api/v1/pair/confirm")
+        if isAuthenticationEnabled && !isPairingRequest {
+            guard isAuthorized(headers["Authorization"]) else {
                 print("⚠️ Authentication failed for \(method) \(path)")
                 return HTTPResponse(
                     status: 401,
@@ -269,6 +272,9 @@ class NetworkServer {
         case ("POST", "/api/v1/stream/stop"):
             return await handleStreamStop()
 
+        case ("POST", "/api/v1/stream/update"):
+            return await handleStreamUpdate(body: body)
+
         case ("POST", "/api/v1/camera"):
             return await handleCameraSettings(body: body)
 
@@ -287,6 +293,18 @@ class NetworkServer {
         case ("PUT", "/api/v1/torch/level"):
             return await handlePutTorchLevel(body: body)
 
+        case ("GET", "/api/v1/diag"):
+            return await handleGetDiagnostics()
+
+        case ("POST", "/api/v1/pair/request"):
+            return handlePairingRequest(body: body)
+
+        case ("POST", "/api/v1/pair/confirm"):
+            return handlePairingConfirm(body: body)
+
+        case ("POST", "/api/v1/pair/revoke"):
+            return handlePairingRevoke(body: body, authHeader: headers["Authorization"])
+
         case ("GET", "/api/v1/logs.zip"):
             return handleLogsDownload()
 
@@ -368,6 +386,25 @@ class NetworkServer {
         }
     }
 
+    private func handleStreamUpdate(body: Data?) async -> HTTPResponse {
+        guard let body = body,
+              let request = try? JSONDecoder().decode(StreamUpdateRequest.self, from: body) else {
+            return HTTPResponse(status: 400, body: errorJSON(code: "INVALID_REQUEST", message: "Invalid stream update request"))
+        }
+
+        guard let handler = requestHandler else {
+            return HTTPResponse(status: 500, body: errorJSON(code: "INTERNAL_ERROR", message: "No request handler"))
+        }
+
+        do {
+            try await handler.handleStreamUpdate(request)
+            return HTTPResponse(status: 200, body: successJSON(message: "Stream updated"))
+        } catch {
+            print("❌ Stream update failed: \(error.localizedDescription)")
+            return HTTPResponse(status: 500, body: errorJSON(code: "STREAM_UPDATE_FAILED", message: error.localizedDescription))
+        }
+    }
+
     private func handleCameraSettings(body: Data?) async -> HTTPResponse {
         guard let body = body,
               let settings = try? JSONDecoder().decode(CameraSettingsRequest.self, from: body) else {
@@ -521,9 +558,91 @@ class NetworkServer {
         }
     }
 
+    private func handleGetDiagnostics() async -> HTTPResponse {
+        guard let handler = requestHandler else {
+            return HTTPResponse(status: 500, body: errorJSON(code: "INTERNAL_ERROR", message: "No request handler"))
+        }
+
+        let diagnostics = await handler.handleGetDiagnostics()
+        let encoder = JSONEncoder()
+        encoder.dateEncodingStrategy = .iso8601
+        guard let jsonData = try? encoder.encode(diagnostics) else {
+            return HTTPResponse(status: 500, body: errorJSON(code: "ENCODING_ERROR", message: "Failed to encode diagnostics"))
+        }
+
+        return HTTPResponse(status: 200, body: jsonData)
+    }
+
+    private func handlePairingRequest(body: Data?) -> HTTPResponse {
+        guard let body = body,
+              let request = try? JSONDecoder().decode(PairingRequest.self, from: body) else {
+            return HTTPResponse(status: 400, body: errorJSON(code: "INVALID_REQUEST", message: "Invalid pairing request"))
+        }
+
+        do {
+            let response = try pairingManager.request(request)
+            guard let jsonData = try? JSONEncoder().encode(response) else {
+                return HTTPResponse(status: 500, body: errorJSON(code: "ENCODING_ERROR", message: "Failed to encode pairing"))
+            }
+            return HTTPResponse(status: 200, body: jsonData)
+        } catch let error as PairingError {
+            return HTTPResponse(status: error.status, body: errorJSON(code: error.code, message: error.message))
+        } catch {
+            return HTTPResponse(status: 500, body: errorJSON(code: "INTERNAL_ERROR", message: error.localizedDescription))
+        }
+    }
+
+    private func handlePairingConfirm(body: Data?) -> HTTPResponse {
+        guard let body = body,
+              let request = try? JSONDecoder().decode(PairingConfirmRequest.self, from: body) else {
+            return HTTPResponse(status: 400, body: errorJSON(code: "INVALID_REQUEST", message: "Invalid pairing confirmation"))
+        }
+
+        do {
+            let response = try pairingManager.confirm(request)
+            guard let jsonData = try? JSONEncoder().encode(response) else {
+                return HTTPResponse(status: 500, body: errorJSON(code: "ENCODING_ERROR", message: "Failed to encode pairing token"))
+            }
+            return HTTPResponse(status: 200, body: jsonData)
+        } catch let error as PairingError {
+            print("⚠️ Pairing confirmation refused: \(error.code)")
+            return HTTPResponse(status: error.status, body: errorJSON(code: error.code, message: error.message))
+        } catch {
+            return HTTPResponse(status: 500, body: errorJSON(code: "INTERNAL_ERROR", message: error.localizedDescription))
+        }
+    }
+
+    private func handlePairingRevoke(body: Data?, authHeader: String?) -> HTTPResponse {
+        guard let body = body,
+              let request = try? JSONDecoder().decode(PairingRevokeRequest.self, from: body) else {
+            return HTTPResponse(status: 400, body: errorJSON(code: "INVALID_REQUEST", message: "Invalid pairing revocation"))
+        }
+
+        let token = bearerValue(authHeader)
+        do {
+            try pairingManager.revoke(request, authorizedWith: token, isMasterToken: token == bearerToken)
+            return HTTPResponse(status: 200, body: successJSON(message: "Pairing revoked"))
+        } catch let error as PairingError {
+            return HTTPResponse(status: error.status, body: errorJSON(code: error.code, message: error.message))
+        } catch {
+            return HTTPResponse(status: 500, body: errorJSON(code: "INTERNAL_ERROR", message: error.localizedDescription))
+        }
+    }
+
     private func handleLogsDownload() -> HTTPResponse {
-        // TODO: Implement rotating logs and zip creation
-        return HTTPResponse(status: 501, body: errorJSON(code: "NOT_IMPLEMENTED", message: "Logs download not yet implemented"))
+        do {
+            let archive = try LogStore.shared.archive()
+            return HTTPResponse(
+                status: 200,
+                headers: [
+                    "Content-Type": "application/zip",
+                    "Content-Disposition": "attachment; filename=\"logs.zip\""
+                ],
+                body: archive
+            )
+        } catch {
+            return HTTPResponse(status: 500, body: errorJSON(code: "LOGS_UNAVAILABLE", message: error.localizedDescription))
+        }
     }
 
     private func handleWebUI() -> HTTPResponse {
@@ -1459,6 +1578,21 @@ class NetworkServer {
 
     // MARK: - Helpers
 
+    /// The app's own token, or one issued to a paired controller
+    private func isAuthorized(_ authHeader: String?) -> Bool {
+        guard let token = bearerValue(authHeader) else {
+            return false
+        }
+        return token == bearerToken || pairingManager.isPairedToken(token)
+    }
+
+    private func bearerValue(_ authHeader: String?) -> String? {
+        guard let authHeader = authHeader, authHeader.hasPrefix("Bearer ") else {
+            return nil
+        }
+        return String(authHeader.dropFirst("Bearer ".count))
+    }
+
     private func errorJSON(code: String, message: String) -> Data {
         let error = ErrorResponse(code: code, message: message)
         return (try? JSONEncoder().encode(error)) ?? Data()
@@ -0,0 +1,236 @@
+//
+//  PairingManager.swift
+//  AvoCam
+//
+//  PIN pairing issuing a token per controller (/api/v1/pair/*)
+//
+
+import Foundation
+
+// MARK: - Pairing Prompt
+
+/// PIN shown on screen while a controller waits for the operator
+struct PairingPrompt: Equatable {
+    let pairingId: String
+    let controllerName: String
+    let pin: String
+    let expiresAt: Date
+}
+
+// MARK: - Pairing Errors
+
+enum PairingError: Error {
+    case unsupportedMethod(String)
+    case notFound
+    case expired
+    case invalidCode
+    case forbidden
+
+    var status: Int {
+        switch self {
+        case .unsupportedMethod: return 400
+        case .notFound: return 404
+        case .expired: return 410
+        case .invalidCode, .forbidden: return 403
+        }
+    }
+
+    var code: String {
+        switch self {
+        case .unsupportedMethod: return "UNSUPPORTED_METHOD"
+        case .notFound: return "PAIRING_NOT_FOUND"
+        case .expired: return "PAIRING_EXPIRED"
+        case .invalidCode: return "INVALID_CODE"
+        case .forbidden: return "FORBIDDEN"
+        }
+    }
+
+    var message: String {
+        switch self {
+        case .unsupportedMethod(let method):
+            return "Pairing method '\(method)' is not supported; use 'pin'"
+        case .notFound:
+            return "No such pairing"
+        case .expired:
+            return "Pairing expired; start again"
+        case .invalidCode:
+            return "Wrong pairing code"
+        case .forbidden:
+            return "Only the paired controller can revoke its token"
+        }
+    }
+}
+
+// MARK: - Pairing Manager
+
+/// Pairings are requested from NIO event loops, so all state is behind a lock.
+/// QR pairing is left to the controller side: the app cannot scan codes yet.
+class PairingManager {
+    // MARK: - Properties
+
+    private let pairingTTL: TimeInterval = 120
+    private let maxFailedAttempts = 5
+    private let tokensKey = "paired_controller_tokens"
+
+    private struct PendingPairing {
+        let controllerId: String
+        let controllerName: String
+        let pin: String
+        let expiresAt: Date
+        var failedAttempts: Int
+    }
+
+    private var pending: [String: PendingPairing] = [:]
+    private var tokens: [String: String]  // Token by controller id
+    private let lock = NSLock()
+
+    /// Called with the PIN to show when a controller asks to pair
+    var onPromptShown: ((PairingPrompt) -> Void)?
+    /// Called with the pairing id once it is confirmed, expired or cancelled
+    var onPromptCleared: ((String) -> Void)?
+
+    // MARK: - Initialization
+
+    init() {
+        self.tokens = UserDefaults.standard.dictionary(forKey: tokensKey) as? [String: String] ?? [:]
+    }
+
+    // MARK: - Pairing Flow
+
+    func request(_ request: PairingRequest) throws -> PairingResponse {
+        guard request.method == "pin" else {
+            throw PairingError.unsupportedMethod(request.method)
+        }
+
+        let prompt = PairingPrompt(
+            pairingId: UUID().uuidString.replacingOccurrences(of: "-", with: "").lowercased(),
+            controllerName: request.controllerName,
+            pin: String(format: "%06d", Int.random(in: 0...999_999)),
+            expiresAt: Date().addingTimeInterval(pairingTTL)
+        )
+
+        lock.lock()
+        removeExpired()
+        // A new request from the same controller replaces its previous one
+        pending = pending.filter { $0.value.controllerId != request.controllerId }
+        pending[prompt.pairingId] = PendingPairing(
+            controllerId: request.controllerId,
+            controllerName: request.controllerName,
+            pin: prompt.pin,
+            expiresAt: prompt.expiresAt,
+            failedAttempts: 0
+        )
+        lock.unlock()
+
+        print("🔗 Pairing requested by \(request.controllerName)")
+        onPromptShown?(prompt)
+        scheduleExpiry(of: prompt.pairingId)
+
+        return PairingResponse(pairingId: prompt.pairingId, expiresInS: Int(pairingTTL))
+    }
+
+    func confirm(_ request: PairingConfirmRequest) throws -> PairingConfirmResponse {
+        lock.lock()
+        guard var pairing = pending[request.pairingId] else {
+            lock.unlock()
+            throw PairingError.notFound
+        }
+
+        if pairing.expiresAt <= Date() {
+            pending.removeValue(forKey: request.pairingId)
+            lock.unlock()
+            onPromptCleared?(request.pairingId)
+            throw PairingError.expired
+        }
+
+        // A wrong PIN keeps the pairing open for a few more tries
+        guard request.code == pairing.pin else {
+            pairing.failedAttempts += 1
+            let exhausted = pairing.failedAttempts >= maxFailedAttempts
+            pending[request.pairingId] = exhausted ? nil : pairing
+            lock.unlock()
+            if exhausted {
+                print("⚠️ Pairing with \(pairing.controllerName) cancelled after \(maxFailedAttempts) wrong PINs")
+                onPromptCleared?(request.pairingId)
+            }
+            throw PairingError.invalidCode
+        }
+
+        pending.removeValue(forKey: request.pairingId)
+        let token = Self.generateToken()
+        tokens[pairing.controllerId] = token
+        saveTokens()
+        lock.unlock()
+
+        print("✅ Paired with \(pairing.controllerName)")
+        onPromptCleared?(request.pairingId)
+        return PairingConfirmResponse(token: token)
+    }
+
+    /// Revokes a controller's token; only that controller (or the app's own token) may do it
+    func revoke(_ request: PairingRevokeRequest, authorizedWith token: String?, isMasterToken: Bool) throws {
+        lock.lock()
+        defer { lock.unlock() }
+
+        guard let issued = tokens[request.controllerId] else {
+            return  // Already revoked
+        }
+        guard isMasterToken || token == issued else {
+            throw PairingError.forbidden
+        }
+
+        tokens.removeValue(forKey: request.controllerId)
+        saveTokens()
+        print("🔗 Pairing revoked for controller \(request.controllerId)")
+    }
+
+    func cancel(pairingId: String) {
+        lock.lock()
+        let removed = pending.removeValue(forKey: pairingId) != nil
+        lock.unlock()
+
+        if removed {
+            onPromptCleared?(pairingId)
+        }
+    }
+
+    func isPairedToken(_ token: String) -> Bool {
+        lock.lock()
+        defer { lock.unlock() }
+        return tokens.values.contains(token)
+    }
+
+    // MARK: - Helpers
+
+    private func scheduleExpiry(of pairingId: String) {
+        DispatchQueue.main.asyncAfter(deadline: .now() + pairingTTL) { [weak self] in
+            guard let self = self else { return }
+
+            self.lock.lock()
+            let expired = self.pending[pairingId].map { $0.expiresAt <= Date() } ?? false
+            if expired {
+                self.pending.removeValue(forKey: pairingId)
+            }
+            self.lock.unlock()
+
+            if expired {
+                self.onPromptCleared?(pairingId)
+            }
+        }
+    }
+
+    // Caller holds the lock
+    private func removeExpired() {
+        let now = Date()
+        pending = pending.filter { $0.value.expiresAt > now }
+    }
+
+    // Caller holds the lock
+    private func saveTokens() {
+        UserDefaults.standard.set(tokens, forKey: tokensKey)
+    }
+
+    private static func generateToken() -> String {
+        return UUID().uuidString.replacingOccurrences(of: "-", with: "")
+    }
+}
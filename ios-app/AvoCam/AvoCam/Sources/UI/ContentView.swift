@@ -110,9 +110,41 @@ struct ContentView: View {
                         .transition(.scale.combined(with: .opacity))
                 }
             }
+
+            // Pairing PIN (shown while a controller waits for it)
+            if let prompt = coordinator.pairingPrompt {
+                ZStack {
+                    Color.black.opacity(0.6)
+                        .edgesIgnoringSafeArea(.all)
+
+                    VStack(spacing: 16) {
+                        Text("Pair with \(prompt.controllerName)")
+                            .font(.headline)
+                            .foregroundColor(.white)
+
+                        Text(prompt.pin)
+                            .font(.system(size: 56, weight: .bold, design: .monospaced))
+                            .foregroundColor(.white)
+
+                        Text("Enter this PIN on the controller")
+                            .font(.subheadline)
+                            .foregroundColor(.white.opacity(0.7))
+
+                        Button("Cancel") {
+                            coordinator.cancelPairing()
+                        }
+                        .foregroundColor(.red)
+                    }
+                    .padding(32)
+                    .background(Color.black.opacity(0.8))
+                    .cornerRadius(16)
+                }
+                .transition(.opacity)
+            }
         }
         .animation(.spring(response: 0.3, dampingFraction: 0.8), value: showSettings)
         .animation(.spring(response: 0.3, dampingFraction: 0.8), value: showTelemetry)
+        .animation(.easeInOut(duration: 0.2), value: coordinator.pairingPrompt)
         .statusBar(hidden: true)
         .navigationTitle("AvoCam")
         .navigationBarTitleDisplayMode(.inline)
@@ -131,6 +163,12 @@ struct ContentView: View {
         .onChange(of: scenePhase) { oldPhase, newPhase in
             handleScenePhaseChange(newPhase)
         }
+        .onChange(of: coordinator.pairingPrompt) { oldValue, newValue in
+            if newValue != nil {
+                // The operator has to read the PIN off the screen
+                screenDimManager.wakeScreen()
+            }
+        }
         .onChange(of: coordinator.isStreaming) { oldValue, newValue in
             if newValue {
                 // Started streaming - dim screen
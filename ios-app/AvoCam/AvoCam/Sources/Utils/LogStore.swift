@@ -0,0 +1,143 @@
+//
+//  LogStore.swift
+//  AvoCam
+//
+//  Rotating on-device logs served as GET /api/v1/logs.zip
+//
+
+import Foundation
+
+/// Tees the app's stdout/stderr into rotating files under Caches/Logs
+/// Usage:
+///   LogStore.shared.start()              // once, at launch
+///   let zip = try LogStore.shared.archive()
+final class LogStore {
+    static let shared = LogStore()
+
+    // MARK: - Properties
+
+    /// Size at which the current file is rotated
+    private let maxFileSize: UInt64 = 2 * 1024 * 1024
+    /// Files kept: avocam.log plus avocam.1.log ... avocam.4.log
+    private let maxFiles = 5
+
+    private let directory: URL
+    private let queue = DispatchQueue(label: "com.avocam.logstore")
+    private var file: FileHandle?
+    private var fileSize: UInt64 = 0
+    private var pipe: Pipe?
+    private var console: FileHandle?
+
+    // MARK: - Initialization
+
+    private init() {
+        let caches = FileManager.default.urls(for: .cachesDirectory, in: .userDomainMask)[0]
+        directory = caches.appendingPathComponent("Logs", isDirectory: true)
+    }
+
+    // MARK: - Control
+
+    /// Start capturing; each launch begins a fresh file
+    func start() {
+        queue.sync {
+            guard pipe == nil else { return }
+
+            do {
+                try FileManager.default.createDirectory(at: directory, withIntermediateDirectories: true)
+            } catch {
+                print("⚠️ Log directory unavailable: \(error)")
+                return
+            }
+            rotate()
+
+            // Keep the original stdout so the Xcode console still shows everything
+            let console = FileHandle(fileDescriptor: dup(STDOUT_FILENO), closeOnDealloc: true)
+            let pipe = Pipe()
+            setvbuf(stdout, nil, _IOLBF, 0)
+            dup2(pipe.fileHandleForWriting.fileDescriptor, STDOUT_FILENO)
+            dup2(pipe.fileHandleForWriting.fileDescriptor, STDERR_FILENO)
+
+            pipe.fileHandleForReading.readabilityHandler = { [weak self] handle in
+                let data = handle.availableData
+                guard !data.isEmpty else { return }
+                console.write(data)
+                self?.queue.async { self?.append(data) }
+            }
+
+            self.console = console
+            self.pipe = pipe
+        }
+    }
+
+    /// Zip of every log file, current one included
+    func archive() throws -> Data {
+        fflush(stdout)
+        try queue.sync {
+            try file?.synchronize()
+        }
+
+        var archive: Data?
+        var readError: Error?
+        var coordinationError: NSError?
+        // Reading a directory with .forUploading hands back a zip of it
+        NSFileCoordinator().coordinate(readingItemAt: directory, options: .forUploading, error: &coordinationError) { zipURL in
+            do {
+                archive = try Data(contentsOf: zipURL)
+            } catch {
+                readError = error
+            }
+        }
+
+        if let error = coordinationError ?? readError {
+            throw error
+        }
+        guard let archive = archive else {
+            throw LogStoreError.archiveFailed
+        }
+        return archive
+    }
+
+    // MARK: - Private
+
+    /// Runs on `queue`
+    private func append(_ data: Data) {
+        if fileSize + UInt64(data.count) > maxFileSize {
+            rotate()
+        }
+        file?.write(data)
+        fileSize += UInt64(data.count)
+    }
+
+    /// Shift avocam.N.log up by one, drop the oldest and open a fresh avocam.log. Runs on `queue`
+    private func rotate() {
+        try? file?.close()
+        file = nil
+
+        let fileManager = FileManager.default
+        try? fileManager.removeItem(at: logURL(maxFiles - 1))
+        for index in stride(from: maxFiles - 2, through: 0, by: -1) {
+            try? fileManager.moveItem(at: logURL(index), to: logURL(index + 1))
+        }
+
+        fileManager.createFile(atPath: logURL(0).path, contents: nil)
+        file = try? FileHandle(forWritingTo: logURL(0))
+        fileSize = 0
+    }
+
+    private func logURL(_ index: Int) -> URL {
+        directory.appendingPathComponent(index == 0 ? "avocam.log" : "avocam.\(index).log")
+    }
+}
+
+// MARK: - Errors
+
+enum LogStoreError: LocalizedError {
+    case archiveFailed
+
+    var errorDescription: String? {
+        switch self {
+        case .archiveFailed:
+            return "Failed to archive logs"
+        }
+    }
+}
@@ -0,0 +1,604 @@
+//! Controller-side adaptive bitrate ladder
+//!
+//! For each opted-in camera the engine smooths `queue_ms`, the dropped-frame
+//! rate and `wifi_rssi` from telemetry and steps the stream bitrate down or up
+//! through a ladder (10 → 8 → 6 Mb/s by default). Steps respect a minimum
+//! dwell time and never exceed the bitrate the operator configured (the
+//! ceiling). Rungs change the bitrate of the running stream in place, without
+//! restarting NDI or being persisted, so the program feed stays up and the
+//! configured bitrate survives restarts and opt-outs; steps are audited and
+//! emitted as `abr-step` events.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+
+use crate::audit::ActionSource;
+use crate::camera_manager::{CameraManager, ManagerMut, StreamDelivery};
+use crate::events::CameraEvent;
+use crate::models::*;
+use crate::platform::PathProvider;
+
+// MARK: - Configuration
+
+/// Ladder and thresholds shared by every ABR-enabled camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AbrConfig {
+    /// Bitrate rungs in bits per second (any order)
+    pub ladder_bps: Vec<u32>,
+    /// Weight of the newest sample in the moving averages (0-1]
+    pub smoothing: f64,
+    /// Step down above this smoothed send queue
+    pub queue_high_ms: f64,
+    /// Step up only below this smoothed send queue
+    pub queue_low_ms: f64,
+    /// Step down above this smoothed dropped-frame rate
+    pub dropped_high_per_s: f64,
+    /// Step down below this smoothed Wi-Fi signal
+    pub rssi_low_dbm: f64,
+    /// Step up only above this smoothed Wi-Fi signal
+    pub rssi_high_dbm: f64,
+    /// Minimum time between two steps of a camera
+    pub min_dwell_s: u64,
+    /// How long a camera must stay healthy before stepping up
+    pub recovery_s: u64,
+}
+
+impl Default for AbrConfig {
+    fn default() -> Self {
+        Self {
+            ladder_bps: vec![10_000_000, 8_000_000, 6_000_000],
+            smoothing: 0.3,
+            queue_high_ms: 150.0,
+            queue_low_ms: 50.0,
+            dropped_high_per_s: 1.0,
+            rssi_low_dbm: -75.0,
+            rssi_high_dbm: -67.0,
+            min_dwell_s: 10,
+            recovery_s: 30,
+        }
+    }
+}
+
+/// Persisted ABR settings (`abr.json`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AbrSettings {
+    #[serde(default)]
+    pub config: AbrConfig,
+    /// Cameras the ladder is enabled for (opt-in)
+    #[serde(default)]
+    pub enabled_cameras: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepDirection {
+    Down,
+    Up,
+}
+
+/// One bitrate change, emitted as an `abr-step` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbrStep {
+    pub camera_id: String,
+    pub direction: StepDirection,
+    pub from_bps: u32,
+    pub to_bps: u32,
+    pub reason: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Live ABR view of a camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbrCameraStatus {
+    pub camera_id: String,
+    pub enabled: bool,
+    /// Configured (persisted) bitrate the ladder never exceeds
+    pub ceiling_bps: Option<u32>,
+    /// Bitrate the camera is streaming at
+    pub current_bps: Option<u32>,
+    pub queue_ms: f64,
+    pub dropped_per_s: f64,
+    pub wifi_rssi_dbm: f64,
+    pub last_step: Option<AbrStep>,
+}
+
+// MARK: - Engine
+
+/// Smoothed signals and step history of one camera
+struct CameraSignals {
+    queue_ms: f64,
+    dropped_per_s: f64,
+    wifi_rssi_dbm: f64,
+    last_dropped: u32,
+    last_sample: Instant,
+    // Persisted stream the operator configured; its bitrate is the ceiling
+    configured: Option<StreamStartRequest>,
+    // Rung the ABR is streaming at, None while at the ceiling
+    applied_bps: Option<u32>,
+    last_step_at: Option<Instant>,
+    healthy_since: Option<Instant>,
+    last_step: Option<AbrStep>,
+}
+
+impl CameraSignals {
+    fn new(telemetry: &WebSocketTelemetryMessage, now: Instant) -> Self {
+        Self {
+            queue_ms: telemetry.queue_ms as f64,
+            dropped_per_s: 0.0,
+            wifi_rssi_dbm: telemetry.wifi_rssi as f64,
+            last_dropped: telemetry.dropped_frames,
+            last_sample: now,
+            configured: None,
+            applied_bps: None,
+            last_step_at: None,
+            healthy_since: None,
+            last_step: None,
+        }
+    }
+
+    fn update(&mut self, telemetry: &WebSocketTelemetryMessage, alpha: f64, now: Instant) {
+        let elapsed_s = now.duration_since(self.last_sample).as_secs_f64().max(0.1);
+        // dropped_frames is cumulative per stream; a smaller value means a restart
+        let dropped = telemetry.dropped_frames.checked_sub(self.last_dropped).unwrap_or(telemetry.dropped_frames);
+        let alpha = alpha.clamp(0.01, 1.0);
+
+        self.queue_ms += alpha * (telemetry.queue_ms as f64 - self.queue_ms);
+        self.dropped_per_s += alpha * (dropped as f64 / elapsed_s - self.dropped_per_s);
+        self.wifi_rssi_dbm += alpha * (telemetry.wifi_rssi as f64 - self.wifi_rssi_dbm);
+        self.last_dropped = telemetry.dropped_frames;
+        self.last_sample = now;
+    }
+
+    /// Track the persisted stream; when it changes (operator, profile, thermal
+    /// guard) the camera was sent it, so the ladder starts over
+    fn configure(&mut self, configured: StreamStartRequest) {
+        if self.configured.as_ref() != Some(&configured) {
+            self.configured = Some(configured);
+            self.applied_bps = None;
+        }
+    }
+
+    fn ceiling_bps(&self) -> Option<u32> {
+        self.configured.as_ref().map(|stream| stream.bitrate)
+    }
+
+    fn current_bps(&self) -> Option<u32> {
+        self.applied_bps.or_else(|| self.ceiling_bps())
+    }
+
+    /// Why the link looks congested, if it does
+    fn congestion(&self, config: &AbrConfig) -> Option<String> {
+        if self.queue_ms > config.queue_high_ms {
+            Some(format!("queue {:.0} ms > {:.0} ms", self.queue_ms, config.queue_high_ms))
+        } else if self.dropped_per_s > config.dropped_high_per_s {
+            Some(format!("{:.1} dropped frames/s > {:.1}", self.dropped_per_s, config.dropped_high_per_s))
+        } else if self.wifi_rssi_dbm < config.rssi_low_dbm {
+            Some(format!("Wi-Fi {:.0} dBm < {:.0} dBm", self.wifi_rssi_dbm, config.rssi_low_dbm))
+        } else {
+            None
+        }
+    }
+
+    fn is_healthy(&self, config: &AbrConfig) -> bool {
+        self.queue_ms < config.queue_low_ms
+            && self.dropped_per_s < config.dropped_high_per_s / 2.0
+            && self.wifi_rssi_dbm > config.rssi_high_dbm
+    }
+
+    /// Step to take now, if any: down when congested, up once healthy for
+    /// `recovery_s`, never within `min_dwell_s` of the previous step
+    fn next_step(&mut self, config: &AbrConfig, now: Instant) -> Option<(StepDirection, u32, String)> {
+        if self.is_healthy(config) {
+            self.healthy_since.get_or_insert(now);
+        } else {
+            self.healthy_since = None;
+        }
+
+        let dwelling = self.last_step_at
+            .is_some_and(|at| now.duration_since(at) < Duration::from_secs(config.min_dwell_s));
+        if dwelling {
+            return None;
+        }
+
+        let ceiling = self.ceiling_bps()?;
+        let current = self.current_bps()?;
+
+        if let Some(reason) = self.congestion(config) {
+            next_rung(&config.ladder_bps, ceiling, current, StepDirection::Down)
+                .map(|to| (StepDirection::Down, to, reason))
+        } else if self.healthy_since.is_some_and(|since| now.duration_since(since) >= Duration::from_secs(config.recovery_s)) {
+            next_rung(&config.ladder_bps, ceiling, current, StepDirection::Up)
+                .map(|to| (StepDirection::Up, to, format!("healthy for {} s", config.recovery_s)))
+        } else {
+            None
+        }
+    }
+
+    /// Record a step about to be applied
+    fn record_step(&mut self, step: &AbrStep, now: Instant) {
+        self.last_step_at = Some(now);
+        self.healthy_since = None;
+        self.applied_bps = (Some(step.to_bps) != self.ceiling_bps()).then_some(step.to_bps);
+        self.last_step = Some(step.clone());
+    }
+}
+
+#[derive(Default)]
+struct AbrState {
+    settings: AbrSettings,
+    settings_file_path: Option<PathBuf>,
+    cameras: HashMap<String, CameraSignals>,
+}
+
+/// Steps stream bitrates of opted-in cameras along the ladder
+pub struct AbrEngine {
+    manager: Arc<RwLock<CameraManager>>,
+    state: Arc<RwLock<AbrState>>,
+}
+
+impl AbrEngine {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            state: Arc::new(RwLock::new(AbrState::default())),
+        }
+    }
+
+    /// Inject where ABR settings are persisted and load them
+    pub async fn set_path_provider(&self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        let path = paths.abr_file()?;
+        let mut state = self.state.write().await;
+
+        if path.exists() {
+            let json = tokio::fs::read_to_string(&path).await
+                .context("Failed to read ABR settings file")?;
+            state.settings = serde_json::from_str(&json)
+                .context("Failed to deserialize ABR settings")?;
+            log::info!("Loaded ABR settings for {} cameras from {:?}", state.settings.enabled_cameras.len(), path);
+        } else {
+            log::info!("No ABR settings file found at {:?}, ABR disabled", path);
+        }
+
+        state.settings_file_path = Some(path);
+        Ok(())
+    }
+
+    /// Subscribe to telemetry and step bitrates in the background
+    pub async fn start(&self) {
+        let mut events = self.manager.read().await.subscribe();
+        let manager = self.manager.clone();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(CameraEvent::Telemetry { camera_id, telemetry }) => {
+                        handle_telemetry(&manager, &state, camera_id, telemetry).await;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("ABR engine lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            log::warn!("ABR event loop ended");
+        });
+
+        log::info!("ABR engine started");
+    }
+
+    // MARK: - Settings
+
+    pub async fn get_settings(&self) -> AbrSettings {
+        self.state.read().await.settings.clone()
+    }
+
+    pub async fn save_config(&self, config: AbrConfig) -> Result<()> {
+        if config.ladder_bps.is_empty() || config.ladder_bps.contains(&0) {
+            anyhow::bail!("The ABR ladder needs at least one non-zero bitrate");
+        }
+
+        let mut state = self.state.write().await;
+        state.settings.config = config;
+        save_settings_to_disk(&state).await
+    }
+
+    /// Opt a camera in or out; opting out brings a stepped-down camera back
+    /// to its configured bitrate
+    pub async fn set_enabled(&self, camera_id: &str, enabled: bool) -> Result<()> {
+        let signals = {
+            let mut state = self.state.write().await;
+            let cameras = &mut state.settings.enabled_cameras;
+
+            cameras.retain(|id| id != camera_id);
+            if enabled {
+                cameras.push(camera_id.to_string());
+            }
+            let signals = state.cameras.remove(camera_id);
+
+            save_settings_to_disk(&state).await?;
+            signals
+        };
+
+        let Some(signals) = signals.filter(|_| !enabled) else {
+            return Ok(());
+        };
+        let (Some(from_bps), Some(stream)) = (signals.applied_bps, signals.configured) else {
+            return Ok(());
+        };
+
+        let step = AbrStep {
+            camera_id: camera_id.to_string(),
+            direction: StepDirection::Up,
+            from_bps,
+            to_bps: stream.bitrate,
+            reason: "ABR disabled".to_string(),
+            timestamp: Utc::now(),
+        };
+        apply_step(&self.manager, &step, stream).await
+    }
+
+    pub async fn get_status(&self) -> Vec<AbrCameraStatus> {
+        let state = self.state.read().await;
+        let enabled: HashSet<&String> = state.settings.enabled_cameras.iter().collect();
+
+        let mut statuses: Vec<AbrCameraStatus> = state.cameras.iter()
+            .map(|(camera_id, signals)| AbrCameraStatus {
+                camera_id: camera_id.clone(),
+                enabled: enabled.contains(camera_id),
+                ceiling_bps: signals.ceiling_bps(),
+                current_bps: signals.current_bps(),
+                queue_ms: signals.queue_ms,
+                dropped_per_s: signals.dropped_per_s,
+                wifi_rssi_dbm: signals.wifi_rssi_dbm,
+                last_step: signals.last_step.clone(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        statuses
+    }
+}
+
+async fn save_settings_to_disk(state: &AbrState) -> Result<()> {
+    let Some(path) = &state.settings_file_path else {
+        return Ok(()); // No persistence path set
+    };
+
+    let json = serde_json::to_string_pretty(&state.settings)
+        .context("Failed to serialize ABR settings")?;
+
+    tokio::fs::write(path, json).await
+        .context("Failed to write ABR settings to disk")?;
+
+    Ok(())
+}
+
+// MARK: - Stepping
+
+/// Next rung below/above `current`, bounded by the ceiling
+fn next_rung(ladder: &[u32], ceiling: u32, current: u32, direction: StepDirection) -> Option<u32> {
+    let mut rungs: Vec<u32> = ladder.iter().copied().filter(|&r| r < ceiling).collect();
+    rungs.push(ceiling);
+    rungs.sort_unstable();
+    rungs.dedup();
+
+    match direction {
+        StepDirection::Down => rungs.into_iter().rev().find(|&r| r < current),
+        StepDirection::Up => rungs.into_iter().find(|&r| r > current),
+    }
+}
+
+async fn handle_telemetry(
+    manager: &Arc<RwLock<CameraManager>>,
+    engine_state: &Arc<RwLock<AbrState>>,
+    camera_id: String,
+    telemetry: WebSocketTelemetryMessage,
+) {
+    let configured = manager.read().await.stream_settings_or_default(&camera_id);
+    let now = Instant::now();
+
+    let mut guard = engine_state.write().await;
+    let state = &mut *guard;
+    if !state.settings.enabled_cameras.contains(&camera_id) {
+        return;
+    }
+    let config = state.settings.config.clone();
+
+    let signals = state.cameras.entry(camera_id.clone())
+        .or_insert_with(|| CameraSignals::new(&telemetry, now));
+    signals.update(&telemetry, config.smoothing, now);
+    signals.configure(configured.clone());
+
+    if telemetry.ndi_state != NdiState::Streaming {
+        signals.healthy_since = None;
+        return;
+    }
+
+    let Some((direction, to_bps, reason)) = signals.next_step(&config, now) else {
+        return;
+    };
+    let previous_bps = signals.applied_bps;
+
+    let step = AbrStep {
+        camera_id: camera_id.clone(),
+        direction,
+        from_bps: signals.current_bps().unwrap_or(configured.bitrate),
+        to_bps,
+        reason,
+        timestamp: Utc::now(),
+    };
+    signals.record_step(&step, now);
+    drop(guard);
+
+    let stream = StreamStartRequest { bitrate: to_bps, ..configured };
+    if apply_step(manager, &step, stream).await.is_err() {
+        if let Some(signals) = engine_state.write().await.cameras.get_mut(&camera_id) {
+            signals.applied_bps = previous_bps;
+        }
+    }
+}
+
+/// Update the running stream to the step's bitrate without persisting it, so
+/// the operator's bitrate stays the ceiling across restarts and opt-outs
+async fn apply_step(manager: &Arc<RwLock<CameraManager>>, step: &AbrStep, stream: StreamStartRequest) -> Result<()> {
+    log::info!("ABR {:?} on {}: {} -> {} bps ({})", step.direction, step.camera_id, step.from_bps, step.to_bps, step.reason);
+
+    let result = CameraManager::send_stream_via(ManagerMut::Locked(manager), &step.camera_id, stream, StreamDelivery::Bitrate, ActionSource::Automation).await;
+    match &result {
+        Ok(()) => {
+            if let Ok(payload) = serde_json::to_value(step) {
+                manager.read().await.notifier().emit("abr-step", payload);
+            }
+        }
+        Err(e) => log::warn!("ABR step failed on {}: {}", step.camera_id, e),
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    const LADDER: [u32; 3] = [10_000_000, 8_000_000, 6_000_000];
+
+    fn telemetry(queue_ms: u32, dropped_frames: u32, wifi_rssi: i32) -> WebSocketTelemetryMessage {
+        WebSocketTelemetryMessage { queue_ms, dropped_frames, wifi_rssi, ..test_support::telemetry() }
+    }
+
+    fn configured(bitrate: u32) -> StreamStartRequest {
+        StreamStartRequest { bitrate, ..Default::default() }
+    }
+
+    fn step(signals: &CameraSignals, direction: StepDirection, to_bps: u32) -> AbrStep {
+        AbrStep {
+            camera_id: "cam".to_string(),
+            direction,
+            from_bps: signals.current_bps().unwrap(),
+            to_bps,
+            reason: String::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn next_rung_walks_the_ladder_below_the_ceiling() {
+        use StepDirection::*;
+        let cases = [
+            (10_000_000, 10_000_000, Down, Some(8_000_000)),
+            (10_000_000, 8_000_000, Down, Some(6_000_000)),
+            (10_000_000, 6_000_000, Down, None),
+            (10_000_000, 6_000_000, Up, Some(8_000_000)),
+            (10_000_000, 10_000_000, Up, None),
+            // An off-ladder ceiling is a rung of its own, and is never exceeded
+            (9_000_000, 9_000_000, Down, Some(8_000_000)),
+            (9_000_000, 8_000_000, Up, Some(9_000_000)),
+            (7_000_000, 7_000_000, Down, Some(6_000_000)),
+            (5_000_000, 5_000_000, Down, None),
+            (5_000_000, 5_000_000, Up, None),
+        ];
+        for (ceiling, current, direction, expected) in cases {
+            assert_eq!(
+                next_rung(&LADDER, ceiling, current, direction), expected,
+                "{:?} from {} under {}", direction, current, ceiling,
+            );
+        }
+    }
+
+    #[test]
+    fn next_rung_accepts_an_unordered_ladder() {
+        let ladder = [6_000_000, 10_000_000, 8_000_000, 8_000_000];
+        assert_eq!(next_rung(&ladder, 10_000_000, 10_000_000, StepDirection::Down), Some(8_000_000));
+        assert_eq!(next_rung(&ladder, 10_000_000, 6_000_000, StepDirection::Up), Some(8_000_000));
+    }
+
+    #[test]
+    fn update_smooths_signals_with_the_configured_weight() {
+        let start = Instant::now();
+        let mut signals = CameraSignals::new(&telemetry(0, 100, -60), start);
+
+        signals.update(&telemetry(100, 110, -80), 0.5, start + Duration::from_secs(1));
+        assert_eq!(signals.queue_ms, 50.0);
+        assert_eq!(signals.dropped_per_s, 5.0); // half of 10 frames in 1 s
+        assert_eq!(signals.wifi_rssi_dbm, -70.0);
+
+        signals.update(&telemetry(100, 110, -80), 0.5, start + Duration::from_secs(2));
+        assert_eq!(signals.queue_ms, 75.0);
+        assert_eq!(signals.dropped_per_s, 2.5);
+        assert_eq!(signals.wifi_rssi_dbm, -75.0);
+    }
+
+    #[test]
+    fn update_treats_a_dropped_counter_reset_as_a_new_stream() {
+        let start = Instant::now();
+        let mut signals = CameraSignals::new(&telemetry(0, 500, -60), start);
+
+        signals.update(&telemetry(0, 4, -60), 1.0, start + Duration::from_secs(2));
+        assert_eq!(signals.dropped_per_s, 2.0);
+    }
+
+    #[test]
+    fn steps_down_when_congested_and_dwells_before_the_next_step() {
+        let config = AbrConfig::default();
+        let start = Instant::now();
+        let mut signals = CameraSignals::new(&telemetry(400, 0, -60), start);
+        signals.configure(configured(10_000_000));
+
+        let (direction, to_bps, reason) = signals.next_step(&config, start).expect("congested");
+        assert_eq!((direction, to_bps), (StepDirection::Down, 8_000_000));
+        assert!(reason.starts_with("queue"), "{}", reason);
+        signals.record_step(&step(&signals, direction, to_bps), start);
+        assert_eq!(signals.current_bps(), Some(8_000_000));
+
+        let dwell = Duration::from_secs(config.min_dwell_s);
+        assert!(signals.next_step(&config, start + dwell - Duration::from_secs(1)).is_none());
+        let (_, to_bps, _) = signals.next_step(&config, start + dwell).expect("dwell elapsed");
+        assert_eq!(to_bps, 6_000_000);
+    }
+
+    #[test]
+    fn steps_up_only_after_staying_healthy_and_never_above_the_ceiling() {
+        let config = AbrConfig::default();
+        let start = Instant::now();
+        let mut signals = CameraSignals::new(&telemetry(400, 0, -60), start);
+        signals.configure(configured(8_000_000));
+
+        signals.record_step(&step(&signals, StepDirection::Down, 6_000_000), start);
+        signals.update(&telemetry(10, 0, -50), 1.0, start + Duration::from_secs(1));
+
+        let healthy = start + Duration::from_secs(config.min_dwell_s);
+        assert!(signals.next_step(&config, healthy).is_none(), "healthy_since starts now");
+        let recovered = healthy + Duration::from_secs(config.recovery_s);
+        let (direction, to_bps, _) = signals.next_step(&config, recovered).expect("recovered");
+        assert_eq!((direction, to_bps), (StepDirection::Up, 8_000_000));
+
+        signals.record_step(&step(&signals, direction, to_bps), recovered);
+        assert_eq!(signals.applied_bps, None, "back at the ceiling");
+        let later = recovered + Duration::from_secs(config.min_dwell_s + config.recovery_s);
+        assert!(signals.next_step(&config, later).is_none());
+    }
+
+    #[test]
+    fn a_new_configured_stream_restarts_the_ladder_from_its_bitrate() {
+        let start = Instant::now();
+        let mut signals = CameraSignals::new(&telemetry(0, 0, -60), start);
+        signals.configure(configured(10_000_000));
+        signals.record_step(&step(&signals, StepDirection::Down, 8_000_000), start);
+
+        signals.configure(configured(10_000_000));
+        assert_eq!(signals.current_bps(), Some(8_000_000), "unchanged settings keep the rung");
+
+        signals.configure(configured(12_000_000));
+        assert_eq!(signals.ceiling_bps(), Some(12_000_000));
+        assert_eq!(signals.current_bps(), Some(12_000_000));
+    }
+}
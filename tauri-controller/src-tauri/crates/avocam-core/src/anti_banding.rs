@@ -0,0 +1,239 @@
+//! Anti-banding shutter constraints
+//!
+//! Under mains-powered lighting a manual shutter only avoids banding when it
+//! spans a whole number of light flicker periods (1/100 s at 50 Hz, 1/120 s at
+//! 60 Hz). These helpers convert shutter angles and snap manual shutters to
+//! such values before they reach a camera.
+
+use anyhow::Result;
+
+use crate::models::*;
+
+/// Shutter duration (seconds) of a shutter angle at a frame rate, e.g. 180° at 25 fps → 1/50 s
+pub fn shutter_from_angle(angle_deg: f64, fps: u32) -> f64 {
+    angle_deg / 360.0 / fps.max(1) as f64
+}
+
+/// Longest flicker-free shutter at most one frame long, or `None` if the frame
+/// interval is shorter than one flicker period
+fn max_flicker_free_multiple(fps: u32, mains: MainsFrequency) -> Option<f64> {
+    let frame_interval = 1.0 / fps.max(1) as f64;
+    let multiple = (frame_interval / mains.flicker_period_s() + 1e-9).floor();
+    (multiple >= 1.0).then_some(multiple)
+}
+
+/// Snap a shutter to the nearest (in stops) flicker-free value within one frame
+///
+/// Returns the snapped shutter and whether it is flicker-free (`None` without mains).
+/// Shutters shorter than one flicker period are kept as they are (lengthening
+/// them would overexpose by several stops) and reported as not flicker-free.
+pub fn snap_shutter(shutter_s: f64, fps: u32, mains: Option<MainsFrequency>) -> (f64, Option<bool>) {
+    let frame_interval = 1.0 / fps.max(1) as f64;
+    let shutter_s = shutter_s.min(frame_interval);
+
+    let Some(mains) = mains else {
+        return (shutter_s, None);
+    };
+
+    let Some(max_multiple) = max_flicker_free_multiple(fps, mains) else {
+        return (shutter_s, Some(false));
+    };
+
+    let period = mains.flicker_period_s();
+    let ideal = shutter_s / period;
+    if ideal + 1e-9 < 1.0 {
+        return (shutter_s, Some(false));
+    }
+
+    let snapped = [ideal.floor(), ideal.ceil()]
+        .into_iter()
+        .map(|n| n.clamp(1.0, max_multiple) * period)
+        .min_by(|a, b| (a / shutter_s).log2().abs().total_cmp(&(b / shutter_s).log2().abs()))
+        .unwrap_or(period);
+
+    (snapped, Some(true))
+}
+
+/// Turn a shutter angle into `shutter_s` and snap a manual shutter for anti-banding
+///
+/// Only manual shutters are touched: the request's mode, else `current_mode`
+/// (the camera's), must be manual, or an angle must be given (which implies
+/// manual). A `shutter_s` riding along with auto exposure, as profiles and
+/// persisted settings carry it, is sent unchanged. Returns the settings to send
+/// and, when a manual shutter was involved, how it was adjusted.
+pub fn resolve_shutter(
+    mut settings: CameraSettingsRequest,
+    fps: u32,
+    mains: Option<MainsFrequency>,
+    current_mode: Option<ExposureMode>,
+) -> Result<(CameraSettingsRequest, Option<ShutterAdjustment>)> {
+    let requested_angle_deg = settings.shutter_angle_deg.take();
+    let requested_shutter_s = settings.shutter_s;
+
+    let shutter_s = match (requested_angle_deg, requested_shutter_s) {
+        (Some(angle), _) => {
+            if !(angle > 0.0 && angle <= 360.0) {
+                anyhow::bail!("Shutter angle must be in (0, 360] degrees, got {}", angle);
+            }
+            // An explicit angle implies manual shutter
+            settings.shutter_mode.get_or_insert(ExposureMode::Manual);
+            shutter_from_angle(angle, fps)
+        }
+        (None, Some(_)) if settings.shutter_mode.or(current_mode) != Some(ExposureMode::Manual) => {
+            return Ok((settings, None));
+        }
+        (None, Some(shutter_s)) => {
+            if !(shutter_s > 0.0 && shutter_s.is_finite()) {
+                anyhow::bail!("Shutter duration must be positive, got {}", shutter_s);
+            }
+            shutter_s
+        }
+        (None, None) => return Ok((settings, None)),
+    };
+
+    let (applied_shutter_s, flicker_safe) = snap_shutter(shutter_s, fps, mains);
+    settings.shutter_s = Some(applied_shutter_s);
+
+    let adjustment = ShutterAdjustment {
+        requested_shutter_s,
+        requested_angle_deg,
+        fps,
+        mains_frequency: mains,
+        applied_shutter_s,
+        snapped: (applied_shutter_s - shutter_s).abs() > 1e-9,
+        flicker_safe,
+    };
+
+    if adjustment.snapped {
+        log::info!("Shutter {:.6}s snapped to {:.6}s at {} fps ({:?})",
+            shutter_s, applied_shutter_s, fps, mains);
+    }
+    if flicker_safe == Some(false) {
+        log::warn!("Shutter {:.6}s at {} fps cannot be flicker-free at {:?}; banding may be visible",
+            applied_shutter_s, fps, mains);
+    }
+
+    Ok((settings, Some(adjustment)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn frame_interval_limits_flicker_free_multiples() {
+        use MainsFrequency::*;
+        let cases = [
+            (Hz50, 25, 4.0), (Hz50, 30, 3.0), (Hz50, 50, 2.0), (Hz50, 60, 1.0),
+            (Hz60, 25, 4.0), (Hz60, 30, 4.0), (Hz60, 50, 2.0), (Hz60, 60, 2.0),
+        ];
+        for (mains, fps, multiple) in cases {
+            assert_eq!(max_flicker_free_multiple(fps, mains), Some(multiple), "{:?} at {} fps", mains, fps);
+        }
+        assert_eq!(max_flicker_free_multiple(120, Hz50), None);
+    }
+
+    #[test]
+    fn snaps_to_nearest_period_multiple_at_50hz() {
+        let mains = Some(MainsFrequency::Hz50);
+        // 180° shutters: 1/50, 1/60, 1/100, 1/120 s
+        assert_eq!(snap_shutter(1.0 / 50.0, 25, mains), (0.02, Some(true)));
+        let (shutter, safe) = snap_shutter(1.0 / 60.0, 30, mains);
+        assert_close(shutter, 0.02);
+        assert_eq!(safe, Some(true));
+        assert_eq!(snap_shutter(1.0 / 100.0, 50, mains), (0.01, Some(true)));
+        assert_eq!(snap_shutter(1.0 / 120.0, 60, mains), (1.0 / 120.0, Some(false)));
+    }
+
+    #[test]
+    fn snaps_to_nearest_period_multiple_at_60hz() {
+        let mains = Some(MainsFrequency::Hz60);
+        let period = 1.0 / 120.0;
+        for (shutter, fps, expected) in [
+            (1.0 / 50.0, 25, 2.0 * period),
+            (1.0 / 60.0, 30, 2.0 * period),
+            (1.0 / 100.0, 50, period),
+            (1.0 / 120.0, 60, period),
+            // Longer than a frame: capped at the frame interval first
+            (1.0 / 10.0, 30, 4.0 * period),
+        ] {
+            let (snapped, safe) = snap_shutter(shutter, fps, mains);
+            assert_close(snapped, expected);
+            assert_eq!(safe, Some(true));
+        }
+    }
+
+    #[test]
+    fn keeps_shutters_shorter_than_one_period() {
+        for mains in [MainsFrequency::Hz50, MainsFrequency::Hz60] {
+            for fps in [25, 30, 50, 60] {
+                assert_eq!(snap_shutter(0.001, fps, Some(mains)), (0.001, Some(false)), "{:?} at {} fps", mains, fps);
+            }
+        }
+    }
+
+    #[test]
+    fn leaves_shutter_alone_without_mains() {
+        assert_eq!(snap_shutter(0.001, 25, None), (0.001, None));
+        assert_eq!(snap_shutter(0.1, 25, None), (0.04, None));
+    }
+
+    #[test]
+    fn resolve_reports_unsnapped_short_shutter() {
+        let settings = CameraSettingsRequest {
+            shutter_mode: Some(ExposureMode::Manual),
+            shutter_s: Some(0.001),
+            ..Default::default()
+        };
+        let (settings, adjustment) = resolve_shutter(settings, 25, Some(MainsFrequency::Hz50), None).unwrap();
+        let adjustment = adjustment.unwrap();
+
+        assert_eq!(settings.shutter_s, Some(0.001));
+        assert!(!adjustment.snapped);
+        assert_eq!(adjustment.flicker_safe, Some(false));
+    }
+
+    #[test]
+    fn resolve_converts_shutter_angle() {
+        let settings = CameraSettingsRequest { shutter_angle_deg: Some(180.0), ..Default::default() };
+        let (settings, adjustment) = resolve_shutter(settings, 30, Some(MainsFrequency::Hz50), Some(ExposureMode::Auto)).unwrap();
+
+        assert_close(settings.shutter_s.unwrap(), 0.02);
+        assert_eq!(settings.shutter_mode, Some(ExposureMode::Manual));
+        assert!(adjustment.unwrap().snapped);
+    }
+
+    #[test]
+    fn resolve_leaves_shutter_alone_in_auto_mode() {
+        let settings = CameraSettingsRequest {
+            shutter_mode: Some(ExposureMode::Auto),
+            shutter_s: Some(1.0 / 60.0),
+            ..Default::default()
+        };
+        let (settings, adjustment) = resolve_shutter(settings, 25, Some(MainsFrequency::Hz50), Some(ExposureMode::Manual)).unwrap();
+
+        assert_eq!(settings.shutter_s, Some(1.0 / 60.0));
+        assert!(adjustment.is_none());
+    }
+
+    #[test]
+    fn resolve_follows_the_cameras_mode_when_the_request_has_none() {
+        let settings = CameraSettingsRequest { shutter_s: Some(1.0 / 60.0), ..Default::default() };
+
+        let (auto, adjustment) = resolve_shutter(settings.clone(), 25, Some(MainsFrequency::Hz50), Some(ExposureMode::Auto)).unwrap();
+        assert_eq!(auto.shutter_s, Some(1.0 / 60.0));
+        assert!(adjustment.is_none());
+
+        let (unknown, adjustment) = resolve_shutter(settings.clone(), 25, Some(MainsFrequency::Hz50), None).unwrap();
+        assert_eq!(unknown.shutter_s, Some(1.0 / 60.0));
+        assert!(adjustment.is_none());
+
+        let (manual, adjustment) = resolve_shutter(settings, 25, Some(MainsFrequency::Hz50), Some(ExposureMode::Manual)).unwrap();
+        assert_close(manual.shutter_s.unwrap(), 0.02);
+        assert!(adjustment.unwrap().snapped);
+    }
+}
@@ -0,0 +1,445 @@
+//! Append-only audit log of control actions
+//!
+//! Every mutating [`CameraManager`](crate::CameraManager) operation appends one
+//! JSON line to `audit.jsonl`, recording who asked for what and how it went.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+
+use crate::events::{CameraEvent, EventSender};
+use crate::models::GroupCommandResult;
+
+/// Who initiated an action
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ActionSource {
+    Ui,
+    Cli,
+    Api,
+    Automation,
+    Scheduler,
+}
+
+/// Result of an audited action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Success,
+    Failure { error: String },
+    /// Group operation with per-camera results
+    Group { results: Vec<GroupCommandResult> },
+}
+
+impl AuditOutcome {
+    /// Whether the action (or every camera of a group action) succeeded
+    pub fn is_success(&self) -> bool {
+        match self {
+            AuditOutcome::Success => true,
+            AuditOutcome::Failure { .. } => false,
+            AuditOutcome::Group { results } => results.iter().all(|r| r.success),
+        }
+    }
+
+    fn from_result<T>(result: &Result<T>) -> Self {
+        match result {
+            Ok(_) => AuditOutcome::Success,
+            Err(e) => AuditOutcome::Failure { error: e.to_string() },
+        }
+    }
+
+    fn from_group_result(result: &Result<Vec<GroupCommandResult>>) -> Self {
+        match result {
+            Ok(results) => AuditOutcome::Group { results: results.clone() },
+            Err(e) => AuditOutcome::Failure { error: e.to_string() },
+        }
+    }
+}
+
+/// One journaled action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub source: ActionSource,
+    /// Operation name, e.g. `start_stream` or `group_update_settings`
+    pub action: String,
+    /// Cameras targeted (empty for fleet-independent actions like profile edits)
+    pub camera_ids: Vec<String>,
+    /// Request payload as sent by the caller (secrets omitted)
+    pub request: serde_json::Value,
+    pub outcome: AuditOutcome,
+}
+
+/// Filter for [`AuditLog::query`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AuditQuery {
+    pub camera_id: Option<String>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+    /// Keep only the most recent `limit` matches
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        if let Some(camera_id) = &self.camera_id {
+            if !entry.camera_ids.iter().any(|id| id == camera_id) {
+                return false;
+            }
+        }
+
+        self.from.is_none_or(|from| entry.timestamp >= from)
+            && self.to.is_none_or(|to| entry.timestamp <= to)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    Jsonl,
+    Csv,
+}
+
+/// Append-only JSONL journal (disabled until a path is set)
+#[derive(Default)]
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    // Serializes appends so concurrent entries never interleave
+    write_lock: Mutex<()>,
+    // Entries are also published as `CameraEvent::Action`
+    events: Option<EventSender>,
+}
+
+impl AuditLog {
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.path = Some(path);
+    }
+
+    pub fn set_events(&mut self, events: EventSender) {
+        self.events = Some(events);
+    }
+
+    /// Journal a single-outcome action
+    pub async fn record<T>(
+        &self,
+        source: ActionSource,
+        action: &str,
+        camera_ids: &[String],
+        request: &impl Serialize,
+        result: &Result<T>,
+    ) {
+        self.append(source, action, camera_ids, request, AuditOutcome::from_result(result)).await;
+    }
+
+    /// Journal a group action with its per-camera results
+    pub async fn record_group(
+        &self,
+        source: ActionSource,
+        action: &str,
+        camera_ids: &[String],
+        request: &impl Serialize,
+        result: &Result<Vec<GroupCommandResult>>,
+    ) {
+        self.append(source, action, camera_ids, request, AuditOutcome::from_group_result(result)).await;
+    }
+
+    async fn append(
+        &self,
+        source: ActionSource,
+        action: &str,
+        camera_ids: &[String],
+        request: &impl Serialize,
+        outcome: AuditOutcome,
+    ) {
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            source,
+            action: action.to_string(),
+            camera_ids: camera_ids.to_vec(),
+            request: serde_json::to_value(request).unwrap_or(serde_json::Value::Null),
+            outcome,
+        };
+
+        // Auditing must never break the operation itself
+        if let Err(e) = self.write_entry(&entry).await {
+            log::warn!("Failed to write audit entry for {}: {}", action, e);
+        }
+
+        if let Some(events) = &self.events {
+            let _ = events.send(CameraEvent::Action { entry });
+        }
+    }
+
+    async fn write_entry(&self, entry: &AuditEntry) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(()); // No persistence path set
+        };
+
+        let mut line = serde_json::to_string(entry)
+            .context("Failed to serialize audit entry")?;
+        line.push('\n');
+
+        let _guard = self.write_lock.lock().await;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .await
+            .context("Failed to open audit log")?;
+
+        file.write_all(line.as_bytes()).await
+            .context("Failed to append to audit log")?;
+        // tokio writes in the background; flush so the entry is readable once recorded
+        file.flush().await
+            .context("Failed to flush audit log")?;
+
+        Ok(())
+    }
+
+    /// Entries matching the query, oldest first
+    pub async fn query(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        let Some(path) = &self.path else {
+            return Ok(Vec::new());
+        };
+
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = tokio::fs::read_to_string(path).await
+            .context("Failed to read audit log")?;
+
+        let mut entries: Vec<AuditEntry> = content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match serde_json::from_str::<AuditEntry>(line) {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    log::warn!("Skipping malformed audit entry: {}", e);
+                    None
+                }
+            })
+            .filter(|entry| query.matches(entry))
+            .collect();
+
+        if let Some(limit) = query.limit {
+            let excess = entries.len().saturating_sub(limit);
+            entries.drain(..excess);
+        }
+
+        Ok(entries)
+    }
+
+    /// Write matching entries to `destination` as JSONL or CSV, returning the count
+    pub async fn export(&self, query: &AuditQuery, format: ExportFormat, destination: &Path) -> Result<usize> {
+        let entries = self.query(query).await?;
+
+        let content = match format {
+            ExportFormat::Jsonl => {
+                let mut out = String::new();
+                for entry in &entries {
+                    out.push_str(&serde_json::to_string(entry)?);
+                    out.push('\n');
+                }
+                out
+            }
+            ExportFormat::Csv => entries_to_csv(&entries)?,
+        };
+
+        tokio::fs::write(destination, content).await
+            .with_context(|| format!("Failed to write audit export to {:?}", destination))?;
+
+        log::info!("Exported {} audit entries to {:?}", entries.len(), destination);
+        Ok(entries.len())
+    }
+}
+
+fn entries_to_csv(entries: &[AuditEntry]) -> Result<String> {
+    let mut out = String::from("timestamp,source,action,camera_ids,success,error,request,results\n");
+
+    for entry in entries {
+        let source = serde_json::to_value(entry.source)?;
+        let (error, results) = match &entry.outcome {
+            AuditOutcome::Success => (String::new(), String::new()),
+            AuditOutcome::Failure { error } => (error.clone(), String::new()),
+            AuditOutcome::Group { results } => {
+                let errors: Vec<String> = results.iter()
+                    .filter_map(|r| r.error.as_ref().map(|e| format!("{}: {}", r.camera_id, e)))
+                    .collect();
+                (errors.join("; "), serde_json::to_string(results)?)
+            }
+        };
+
+        let row = [
+            entry.timestamp.to_rfc3339(),
+            source.as_str().unwrap_or_default().to_string(),
+            entry.action.clone(),
+            entry.camera_ids.join(";"),
+            entry.outcome.is_success().to_string(),
+            error,
+            entry.request.to_string(),
+            results,
+        ];
+
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    Ok(out)
+}
+
+/// Quote a CSV field when it contains separators, quotes or newlines
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    fn log_at(path: &Path) -> AuditLog {
+        let mut log = AuditLog::default();
+        log.set_path(path.to_path_buf());
+        log
+    }
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn camera_result(camera_id: &str, error: Option<&str>) -> GroupCommandResult {
+        GroupCommandResult {
+            camera_id: camera_id.to_string(),
+            success: error.is_none(),
+            error: error.map(str::to_string),
+            shutter_adjustment: None,
+            issues: Vec::new(),
+        }
+    }
+
+    fn lines(path: &Path) -> Vec<String> {
+        std::fs::read_to_string(path).unwrap().lines().map(str::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn records_are_appended_one_line_each() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = log_at(&path);
+
+        log.record(ActionSource::Ui, "start_stream", &ids(&["a"]), &serde_json::json!({}), &Ok(())).await;
+        let first = lines(&path);
+
+        log.record::<()>(ActionSource::Api, "stop_stream", &ids(&["b"]), &serde_json::json!({}), &Err(anyhow!("timed out"))).await;
+        let all = lines(&path);
+
+        assert_eq!(all.len(), 2);
+        assert_eq!(all[0], first[0], "earlier entries are never rewritten");
+
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(entries[0].action, "start_stream");
+        assert_eq!(entries[0].source, ActionSource::Ui);
+        assert!(entries[0].outcome.is_success());
+        assert_eq!(entries[1].action, "stop_stream");
+        assert!(matches!(&entries[1].outcome, AuditOutcome::Failure { error } if error == "timed out"));
+    }
+
+    #[tokio::test]
+    async fn reopening_the_journal_appends_instead_of_truncating() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+
+        log_at(&path).record(ActionSource::Cli, "add_camera", &ids(&["a"]), &serde_json::json!({}), &Ok(())).await;
+        log_at(&path).record(ActionSource::Cli, "remove_camera", &ids(&["a"]), &serde_json::json!({}), &Ok(())).await;
+
+        let entries = log_at(&path).query(&AuditQuery::default()).await.unwrap();
+        let actions: Vec<&str> = entries.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["add_camera", "remove_camera"]);
+    }
+
+    #[tokio::test]
+    async fn group_outcome_fails_if_any_camera_failed() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = log_at(&dir.path().join("audit.jsonl"));
+
+        let results = vec![camera_result("a", None), camera_result("b", Some("offline"))];
+        log.record_group(ActionSource::Scheduler, "group_start_stream", &ids(&["a", "b"]), &serde_json::json!({}), &Ok(results)).await;
+
+        let entries = log.query(&AuditQuery::default()).await.unwrap();
+        assert!(matches!(&entries[0].outcome, AuditOutcome::Group { results } if results.len() == 2));
+        assert!(!entries[0].outcome.is_success());
+    }
+
+    #[tokio::test]
+    async fn query_filters_by_camera_time_and_limit() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = log_at(&path);
+
+        for (action, camera) in [("one", "a"), ("two", "b"), ("three", "a"), ("four", "a")] {
+            log.record(ActionSource::Ui, action, &ids(&[camera]), &serde_json::json!({}), &Ok(())).await;
+        }
+        // A torn or hand-edited line does not hide the rest of the journal
+        let mut content = std::fs::read_to_string(&path).unwrap();
+        content.push_str("{not json\n");
+        std::fs::write(&path, content).unwrap();
+
+        let all = log.query(&AuditQuery::default()).await.unwrap();
+        assert_eq!(all.len(), 4);
+
+        let camera_a = log.query(&AuditQuery { camera_id: Some("a".into()), limit: Some(2), ..Default::default() }).await.unwrap();
+        let actions: Vec<&str> = camera_a.iter().map(|e| e.action.as_str()).collect();
+        assert_eq!(actions, ["three", "four"]);
+
+        let (from, to) = (all[1].timestamp, all[2].timestamp);
+        let window = log.query(&AuditQuery { from: Some(from), to: Some(to), ..Default::default() }).await.unwrap();
+        assert!(window.iter().all(|e| e.timestamp >= from && e.timestamp <= to));
+        assert!(window.iter().any(|e| e.action == "two"));
+        assert!(window.iter().any(|e| e.action == "three"));
+    }
+
+    #[tokio::test]
+    async fn without_a_path_entries_are_only_published() {
+        let events = crate::events::channel();
+        let mut receiver = events.subscribe();
+        let mut log = AuditLog::default();
+        log.set_events(events);
+
+        log.record(ActionSource::Automation, "update_settings", &ids(&["a"]), &serde_json::json!({}), &Ok(())).await;
+
+        assert!(log.query(&AuditQuery::default()).await.unwrap().is_empty());
+        assert!(matches!(receiver.try_recv(), Ok(CameraEvent::Action { entry }) if entry.action == "update_settings"));
+    }
+
+    #[tokio::test]
+    async fn csv_export_quotes_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = log_at(&dir.path().join("audit.jsonl"));
+        log.record::<()>(ActionSource::Ui, "update_settings", &ids(&["a", "b"]), &serde_json::json!({ "fps": 30 }), &Err(anyhow!("bad \"fps\", retry"))).await;
+
+        let destination = dir.path().join("export.csv");
+        let count = log.export(&AuditQuery::default(), ExportFormat::Csv, &destination).await.unwrap();
+        assert_eq!(count, 1);
+
+        let csv = std::fs::read_to_string(&destination).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.contains(",ui,update_settings,a;b,false,\"bad \"\"fps\"\", retry\",\"{\"\"fps\"\":30}\","));
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("two\nlines"), "\"two\nlines\"");
+    }
+}
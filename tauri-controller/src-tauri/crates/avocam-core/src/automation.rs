@@ -0,0 +1,666 @@
+//! Event-triggered automation rules ("when X then Y")
+//!
+//! Rules subscribe to the manager's event bus and run actions through
+//! [`CameraManager`] on the camera that triggered them, e.g.:
+//!
+//! - temperature above 42 °C → drop bitrate
+//! - camera reconnected → re-apply its profile
+//! - NDI went idle while the controller expected it to stream → restart stream
+//!
+//! Rules are persisted; the execution log is kept for the session only (the
+//! last 500 runs). Every action a rule runs is also journaled to the audit log,
+//! which is the durable record.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+use uuid::Uuid;
+
+use crate::audit::ActionSource;
+use crate::camera_manager::{CameraManager, ManagerMut, ManagerRef, StreamDelivery};
+use crate::events::CameraEvent;
+use crate::models::*;
+use crate::platform::PathProvider;
+
+const MAX_LOG_ENTRIES: usize = 500;
+const DEFAULT_COOLDOWN_S: u64 = 60;
+
+// MARK: - Rule Model
+
+/// A persisted automation rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutomationRule {
+    /// Assigned on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    /// All conditions must hold (evaluated against the camera's latest telemetry)
+    #[serde(default)]
+    pub conditions: Vec<RuleCondition>,
+    /// Executed in order on the triggering camera
+    pub actions: Vec<RuleAction>,
+    /// Minimum delay between two executions for the same camera
+    #[serde(default = "default_cooldown")]
+    pub cooldown_s: u64,
+    /// Cameras the rule applies to (empty = all cameras)
+    #[serde(default)]
+    pub camera_ids: Vec<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_cooldown() -> u64 {
+    DEFAULT_COOLDOWN_S
+}
+
+/// Event that makes a rule evaluate its conditions
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleTrigger {
+    /// Every telemetry sample (1 Hz per camera)
+    Telemetry,
+    /// Telemetry WebSocket (re)connected
+    Connected,
+    /// Telemetry WebSocket dropped
+    Disconnected,
+    /// NDI went from streaming to idle while the controller expected it to stream
+    NdiIdleUnexpected,
+    /// Camera (re)appeared over mDNS
+    Discovered,
+}
+
+/// Comparison of a telemetry metric against a threshold
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleCondition {
+    pub metric: TelemetryMetric,
+    pub op: ComparisonOp,
+    pub value: f64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TelemetryMetric {
+    Fps,
+    Bitrate,
+    QueueMs,
+    Battery,
+    TempC,
+    WifiRssi,
+    CpuUsage,
+    DroppedFrames,
+}
+
+impl TelemetryMetric {
+    fn read(self, telemetry: &WebSocketTelemetryMessage) -> f64 {
+        match self {
+            TelemetryMetric::Fps => telemetry.fps,
+            TelemetryMetric::Bitrate => telemetry.bitrate as f64,
+            TelemetryMetric::QueueMs => telemetry.queue_ms as f64,
+            TelemetryMetric::Battery => telemetry.battery,
+            TelemetryMetric::TempC => telemetry.temp_c,
+            TelemetryMetric::WifiRssi => telemetry.wifi_rssi as f64,
+            TelemetryMetric::CpuUsage => telemetry.cpu_usage,
+            TelemetryMetric::DroppedFrames => telemetry.dropped_frames as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOp {
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Eq,
+    Ne,
+}
+
+impl RuleCondition {
+    fn matches(&self, telemetry: &WebSocketTelemetryMessage) -> bool {
+        let actual = self.metric.read(telemetry);
+        match self.op {
+            ComparisonOp::Gt => actual > self.value,
+            ComparisonOp::Gte => actual >= self.value,
+            ComparisonOp::Lt => actual < self.value,
+            ComparisonOp::Lte => actual <= self.value,
+            ComparisonOp::Eq => (actual - self.value).abs() < f64::EPSILON,
+            ComparisonOp::Ne => (actual - self.value).abs() >= f64::EPSILON,
+        }
+    }
+}
+
+/// Action run through the camera manager on the triggering camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RuleAction {
+    ApplyProfile { profile_name: String },
+    UpdateSettings { settings: CameraSettingsRequest },
+    /// Merge into the persisted stream settings; a live stream is updated in place
+    UpdateStreamSettings {
+        resolution: Option<String>,
+        framerate: Option<u32>,
+        bitrate: Option<u32>,
+        codec: Option<String>,
+    },
+    /// Start with the given settings, or the persisted ones when omitted
+    StartStream { settings: Option<StreamStartRequest> },
+    StopStream,
+    /// `{camera_id}` in title/body is replaced with the triggering camera
+    Notify { title: String, body: String },
+}
+
+impl RuleAction {
+    fn label(&self) -> &'static str {
+        match self {
+            RuleAction::ApplyProfile { .. } => "apply_profile",
+            RuleAction::UpdateSettings { .. } => "update_settings",
+            RuleAction::UpdateStreamSettings { .. } => "update_stream_settings",
+            RuleAction::StartStream { .. } => "start_stream",
+            RuleAction::StopStream => "stop_stream",
+            RuleAction::Notify { .. } => "notify",
+        }
+    }
+}
+
+// MARK: - Execution Log
+
+/// One execution of a rule
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleExecution {
+    pub rule_id: String,
+    pub rule_name: String,
+    pub camera_id: String,
+    pub trigger: RuleTrigger,
+    pub timestamp: DateTime<Utc>,
+    pub actions: Vec<ActionOutcome>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActionOutcome {
+    pub action: String,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RulesPersistence {
+    rules: Vec<AutomationRule>,
+}
+
+// MARK: - Engine
+
+#[derive(Default)]
+struct EngineState {
+    rules: Vec<AutomationRule>,
+    rules_file_path: Option<PathBuf>,
+    // Last execution per (rule_id, camera_id), for cooldowns
+    last_fired: HashMap<(String, String), Instant>,
+    last_telemetry: HashMap<String, WebSocketTelemetryMessage>,
+    log: VecDeque<RuleExecution>,
+}
+
+/// Evaluates automation rules against camera events
+pub struct AutomationEngine {
+    manager: Arc<RwLock<CameraManager>>,
+    state: Arc<RwLock<EngineState>>,
+}
+
+impl AutomationEngine {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            state: Arc::new(RwLock::new(EngineState::default())),
+        }
+    }
+
+    /// Inject where rules are persisted and load them
+    pub async fn set_path_provider(&self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        let path = paths.automation_rules_file()?;
+        let mut state = self.state.write().await;
+
+        if path.exists() {
+            let json = tokio::fs::read_to_string(&path).await
+                .context("Failed to read automation rules file")?;
+            let persistence: RulesPersistence = serde_json::from_str(&json)
+                .context("Failed to deserialize automation rules")?;
+            log::info!("Loaded {} automation rules from {:?}", persistence.rules.len(), path);
+            state.rules = persistence.rules;
+        } else {
+            log::info!("No automation rules file found at {:?}, starting fresh", path);
+        }
+
+        state.rules_file_path = Some(path);
+        Ok(())
+    }
+
+    /// Subscribe to the manager's events and evaluate rules in the background
+    pub async fn start(&self) {
+        let mut events = self.manager.read().await.subscribe();
+        let manager = self.manager.clone();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => handle_event(&manager, &state, event).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Automation engine lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            log::warn!("Automation event loop ended");
+        });
+
+        log::info!("Automation engine started");
+    }
+
+    // MARK: - Rule Management
+
+    pub async fn get_rules(&self) -> Vec<AutomationRule> {
+        self.state.read().await.rules.clone()
+    }
+
+    /// Create or replace a rule (by id), returning the stored rule
+    pub async fn save_rule(&self, mut rule: AutomationRule) -> Result<AutomationRule> {
+        if rule.actions.is_empty() {
+            anyhow::bail!("Rule must have at least one action");
+        }
+
+        let mut state = self.state.write().await;
+
+        if rule.id.is_empty() {
+            rule.id = Uuid::new_v4().to_string();
+        }
+
+        if let Some(existing) = state.rules.iter_mut().find(|r| r.id == rule.id) {
+            *existing = rule.clone();
+            log::info!("Updated automation rule: {}", rule.name);
+        } else {
+            state.rules.push(rule.clone());
+            log::info!("Created automation rule: {}", rule.name);
+        }
+
+        save_rules_to_disk(&state).await?;
+        Ok(rule)
+    }
+
+    pub async fn delete_rule(&self, rule_id: &str) -> Result<()> {
+        let mut state = self.state.write().await;
+
+        let initial_len = state.rules.len();
+        state.rules.retain(|r| r.id != rule_id);
+
+        if state.rules.len() == initial_len {
+            anyhow::bail!("Rule not found: {}", rule_id);
+        }
+
+        state.last_fired.retain(|(id, _), _| id != rule_id);
+        save_rules_to_disk(&state).await?;
+
+        log::info!("Deleted automation rule: {}", rule_id);
+        Ok(())
+    }
+
+    pub async fn set_rule_enabled(&self, rule_id: &str, enabled: bool) -> Result<()> {
+        let mut state = self.state.write().await;
+
+        let rule = state.rules.iter_mut()
+            .find(|r| r.id == rule_id)
+            .ok_or_else(|| anyhow::anyhow!("Rule not found: {}", rule_id))?;
+        rule.enabled = enabled;
+
+        save_rules_to_disk(&state).await
+    }
+
+    /// Most recent executions of this session, newest first
+    pub async fn get_execution_log(&self) -> Vec<RuleExecution> {
+        self.state.read().await.log.iter().rev().cloned().collect()
+    }
+}
+
+async fn save_rules_to_disk(state: &EngineState) -> Result<()> {
+    let Some(path) = &state.rules_file_path else {
+        return Ok(()); // No persistence path set
+    };
+
+    let persistence = RulesPersistence { rules: state.rules.clone() };
+    let json = serde_json::to_string_pretty(&persistence)
+        .context("Failed to serialize automation rules")?;
+
+    tokio::fs::write(path, json).await
+        .context("Failed to write automation rules to disk")?;
+
+    log::info!("Saved {} automation rules to {:?}", persistence.rules.len(), path);
+    Ok(())
+}
+
+// MARK: - Evaluation
+
+async fn handle_event(
+    manager: &Arc<RwLock<CameraManager>>,
+    state: &Arc<RwLock<EngineState>>,
+    event: CameraEvent,
+) {
+    let Some(camera_id) = event.camera_id() else {
+        return;
+    };
+
+    let mut triggers = Vec::new();
+    match &event {
+        CameraEvent::Telemetry { telemetry, .. } => {
+            let previous = state.write().await.last_telemetry
+                .insert(camera_id.clone(), telemetry.clone());
+
+            triggers.push(RuleTrigger::Telemetry);
+
+            let went_idle = previous.is_some_and(|p| p.ndi_state == NdiState::Streaming)
+                && telemetry.ndi_state == NdiState::Idle;
+            if went_idle && manager.read().await.is_stream_requested(&camera_id) {
+                triggers.push(RuleTrigger::NdiIdleUnexpected);
+            }
+        }
+        CameraEvent::ConnectionChanged { state: ConnectionState::Connected, .. } => {
+            triggers.push(RuleTrigger::Connected);
+        }
+        CameraEvent::ConnectionChanged { .. } => triggers.push(RuleTrigger::Disconnected),
+        CameraEvent::Discovered { .. } => triggers.push(RuleTrigger::Discovered),
+        CameraEvent::DiscoveryLost { .. }
+        | CameraEvent::Action { .. }
+        | CameraEvent::Alert { .. }
+        | CameraEvent::Notice { .. } => {}
+    }
+
+    for trigger in triggers {
+        let fired = select_rules(&mut *state.write().await, trigger, &camera_id, Instant::now());
+
+        for rule in fired {
+            let manager = manager.clone();
+            let state = state.clone();
+            let camera_id = camera_id.clone();
+
+            // Actions may take seconds (HTTP timeouts); don't stall the event loop
+            tokio::spawn(async move {
+                execute_rule(&manager, &state, rule, trigger, camera_id).await;
+            });
+        }
+    }
+}
+
+/// Rules matching the trigger whose conditions hold and cooldown elapsed
+/// (marks them as fired)
+fn select_rules(
+    state: &mut EngineState,
+    trigger: RuleTrigger,
+    camera_id: &str,
+    now: Instant,
+) -> Vec<AutomationRule> {
+    let telemetry = state.last_telemetry.get(camera_id).cloned();
+    let mut fired = Vec::new();
+
+    for rule in &state.rules {
+        if !rule.enabled || rule.trigger != trigger {
+            continue;
+        }
+
+        if !rule.camera_ids.is_empty() && !rule.camera_ids.iter().any(|id| id == camera_id) {
+            continue;
+        }
+
+        let conditions_hold = match &telemetry {
+            Some(t) => rule.conditions.iter().all(|c| c.matches(t)),
+            None => rule.conditions.is_empty(),
+        };
+        if !conditions_hold {
+            continue;
+        }
+
+        let key = (rule.id.clone(), camera_id.to_string());
+        let cooling_down = state.last_fired.get(&key)
+            .is_some_and(|last| now.duration_since(*last) < Duration::from_secs(rule.cooldown_s));
+        if cooling_down {
+            continue;
+        }
+
+        fired.push(rule.clone());
+    }
+
+    for rule in &fired {
+        state.last_fired.insert((rule.id.clone(), camera_id.to_string()), now);
+    }
+
+    fired
+}
+
+async fn execute_rule(
+    manager: &Arc<RwLock<CameraManager>>,
+    state: &Arc<RwLock<EngineState>>,
+    rule: AutomationRule,
+    trigger: RuleTrigger,
+    camera_id: String,
+) {
+    log::info!("Automation rule '{}' triggered on {} ({:?})", rule.name, camera_id, trigger);
+
+    let mut outcomes = Vec::new();
+    for action in &rule.actions {
+        let result = run_action(manager, action, &camera_id).await;
+
+        if let Err(e) = &result {
+            log::warn!("Automation action {} failed on {}: {}", action.label(), camera_id, e);
+        }
+
+        outcomes.push(ActionOutcome {
+            action: action.label().to_string(),
+            success: result.is_ok(),
+            error: result.err().map(|e| e.to_string()),
+        });
+    }
+
+    let execution = RuleExecution {
+        rule_id: rule.id,
+        rule_name: rule.name,
+        camera_id,
+        trigger,
+        timestamp: Utc::now(),
+        actions: outcomes,
+    };
+
+    if let Ok(payload) = serde_json::to_value(&execution) {
+        manager.read().await.notifier().emit("automation-rule-executed", payload);
+    }
+
+    let mut state = state.write().await;
+    state.log.push_back(execution);
+    while state.log.len() > MAX_LOG_ENTRIES {
+        state.log.pop_front();
+    }
+}
+
+async fn run_action(
+    manager: &Arc<RwLock<CameraManager>>,
+    action: &RuleAction,
+    camera_id: &str,
+) -> Result<()> {
+    let camera_ids = [camera_id.to_string()];
+
+    // The shared variants only lock the manager around bookkeeping, never across camera I/O
+    match action {
+        RuleAction::ApplyProfile { profile_name } => {
+            let results = CameraManager::apply_profile_via(ManagerMut::Locked(manager), profile_name, &camera_ids, ActionSource::Automation).await?;
+            group_result_to_error(results)
+        }
+        RuleAction::UpdateSettings { settings } => {
+            CameraManager::update_camera_settings_via(ManagerMut::Locked(manager), camera_id, settings.clone(), ActionSource::Automation).await?;
+            Ok(())
+        }
+        RuleAction::UpdateStreamSettings { resolution, framerate, bitrate, codec } => {
+            let mut settings = manager.read().await.stream_settings_or_default(camera_id);
+
+            if let Some(resolution) = resolution {
+                settings.resolution = resolution.clone();
+            }
+            if let Some(framerate) = framerate {
+                settings.framerate = *framerate;
+            }
+            if let Some(bitrate) = bitrate {
+                settings.bitrate = *bitrate;
+            }
+            if let Some(codec) = codec {
+                settings.codec = codec.clone();
+            }
+
+            CameraManager::send_stream_via(ManagerMut::Locked(manager), camera_id, settings, StreamDelivery::Update, ActionSource::Automation).await
+        }
+        RuleAction::StartStream { settings } => {
+            let settings = match settings {
+                Some(settings) => settings.clone(),
+                None => manager.read().await.stream_settings_or_default(camera_id),
+            };
+            CameraManager::send_stream_via(ManagerMut::Locked(manager), camera_id, settings, StreamDelivery::Start, ActionSource::Automation).await
+        }
+        RuleAction::StopStream => CameraManager::stop_stream_via(ManagerRef::Locked(manager), camera_id, ActionSource::Automation).await,
+        RuleAction::Notify { title, body } => {
+            manager.read().await.notifier().notify(
+                &title.replace("{camera_id}", camera_id),
+                &body.replace("{camera_id}", camera_id),
+            );
+            Ok(())
+        }
+    }
+}
+
+fn group_result_to_error(results: Vec<GroupCommandResult>) -> Result<()> {
+    match results.into_iter().find(|r| !r.success) {
+        Some(failed) => Err(anyhow::anyhow!(failed.error.unwrap_or_else(|| "Unknown error".to_string()))),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn telemetry(temp_c: f64, battery: f64) -> WebSocketTelemetryMessage {
+        WebSocketTelemetryMessage { temp_c, battery, ..test_support::telemetry() }
+    }
+
+    fn condition(metric: TelemetryMetric, op: ComparisonOp, value: f64) -> RuleCondition {
+        RuleCondition { metric, op, value }
+    }
+
+    fn rule(id: &str, trigger: RuleTrigger, conditions: Vec<RuleCondition>) -> AutomationRule {
+        AutomationRule {
+            id: id.to_string(),
+            name: id.to_string(),
+            enabled: true,
+            trigger,
+            conditions,
+            actions: vec![RuleAction::StopStream],
+            cooldown_s: 60,
+            camera_ids: Vec::new(),
+        }
+    }
+
+    fn fired(state: &mut EngineState, trigger: RuleTrigger, camera_id: &str, now: Instant) -> Vec<String> {
+        select_rules(state, trigger, camera_id, now).into_iter().map(|r| r.id).collect()
+    }
+
+    #[test]
+    fn conditions_compare_the_telemetry_metric() {
+        use ComparisonOp::*;
+        let sample = telemetry(42.0, 15.0);
+        let cases = [
+            (TelemetryMetric::TempC, Gt, 41.9, true),
+            (TelemetryMetric::TempC, Gt, 42.0, false),
+            (TelemetryMetric::TempC, Gte, 42.0, true),
+            (TelemetryMetric::Battery, Lt, 20.0, true),
+            (TelemetryMetric::Battery, Lte, 14.0, false),
+            (TelemetryMetric::WifiRssi, Eq, -55.0, true),
+            (TelemetryMetric::QueueMs, Ne, 20.0, false),
+            (TelemetryMetric::Bitrate, Gte, 10_000_000.0, true),
+        ];
+        for (metric, op, value, expected) in cases {
+            assert_eq!(condition(metric, op, value).matches(&sample), expected, "{:?} {:?} {}", metric, op, value);
+        }
+    }
+
+    #[test]
+    fn selects_enabled_rules_for_the_trigger_and_camera() {
+        let mut disabled = rule("disabled", RuleTrigger::Connected, Vec::new());
+        disabled.enabled = false;
+        let mut other_camera = rule("other-camera", RuleTrigger::Connected, Vec::new());
+        other_camera.camera_ids = vec!["cam-2".to_string()];
+        let mut this_camera = rule("this-camera", RuleTrigger::Connected, Vec::new());
+        this_camera.camera_ids = vec!["cam-1".to_string()];
+
+        let mut state = EngineState {
+            rules: vec![
+                rule("any", RuleTrigger::Connected, Vec::new()),
+                rule("on-disconnect", RuleTrigger::Disconnected, Vec::new()),
+                disabled,
+                other_camera,
+                this_camera,
+            ],
+            ..Default::default()
+        };
+
+        assert_eq!(fired(&mut state, RuleTrigger::Connected, "cam-1", Instant::now()), vec!["any", "this-camera"]);
+    }
+
+    #[test]
+    fn conditions_need_telemetry_unless_there_are_none() {
+        let hot = vec![condition(TelemetryMetric::TempC, ComparisonOp::Gt, 42.0)];
+        let mut state = EngineState {
+            rules: vec![rule("hot", RuleTrigger::Telemetry, hot), rule("always", RuleTrigger::Telemetry, Vec::new())],
+            ..Default::default()
+        };
+        let start = Instant::now();
+
+        assert_eq!(fired(&mut state, RuleTrigger::Telemetry, "cam-1", start), vec!["always"]);
+
+        state.last_telemetry.insert("cam-1".to_string(), telemetry(40.0, 80.0));
+        assert_eq!(fired(&mut state, RuleTrigger::Telemetry, "cam-1", start + Duration::from_secs(60)), vec!["always"]);
+
+        state.last_telemetry.insert("cam-1".to_string(), telemetry(43.0, 80.0));
+        assert_eq!(fired(&mut state, RuleTrigger::Telemetry, "cam-1", start + Duration::from_secs(120)), vec!["hot", "always"]);
+    }
+
+    #[test]
+    fn cooldown_applies_per_rule_and_camera() {
+        let mut state = EngineState {
+            rules: vec![rule("restart", RuleTrigger::NdiIdleUnexpected, Vec::new())],
+            ..Default::default()
+        };
+        let start = Instant::now();
+        let trigger = RuleTrigger::NdiIdleUnexpected;
+
+        assert_eq!(fired(&mut state, trigger, "cam-1", start), vec!["restart"]);
+        assert!(fired(&mut state, trigger, "cam-1", start + Duration::from_secs(59)).is_empty());
+        assert_eq!(fired(&mut state, trigger, "cam-2", start + Duration::from_secs(59)), vec!["restart"], "other camera");
+        assert_eq!(fired(&mut state, trigger, "cam-1", start + Duration::from_secs(60)), vec!["restart"]);
+    }
+
+    #[tokio::test]
+    async fn new_rules_get_distinct_ids() {
+        let engine = AutomationEngine::new(Arc::new(RwLock::new(CameraManager::new())));
+        let first = engine.save_rule(rule("", RuleTrigger::Connected, Vec::new())).await.unwrap();
+        let second = engine.save_rule(rule("", RuleTrigger::Connected, Vec::new())).await.unwrap();
+
+        assert!(!first.id.is_empty());
+        assert_ne!(first.id, second.id);
+        assert_eq!(engine.get_rules().await.len(), 2);
+    }
+}
@@ -0,0 +1,354 @@
+//! Network bandwidth budget planner
+//!
+//! Each [`LinkBudget`] from the app settings declares the capacity of one
+//! access point (or any group of cameras sharing a link). The planner sums the
+//! configured bitrates of its cameras, compares them with the observed
+//! telemetry and the usable capacity, and recommends a per-camera allocation
+//! that fits. [`CameraManager::group_start_stream`] checks the same budgets
+//! and warns or refuses according to the [`BudgetPolicy`].
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::camera_manager::CameraManager;
+use crate::models::*;
+
+/// Telemetry older than this no longer counts as observed
+const TELEMETRY_MAX_AGE_S: i64 = 10;
+
+/// Recommendations are rounded down to this step
+const ALLOCATION_STEP_BPS: u32 = 100_000;
+
+/// One camera's share of a link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraBandwidth {
+    pub camera_id: String,
+    /// Persisted stream bitrate (or the default)
+    pub configured_bps: u32,
+    /// Bitrate from recent telemetry, if any
+    pub observed_bps: Option<u32>,
+    pub streaming: bool,
+    /// Bitrate that fits the budget when every camera of the link streams
+    pub recommended_bps: u32,
+}
+
+/// Planned vs available bandwidth of one link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BudgetReport {
+    pub name: String,
+    pub capacity_bps: u64,
+    /// Capacity minus the reserve
+    pub usable_bps: u64,
+    /// Configured bitrates of every camera on the link
+    pub planned_bps: u64,
+    /// Configured bitrates of the cameras streaming now
+    pub active_bps: u64,
+    /// Observed bitrates of the cameras streaming now
+    pub observed_bps: u64,
+    /// `usable_bps - planned_bps` (negative when over budget)
+    pub headroom_bps: i64,
+    pub over_budget: bool,
+    pub cameras: Vec<CameraBandwidth>,
+}
+
+/// A link whose planned total would exceed its budget on group start
+#[derive(Debug, Clone)]
+pub(crate) struct BudgetOverrun {
+    pub budget: String,
+    pub projected_bps: u64,
+    pub usable_bps: u64,
+    /// Cameras of the link being started
+    pub starting: Vec<String>,
+}
+
+impl BudgetOverrun {
+    pub fn message(&self) -> String {
+        format!(
+            "'{}' would carry {:.1} Mb/s, over its {:.1} Mb/s budget",
+            self.budget, self.projected_bps as f64 / 1e6, self.usable_bps as f64 / 1e6,
+        )
+    }
+}
+
+/// Scale bitrates down proportionally so their sum fits `usable_bps`
+pub fn recommend_allocation(configured: &[u32], usable_bps: u64) -> Vec<u32> {
+    let total: u64 = configured.iter().map(|&bps| bps as u64).sum();
+    if total <= usable_bps {
+        return configured.to_vec();
+    }
+
+    let scale = usable_bps as f64 / total as f64;
+    configured.iter()
+        .map(|&bps| {
+            let share = (bps as f64 * scale) as u32;
+            // Round down, but never a small share to zero
+            (share / ALLOCATION_STEP_BPS * ALLOCATION_STEP_BPS).max(share.min(ALLOCATION_STEP_BPS))
+        })
+        .collect()
+}
+
+impl CameraManager {
+    /// Planned, active and observed bandwidth of every declared link
+    pub async fn plan_bandwidth(&self) -> Result<Vec<BudgetReport>> {
+        let settings = self.get_app_settings().await?;
+
+        let reports = settings.link_budgets.iter()
+            .map(|budget| {
+                let configured: Vec<u32> = budget.camera_ids.iter()
+                    .map(|camera_id| self.stream_settings_or_default(camera_id).bitrate)
+                    .collect();
+                let recommended = recommend_allocation(&configured, budget.usable_bps());
+
+                let cameras: Vec<CameraBandwidth> = budget.camera_ids.iter()
+                    .zip(configured.iter().zip(recommended))
+                    .map(|(camera_id, (&configured_bps, recommended_bps))| {
+                        let observed = self.observed_telemetry(camera_id);
+                        let streaming = self.is_stream_requested(camera_id)
+                            || observed.as_ref().is_some_and(|t| t.ndi_state == NdiState::Streaming);
+                        CameraBandwidth {
+                            camera_id: camera_id.clone(),
+                            configured_bps,
+                            observed_bps: observed.filter(|t| t.ndi_state == NdiState::Streaming).map(|t| t.bitrate),
+                            streaming,
+                            recommended_bps,
+                        }
+                    })
+                    .collect();
+
+                let usable_bps = budget.usable_bps();
+                let planned_bps: u64 = cameras.iter().map(|c| c.configured_bps as u64).sum();
+                BudgetReport {
+                    name: budget.name.clone(),
+                    capacity_bps: budget.capacity_bps,
+                    usable_bps,
+                    planned_bps,
+                    active_bps: cameras.iter().filter(|c| c.streaming).map(|c| c.configured_bps as u64).sum(),
+                    observed_bps: cameras.iter().filter_map(|c| c.observed_bps).map(u64::from).sum(),
+                    headroom_bps: usable_bps as i64 - planned_bps as i64,
+                    over_budget: planned_bps > usable_bps,
+                    cameras,
+                }
+            })
+            .collect();
+
+        Ok(reports)
+    }
+
+    /// Links that would go over budget once `starting` cameras stream with the given settings
+    pub(crate) async fn check_bandwidth_budgets(
+        &self,
+        starting: &HashMap<String, StreamStartRequest>,
+    ) -> (BudgetPolicy, Vec<BudgetOverrun>) {
+        let settings = match self.get_app_settings().await {
+            Ok(settings) => settings,
+            Err(e) => {
+                log::warn!("Bandwidth budgets not checked: {}", e);
+                return (BudgetPolicy::default(), Vec::new());
+            }
+        };
+
+        let overruns = find_overruns(&settings.link_budgets, starting, |camera_id| {
+            self.is_stream_requested(camera_id).then(|| self.stream_settings_or_default(camera_id).bitrate)
+        });
+
+        (settings.budget_policy, overruns)
+    }
+
+    /// Warn on (or leave out, per the [`BudgetPolicy`]) cameras whose start would
+    /// put their link over budget. Refused cameras are removed from `requests`.
+    pub(crate) async fn enforce_bandwidth_budgets(
+        &self,
+        requests: &mut HashMap<String, StreamStartRequest>,
+    ) -> (HashMap<String, Vec<FieldIssue>>, Vec<GroupCommandResult>) {
+        let (policy, overruns) = self.check_bandwidth_budgets(requests).await;
+        apply_budget_policy(policy, overruns, requests)
+    }
+
+    fn observed_telemetry(&self, camera_id: &str) -> Option<WebSocketTelemetryMessage> {
+        let (telemetry, at) = self.metrics().latest_telemetry(camera_id)?;
+        ((Utc::now() - at).num_seconds() <= TELEMETRY_MAX_AGE_S).then_some(telemetry)
+    }
+}
+
+/// Links that would go over budget once `starting` cameras stream with the
+/// given settings; `running` is the bitrate of a camera already streaming
+fn find_overruns(
+    budgets: &[LinkBudget],
+    starting: &HashMap<String, StreamStartRequest>,
+    running: impl Fn(&str) -> Option<u32>,
+) -> Vec<BudgetOverrun> {
+    budgets.iter()
+        .filter_map(|budget| {
+            let starting_ids: Vec<String> = budget.camera_ids.iter()
+                .filter(|id| starting.contains_key(*id))
+                .cloned()
+                .collect();
+            if starting_ids.is_empty() {
+                return None;
+            }
+
+            let projected_bps: u64 = budget.camera_ids.iter()
+                .map(|camera_id| match starting.get(camera_id) {
+                    Some(request) => request.bitrate as u64,
+                    None => running(camera_id).unwrap_or(0) as u64,
+                })
+                .sum();
+
+            (projected_bps > budget.usable_bps()).then(|| BudgetOverrun {
+                budget: budget.name.clone(),
+                projected_bps,
+                usable_bps: budget.usable_bps(),
+                starting: starting_ids,
+            })
+        })
+        .collect()
+}
+
+/// Warnings per camera under [`BudgetPolicy::Warn`]; under
+/// [`BudgetPolicy::Refuse`] the starting cameras of each overrun are removed
+/// from `requests` and returned as failed results
+fn apply_budget_policy(
+    policy: BudgetPolicy,
+    overruns: Vec<BudgetOverrun>,
+    requests: &mut HashMap<String, StreamStartRequest>,
+) -> (HashMap<String, Vec<FieldIssue>>, Vec<GroupCommandResult>) {
+    let mut warnings: HashMap<String, Vec<FieldIssue>> = HashMap::new();
+    let mut refused = Vec::new();
+
+    for overrun in overruns {
+        log::warn!("Bandwidth budget exceeded: {}", overrun.message());
+        for camera_id in &overrun.starting {
+            let mut issue = FieldIssue {
+                field: "bitrate".to_string(),
+                severity: IssueSeverity::Warning,
+                message: overrun.message(),
+            };
+
+            if policy == BudgetPolicy::Warn {
+                warnings.entry(camera_id.clone()).or_default().push(issue);
+            } else if requests.remove(camera_id).is_some() {
+                issue.severity = IssueSeverity::Error;
+                warnings.remove(camera_id);
+                refused.push(GroupCommandResult {
+                    camera_id: camera_id.clone(),
+                    success: false,
+                    error: Some(format!("Bandwidth budget: {}", issue.message)),
+                    shutter_adjustment: None,
+                    issues: vec![issue],
+                });
+            }
+        }
+    }
+
+    (warnings, refused)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBPS: u32 = 1_000_000;
+
+    fn budget(name: &str, capacity_bps: u64, camera_ids: &[&str]) -> LinkBudget {
+        LinkBudget {
+            name: name.to_string(),
+            capacity_bps,
+            camera_ids: camera_ids.iter().map(|id| id.to_string()).collect(),
+            reserve_ratio: 0.25,
+        }
+    }
+
+    fn starting(cameras: &[(&str, u32)]) -> HashMap<String, StreamStartRequest> {
+        cameras.iter()
+            .map(|(id, bitrate)| (id.to_string(), StreamStartRequest { bitrate: *bitrate, ..Default::default() }))
+            .collect()
+    }
+
+    fn overrun(starting: &[&str]) -> BudgetOverrun {
+        BudgetOverrun {
+            budget: "AP".to_string(),
+            projected_bps: 30_000_000,
+            usable_bps: 22_500_000,
+            starting: starting.iter().map(|id| id.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn allocation_under_budget_keeps_configured_bitrates() {
+        let configured = [10 * MBPS, 8 * MBPS];
+        assert_eq!(recommend_allocation(&configured, 18_000_000), configured.to_vec());
+        assert_eq!(recommend_allocation(&configured, 40_000_000), configured.to_vec());
+    }
+
+    #[test]
+    fn allocation_scales_proportionally_and_rounds_down_to_the_step() {
+        let recommended = recommend_allocation(&[10 * MBPS, 8 * MBPS, 6 * MBPS], 15_000_000);
+
+        // 0.625 of each, rounded down to 100 kb/s
+        assert_eq!(recommended, vec![6_200_000, 5_000_000, 3_700_000]);
+        assert!(recommended.iter().map(|&bps| bps as u64).sum::<u64>() <= 15_000_000);
+    }
+
+    #[test]
+    fn allocation_with_a_tiny_or_zero_budget() {
+        // Shares below one step are kept rather than rounded to zero
+        assert_eq!(recommend_allocation(&[10 * MBPS, 10 * MBPS], 150_000), vec![75_000, 75_000]);
+        assert_eq!(recommend_allocation(&[10 * MBPS, 6 * MBPS], 0), vec![0, 0]);
+        assert_eq!(recommend_allocation(&[], 0), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn overruns_count_starting_and_running_cameras_against_the_usable_capacity() {
+        // 30 Mb/s link, 25 % reserve: 22.5 Mb/s usable
+        let budgets = [budget("AP", 30_000_000, &["a", "b", "c"]), budget("Other", 30_000_000, &["d"])];
+        let running = |camera_id: &str| (camera_id == "c").then_some(10 * MBPS);
+
+        assert!(find_overruns(&budgets, &starting(&[("a", 10 * MBPS), ("b", 2 * MBPS)]), |_| None).is_empty());
+
+        let overruns = find_overruns(&budgets, &starting(&[("a", 10 * MBPS), ("b", 5 * MBPS)]), running);
+        assert_eq!(overruns.len(), 1);
+        assert_eq!(overruns[0].budget, "AP");
+        assert_eq!(overruns[0].projected_bps, 25_000_000);
+        assert_eq!(overruns[0].usable_bps, 22_500_000);
+        assert_eq!(overruns[0].starting, vec!["a", "b"]);
+
+        // Links none of the starting cameras are on are not checked
+        assert!(find_overruns(&budgets, &starting(&[("d", 20 * MBPS)]), running).is_empty());
+    }
+
+    #[test]
+    fn warn_policy_starts_everyone_with_a_warning() {
+        let mut requests = starting(&[("a", 10 * MBPS), ("b", 10 * MBPS), ("x", 10 * MBPS)]);
+        let (warnings, refused) = apply_budget_policy(BudgetPolicy::Warn, vec![overrun(&["a", "b"])], &mut requests);
+
+        assert!(refused.is_empty());
+        assert_eq!(requests.len(), 3);
+        assert_eq!(warnings.len(), 2);
+        assert_eq!(warnings["a"][0].severity, IssueSeverity::Warning);
+        assert_eq!(warnings["a"][0].field, "bitrate");
+    }
+
+    #[test]
+    fn refuse_policy_leaves_the_links_starting_cameras_out() {
+        let mut requests = starting(&[("a", 10 * MBPS), ("b", 10 * MBPS), ("x", 10 * MBPS)]);
+        let (warnings, mut refused) = apply_budget_policy(BudgetPolicy::Refuse, vec![overrun(&["a", "b"])], &mut requests);
+
+        assert!(warnings.is_empty());
+        assert_eq!(requests.keys().collect::<Vec<_>>(), vec!["x"]);
+        refused.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        assert_eq!(refused.iter().map(|r| r.camera_id.as_str()).collect::<Vec<_>>(), vec!["a", "b"]);
+        assert!(refused.iter().all(|r| !r.success && r.issues[0].severity == IssueSeverity::Error));
+        assert!(refused[0].error.as_deref().unwrap().starts_with("Bandwidth budget: 'AP'"));
+    }
+
+    #[test]
+    fn a_camera_on_two_overrun_links_is_refused_once() {
+        let mut requests = starting(&[("a", 10 * MBPS)]);
+        let (_, refused) = apply_budget_policy(BudgetPolicy::Refuse, vec![overrun(&["a"]), overrun(&["a"])], &mut requests);
+
+        assert_eq!(refused.len(), 1);
+        assert!(requests.is_empty());
+    }
+}
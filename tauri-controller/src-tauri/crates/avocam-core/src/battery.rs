@@ -0,0 +1,331 @@
+//! Battery runtime prediction
+//!
+//! Fed with every telemetry sample, the predictor fits the battery slope over a
+//! sliding window, separately for each [`BatteryRegime`] (streaming vs idle,
+//! charging vs unplugged). The slope of the current regime gives the minutes
+//! left and the predicted empty time shown in [`CameraInfo`]. Slopes learned
+//! earlier are kept, so a camera that starts streaming is predicted with its
+//! streaming drain right away. When an unplugged camera is predicted to die
+//! before the configured show end, the operator is notified once.
+
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use crate::models::*;
+use crate::platform::{LogNotifier, Notifier};
+
+/// Samples older than this are dropped from the slope fit
+const WINDOW_MINUTES: i64 = 15;
+
+/// Shortest window that yields a slope (battery levels move in 1% steps)
+const MIN_SPAN_MINUTES: f64 = 3.0;
+
+/// Weight of a new fit in a regime's learned slope
+const SLOPE_SMOOTHING: f64 = 0.3;
+
+#[derive(Default)]
+struct CameraBattery {
+    regime: Option<BatteryRegime>,
+    level: f64,
+    charging_state: Option<ChargingState>,
+    // (time, level) since the regime last changed
+    samples: VecDeque<(DateTime<Utc>, f64)>,
+    // Learned level change per minute, per regime
+    slopes: HashMap<BatteryRegime, f64>,
+    alerted: bool,
+}
+
+impl CameraBattery {
+    /// Least-squares slope of the window, in level per minute
+    fn fit(&self) -> Option<f64> {
+        let (first, _) = self.samples.front()?;
+        let (last, _) = self.samples.back()?;
+        if ((*last - *first).num_seconds() as f64 / 60.0) < MIN_SPAN_MINUTES {
+            return None;
+        }
+
+        let points: Vec<(f64, f64)> = self.samples.iter()
+            .map(|(at, level)| ((*at - *first).num_milliseconds() as f64 / 60_000.0, *level))
+            .collect();
+        let n = points.len() as f64;
+        let mean_t = points.iter().map(|(t, _)| t).sum::<f64>() / n;
+        let mean_level = points.iter().map(|(_, level)| level).sum::<f64>() / n;
+        let covariance: f64 = points.iter().map(|(t, level)| (t - mean_t) * (level - mean_level)).sum();
+        let variance: f64 = points.iter().map(|(t, _)| (t - mean_t).powi(2)).sum();
+
+        (variance > 0.0).then(|| covariance / variance)
+    }
+}
+
+/// Per-camera battery models, shared with the telemetry callbacks
+pub struct BatteryPredictor {
+    cameras: Mutex<HashMap<String, CameraBattery>>,
+    show_end: Mutex<Option<DateTime<Utc>>>,
+    notifier: Mutex<Arc<dyn Notifier>>,
+}
+
+impl Default for BatteryPredictor {
+    fn default() -> Self {
+        Self {
+            cameras: Mutex::new(HashMap::new()),
+            show_end: Mutex::new(None),
+            notifier: Mutex::new(Arc::new(LogNotifier)),
+        }
+    }
+}
+
+impl BatteryPredictor {
+    pub fn set_notifier(&self, notifier: Arc<dyn Notifier>) {
+        *self.notifier.lock().unwrap() = notifier;
+    }
+
+    /// Show end time cameras are checked against (re-arms the alerts)
+    pub fn set_show_end(&self, show_end: Option<DateTime<Utc>>) {
+        *self.show_end.lock().unwrap() = show_end;
+        for camera in self.cameras.lock().unwrap().values_mut() {
+            camera.alerted = false;
+        }
+    }
+
+    pub fn observe(&self, camera_id: &str, alias: &str, telemetry: &WebSocketTelemetryMessage) {
+        self.observe_at(camera_id, alias, telemetry, Utc::now());
+    }
+
+    fn observe_at(&self, camera_id: &str, alias: &str, telemetry: &WebSocketTelemetryMessage, now: DateTime<Utc>) {
+        let regime = BatteryRegime::from_telemetry(telemetry);
+
+        let prediction = {
+            let mut cameras = self.cameras.lock().unwrap();
+            let camera = cameras.entry(camera_id.to_string()).or_default();
+
+            if camera.regime != Some(regime) {
+                camera.samples.clear();
+                camera.regime = Some(regime);
+            }
+            camera.level = telemetry.battery;
+            camera.charging_state = Some(telemetry.charging_state);
+            camera.samples.push_back((now, telemetry.battery));
+            while camera.samples.front().is_some_and(|(at, _)| now - *at > Duration::minutes(WINDOW_MINUTES)) {
+                camera.samples.pop_front();
+            }
+
+            if let Some(slope) = camera.fit() {
+                let learned = camera.slopes.entry(regime).or_insert(slope);
+                *learned += SLOPE_SMOOTHING * (slope - *learned);
+            }
+
+            let show_end = *self.show_end.lock().unwrap();
+            let prediction = predict(camera, show_end, now);
+            let dies_early = prediction.as_ref().is_some_and(|p| p.dies_before_show_end);
+
+            // Alert once per show end, re-armed when the camera is no longer predicted to die early
+            if dies_early && !camera.alerted {
+                camera.alerted = true;
+                prediction
+            } else {
+                if !dies_early {
+                    camera.alerted = false;
+                }
+                None
+            }
+        };
+
+        if let Some(prediction) = prediction {
+            let notifier = self.notifier.lock().unwrap().clone();
+            let empty_at = prediction.predicted_empty_at
+                .map(|at| at.with_timezone(&chrono::Local).format("%H:%M").to_string())
+                .unwrap_or_default();
+            notifier.notify(
+                "Battery Warning",
+                &format!("{} is predicted to run out of battery at {}, before the end of the show", alias, empty_at),
+            );
+            notifier.emit("battery-alert", serde_json::json!({
+                "camera_id": camera_id,
+                "prediction": prediction,
+            }));
+        }
+    }
+
+    /// Current prediction of a camera, if telemetry has been seen
+    pub fn prediction(&self, camera_id: &str) -> Option<BatteryRuntime> {
+        let cameras = self.cameras.lock().unwrap();
+        let camera = cameras.get(camera_id)?;
+        predict(camera, *self.show_end.lock().unwrap(), Utc::now())
+    }
+
+    pub fn forget_camera(&self, camera_id: &str) {
+        self.cameras.lock().unwrap().remove(camera_id);
+    }
+}
+
+fn predict(camera: &CameraBattery, show_end: Option<DateTime<Utc>>, now: DateTime<Utc>) -> Option<BatteryRuntime> {
+    let regime = camera.regime?;
+    let slope = camera.slopes.get(&regime).copied();
+
+    // Only a draining battery runs out
+    let minutes_left = slope
+        .filter(|&slope| slope < 0.0)
+        .map(|slope| camera.level / -slope);
+    let predicted_empty_at = minutes_left.map(|minutes| now + Duration::seconds((minutes * 60.0) as i64));
+
+    Some(BatteryRuntime {
+        level: camera.level,
+        charging_state: camera.charging_state,
+        regime,
+        drain_per_hour: slope.map(|slope| -slope * 60.0),
+        minutes_left,
+        predicted_empty_at,
+        dies_before_show_end: matches!((predicted_empty_at, show_end), (Some(empty), Some(end)) if empty < end && end > now),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        alerts: Mutex<Vec<String>>,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, _title: &str, body: &str) {
+            self.alerts.lock().unwrap().push(body.to_string());
+        }
+    }
+
+    fn telemetry(battery: f64, streaming: bool, charging_state: ChargingState) -> WebSocketTelemetryMessage {
+        WebSocketTelemetryMessage {
+            battery,
+            ndi_state: if streaming { NdiState::Streaming } else { NdiState::Idle },
+            charging_state,
+            ..test_support::telemetry()
+        }
+    }
+
+    /// One sample every 30 s for `minutes`, the level moving by `per_minute`;
+    /// returns the time and level after the last sample
+    fn feed(
+        predictor: &BatteryPredictor,
+        start: DateTime<Utc>,
+        level: f64,
+        per_minute: f64,
+        minutes: i64,
+        streaming: bool,
+        charging_state: ChargingState,
+    ) -> (DateTime<Utc>, f64) {
+        let mut at = start;
+        let mut level = level;
+        for step in 0..=minutes * 2 {
+            at = start + Duration::seconds(step * 30);
+            if step > 0 {
+                level += per_minute / 2.0;
+            }
+            predictor.observe_at("cam", "Cam 1", &telemetry(level, streaming, charging_state), at);
+        }
+        (at, level)
+    }
+
+    fn prediction_at(predictor: &BatteryPredictor, now: DateTime<Utc>) -> Option<BatteryRuntime> {
+        let cameras = predictor.cameras.lock().unwrap();
+        predict(&cameras["cam"], *predictor.show_end.lock().unwrap(), now)
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn no_slope_before_the_minimum_span() {
+        let predictor = BatteryPredictor::default();
+        let start = Utc::now();
+        let (at, _) = feed(&predictor, start, 0.8, -0.01, 2, true, ChargingState::Unplugged);
+
+        let prediction = prediction_at(&predictor, at).unwrap();
+        assert_eq!(prediction.drain_per_hour, None);
+        assert_eq!(prediction.minutes_left, None);
+    }
+
+    #[test]
+    fn draining_battery_predicts_minutes_left() {
+        let predictor = BatteryPredictor::default();
+        let start = Utc::now();
+        let (at, level) = feed(&predictor, start, 0.8, -0.01, 10, true, ChargingState::Unplugged);
+
+        let prediction = prediction_at(&predictor, at).unwrap();
+        assert_eq!(prediction.regime, BatteryRegime::StreamingUnplugged);
+        assert_close(prediction.drain_per_hour.unwrap(), 0.6);
+        assert_close(prediction.minutes_left.unwrap(), level / 0.01);
+        let empty_at = prediction.predicted_empty_at.unwrap();
+        assert!((empty_at - (at + Duration::minutes((level * 100.0).round() as i64))).num_seconds().abs() <= 1);
+    }
+
+    #[test]
+    fn charging_battery_never_runs_out() {
+        let predictor = BatteryPredictor::default();
+        predictor.set_show_end(Some(Utc::now() + Duration::hours(8)));
+        let start = Utc::now();
+        let (at, _) = feed(&predictor, start, 0.3, 0.005, 10, true, ChargingState::Charging);
+
+        let prediction = prediction_at(&predictor, at).unwrap();
+        assert_eq!(prediction.regime, BatteryRegime::StreamingCharging);
+        assert!(prediction.drain_per_hour.unwrap() < 0.0);
+        assert_eq!(prediction.minutes_left, None);
+        assert!(!prediction.dies_before_show_end);
+    }
+
+    #[test]
+    fn regime_change_uses_the_slope_learned_for_that_regime() {
+        let predictor = BatteryPredictor::default();
+        let start = Utc::now();
+
+        let (at, level) = feed(&predictor, start, 0.9, -0.002, 10, false, ChargingState::Unplugged);
+        let (at, level) = feed(&predictor, at + Duration::seconds(30), level, -0.02, 10, true, ChargingState::Unplugged);
+        assert_close(prediction_at(&predictor, at).unwrap().drain_per_hour.unwrap(), 1.2);
+
+        // Back to idle: predicted with the idle drain from the first sample on
+        let back = at + Duration::seconds(30);
+        predictor.observe_at("cam", "Cam 1", &telemetry(level, false, ChargingState::Unplugged), back);
+        let prediction = prediction_at(&predictor, back).unwrap();
+        assert_eq!(prediction.regime, BatteryRegime::IdleUnplugged);
+        assert_close(prediction.drain_per_hour.unwrap(), 0.12);
+    }
+
+    #[test]
+    fn alerts_once_per_show_end() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let predictor = BatteryPredictor::default();
+        predictor.set_notifier(notifier.clone());
+        let start = Utc::now();
+        predictor.set_show_end(Some(start + Duration::hours(3)));
+
+        // 1 %/min from 80 %: empty well before the show ends
+        let (at, level) = feed(&predictor, start, 0.8, -0.01, 10, true, ChargingState::Unplugged);
+        assert_eq!(notifier.alerts.lock().unwrap().len(), 1);
+        assert!(notifier.alerts.lock().unwrap()[0].starts_with("Cam 1 is predicted to run out"));
+
+        // A new show end re-arms the alert
+        predictor.set_show_end(Some(start + Duration::hours(4)));
+        feed(&predictor, at + Duration::seconds(30), level, -0.01, 4, true, ChargingState::Unplugged);
+        assert_eq!(notifier.alerts.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn alert_rearms_once_the_camera_is_no_longer_predicted_to_die_early() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let predictor = BatteryPredictor::default();
+        predictor.set_notifier(notifier.clone());
+        let start = Utc::now();
+        predictor.set_show_end(Some(start + Duration::hours(3)));
+
+        let (at, level) = feed(&predictor, start, 0.8, -0.01, 10, true, ChargingState::Unplugged);
+        let (at, level) = feed(&predictor, at + Duration::seconds(30), level, 0.01, 5, true, ChargingState::Charging);
+        assert_eq!(notifier.alerts.lock().unwrap().len(), 1);
+
+        // Unplugged again: the learned drain predicts an early death right away
+        feed(&predictor, at + Duration::seconds(30), level, -0.01, 1, true, ChargingState::Unplugged);
+        assert_eq!(notifier.alerts.lock().unwrap().len(), 2);
+    }
+}
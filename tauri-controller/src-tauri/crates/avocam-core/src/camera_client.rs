@@ -0,0 +1,600 @@
+//! HTTP and WebSocket client for camera communication
+
+use anyhow::{Context, Result};
+use reqwest::Client;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::{mpsc, RwLock};
+use tokio_tungstenite::{connect_async, connect_async_tls_with_config, tungstenite::Message, Connector};
+use futures_util::{SinkExt, StreamExt};
+
+use crate::latency::{LatencyChannel, LatencyMonitor};
+use crate::metrics::ControllerMetrics;
+use crate::models::*;
+use crate::tls::{self, CertificatePin};
+
+const HTTP_TIMEOUT: Duration = Duration::from_secs(5);
+const WS_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_ATTEMPTS: u32 = 1000; // Very high limit for production use
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30); // Cap backoff at 30s
+const WS_PING_INTERVAL: Duration = Duration::from_secs(5);
+const LOG_DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(120); // Log archives can be tens of MB
+
+/// Client for a single camera's HTTP control API and WebSocket telemetry feed
+pub struct CameraClient {
+    id: String,
+    base_url: String,
+    token: String,
+    http_client: Client,
+    ws_stop_tx: Option<mpsc::UnboundedSender<()>>, // Channel to stop WebSocket reconnection
+    connected: Arc<RwLock<bool>>,
+    metrics: Option<Arc<ControllerMetrics>>,
+    latency: Option<Arc<LatencyMonitor>>,
+    // Set for https cameras
+    tls: Option<(Arc<CertificatePin>, Arc<rustls::ClientConfig>)>,
+}
+
+impl CameraClient {
+    /// Create a client for `http://<ip>:<port>` authenticated with a bearer token
+    /// (an empty token disables the `Authorization` header)
+    pub fn new(ip: String, port: u16, token: String) -> Self {
+        let base_url = format!("http://{}:{}", ip, port);
+
+        let http_client = Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .build()
+            .expect("Failed to create HTTP client");
+
+        Self {
+            id: format!("{}:{}", ip, port),
+            base_url,
+            token,
+            http_client,
+            ws_stop_tx: None,
+            connected: Arc::new(RwLock::new(false)),
+            metrics: None,
+            latency: None,
+            tls: None,
+        }
+    }
+
+    /// Switch to `https://`/`wss://`, accepting only the certificate with the
+    /// `pinned` fingerprint (or, when None, the first one seen)
+    pub fn with_tls(mut self, pinned: Option<String>) -> Self {
+        let pin = Arc::new(CertificatePin::new(&self.id, pinned));
+        let config = tls::pinned_client_config(pin.clone());
+
+        self.base_url = self.base_url.replacen("http://", "https://", 1);
+        self.http_client = Client::builder()
+            .timeout(HTTP_TIMEOUT)
+            .use_preconfigured_tls(config.clone())
+            .build()
+            .expect("Failed to create HTTP client");
+        self.tls = Some((pin, Arc::new(config)));
+        self
+    }
+
+    /// Fingerprint of the pinned certificate, once a TLS handshake succeeded
+    pub fn cert_fingerprint(&self) -> Option<String> {
+        self.tls.as_ref().and_then(|(pin, _)| pin.pinned())
+    }
+
+    /// Count failed HTTP calls in the controller metrics
+    pub fn with_metrics(mut self, metrics: Arc<ControllerMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Time HTTP calls and WebSocket pings into the latency monitor
+    pub fn with_latency(mut self, latency: Arc<LatencyMonitor>) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    // MARK: - HTTP Requests
+
+    async fn get(&self, path: &str) -> Result<reqwest::Response> {
+        let started = Instant::now();
+        let result = self.get_with_timeout(path, HTTP_TIMEOUT).await;
+        self.track_latency(&result, started);
+        result
+    }
+
+    async fn get_with_timeout(&self, path: &str, timeout: Duration) -> Result<reqwest::Response> {
+        let mut request = self.http_client
+            .get(format!("{}{}", self.base_url, path))
+            .timeout(timeout);
+
+        // Only add Authorization header if token is not empty
+        if !self.token.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.token));
+        }
+
+        let result = request.send().await;
+        self.track_errors(&result);
+        self.check_certificate(&result)?;
+        result.context("HTTP GET request failed")
+    }
+
+    async fn post<T: serde::Serialize>(&self, path: &str, body: &T) -> Result<reqwest::Response> {
+        let mut request = self.http_client
+            .post(format!("{}{}", self.base_url, path))
+            .json(body);
+
+        // Only add Authorization header if token is not empty
+        if !self.token.is_empty() {
+            request = request.header("Authorization", format!("Bearer {}", self.token));
+        }
+
+        let started = Instant::now();
+        let result = request.send().await;
+        self.track_errors(&result);
+        self.check_certificate(&result)?;
+        let result = result.context("HTTP POST request failed");
+        self.track_latency(&result, started);
+        result
+    }
+
+    /// Surface a pinning failure as [`tls::CertificateMismatch`] rather than a transport error
+    fn check_certificate(&self, result: &reqwest::Result<reqwest::Response>) -> Result<()> {
+        if let (Some((pin, _)), Err(_)) = (&self.tls, result) {
+            if let Some(mismatch) = pin.take_mismatch() {
+                return Err(mismatch.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// Time to response headers; failed calls carry no round trip
+    fn track_latency(&self, result: &Result<reqwest::Response>, started: Instant) {
+        if let (Some(latency), Ok(_)) = (&self.latency, result) {
+            latency.observe_rtt(&self.id, LatencyChannel::Http, started.elapsed());
+        }
+    }
+
+    fn track_errors(&self, result: &reqwest::Result<reqwest::Response>) {
+        let Some(metrics) = &self.metrics else {
+            return;
+        };
+
+        match result {
+            Ok(response) if !response.status().is_success() => {
+                metrics.record_http_error(&self.id, response.status().as_str());
+            }
+            Ok(_) => {}
+            Err(e) if e.is_timeout() => metrics.record_http_error(&self.id, "timeout"),
+            Err(e) if e.is_connect() => metrics.record_http_error(&self.id, "connect"),
+            Err(_) => metrics.record_http_error(&self.id, "transport"),
+        }
+    }
+
+    // MARK: - API Methods
+
+    /// `GET /api/v1/status`
+    pub async fn get_status(&self) -> Result<StatusResponse> {
+        let response = self.get("/api/v1/status").await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse status response")
+    }
+
+    /// `GET /api/v1/capabilities`
+    pub async fn get_capabilities(&self) -> Result<Vec<Capability>> {
+        let response = self.get("/api/v1/capabilities").await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse capabilities response")
+    }
+
+    /// `POST /api/v1/stream/start`
+    pub async fn start_stream(&self, request: StreamStartRequest) -> Result<()> {
+        let response = self.post("/api/v1/stream/start", &request).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        Ok(())
+    }
+
+    /// `POST /api/v1/stream/stop`
+    pub async fn stop_stream(&self) -> Result<()> {
+        let response = self.post("/api/v1/stream/stop", &()).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        Ok(())
+    }
+
+    /// `POST /api/v1/stream/update`; the app refuses `stream/start` while streaming
+    pub async fn update_stream(&self, request: StreamUpdateRequest) -> Result<()> {
+        let response = self.post("/api/v1/stream/update", &request).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        Ok(())
+    }
+
+    /// `POST /api/v1/camera`
+    pub async fn update_camera_settings(&self, settings: CameraSettingsRequest) -> Result<()> {
+        let response = self.post("/api/v1/camera", &settings).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        Ok(())
+    }
+
+    /// `POST /api/v1/camera/wb/measure`
+    pub async fn measure_white_balance(&self) -> Result<WhiteBalanceMeasureResponse> {
+        let response = self.post("/api/v1/camera/wb/measure", &()).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse white balance measure response")
+    }
+
+    /// `GET /api/v1/diag`
+    pub async fn get_diagnostics(&self) -> Result<DiagnosticsResponse> {
+        let response = self.get("/api/v1/diag").await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse diagnostics response")
+    }
+
+    /// `GET /api/v1/logs.zip`, streamed into `dir` as `logs-<host>_<port>-<timestamp>.zip`
+    ///
+    /// The archive is written to a `.part` file first, so an interrupted download
+    /// never leaves a truncated zip behind. Returns the final path and its size.
+    pub async fn download_logs(&self, dir: &Path) -> Result<(PathBuf, u64)> {
+        let mut response = self.get_with_timeout("/api/v1/logs.zip", LOG_DOWNLOAD_TIMEOUT).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        tokio::fs::create_dir_all(dir).await
+            .with_context(|| format!("Failed to create {}", dir.display()))?;
+
+        let file_name = format!(
+            "logs-{}-{}.zip",
+            self.id.replace(':', "_"),
+            chrono::Utc::now().format("%Y%m%d-%H%M%S"),
+        );
+        let path = dir.join(file_name);
+        let partial = path.with_extension("zip.part");
+
+        let result: Result<u64> = async {
+            let mut file = tokio::fs::File::create(&partial).await
+                .with_context(|| format!("Failed to create {}", partial.display()))?;
+            let mut bytes = 0u64;
+            while let Some(chunk) = response.chunk().await.context("Log download interrupted")? {
+                file.write_all(&chunk).await.context("Failed to write log archive")?;
+                bytes += chunk.len() as u64;
+            }
+            file.flush().await.context("Failed to write log archive")?;
+            Ok(bytes)
+        }.await;
+
+        match result {
+            Ok(bytes) => {
+                tokio::fs::rename(&partial, &path).await
+                    .with_context(|| format!("Failed to move log archive to {}", path.display()))?;
+                Ok((path, bytes))
+            }
+            Err(e) => {
+                let _ = tokio::fs::remove_file(&partial).await;
+                Err(e)
+            }
+        }
+    }
+
+    // MARK: - Pairing
+
+    /// `POST /api/v1/pair/request`; the phone starts showing a PIN or waits for the QR scan
+    pub async fn request_pairing(&self, request: &PairingRequest) -> Result<PairingResponse> {
+        let response = self.post("/api/v1/pair/request", request).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse pairing response")
+    }
+
+    /// `POST /api/v1/pair/confirm`
+    pub async fn confirm_pairing(&self, request: &PairingConfirmRequest) -> Result<PairingConfirmResponse> {
+        let response = self.post("/api/v1/pair/confirm", request).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        response.json().await
+            .context("Failed to parse pairing confirmation")
+    }
+
+    /// `POST /api/v1/pair/revoke`
+    pub async fn revoke_pairing(&self, request: &PairingRevokeRequest) -> Result<()> {
+        let response = self.post("/api/v1/pair/revoke", request).await?;
+
+        if !response.status().is_success() {
+            let error: ErrorResponse = response.json().await
+                .context("Failed to parse error response")?;
+            anyhow::bail!("{}: {}", error.code, error.message);
+        }
+
+        Ok(())
+    }
+
+    // MARK: - WebSocket
+
+    /// Open the telemetry WebSocket in a background task that reconnects with
+    /// exponential backoff until [`CameraClient::disconnect_websocket`] is called
+    ///
+    /// `state_callback` is invoked with [`ConnectionState::Connected`] each time the
+    /// socket is (re)established and [`ConnectionState::Disconnected`] when it drops.
+    pub async fn connect_websocket(
+        &mut self,
+        telemetry_callback: impl Fn(WebSocketTelemetryMessage) + Send + Sync + 'static,
+        state_callback: impl Fn(ConnectionState) + Send + Sync + 'static,
+    ) -> Result<()> {
+        // http -> ws, https -> wss
+        let ws_url = self.base_url.replacen("http", "ws", 1) + "/ws";
+        let token = self.token.clone();
+        let connected = self.connected.clone();
+        let camera_id = self.id.clone();
+        let latency = self.latency.clone();
+        let tls = self.tls.clone();
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        self.ws_stop_tx = Some(tx);
+
+        // Spawn WebSocket connection task with reconnection logic
+        tokio::spawn(async move {
+            let mut reconnect_attempts = 0;
+
+            loop {
+                log::info!("Connecting to WebSocket: {} (attempt {}/{})",
+                    ws_url, reconnect_attempts + 1, MAX_RECONNECT_ATTEMPTS);
+
+                let result = connect_websocket_internal(
+                    &ws_url, &token, &telemetry_callback, &state_callback, &connected,
+                    latency.as_deref().map(|latency| (camera_id.as_str(), latency)), tls.as_ref(),
+                ).await;
+
+                // Connection is over either way; report the drop if it had been established
+                let was_connected = std::mem::replace(&mut *connected.write().await, false);
+                if was_connected {
+                    state_callback(ConnectionState::Disconnected);
+                }
+
+                match result {
+                    Ok(_) => {
+                        log::info!("WebSocket connection ended normally");
+                        reconnect_attempts = 0; // Reset on successful connection
+                    }
+                    Err(e) => {
+                        log::error!("WebSocket connection error: {}", e);
+                        reconnect_attempts += 1;
+
+                        if reconnect_attempts >= MAX_RECONNECT_ATTEMPTS {
+                            log::error!("Max reconnection attempts reached, giving up");
+                            break;
+                        }
+                    }
+                }
+
+                // Check if we should stop reconnecting before sleeping
+                if rx.try_recv().is_ok() {
+                    log::info!("Stop signal received, ending WebSocket reconnection");
+                    *connected.write().await = false;
+                    break;
+                }
+
+                // Exponential backoff with cap
+                let backoff_multiplier = 2_u32.pow(reconnect_attempts.min(5));
+                let calculated_delay = WS_RECONNECT_DELAY * backoff_multiplier;
+                let delay = calculated_delay.min(MAX_RECONNECT_DELAY);
+
+                if reconnect_attempts > 0 {
+                    log::info!("Reconnecting in {:?} (attempt {}/{})",
+                        delay, reconnect_attempts + 1, MAX_RECONNECT_ATTEMPTS);
+                }
+
+                tokio::time::sleep(delay).await;
+
+                // Check again after sleep
+                if rx.try_recv().is_ok() {
+                    log::info!("Stop signal received during backoff, ending WebSocket reconnection");
+                    *connected.write().await = false;
+                    break;
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Stop the telemetry WebSocket and its reconnection loop
+    pub async fn disconnect_websocket(&mut self) {
+        *self.connected.write().await = false;
+
+        // Send stop signal through channel if available
+        if let Some(tx) = self.ws_stop_tx.take() {
+            let _ = tx.send(()); // Ignore error if receiver already dropped
+            log::info!("Sent stop signal to WebSocket reconnection task");
+        }
+    }
+
+    /// Query WebSocket connection state
+    ///
+    /// **TODO (LOT B):** Expose this to frontend for connection status indicators
+    /// See [DEAD_CODE_ANALYSIS.md](../../../../DEAD_CODE_ANALYSIS.md#3-is_connected-method) for implementation guide
+    #[allow(dead_code)]
+    pub async fn is_connected(&self) -> bool {
+        *self.connected.read().await
+    }
+}
+
+// Internal WebSocket connection handler
+async fn connect_websocket_internal<F, S>(
+    ws_url: &str,
+    token: &str,
+    telemetry_callback: &F,
+    state_callback: &S,
+    connected: &RwLock<bool>,
+    // Where ping round trips are recorded, with the camera id
+    latency: Option<(&str, &LatencyMonitor)>,
+    tls: Option<&(Arc<CertificatePin>, Arc<rustls::ClientConfig>)>,
+) -> Result<()>
+where
+    F: Fn(WebSocketTelemetryMessage) + Send + Sync + 'static,
+    S: Fn(ConnectionState) + Send + Sync + 'static,
+{
+    // Build request with Authorization header (Bearer token) if token is provided
+    use tokio_tungstenite::tungstenite::http::Request;
+
+    let mut request_builder = Request::builder()
+        .uri(ws_url)
+        .header("Host", ws_url.split("//").nth(1).unwrap_or(ws_url).split('/').next().unwrap_or(ws_url))
+        .header("Connection", "Upgrade")
+        .header("Upgrade", "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", tokio_tungstenite::tungstenite::handshake::client::generate_key());
+
+    // Only add Authorization header if token is not empty
+    if !token.is_empty() {
+        request_builder = request_builder.header("Authorization", format!("Bearer {}", token));
+    }
+
+    let request = request_builder
+        .body(())
+        .context("Failed to build WebSocket request")?;
+
+    log::info!("Connecting to WebSocket: {}", ws_url);
+
+    let connected_result = match tls {
+        Some((_, config)) => {
+            connect_async_tls_with_config(request, None, false, Some(Connector::Rustls(config.clone()))).await
+        }
+        None => connect_async(request).await,
+    };
+    if let (Some((pin, _)), Err(_)) = (tls, &connected_result) {
+        if let Some(mismatch) = pin.take_mismatch() {
+            return Err(mismatch.into());
+        }
+    }
+    let (ws_stream, response) = connected_result
+        .context("Failed to connect to WebSocket")?;
+
+    log::info!("WebSocket connected successfully: {} (status: {})", ws_url, response.status());
+
+    *connected.write().await = true;
+    state_callback(ConnectionState::Connected);
+
+    let (mut write, mut read) = ws_stream.split();
+
+    // Periodic pings time the round trip; the payload is a sequence number so a
+    // late pong is never matched with a newer ping
+    let mut ping_interval = tokio::time::interval(WS_PING_INTERVAL);
+    ping_interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut ping_seq: u64 = 0;
+    let mut ping_sent: Option<Instant> = None;
+
+    loop {
+        tokio::select! {
+            _ = ping_interval.tick() => {
+                ping_seq += 1;
+                write.send(Message::Ping(ping_seq.to_be_bytes().to_vec())).await
+                    .context("Failed to send WebSocket ping")?;
+                ping_sent = Some(Instant::now());
+            }
+            msg = read.next() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+
+                match msg {
+                    Ok(Message::Text(text)) => {
+                        // Parse telemetry message
+                        match serde_json::from_str::<WebSocketTelemetryMessage>(&text) {
+                            Ok(telemetry) => {
+                                telemetry_callback(telemetry);
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to parse WebSocket message: {}", e);
+                            }
+                        }
+                    }
+                    Ok(Message::Close(_)) => {
+                        log::info!("WebSocket closed by server");
+                        break;
+                    }
+                    Ok(Message::Ping(_)) => {
+                        // Pong is sent automatically by tungstenite
+                    }
+                    Ok(Message::Pong(payload)) => {
+                        if payload == ping_seq.to_be_bytes() {
+                            if let (Some(sent), Some((camera_id, latency))) = (ping_sent.take(), latency) {
+                                latency.observe_rtt(camera_id, LatencyChannel::WebSocket, sent.elapsed());
+                            }
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        log::error!("WebSocket error: {}", e);
+                        return Err(e.into());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -6,16 +6,20 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::events::{CameraEvent, EventSender};
 use crate::models::DiscoveredCamera;
 
 const SERVICE_TYPE: &str = "_avolocam._tcp.local.";
 
+/// Continuous mDNS browser for AvoCam services
 pub struct CameraDiscovery {
     daemon: ServiceDaemon,
     discovered: Arc<RwLock<HashMap<String, DiscoveredCamera>>>,
+    events: Option<EventSender>,
 }
 
 impl CameraDiscovery {
+    /// Create the mDNS daemon (browsing starts with [`CameraDiscovery::start_browsing`])
     pub fn new() -> Result<Self> {
         let daemon = ServiceDaemon::new()
             .context("Failed to create mDNS service daemon")?;
@@ -23,15 +27,23 @@ impl CameraDiscovery {
         Ok(Self {
             daemon,
             discovered: Arc::new(RwLock::new(HashMap::new())),
+            events: None,
         })
     }
 
+    /// Publish discovery/removal events on the given event bus
+    pub fn with_event_sender(mut self, events: EventSender) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     /// Start continuous mDNS browsing
     pub async fn start_browsing(&self) -> Result<()> {
         let receiver = self.daemon.browse(SERVICE_TYPE)
             .context("Failed to start mDNS browse")?;
 
         let discovered = self.discovered.clone();
+        let events = self.events.clone();
 
         // Spawn background task to process mDNS events
         tokio::spawn(async move {
@@ -79,8 +91,13 @@ impl CameraDiscovery {
 
                         log::info!("Parsed camera: alias={}, ip={}, port={}", alias, ip, port);
 
-                        // Add to discovered list
-                        discovered.write().await.insert(alias, camera);
+                        // Add to discovered list (re-resolves of a known camera are not new events)
+                        let is_new = discovered.write().await.insert(alias, camera.clone()).is_none();
+                        if is_new {
+                            if let Some(events) = &events {
+                                let _ = events.send(CameraEvent::Discovered { camera });
+                            }
+                        }
                     }
                     ServiceEvent::ServiceRemoved(_, fullname) => {
                         log::info!("Camera removed: {}", fullname);
@@ -90,7 +107,11 @@ impl CameraDiscovery {
                             .trim_end_matches('.')
                             .to_string();
 
-                        discovered.write().await.remove(&alias);
+                        if discovered.write().await.remove(&alias).is_some() {
+                            if let Some(events) = &events {
+                                let _ = events.send(CameraEvent::DiscoveryLost { alias });
+                            }
+                        }
                     }
                     ServiceEvent::SearchStarted(_) => {
                         log::debug!("mDNS search started");
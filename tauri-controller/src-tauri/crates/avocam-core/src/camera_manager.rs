@@ -0,0 +1,2698 @@
+//! Manager for multiple camera clients with group control
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::{Deref, DerefMut};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tokio::sync::{broadcast, RwLock, RwLockReadGuard, RwLockWriteGuard, Semaphore};
+
+use crate::anti_banding;
+use crate::audit::{ActionSource, AuditEntry, AuditLog, AuditQuery, ExportFormat};
+use crate::battery::BatteryPredictor;
+use crate::camera_client::CameraClient;
+use crate::camera_discovery::CameraDiscovery;
+use crate::events::{self, BusNotifier, CameraEvent, EventSender};
+use crate::latency::{CameraLatency, LatencyMonitor};
+use crate::metrics::ControllerMetrics;
+use crate::models::*;
+use crate::pairing::{self, PairingRegistry, PairingSession, PendingPairing};
+use crate::platform::{LogNotifier, Notifier, PathProvider};
+use crate::tls::CertificateMismatch;
+use crate::token_vault::{TokenKeyInfo, TokenKeySource, TokenStorageStatus, TokenVault};
+use crate::profiles::{self, ConflictResolution, ProfileImportReport, RenamedProfile};
+use crate::validation::{self, SettingsRejection, SettingsValidation};
+
+const MAX_CONCURRENT_OPERATIONS: usize = 10;
+
+// MARK: - Persistence
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedCamera {
+    id: String,
+    alias: String,
+    ip: String,
+    port: u16,
+    // Plaintext only in files written before token encryption (migrated on load)
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted_token: Option<String>,
+    #[serde(default)]
+    scheme: CameraScheme,
+    // Pinned TLS certificate (trusted on first use)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cert_fingerprint: Option<String>,
+    // Persisted stream settings (optional for backward compatibility)
+    stream_settings: Option<StreamStartRequest>,
+    // Persisted camera settings (optional for backward compatibility)
+    camera_settings: Option<CameraSettingsRequest>,
+    // Anti-banding override (optional for backward compatibility)
+    #[serde(default)]
+    mains_frequency: Option<MainsFrequency>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    lens_presets: BTreeMap<u32, LensPreset>,
+}
+
+impl PersistedCamera {
+    /// Saved form of a connected camera, with the settings the controller
+    /// applied to it (the last reported status may hold transient ABR or
+    /// thermal rungs and is not saved)
+    fn from_camera_info(
+        info: &CameraInfo,
+        settings: Option<&(Option<StreamStartRequest>, Option<CameraSettingsRequest>)>,
+    ) -> Self {
+        let (stream_settings, camera_settings) = settings.cloned().unwrap_or_default();
+
+        Self {
+            id: info.id.clone(),
+            alias: info.alias.clone(),
+            ip: info.ip.clone(),
+            port: info.port,
+            token: info.token.clone(),
+            encrypted_token: None,
+            scheme: info.scheme,
+            cert_fingerprint: info.cert_fingerprint.clone(),
+            stream_settings,
+            camera_settings,
+            mains_frequency: info.mains_frequency,
+            lens_presets: info.lens_presets.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CamerasPersistence {
+    // Identifies this controller to paired cameras
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    controller_id: Option<String>,
+    // Absent in files written before token encryption
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_key: Option<TokenKeyInfo>,
+    cameras: Vec<PersistedCamera>,
+}
+
+impl PersistedCamera {
+    /// Copy to write to disk, with the token encrypted
+    fn sealed(&self, vault: &TokenVault) -> Result<Self> {
+        let mut sealed = self.clone();
+        if !sealed.token.is_empty() {
+            sealed.encrypted_token = Some(vault.encrypt(&sealed.token)?);
+            sealed.token.clear();
+        }
+        Ok(sealed)
+    }
+
+    /// Decrypt the token read from disk in place
+    fn unseal(&mut self, vault: &TokenVault) -> Result<()> {
+        if let Some(encrypted) = self.encrypted_token.take() {
+            self.token = vault.decrypt(&encrypted)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProfilesPersistence {
+    profiles: Vec<CameraProfile>,
+}
+
+/// Owns every connected camera and fans commands out to them
+///
+/// Persistence is enabled by injecting a [`PathProvider`] through
+/// [`CameraManager::set_path_provider`]; without one the manager runs purely in memory.
+pub struct CameraManager {
+    cameras: HashMap<String, Camera>,
+    discovery: Option<CameraDiscovery>,
+    operation_semaphore: Arc<Semaphore>,
+    paths: Option<Arc<dyn PathProvider>>,
+    notifier: Arc<dyn Notifier>,
+    events: EventSender,
+    audit: AuditLog,
+    metrics: Arc<ControllerMetrics>,
+    battery: Arc<BatteryPredictor>,
+    latency: Arc<LatencyMonitor>,
+    // Cameras the controller last asked to stream (cleared on stop)
+    streaming_requested: Mutex<HashSet<String>>,
+    persistence_file_path: Option<PathBuf>,
+    profiles_file_path: Option<PathBuf>,
+    settings_file_path: Option<PathBuf>,
+    // Cameras not loaded because their certificate changed, kept until re-trusted
+    untrusted: HashMap<String, PersistedCamera>,
+    // Encrypts tokens on save; None while waiting for the passphrase
+    vault: Option<TokenVault>,
+    token_key_file_path: Option<PathBuf>,
+    // Saved cameras (tokens still encrypted) and key parameters awaiting the passphrase
+    locked: Option<(TokenKeyInfo, Vec<PersistedCamera>)>,
+    controller_id: Option<String>,
+    pairings: PairingRegistry,
+    // Store persisted settings for each camera (keyed by camera_id)
+    persisted_settings: HashMap<String, (Option<StreamStartRequest>, Option<CameraSettingsRequest>)>,
+}
+
+struct Camera {
+    info: CameraInfo,
+    client: Arc<RwLock<CameraClient>>,
+}
+
+impl Default for CameraManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CameraManager {
+    /// Create an in-memory manager with a logging notifier
+    pub fn new() -> Self {
+        let events = events::channel();
+        let mut audit = AuditLog::default();
+        audit.set_events(events.clone());
+        let notifier: Arc<dyn Notifier> = Arc::new(BusNotifier::new(Arc::new(LogNotifier), events.clone()));
+        let battery = Arc::new(BatteryPredictor::default());
+        battery.set_notifier(notifier.clone());
+        let latency = Arc::new(LatencyMonitor::default());
+        latency.set_notifier(notifier.clone());
+
+        Self {
+            cameras: HashMap::new(),
+            discovery: None,
+            operation_semaphore: Arc::new(Semaphore::new(MAX_CONCURRENT_OPERATIONS)),
+            paths: None,
+            notifier,
+            events,
+            audit,
+            metrics: Arc::new(ControllerMetrics::default()),
+            battery,
+            latency,
+            streaming_requested: Mutex::new(HashSet::new()),
+            persistence_file_path: None,
+            profiles_file_path: None,
+            settings_file_path: None,
+            untrusted: HashMap::new(),
+            vault: None,
+            token_key_file_path: None,
+            locked: None,
+            controller_id: None,
+            pairings: PairingRegistry::default(),
+            persisted_settings: HashMap::new(),
+        }
+    }
+
+    /// Inject where persisted data lives and load any saved cameras
+    pub async fn set_path_provider(&mut self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        self.persistence_file_path = Some(paths.cameras_file()?);
+        self.profiles_file_path = Some(paths.profiles_file()?);
+        self.settings_file_path = Some(paths.settings_file()?);
+        self.token_key_file_path = Some(paths.token_key_file()?);
+        self.audit.set_path(paths.audit_log_file()?);
+        self.paths = Some(paths);
+
+        match self.get_app_settings().await {
+            Ok(settings) => {
+                self.battery.set_show_end(settings.show_end_time);
+                self.latency.set_threshold_ms(settings.latency_threshold_ms);
+            }
+            Err(e) => log::warn!("Show end time and latency threshold not loaded: {}", e),
+        }
+
+        self.load_cameras_from_disk().await?;
+        Ok(())
+    }
+
+    /// Path provider injected by the host, if persistence is enabled
+    pub fn path_provider(&self) -> Option<Arc<dyn PathProvider>> {
+        self.paths.clone()
+    }
+
+    /// Replace the notifier used to surface alerts and events
+    ///
+    /// Whatever it shows or emits is also published on the event bus.
+    pub fn set_notifier(&mut self, notifier: Arc<dyn Notifier>) {
+        let notifier: Arc<dyn Notifier> = Arc::new(BusNotifier::new(notifier, self.events.clone()));
+        self.battery.set_notifier(notifier.clone());
+        self.latency.set_notifier(notifier.clone());
+        self.notifier = notifier;
+    }
+
+    /// Notifier injected by the host (logs only until one is set)
+    pub fn notifier(&self) -> Arc<dyn Notifier> {
+        self.notifier.clone()
+    }
+
+    /// Controller metrics fed by this manager, for the `/metrics` exporter
+    pub fn metrics(&self) -> Arc<ControllerMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Audit log, for engines journaling their own actions
+    pub(crate) fn audit(&self) -> &AuditLog {
+        &self.audit
+    }
+
+    /// HTTP client of a camera, for engines that send many small updates
+    pub(crate) fn camera_client(&self, camera_id: &str) -> Option<Arc<RwLock<CameraClient>>> {
+        self.cameras.get(camera_id).map(|camera| camera.client.clone())
+    }
+
+    /// Save all cameras to disk, tokens encrypted
+    async fn save_cameras_to_disk(&self) -> Result<()> {
+        let Some(path) = &self.persistence_file_path else {
+            return Ok(()); // No persistence path set
+        };
+        let Some(vault) = &self.vault else {
+            anyhow::bail!("Camera tokens are locked; enter the passphrase first");
+        };
+
+        let persisted_cameras: Vec<PersistedCamera> = self.cameras
+            .iter()
+            .map(|(id, camera)| PersistedCamera::from_camera_info(&camera.info, self.persisted_settings.get(id)))
+            .chain(self.untrusted.values().cloned())
+            .map(|camera| camera.sealed(vault))
+            .collect::<Result<_>>()?;
+
+        let persistence = CamerasPersistence {
+            controller_id: self.controller_id.clone(),
+            token_key: Some(vault.info().clone()),
+            cameras: persisted_cameras,
+        };
+
+        let json = serde_json::to_string_pretty(&persistence)
+            .context("Failed to serialize cameras")?;
+
+        tokio::fs::write(path, json).await
+            .context("Failed to write cameras to disk")?;
+
+        log::info!("Saved {} cameras to {:?}", persistence.cameras.len(), path);
+        Ok(())
+    }
+
+    /// Load cameras from disk and add them to the manager
+    ///
+    /// Cameras protected by a passphrase wait for [`CameraManager::unlock_tokens`].
+    async fn load_cameras_from_disk(&mut self) -> Result<()> {
+        let Some(path) = self.persistence_file_path.clone() else {
+            return Ok(()); // No persistence path set
+        };
+
+        let persistence = if path.exists() {
+            let json = tokio::fs::read_to_string(&path).await
+                .context("Failed to read cameras file")?;
+            serde_json::from_str(&json)
+                .context("Failed to deserialize cameras")?
+        } else {
+            log::info!("No cameras file found at {:?}, starting fresh", path);
+            CamerasPersistence { controller_id: None, token_key: None, cameras: Vec::new() }
+        };
+        self.controller_id = persistence.controller_id.clone();
+
+        if let Some(info) = persistence.token_key.as_ref().filter(|info| info.source == TokenKeySource::Passphrase) {
+            log::info!("{} cameras are waiting for the token passphrase", persistence.cameras.len());
+            self.notifier.notify(
+                "Camera Tokens Locked",
+                "Enter the token passphrase to connect the saved cameras.",
+            );
+            self.locked = Some((info.clone(), persistence.cameras));
+            return Ok(());
+        }
+
+        let vault = TokenVault::open_key_file(&self.token_key_path()?, persistence.token_key.as_ref())?;
+
+        // Files written before token encryption are re-written encrypted right away
+        let plaintext = persistence.cameras.iter()
+            .filter(|camera| !camera.token.is_empty() && camera.encrypted_token.is_none())
+            .count();
+        if plaintext > 0 {
+            let migrated = CamerasPersistence {
+                controller_id: persistence.controller_id.clone(),
+                token_key: Some(vault.info().clone()),
+                cameras: persistence.cameras.iter().map(|camera| camera.sealed(&vault)).collect::<Result<_>>()?,
+            };
+            let json = serde_json::to_string_pretty(&migrated)
+                .context("Failed to serialize cameras")?;
+            tokio::fs::write(&path, json).await
+                .context("Failed to write cameras to disk")?;
+            log::info!("Encrypted {} plaintext camera tokens in {:?}", plaintext, path);
+        }
+
+        self.vault = Some(vault);
+        self.connect_persisted_cameras(persistence.cameras).await;
+        Ok(())
+    }
+
+    fn token_key_path(&self) -> Result<PathBuf> {
+        self.token_key_file_path.clone()
+            .ok_or_else(|| anyhow::anyhow!("Token key path not set"))
+    }
+
+    /// Connect saved cameras (tokens still encrypted); one failing camera does not stop the others
+    async fn connect_persisted_cameras(&mut self, cameras: Vec<PersistedCamera>) -> usize {
+        log::info!("Loading {} cameras", cameras.len());
+        let mut loaded = 0;
+
+        for mut persisted in cameras {
+            if let Some(vault) = &self.vault {
+                if let Err(e) = persisted.unseal(vault) {
+                    log::warn!("Failed to load camera {}: {}", persisted.alias, e);
+                    continue;
+                }
+            }
+
+            let camera_id = persisted.id.clone();
+            let stream_settings = persisted.stream_settings.clone();
+            let camera_settings = persisted.camera_settings.clone();
+            let mains_frequency = persisted.mains_frequency;
+            let lens_presets = persisted.lens_presets.clone();
+
+            // Try to add camera, but don't fail if one camera fails
+            let connected = self.connect_camera(
+                persisted.ip.clone(), persisted.port, persisted.token.clone(),
+                persisted.scheme, persisted.cert_fingerprint.clone(),
+            ).await;
+            match connected {
+                Ok(id) => {
+                    log::info!("Loaded camera: {} ({})", persisted.alias, id);
+                    loaded += 1;
+
+                    if let Some(camera) = self.cameras.get_mut(&id) {
+                        camera.info.mains_frequency = mains_frequency;
+                        camera.info.lens_presets = lens_presets;
+                    }
+
+                    // Store persisted settings for this camera
+                    if stream_settings.is_some() || camera_settings.is_some() {
+                        self.persisted_settings.insert(camera_id, (stream_settings, camera_settings));
+                        log::info!("Stored persisted settings for camera: {}", id);
+                    }
+                }
+                Err(e) if e.downcast_ref::<CertificateMismatch>().is_some() => {
+                    log::error!("Not loading camera {}: {}", persisted.alias, e);
+                    self.notifier.notify(
+                        "Camera Certificate Changed",
+                        &format!("{} presented a different certificate and was not connected. Re-trust it if this is expected.", persisted.alias),
+                    );
+                    self.untrusted.insert(camera_id, persisted);
+                }
+                Err(e) => {
+                    log::warn!("Failed to load camera {}: {}", persisted.alias, e);
+                }
+            }
+        }
+
+        // Certificates pinned on first use are saved with the cameras
+        if loaded > 0 {
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after loading: {}", e);
+            }
+        }
+        loaded
+    }
+
+    // MARK: - Pairing
+
+    /// Id paired cameras know this controller by (generated once, saved with the cameras)
+    fn controller_id(&mut self) -> Result<String> {
+        if let Some(id) = &self.controller_id {
+            return Ok(id.clone());
+        }
+        let id = pairing::random_id()?;
+        self.controller_id = Some(id.clone());
+        Ok(id)
+    }
+
+    /// Ask a camera to pair; the phone then shows a PIN, or waits for the
+    /// returned QR payload to be scanned
+    pub async fn start_pairing(
+        &mut self,
+        ip: String,
+        port: u16,
+        scheme: CameraScheme,
+        method: PairingMethod,
+    ) -> Result<PairingSession> {
+        if self.locked.is_some() {
+            anyhow::bail!("Camera tokens are locked; enter the passphrase first");
+        }
+
+        let controller_id = self.controller_id()?;
+        let mut client = CameraClient::new(ip.clone(), port, String::new())
+            .with_metrics(self.metrics.clone())
+            .with_latency(self.latency.clone());
+        if scheme == CameraScheme::Https {
+            client = client.with_tls(None);
+        }
+
+        let response = client.request_pairing(&PairingRequest {
+            controller_id: controller_id.clone(),
+            controller_name: pairing::controller_name(),
+            method,
+        }).await
+            .context("Camera refused to pair")?;
+
+        let code = match method {
+            PairingMethod::Pin => None,
+            PairingMethod::Qr => Some(pairing::random_id()?),
+        };
+        let camera_id = format!("{}:{}", ip, port);
+        let session = pairing::session(&camera_id, &controller_id, method, &response, code.as_deref());
+
+        self.pairings.insert(response.pairing_id.clone(), PendingPairing {
+            ip,
+            port,
+            scheme,
+            method,
+            code,
+            client: Arc::new(client),
+            expires_at: session.expires_at,
+        });
+
+        log::info!("Pairing {} started with {} ({:?})", session.pairing_id, camera_id, method);
+        Ok(session)
+    }
+
+    /// Finish a pairing with the PIN shown on the phone (ignored for QR pairing)
+    /// and add the camera with its new token, returning its id
+    ///
+    /// A QR pairing fails with the camera's error until the phone has scanned
+    /// the code; it can be confirmed again until it expires.
+    pub async fn confirm_pairing(&mut self, pairing_id: &str, pin: Option<String>, source: ActionSource) -> Result<String> {
+        let pending = self.pairings.get(pairing_id);
+        let payload = match &pending {
+            Ok(pending) => serde_json::json!({ "ip": pending.ip, "port": pending.port, "method": pending.method }),
+            Err(_) => serde_json::Value::Null,
+        };
+        let camera_ids: Vec<String> = pending.as_ref()
+            .map(|pending| vec![format!("{}:{}", pending.ip, pending.port)])
+            .unwrap_or_default();
+
+        let result: Result<String> = async {
+            let pending = self.pairings.get(pairing_id)?;
+            let code = match pending.method {
+                PairingMethod::Pin => pin.filter(|pin| !pin.trim().is_empty())
+                    .ok_or_else(|| anyhow::anyhow!("Enter the PIN shown on the camera"))?,
+                PairingMethod::Qr => pending.code.clone().unwrap_or_default(),
+            };
+            let (ip, port, scheme, client) = (pending.ip.clone(), pending.port, pending.scheme, pending.client.clone());
+
+            let response = client.confirm_pairing(&PairingConfirmRequest {
+                pairing_id: pairing_id.to_string(),
+                code: code.trim().to_string(),
+            }).await?;
+            self.pairings.remove(pairing_id);
+
+            let id = self.connect_camera(ip, port, response.token, scheme, client.cert_fingerprint()).await?;
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after pairing: {}", e);
+            }
+            Ok(id)
+        }.await;
+
+        self.audit.record(source, "pair_camera", &camera_ids, &payload, &result).await;
+        result
+    }
+
+    /// Have the camera invalidate this controller's token, then remove it
+    pub async fn revoke_pairing(&mut self, camera_id: &str, source: ActionSource) -> Result<()> {
+        let payload = serde_json::Value::Null;
+        let result: Result<()> = async {
+            let client = self.camera_client(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+            let controller_id = self.controller_id()?;
+            client.read().await.revoke_pairing(&PairingRevokeRequest { controller_id }).await?;
+            Ok(())
+        }.await;
+
+        self.audit.record(source, "revoke_pairing", &[camera_id.to_string()], &payload, &result).await;
+        result?;
+
+        self.remove_camera(camera_id, source).await
+    }
+
+    // MARK: - Token Storage
+
+    /// Where the token key comes from and whether cameras wait for the passphrase
+    pub fn token_storage_status(&self) -> TokenStorageStatus {
+        match &self.locked {
+            Some((info, cameras)) => TokenStorageStatus {
+                source: info.source,
+                locked: true,
+                locked_cameras: cameras.len(),
+            },
+            None => TokenStorageStatus {
+                source: self.vault.as_ref().map(|vault| vault.info().source).unwrap_or_default(),
+                locked: false,
+                locked_cameras: 0,
+            },
+        }
+    }
+
+    /// Derive the token key from the passphrase and connect the saved cameras,
+    /// returning how many connected
+    ///
+    /// The key is derived before the manager lock is taken, so PBKDF2 does not
+    /// stall the other users of the manager.
+    pub async fn unlock_tokens(manager: &RwLock<Self>, passphrase: &str, source: ActionSource) -> Result<usize> {
+        let payload = serde_json::Value::Null;
+        let result: Result<usize> = async {
+            let info = manager.read().await.locked.as_ref().map(|(info, _)| info.clone())
+                .ok_or_else(|| anyhow::anyhow!("Camera tokens are not locked"))?;
+            let vault = open_passphrase_vault(passphrase.to_string(), Some(info)).await?;
+
+            let mut manager = manager.write().await;
+            // Another unlock may have won the race
+            let Some((_, cameras)) = manager.locked.take() else {
+                anyhow::bail!("Camera tokens are not locked");
+            };
+            manager.vault = Some(vault);
+            Ok(manager.connect_persisted_cameras(cameras).await)
+        }.await;
+
+        manager.read().await.audit.record(source, "unlock_tokens", &[], &payload, &result).await;
+        result
+    }
+
+    /// Protect the saved tokens with a passphrase, or with the machine-local
+    /// key file when `passphrase` is None; every token is re-encrypted
+    ///
+    /// The key is derived before the manager lock is taken; the write lock is
+    /// held only to install the new vault and save.
+    pub async fn set_token_passphrase(manager: &RwLock<Self>, passphrase: Option<String>, source: ActionSource) -> Result<()> {
+        // The passphrase is deliberately left out of the audit payload
+        let payload = serde_json::json!({ "passphrase": passphrase.is_some() });
+        let result: Result<()> = async {
+            let key_path = {
+                let manager = manager.read().await;
+                if manager.locked.is_some() {
+                    anyhow::bail!("Camera tokens are locked; enter the current passphrase first");
+                }
+                manager.token_key_path()?
+            };
+
+            let vault = match passphrase {
+                Some(passphrase) => open_passphrase_vault(passphrase, None).await?,
+                None => TokenVault::open_key_file(&key_path, None)?,
+            };
+
+            let mut manager = manager.write().await;
+            let previous = manager.vault.replace(vault);
+            if let Err(e) = manager.save_cameras_to_disk().await {
+                manager.vault = previous;
+                return Err(e);
+            }
+            Ok(())
+        }.await;
+
+        manager.read().await.audit.record(source, "set_token_passphrase", &[], &payload, &result).await;
+        result
+    }
+
+    // MARK: - Events
+
+    /// Subscribe to telemetry, connection-state and discovery events
+    pub fn subscribe(&self) -> broadcast::Receiver<CameraEvent> {
+        self.events.subscribe()
+    }
+
+    /// Whether the controller last asked this camera to stream
+    ///
+    /// Lets observers tell an unexpected NDI drop from a requested stop.
+    pub fn is_stream_requested(&self, camera_id: &str) -> bool {
+        self.streaming_requested.lock().unwrap().contains(camera_id)
+    }
+
+    fn mark_stream_requested(&self, camera_id: &str, requested: bool) {
+        let mut streaming = self.streaming_requested.lock().unwrap();
+        if requested {
+            streaming.insert(camera_id.to_string());
+        } else {
+            streaming.remove(camera_id);
+        }
+    }
+
+    fn mark_group_stream_requested(&self, results: &[GroupCommandResult], requested: bool) {
+        for result in results.iter().filter(|r| r.success) {
+            self.mark_stream_requested(&result.camera_id, requested);
+        }
+    }
+
+    // MARK: - Audit Log
+
+    /// Journaled actions matching the query, oldest first
+    pub async fn query_audit_log(&self, query: &AuditQuery) -> Result<Vec<AuditEntry>> {
+        self.audit.query(query).await
+    }
+
+    /// Export journaled actions matching the query to a JSONL or CSV file
+    pub async fn export_audit_log(&self, query: &AuditQuery, format: ExportFormat, destination: &Path) -> Result<usize> {
+        self.audit.export(query, format, destination).await
+    }
+
+    // MARK: - Profile Management
+
+    /// Save a profile, replacing any local profile with the same name
+    ///
+    /// A local profile named like a shared one shadows it.
+    pub async fn save_profile(&mut self, mut profile: CameraProfile) -> Result<()> {
+        // Load existing profiles
+        let mut profiles = self.load_profiles_from_disk().await.unwrap_or_default();
+
+        let now = chrono::Utc::now();
+        profile.shared = false;
+        profile.metadata.updated_at = Some(now);
+
+        // Check if profile with this name already exists
+        if let Some(existing) = profiles.iter_mut().find(|p| p.name == profile.name) {
+            // Update existing profile
+            log::info!("Updated existing profile: {}", profile.name);
+            profile.metadata.created_at = existing.metadata.created_at.or(Some(now));
+            *existing = profile;
+        } else {
+            // Add new profile
+            log::info!("Created new profile: {}", profile.name);
+            profile.metadata.created_at.get_or_insert(now);
+            profiles.push(profile);
+        }
+
+        self.save_profiles_to_disk(profiles).await
+    }
+
+    /// Get all profiles: local ones, then shared ones they do not shadow
+    pub async fn get_profiles(&self) -> Result<Vec<CameraProfile>> {
+        let mut profiles = self.load_profiles_from_disk().await?;
+
+        let local: HashSet<String> = profiles.iter().map(|p| p.name.clone()).collect();
+        profiles.extend(
+            self.load_shared_profiles().await
+                .into_iter()
+                .filter(|p| !local.contains(&p.name)),
+        );
+
+        Ok(profiles)
+    }
+
+    /// Delete a local profile by name (shared profiles are read-only)
+    pub async fn delete_profile(&mut self, name: &str) -> Result<()> {
+        // Load existing profiles
+        let mut profiles = self.load_profiles_from_disk().await.unwrap_or_default();
+
+        // Remove the profile
+        let initial_len = profiles.len();
+        profiles.retain(|p| p.name != name);
+
+        if profiles.len() == initial_len {
+            if self.load_shared_profiles().await.iter().any(|p| p.name == name) {
+                anyhow::bail!("Profile '{}' comes from the shared profiles folder and is read-only", name);
+            }
+            anyhow::bail!("Profile not found: {}", name);
+        }
+
+        self.save_profiles_to_disk(profiles).await?;
+
+        log::info!("Deleted profile: {}", name);
+        Ok(())
+    }
+
+    /// Export the named profiles (local or shared) to a portable library file
+    pub async fn export_profiles(
+        &self,
+        names: &[String],
+        destination: &Path,
+        exported_by: Option<String>,
+    ) -> Result<usize> {
+        let available = self.get_profiles().await?;
+
+        let mut selected = Vec::new();
+        for name in names {
+            let mut profile = available.iter()
+                .find(|p| &p.name == name)
+                .cloned()
+                .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", name))?;
+            profile.shared = false;
+            selected.push(profile);
+        }
+
+        let count = selected.len();
+        profiles::write_library(destination, selected, exported_by).await?;
+
+        log::info!("Exported {} profiles to {:?}", count, destination);
+        Ok(count)
+    }
+
+    /// Import a library file into the local profiles
+    pub async fn import_profiles(
+        &mut self,
+        source: &Path,
+        resolution: ConflictResolution,
+    ) -> Result<ProfileImportReport> {
+        let library = profiles::read_library(source).await?;
+        let mut local = self.load_profiles_from_disk().await?;
+
+        // Shared names count as taken so imports never silently shadow them
+        let mut taken: HashSet<String> = local.iter().map(|p| p.name.clone()).collect();
+        taken.extend(self.load_shared_profiles().await.into_iter().map(|p| p.name));
+
+        let mut report = ProfileImportReport::default();
+        for mut profile in library.profiles {
+            profile.shared = false;
+
+            if !taken.contains(&profile.name) {
+                report.imported.push(profile.name.clone());
+            } else {
+                match resolution {
+                    ConflictResolution::Skip => {
+                        report.skipped.push(profile.name);
+                        continue;
+                    }
+                    ConflictResolution::Overwrite => {
+                        local.retain(|p| p.name != profile.name);
+                        report.overwritten.push(profile.name.clone());
+                    }
+                    ConflictResolution::Rename => {
+                        let new_name = profiles::free_name(&profile.name, &taken);
+                        report.renamed.push(RenamedProfile { from: profile.name.clone(), to: new_name.clone() });
+                        profile.name = new_name;
+                    }
+                }
+            }
+
+            taken.insert(profile.name.clone());
+            local.push(profile);
+        }
+
+        self.save_profiles_to_disk(local).await?;
+
+        log::info!("Imported profiles from {:?}: {} new, {} renamed, {} overwritten, {} skipped",
+            source, report.imported.len(), report.renamed.len(), report.overwritten.len(), report.skipped.len());
+        Ok(report)
+    }
+
+    /// Apply a profile to selected cameras
+    ///
+    /// Only the fields in the profile's mask are sent. Stream settings are sent only
+    /// to cameras whose stream settings actually change, and running streams are
+    /// updated in place rather than restarted. The application is one audit entry.
+    pub async fn apply_profile(
+        &mut self,
+        profile_name: &str,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::apply_profile_via(ManagerMut::Borrowed(self), profile_name, camera_ids, source).await
+    }
+
+    /// Masked camera settings of a profile for every camera, and the stream
+    /// changes of cameras whose stream settings differ from it
+    async fn prepare_profile(&self, profile_name: &str, camera_ids: &[String]) -> Result<PreparedProfile> {
+        let profile = self.find_profile(profile_name).await?;
+
+        let settings = match profile.masked_settings() {
+            Some(settings) => {
+                let settings = camera_ids.iter()
+                    .map(|camera_id| (camera_id.clone(), settings.clone()))
+                    .collect();
+                Some(self.prepare_settings_per_camera(settings, ValidationPolicy::Skip).await)
+            }
+            None => None,
+        };
+
+        // Per camera since current settings differ
+        let mut streams = Vec::new();
+        let mut refused = Vec::new();
+        for camera_id in camera_ids {
+            let current = self.current_stream_settings(camera_id);
+            let Some(stream) = profile.masked_stream(&current) else {
+                break;
+            };
+            if stream == current {
+                continue;
+            }
+
+            match self.prepare_stream(camera_id, stream, StreamDelivery::Update).await {
+                Ok(prepared) => streams.push(prepared),
+                Err(e) => refused.push((camera_id.clone(), e)),
+            }
+        }
+
+        Ok(PreparedProfile { camera_ids: camera_ids.to_vec(), settings, streams, refused })
+    }
+
+    /// Persist the camera and stream settings cameras accepted from a profile
+    async fn remember_applied_profile(&mut self, applied: HashMap<String, CameraSettingsRequest>, streams: Vec<PreparedStream>) {
+        for stream in streams {
+            self.remember_sent_stream(stream);
+        }
+        self.remember_applied_settings(applied).await;
+    }
+
+    async fn find_profile(&self, profile_name: &str) -> Result<CameraProfile> {
+        self.get_profiles().await?
+            .into_iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", profile_name))
+    }
+
+    /// Load profiles from disk
+    async fn load_profiles_from_disk(&self) -> Result<Vec<CameraProfile>> {
+        let Some(path) = &self.profiles_file_path else {
+            return Ok(Vec::new());
+        };
+
+        if !path.exists() {
+            log::info!("No profiles file found at {:?}, starting fresh", path);
+            return Ok(Vec::new());
+        }
+
+        let json = tokio::fs::read_to_string(path).await
+            .context("Failed to read profiles file")?;
+
+        let persistence: ProfilesPersistence = serde_json::from_str(&json)
+            .context("Failed to deserialize profiles")?;
+
+        log::info!("Loaded {} profiles from {:?}", persistence.profiles.len(), path);
+        Ok(persistence.profiles)
+    }
+
+    async fn save_profiles_to_disk(&self, profiles: Vec<CameraProfile>) -> Result<()> {
+        let Some(path) = &self.profiles_file_path else {
+            anyhow::bail!("Profiles path not set");
+        };
+
+        let persistence = ProfilesPersistence { profiles };
+        let json = serde_json::to_string_pretty(&persistence)
+            .context("Failed to serialize profiles")?;
+
+        tokio::fs::write(path, json).await
+            .context("Failed to write profiles to disk")?;
+
+        log::info!("Saved profiles to {:?}", path);
+        Ok(())
+    }
+
+    /// Profiles of the shared folder from the app settings (empty if unset or unreadable)
+    async fn load_shared_profiles(&self) -> Vec<CameraProfile> {
+        let Some(dir) = self.get_app_settings().await.ok().and_then(|s| s.shared_profiles_dir) else {
+            return Vec::new();
+        };
+
+        match profiles::load_shared_profiles(&dir).await {
+            Ok(profiles) => profiles,
+            Err(e) => {
+                log::warn!("Shared profiles unavailable: {:#}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    // MARK: - App Settings Management
+
+    /// Get app settings from disk (or defaults if not found)
+    pub async fn get_app_settings(&self) -> Result<AppSettings> {
+        let Some(path) = &self.settings_file_path else {
+            log::warn!("Settings path not set, returning defaults");
+            return Ok(AppSettings::default());
+        };
+
+        if !path.exists() {
+            log::info!("No settings file found at {:?}, returning defaults", path);
+            return Ok(AppSettings::default());
+        }
+
+        let json = tokio::fs::read_to_string(path).await
+            .context("Failed to read settings file")?;
+
+        let settings: AppSettings = serde_json::from_str(&json)
+            .context("Failed to deserialize settings")?;
+
+        log::info!("Loaded app settings from {:?}", path);
+        Ok(settings)
+    }
+
+    /// Save app settings to disk
+    pub async fn save_app_settings(&mut self, settings: AppSettings) -> Result<()> {
+        let Some(path) = &self.settings_file_path else {
+            anyhow::bail!("Settings path not set");
+        };
+
+        let json = serde_json::to_string_pretty(&settings)
+            .context("Failed to serialize settings")?;
+
+        tokio::fs::write(path, json).await
+            .context("Failed to write settings to disk")?;
+        self.battery.set_show_end(settings.show_end_time);
+        self.latency.set_threshold_ms(settings.latency_threshold_ms);
+
+        log::info!("Saved app settings to {:?}", path);
+        Ok(())
+    }
+
+    /// Delete cameras.json file (useful for resetting the app)
+    pub async fn delete_cameras_data(&mut self, source: ActionSource) -> Result<()> {
+        let payload = serde_json::Value::Null;
+        let camera_ids: Vec<String> = self.cameras.keys().cloned().collect();
+        let result: Result<()> = async {
+            let Some(path) = &self.persistence_file_path else {
+                anyhow::bail!("Cameras persistence path not set");
+            };
+
+            if path.exists() {
+                tokio::fs::remove_file(path).await
+                    .context("Failed to delete cameras file")?;
+                log::info!("Deleted cameras data file: {:?}", path);
+            } else {
+                log::info!("Cameras data file does not exist: {:?}", path);
+            }
+
+            // Clear in-memory cameras
+            self.cameras.clear();
+            self.untrusted.clear();
+
+            // Nothing left to unlock; new cameras use the key file
+            if self.locked.take().is_some() {
+                self.vault = Some(TokenVault::open_key_file(&self.token_key_path()?, None)?);
+            }
+
+            Ok(())
+        }.await;
+
+        self.audit.record(source, "delete_cameras_data", &camera_ids, &payload, &result).await;
+        result
+    }
+
+    // MARK: - Discovery
+
+    /// Start mDNS browsing for cameras on the local network
+    pub async fn start_discovery(&mut self) -> Result<()> {
+        let discovery = CameraDiscovery::new()
+            .context("Failed to create camera discovery")?
+            .with_event_sender(self.events.clone());
+
+        discovery.start_browsing().await
+            .context("Failed to start mDNS browsing")?;
+
+        self.discovery = Some(discovery);
+
+        log::info!("Camera discovery started");
+        Ok(())
+    }
+
+    /// Cameras currently advertised over mDNS
+    pub async fn get_discovered_cameras(&self) -> Result<Vec<DiscoveredCamera>> {
+        if let Some(discovery) = &self.discovery {
+            Ok(discovery.get_discovered().await)
+        } else {
+            Ok(Vec::new())
+        }
+    }
+
+    // MARK: - Camera Management
+
+    /// Connect to a camera by address, returning its id (`ip:port`)
+    ///
+    /// An https camera's certificate is trusted on first use and pinned.
+    pub async fn add_camera_manual(
+        &mut self,
+        ip: String,
+        port: u16,
+        token: String,
+        scheme: CameraScheme,
+        source: ActionSource,
+    ) -> Result<String> {
+        // The token is deliberately left out of the audit payload
+        let payload = serde_json::json!({ "ip": ip, "port": port, "scheme": scheme });
+        let camera_id = format!("{}:{}", ip, port);
+
+        let result = if self.locked.is_some() {
+            Err(anyhow::anyhow!("Camera tokens are locked; enter the passphrase first"))
+        } else {
+            self.connect_camera(ip, port, token, scheme, None).await
+        };
+        if result.is_ok() {
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk: {}", e);
+            }
+        }
+
+        self.audit.record(source, "add_camera", &[camera_id], &payload, &result).await;
+        result
+    }
+
+    /// Connect to a camera; `pinned` is the trusted certificate of an https camera
+    ///
+    /// Not persisted: callers save once their own bookkeeping is done.
+    async fn connect_camera(
+        &mut self,
+        ip: String,
+        port: u16,
+        token: String,
+        scheme: CameraScheme,
+        pinned: Option<String>,
+    ) -> Result<String> {
+        let id = format!("{}:{}", ip, port);
+
+        // Create client
+        let mut client = CameraClient::new(ip.clone(), port, token.clone())
+            .with_metrics(self.metrics.clone())
+            .with_latency(self.latency.clone());
+        if scheme == CameraScheme::Https {
+            client = client.with_tls(pinned);
+        }
+
+        // Try to get status to verify connectivity
+        let status = client.get_status().await
+            .context("Failed to connect to camera")?;
+        let cert_fingerprint = client.cert_fingerprint();
+
+        self.metrics.register_camera(&id, &status.alias);
+
+        // Connect WebSocket for telemetry
+        let client_arc = Arc::new(RwLock::new(client));
+        let telemetry_events = self.events.clone();
+        let telemetry_metrics = self.metrics.clone();
+        let telemetry_battery = self.battery.clone();
+        let telemetry_latency = self.latency.clone();
+        let telemetry_alias = status.alias.clone();
+        let telemetry_id = id.clone();
+        let state_events = self.events.clone();
+        let state_metrics = self.metrics.clone();
+        let state_id = id.clone();
+
+        client_arc.write().await.connect_websocket(
+            move |telemetry| {
+                telemetry_metrics.observe_telemetry(&telemetry_id, &telemetry);
+                telemetry_battery.observe(&telemetry_id, &telemetry_alias, &telemetry);
+                telemetry_latency.observe_telemetry(&telemetry_id, &telemetry);
+                // No subscribers is fine; the send error is ignored
+                let _ = telemetry_events.send(CameraEvent::Telemetry {
+                    camera_id: telemetry_id.clone(),
+                    telemetry,
+                });
+            },
+            move |state| {
+                state_metrics.observe_connection(&state_id, state);
+                let _ = state_events.send(CameraEvent::ConnectionChanged {
+                    camera_id: state_id.clone(),
+                    state,
+                });
+            },
+        ).await
+            .context("Failed to connect WebSocket")?;
+
+        // Create camera info
+        let info = CameraInfo {
+            id: id.clone(),
+            alias: status.alias.clone(),
+            ip,
+            port,
+            token,
+            scheme,
+            cert_fingerprint,
+            status: Some(status),
+            connection_state: ConnectionState::Connected,
+            mains_frequency: None,
+            lens_presets: BTreeMap::new(),
+            battery_runtime: None,
+        };
+
+        // Store camera
+        self.cameras.insert(id.clone(), Camera {
+            info,
+            client: client_arc,
+        });
+
+        log::info!("Added camera: {}", id);
+        Ok(id)
+    }
+
+    /// Trust the certificate a camera presents now, replacing its pin
+    ///
+    /// For a camera whose certificate was regenerated (reset, reinstall). Also
+    /// connects cameras left out at startup because of a certificate mismatch.
+    /// Returns the new fingerprint.
+    pub async fn retrust_camera(&mut self, camera_id: &str, source: ActionSource) -> Result<String> {
+        let payload = serde_json::Value::Null;
+        let result: Result<String> = async {
+            let (previous, old_client) = match (self.cameras.get(camera_id), self.untrusted.get(camera_id)) {
+                (Some(camera), _) => {
+                    let settings = self.persisted_settings.get(camera_id);
+                    (PersistedCamera::from_camera_info(&camera.info, settings), Some(camera.client.clone()))
+                }
+                (None, Some(persisted)) => (persisted.clone(), None),
+                (None, None) => anyhow::bail!("Camera not found: {}", camera_id),
+            };
+            if previous.scheme != CameraScheme::Https {
+                anyhow::bail!("Camera {} does not use TLS", camera_id);
+            }
+
+            // A connected camera keeps its old connection until the new one succeeds;
+            // an untrusted entry is set aside meanwhile and restored on failure
+            let untrusted = self.untrusted.remove(camera_id);
+            let connected = self.connect_camera(previous.ip.clone(), previous.port, previous.token.clone(), CameraScheme::Https, None).await;
+            let id = match connected {
+                Ok(id) => id,
+                Err(e) => {
+                    if let Some(untrusted) = untrusted {
+                        self.untrusted.insert(camera_id.to_string(), untrusted);
+                    }
+                    return Err(e);
+                }
+            };
+            if let Some(old_client) = old_client {
+                old_client.write().await.disconnect_websocket().await;
+            }
+
+            let camera = self.cameras.get_mut(&id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", id))?;
+            camera.info.mains_frequency = previous.mains_frequency;
+            camera.info.lens_presets = previous.lens_presets;
+            let fingerprint = camera.info.cert_fingerprint.clone()
+                .ok_or_else(|| anyhow::anyhow!("No certificate received from {}", id))?;
+            if !self.persisted_settings.contains_key(&id)
+                && (previous.stream_settings.is_some() || previous.camera_settings.is_some())
+            {
+                self.persisted_settings.insert(id.clone(), (previous.stream_settings, previous.camera_settings));
+            }
+
+            self.save_cameras_to_disk().await?;
+            log::info!("Re-trusted camera {} with certificate {}", id, fingerprint);
+            Ok(fingerprint)
+        }.await;
+
+        self.audit.record(source, "retrust_camera", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Disconnect and forget a camera
+    pub async fn remove_camera(&mut self, camera_id: &str, source: ActionSource) -> Result<()> {
+        let payload = serde_json::Value::Null;
+        let result: Result<()> = async {
+            if let Some(camera) = self.cameras.remove(camera_id) {
+                // Disconnect WebSocket
+                camera.client.write().await.disconnect_websocket().await;
+                self.mark_stream_requested(camera_id, false);
+                self.metrics.forget_camera(camera_id);
+                self.battery.forget_camera(camera_id);
+                self.latency.forget_camera(camera_id);
+                log::info!("Removed camera: {}", camera_id);
+
+                // Persist to disk
+                if let Err(e) = self.save_cameras_to_disk().await {
+                    log::warn!("Failed to save cameras to disk: {}", e);
+                }
+
+                Ok(())
+            } else {
+                anyhow::bail!("Camera not found: {}", camera_id);
+            }
+        }.await;
+
+        self.audit.record(source, "remove_camera", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// All managed cameras with freshly fetched status
+    pub async fn get_all_cameras(&self) -> Vec<CameraInfo> {
+        let mut result = Vec::new();
+
+        for (id, camera) in &self.cameras {
+            let mut info = camera.info.clone();
+            info.battery_runtime = self.battery.prediction(id);
+
+            // Fetch fresh status from camera
+            match camera.client.read().await.get_status().await {
+                Ok(status) => {
+                    info.status = Some(status);
+                    info.connection_state = ConnectionState::Connected;
+                }
+                Err(e) => {
+                    log::warn!("Failed to get status for camera {}: {}", id, e);
+                    info.connection_state = ConnectionState::Error;
+                    // Keep existing status if available
+                }
+            }
+
+            result.push(info);
+        }
+
+        result
+    }
+
+    /// Controller-side alias of a managed camera
+    pub fn camera_alias(&self, camera_id: &str) -> Option<String> {
+        self.cameras.get(camera_id).map(|camera| camera.info.alias.clone())
+    }
+
+    /// Rename a camera (controller-side alias)
+    pub async fn update_camera_alias(&mut self, camera_id: &str, alias: String, source: ActionSource) -> Result<()> {
+        let payload = serde_json::json!({ "alias": alias });
+        let result: Result<()> = async {
+            if let Some(camera) = self.cameras.get_mut(camera_id) {
+                camera.info.alias = alias.clone();
+                self.metrics.register_camera(camera_id, &alias);
+                log::info!("Updated camera {} alias to: {}", camera_id, alias);
+
+                // Persist to disk
+                if let Err(e) = self.save_cameras_to_disk().await {
+                    log::warn!("Failed to save cameras to disk after alias update: {}", e);
+                }
+
+                Ok(())
+            } else {
+                anyhow::bail!("Camera not found: {}", camera_id);
+            }
+        }.await;
+
+        self.audit.record(source, "update_camera_alias", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    // MARK: - Single Camera Operations
+
+    /// Fetch live status of a single camera
+    pub async fn get_camera_status(&self, camera_id: &str) -> Result<StatusResponse> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        camera.client.read().await.get_status().await
+    }
+
+    /// Start streaming on a camera and persist the stream settings
+    pub async fn start_stream(&mut self, camera_id: &str, request: StreamStartRequest, source: ActionSource) -> Result<()> {
+        Self::send_stream_via(ManagerMut::Borrowed(self), camera_id, request, StreamDelivery::Start, source).await
+    }
+
+    /// Stop streaming on a camera
+    pub async fn stop_stream(&self, camera_id: &str, source: ActionSource) -> Result<()> {
+        Self::stop_stream_via(ManagerRef::Borrowed(self), camera_id, source).await
+    }
+
+    /// Apply camera settings and persist them
+    ///
+    /// Settings are validated against the camera's capabilities (rejected on any
+    /// error), a shutter angle is converted to `shutter_s` and manual shutters are
+    /// snapped for anti-banding; the returned outcome describes what was sent.
+    pub async fn update_camera_settings(
+        &mut self,
+        camera_id: &str,
+        settings: CameraSettingsRequest,
+        source: ActionSource,
+    ) -> Result<SettingsOutcome> {
+        Self::update_camera_settings_via(ManagerMut::Borrowed(self), camera_id, settings, source).await
+    }
+
+    /// Update stream settings for a camera and persist them; a stream the
+    /// controller started is updated in place (never restarted, which the
+    /// app refuses while streaming)
+    pub async fn update_stream_settings(&mut self, camera_id: &str, settings: StreamStartRequest, source: ActionSource) -> Result<()> {
+        Self::send_stream_via(ManagerMut::Borrowed(self), camera_id, settings, StreamDelivery::Update, source).await
+    }
+
+    fn remember_stream_settings(&mut self, camera_id: &str, settings: StreamStartRequest) {
+        self.persisted_settings
+            .entry(camera_id.to_string())
+            .and_modify(|(stream, _)| *stream = Some(settings.clone()))
+            .or_insert((Some(settings), None));
+    }
+
+    fn remember_camera_settings(&mut self, camera_id: &str, settings: CameraSettingsRequest) {
+        self.persisted_settings
+            .entry(camera_id.to_string())
+            .and_modify(|(_, camera)| *camera = Some(settings.clone()))
+            .or_insert((None, Some(settings)));
+    }
+
+    /// Stream settings a camera is configured with: persisted, else last reported, else defaults
+    fn current_stream_settings(&self, camera_id: &str) -> StreamStartRequest {
+        if let Some(stream) = self.persisted_settings.get(camera_id).and_then(|(stream, _)| stream.clone()) {
+            return stream;
+        }
+
+        self.cameras.get(camera_id)
+            .and_then(|camera| camera.info.status.as_ref())
+            .map(|status| StreamStartRequest {
+                resolution: status.current.resolution.clone(),
+                framerate: status.current.fps,
+                bitrate: status.current.bitrate,
+                codec: status.current.codec.clone(),
+            })
+            .unwrap_or_default()
+    }
+
+    /// Persisted stream settings of a camera, or the defaults if none were saved
+    pub fn stream_settings_or_default(&self, camera_id: &str) -> StreamStartRequest {
+        self.persisted_settings
+            .get(camera_id)
+            .and_then(|(stream, _)| stream.clone())
+            .unwrap_or_default()
+    }
+
+    /// Supported resolution/fps/codec combinations of a camera
+    pub async fn get_capabilities(&self, camera_id: &str) -> Result<Vec<Capability>> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        camera.client.read().await.get_capabilities().await
+    }
+
+    /// Measure scene white balance on a camera
+    pub async fn measure_white_balance(&self, camera_id: &str) -> Result<WhiteBalanceMeasureResponse> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        camera.client.read().await.measure_white_balance().await
+    }
+
+    // MARK: - Group Operations (Parallel with Bounded Concurrency)
+
+    /// Start streaming on several cameras with the same settings
+    pub async fn group_start_stream(
+        &mut self,
+        camera_ids: &[String],
+        request: StreamStartRequest,
+        policy: ValidationPolicy,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let payload = serde_json::json!({ "request": request, "policy": policy });
+        let started = Instant::now();
+
+        // Validate per camera since capabilities differ
+        let mut requests = HashMap::new();
+        let mut warnings = HashMap::new();
+        let mut rejected = Vec::new();
+        for camera_id in camera_ids {
+            match self.check_stream_settings(camera_id, request.clone(), policy).await {
+                Ok((_, issues)) if validation::has_errors(&issues) => {
+                    rejected.push(rejected_result(
+                        camera_id.clone(),
+                        SettingsRejection::new(camera_id, issues).into(),
+                    ));
+                }
+                Ok((camera_request, issues)) => {
+                    requests.insert(camera_id.clone(), camera_request);
+                    warnings.insert(camera_id.clone(), issues);
+                }
+                Err(e) => rejected.push(rejected_result(camera_id.clone(), e)),
+            }
+        }
+
+        // Check the planned total of each link against its budget
+        let (budget_warnings, refused) = self.enforce_bandwidth_budgets(&mut requests).await;
+        for (camera_id, issues) in budget_warnings {
+            warnings.entry(camera_id).or_default().extend(issues);
+        }
+        rejected.extend(refused);
+
+        let valid_ids: Vec<String> = camera_ids.iter()
+            .filter(|id| requests.contains_key(*id))
+            .cloned()
+            .collect();
+
+        // Store settings for each camera before starting streams
+        for (camera_id, camera_request) in &requests {
+            self.persisted_settings
+                .entry(camera_id.to_string())
+                .and_modify(|(stream, _)| *stream = Some(camera_request.clone()))
+                .or_insert((Some(camera_request.clone()), None));
+        }
+
+        let requests = Arc::new(requests);
+        let result = self.execute_group_operation(&valid_ids, move |camera_id, client| {
+            let req = requests.get(&camera_id).cloned();
+            async move {
+                let req = req.ok_or_else(|| anyhow::anyhow!("No stream settings for {}", camera_id))?;
+                client.read().await.start_stream(req).await
+            }
+        }).await;
+
+        let result = result.map(|mut results| {
+            for result in &mut results {
+                result.issues = warnings.remove(&result.camera_id).unwrap_or_default();
+            }
+            results.extend(rejected);
+            results
+        });
+
+        if let Ok(results) = &result {
+            self.mark_group_stream_requested(results, true);
+        }
+
+        // Save to disk after successful group start
+        if let Err(e) = self.save_cameras_to_disk().await {
+            log::warn!("Failed to save cameras to disk after group start: {}", e);
+        }
+
+        self.metrics.observe_group_operation("group_start_stream", started.elapsed());
+        self.audit.record_group(source, "group_start_stream", camera_ids, &payload, &result).await;
+        result
+    }
+
+    /// Stop streaming on several cameras
+    pub async fn group_stop_stream(
+        &self,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::group_stop_stream_via(ManagerRef::Borrowed(self), camera_ids, source).await
+    }
+
+    /// Apply the same camera settings to several cameras
+    ///
+    /// Cameras whose settings fail validation are skipped or clamped per `policy`.
+    pub async fn group_update_settings(
+        &mut self,
+        camera_ids: &[String],
+        settings: CameraSettingsRequest,
+        policy: ValidationPolicy,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let payload = serde_json::json!({ "settings": settings, "policy": policy });
+        let started = Instant::now();
+        let result = self.update_settings_for_cameras(camera_ids, settings, policy).await;
+
+        self.metrics.observe_group_operation("group_update_settings", started.elapsed());
+        self.audit.record_group(source, "group_update_settings", camera_ids, &payload, &result).await;
+        result
+    }
+
+    async fn update_settings_for_cameras(
+        &mut self,
+        camera_ids: &[String],
+        settings: CameraSettingsRequest,
+        policy: ValidationPolicy,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let settings = camera_ids.iter()
+            .map(|camera_id| (camera_id.clone(), settings.clone()))
+            .collect();
+
+        Self::update_settings_per_camera_via(ManagerMut::Borrowed(self), settings, policy).await
+    }
+
+    /// Apply different camera settings to several cameras in one parallel batch
+    pub async fn group_update_settings_per_camera(
+        &mut self,
+        settings: HashMap<String, CameraSettingsRequest>,
+        policy: ValidationPolicy,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::group_update_settings_per_camera_via(ManagerMut::Borrowed(self), settings, policy, source).await
+    }
+
+    /// Validate and anti-band settings per camera (capabilities, fps and mains
+    /// differ) and snapshot the clients to send them to
+    async fn prepare_settings_per_camera(
+        &self,
+        settings: HashMap<String, CameraSettingsRequest>,
+        policy: ValidationPolicy,
+    ) -> PreparedSettings {
+        let mut resolved = HashMap::new();
+        let mut outcomes = HashMap::new();
+        let mut rejected = Vec::new();
+
+        for (camera_id, camera_settings) in settings {
+            match self.resolve_camera_settings(&camera_id, camera_settings, policy).await {
+                Ok((camera_settings, outcome)) => {
+                    outcomes.insert(camera_id.clone(), outcome);
+                    resolved.insert(camera_id, camera_settings);
+                }
+                Err(e) => rejected.push(rejected_result(camera_id, e)),
+            }
+        }
+
+        let mut camera_ids: Vec<String> = resolved.keys().cloned().collect();
+        camera_ids.sort();
+
+        PreparedSettings {
+            clients: self.group_clients(&camera_ids),
+            semaphore: self.operation_semaphore.clone(),
+            settings: resolved,
+            outcomes,
+            rejected,
+        }
+    }
+
+    /// Persist the settings cameras accepted
+    async fn remember_applied_settings(&mut self, applied: HashMap<String, CameraSettingsRequest>) {
+        for (camera_id, camera_settings) in applied {
+            self.remember_camera_settings(&camera_id, camera_settings);
+        }
+
+        if let Err(e) = self.save_cameras_to_disk().await {
+            log::warn!("Failed to save cameras to disk after group update: {}", e);
+        }
+    }
+
+    // Generic group operation executor with bounded concurrency
+    async fn execute_group_operation<F, Fut>(
+        &self,
+        camera_ids: &[String],
+        operation: F,
+    ) -> Result<Vec<GroupCommandResult>>
+    where
+        F: Fn(String, Arc<RwLock<CameraClient>>) -> Fut + Send + Sync + 'static,
+        Fut: std::future::Future<Output = Result<()>> + Send,
+    {
+        fan_out(self.group_clients(camera_ids), self.operation_semaphore.clone(), operation).await
+    }
+
+    /// Client of each camera (None when unknown), to fan out without the manager
+    fn group_clients(&self, camera_ids: &[String]) -> Vec<(String, Option<Arc<RwLock<CameraClient>>>)> {
+        camera_ids.iter()
+            .map(|camera_id| (camera_id.clone(), self.cameras.get(camera_id).map(|camera| camera.client.clone())))
+            .collect()
+    }
+
+    // MARK: - Shared Access
+
+    // Each operation below is written once against [`ManagerRef`] or
+    // [`ManagerMut`]: the methods above pass `self`, the engines pass their
+    // lock. It reads to resolve settings and snapshot clients, sends to the
+    // cameras with no guard held, and writes only to remember what they
+    // accepted, so one slow camera does not stall telemetry handling, UI
+    // commands or the other engines.
+
+    /// Validate, anti-band and send camera settings to one camera, then persist them
+    async fn apply_camera_settings_via(
+        mut manager: ManagerMut<'_>,
+        camera_id: &str,
+        settings: CameraSettingsRequest,
+    ) -> Result<SettingsOutcome> {
+        let (client, settings, outcome) = {
+            let manager = manager.read().await;
+            let (settings, outcome) = manager.resolve_camera_settings(camera_id, settings, ValidationPolicy::Skip).await?;
+            let client = manager.camera_client(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+            (client, settings, outcome)
+        };
+
+        client.read().await.update_camera_settings(settings.clone()).await?;
+
+        let mut manager = manager.write().await;
+        manager.remember_camera_settings(camera_id, settings);
+        if let Err(e) = manager.save_cameras_to_disk().await {
+            log::warn!("Failed to save cameras to disk after updating settings: {}", e);
+        }
+        Ok(outcome)
+    }
+
+    /// See [`CameraManager::update_camera_settings`]
+    pub(crate) async fn update_camera_settings_via(
+        mut manager: ManagerMut<'_>,
+        camera_id: &str,
+        settings: CameraSettingsRequest,
+        source: ActionSource,
+    ) -> Result<SettingsOutcome> {
+        let payload = settings.clone();
+        let result = Self::apply_camera_settings_via(manager.reborrow(), camera_id, settings).await;
+
+        manager.read().await.audit
+            .record(source, "update_camera_settings", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Send different camera settings to several cameras in one parallel batch
+    /// and persist those the cameras accepted
+    async fn update_settings_per_camera_via(
+        mut manager: ManagerMut<'_>,
+        settings: HashMap<String, CameraSettingsRequest>,
+        policy: ValidationPolicy,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let prepared = manager.read().await.prepare_settings_per_camera(settings, policy).await;
+        let (results, applied) = prepared.send().await?;
+        manager.write().await.remember_applied_settings(applied).await;
+        Ok(results)
+    }
+
+    /// See [`CameraManager::group_update_settings_per_camera`]
+    pub(crate) async fn group_update_settings_per_camera_via(
+        mut manager: ManagerMut<'_>,
+        settings: HashMap<String, CameraSettingsRequest>,
+        policy: ValidationPolicy,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let mut camera_ids: Vec<String> = settings.keys().cloned().collect();
+        camera_ids.sort();
+        let payload = serde_json::json!({ "settings": settings, "policy": policy });
+        let started = Instant::now();
+
+        let result = Self::update_settings_per_camera_via(manager.reborrow(), settings, policy).await;
+
+        manager.read().await
+            .record_group_operation(source, "group_update_settings_per_camera", &camera_ids, &payload, started, &result).await;
+        result
+    }
+
+    /// Validate, anti-band and send intermediate settings (ramp steps) without
+    /// persisting or journaling them; the caller audits what they lead to
+    pub(crate) async fn send_transient_settings_via(
+        manager: ManagerRef<'_>,
+        settings: HashMap<String, CameraSettingsRequest>,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let prepared = manager.read().await.prepare_settings_per_camera(settings, ValidationPolicy::Skip).await;
+        prepared.send().await.map(|(results, _)| results)
+    }
+
+    /// Validate stream settings, send them to one camera as `delivery` says and
+    /// persist them (bitrate rungs excepted); audited as [`StreamDelivery::action`]
+    pub(crate) async fn send_stream_via(
+        mut manager: ManagerMut<'_>,
+        camera_id: &str,
+        settings: StreamStartRequest,
+        delivery: StreamDelivery,
+        source: ActionSource,
+    ) -> Result<()> {
+        let payload = settings.clone();
+        let result: Result<()> = async {
+            let stream = manager.read().await.prepare_stream(camera_id, settings, delivery).await?;
+            stream.send().await?;
+
+            // A bitrate rung is not persisted: the operator's bitrate stays the ceiling
+            if delivery == StreamDelivery::Bitrate {
+                return Ok(());
+            }
+
+            let mut manager = manager.write().await;
+            manager.remember_sent_stream(stream);
+            if let Err(e) = manager.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after updating stream settings: {}", e);
+            }
+            Ok(())
+        }.await;
+
+        manager.read().await.audit
+            .record(source, delivery.action(), &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// See [`CameraManager::stop_stream`]
+    pub(crate) async fn stop_stream_via(manager: ManagerRef<'_>, camera_id: &str, source: ActionSource) -> Result<()> {
+        let payload = serde_json::Value::Null;
+        let result: Result<()> = async {
+            let client = manager.read().await.camera_client(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+            client.read().await.stop_stream().await?;
+            manager.read().await.mark_stream_requested(camera_id, false);
+            Ok(())
+        }.await;
+
+        manager.read().await.audit
+            .record(source, "stop_stream", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// See [`CameraManager::apply_profile`]
+    pub(crate) async fn apply_profile_via(
+        mut manager: ManagerMut<'_>,
+        profile_name: &str,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let payload = serde_json::json!({ "profile_name": profile_name });
+        let started = Instant::now();
+        let result = async {
+            let prepared = manager.read().await.prepare_profile(profile_name, camera_ids).await?;
+            let (results, applied, streams) = prepared.send().await?;
+            manager.write().await.remember_applied_profile(applied, streams).await;
+            Ok(results)
+        }.await;
+
+        manager.read().await
+            .record_group_operation(source, "apply_profile", camera_ids, &payload, started, &result).await;
+        result
+    }
+
+    /// See [`CameraManager::start_all_cameras`]
+    pub(crate) async fn start_all_cameras_via(manager: ManagerRef<'_>, source: ActionSource) -> Result<Vec<GroupCommandResult>> {
+        let camera_ids: Vec<String> = manager.read().await.cameras.keys().cloned().collect();
+
+        if camera_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::start_persisted_via(manager, &camera_ids, "start_all_cameras", source).await
+    }
+
+    /// See [`CameraManager::start_cameras_with_persisted_settings`]
+    pub(crate) async fn start_cameras_with_persisted_settings_via(
+        manager: ManagerRef<'_>,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::start_persisted_via(manager, camera_ids, "start_cameras", source).await
+    }
+
+    async fn start_persisted_via(
+        manager: ManagerRef<'_>,
+        camera_ids: &[String],
+        action: &str,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let started = Instant::now();
+        let prepared = manager.read().await.prepare_persisted_starts(camera_ids).await;
+        let result = prepared.send().await;
+
+        let manager = manager.read().await;
+        let result = result.map(|(results, refused)| {
+            manager.mark_group_stream_requested(&results, true);
+            [results, refused].concat()
+        });
+
+        manager.record_group_operation(source, action, camera_ids, &serde_json::Value::Null, started, &result).await;
+        result
+    }
+
+    /// See [`CameraManager::stop_all_cameras`]
+    pub(crate) async fn stop_all_cameras_via(manager: ManagerRef<'_>, source: ActionSource) -> Result<Vec<GroupCommandResult>> {
+        let camera_ids: Vec<String> = manager.read().await.cameras.keys().cloned().collect();
+
+        if camera_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Self::stop_streams_via(manager, &camera_ids, "stop_all_cameras", source).await
+    }
+
+    /// See [`CameraManager::group_stop_stream`]
+    pub(crate) async fn group_stop_stream_via(
+        manager: ManagerRef<'_>,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::stop_streams_via(manager, camera_ids, "group_stop_stream", source).await
+    }
+
+    async fn stop_streams_via(
+        manager: ManagerRef<'_>,
+        camera_ids: &[String],
+        action: &str,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let started = Instant::now();
+        let (clients, semaphore) = {
+            let manager = manager.read().await;
+            (manager.group_clients(camera_ids), manager.operation_semaphore.clone())
+        };
+
+        let result = fan_out(clients, semaphore, |_, client| async move {
+            client.read().await.stop_stream().await
+        }).await;
+
+        let manager = manager.read().await;
+        if let Ok(results) = &result {
+            manager.mark_group_stream_requested(results, false);
+        }
+
+        manager.record_group_operation(source, action, camera_ids, &serde_json::Value::Null, started, &result).await;
+        result
+    }
+
+    /// See [`CameraManager::camera_capabilities`]
+    pub(crate) async fn camera_capabilities_via(manager: ManagerRef<'_>, camera_id: &str) -> Option<Vec<Capability>> {
+        let client = {
+            let manager = manager.read().await;
+            let camera = manager.cameras.get(camera_id)?;
+            if let Some(status) = &camera.info.status {
+                if !status.capabilities.is_empty() {
+                    return Some(status.capabilities.clone());
+                }
+            }
+            camera.client.clone()
+        };
+
+        let client = client.read().await;
+        client.get_capabilities().await.ok()
+    }
+
+    /// Time and journal a group operation
+    async fn record_group_operation(
+        &self,
+        source: ActionSource,
+        action: &str,
+        camera_ids: &[String],
+        payload: &impl Serialize,
+        started: Instant,
+        result: &Result<Vec<GroupCommandResult>>,
+    ) {
+        self.metrics.observe_group_operation(action, started.elapsed());
+        self.audit.record_group(source, action, camera_ids, payload, result).await;
+    }
+
+    // MARK: - Diagnostics
+
+    /// HTTP/WebSocket round trips and clock offset of every measured camera
+    pub fn get_latency_stats(&self) -> Vec<CameraLatency> {
+        self.latency.all_stats()
+    }
+
+    /// Dropped frames, queue depth and temperature history of a camera
+    pub async fn get_diagnostics(&self, camera_id: &str) -> Result<DiagnosticsResponse> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        camera.client.read().await.get_diagnostics().await
+    }
+
+    /// Download a camera's log archive into `dir`, returning the archive path
+    pub async fn download_logs(&self, camera_id: &str, dir: &Path) -> Result<PathBuf> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        let (path, bytes) = camera.client.read().await.download_logs(dir).await?;
+        log::info!("Downloaded {} bytes of logs from {} to {}", bytes, camera_id, path.display());
+        Ok(path)
+    }
+
+    /// Download the log archives of several cameras into a new
+    /// `logs-<timestamp>` folder under `dir`
+    ///
+    /// A camera that fails does not stop the others; the bundle holds one result
+    /// per camera.
+    pub async fn group_download_logs(
+        &self,
+        camera_ids: &[String],
+        dir: &Path,
+    ) -> Result<LogBundle> {
+        let started = Instant::now();
+        let folder = dir.join(format!("logs-{}", chrono::Utc::now().format("%Y%m%d-%H%M%S")));
+        tokio::fs::create_dir_all(&folder).await
+            .with_context(|| format!("Failed to create {}", folder.display()))?;
+
+        let mut tasks = Vec::new();
+        for camera_id in camera_ids {
+            let camera_id = camera_id.clone();
+            let client = self.cameras.get(&camera_id).map(|camera| camera.client.clone());
+            let folder = folder.clone();
+            let semaphore = self.operation_semaphore.clone();
+
+            tasks.push(tokio::spawn(async move {
+                let result = match client {
+                    Some(client) => {
+                        let _permit = semaphore.acquire().await.unwrap();
+                        client.read().await.download_logs(&folder).await
+                    }
+                    None => Err(anyhow::anyhow!("Camera not found: {}", camera_id)),
+                };
+
+                match result {
+                    Ok((path, bytes)) => LogDownloadResult { camera_id, path: Some(path), bytes, error: None },
+                    Err(e) => {
+                        log::warn!("Log download from {} failed: {}", camera_id, e);
+                        LogDownloadResult { camera_id, path: None, bytes: 0, error: Some(e.to_string()) }
+                    }
+                }
+            }));
+        }
+
+        let mut results = Vec::new();
+        for task in tasks {
+            match task.await {
+                Ok(result) => results.push(result),
+                Err(e) => log::error!("Log download task failed: {}", e),
+            }
+        }
+
+        self.metrics.observe_group_operation("group_download_logs", started.elapsed());
+        Ok(LogBundle { folder, results })
+    }
+
+    // MARK: - Anti-Banding
+
+    /// Set or clear a camera's mains frequency override (falls back to the app setting)
+    pub async fn set_camera_mains_frequency(
+        &mut self,
+        camera_id: &str,
+        mains_frequency: Option<MainsFrequency>,
+        source: ActionSource,
+    ) -> Result<()> {
+        let payload = serde_json::json!({ "mains_frequency": mains_frequency });
+        let result: Result<()> = async {
+            let camera = self.cameras.get_mut(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+            camera.info.mains_frequency = mains_frequency;
+
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after mains frequency update: {}", e);
+            }
+
+            Ok(())
+        }.await;
+
+        self.audit.record(source, "set_camera_mains_frequency", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Mains frequency used for a camera: its override, else the app setting
+    pub async fn effective_mains_frequency(&self, camera_id: &str) -> Option<MainsFrequency> {
+        if let Some(mains) = self.cameras.get(camera_id).and_then(|c| c.info.mains_frequency) {
+            return Some(mains);
+        }
+
+        self.get_app_settings().await.ok().and_then(|settings| settings.mains_frequency)
+    }
+
+    /// Frame rate a camera is (or will be) streaming at
+    pub fn current_fps(&self, camera_id: &str) -> u32 {
+        self.persisted_settings
+            .get(camera_id)
+            .and_then(|(stream, _)| stream.as_ref().map(|s| s.framerate))
+            .or_else(|| {
+                self.cameras.get(camera_id)
+                    .and_then(|c| c.info.status.as_ref().map(|s| s.current.fps))
+            })
+            .unwrap_or(StreamStartRequest::default().framerate)
+    }
+
+    /// Shutter mode a camera is in: persisted, else last reported
+    fn current_shutter_mode(&self, camera_id: &str) -> Option<ExposureMode> {
+        self.persisted_settings
+            .get(camera_id)
+            .and_then(|(_, settings)| settings.as_ref().and_then(|s| s.shutter_mode))
+            .or_else(|| {
+                self.cameras.get(camera_id)
+                    .and_then(|c| c.info.status.as_ref().map(|s| s.current.shutter_mode))
+            })
+    }
+
+    // MARK: - Lens Presets
+
+    /// Save the camera's current zoom/lens/position/focus into a preset slot
+    pub async fn save_lens_preset(
+        &mut self,
+        camera_id: &str,
+        slot: u32,
+        name: Option<String>,
+        source: ActionSource,
+    ) -> Result<LensPreset> {
+        let payload = serde_json::json!({ "slot": slot, "name": name });
+        let result: Result<LensPreset> = async {
+            check_preset_slot(slot)?;
+            let camera = self.cameras.get_mut(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+            // Capture the live framing rather than the last cached status
+            let status = camera.client.read().await.get_status().await
+                .context("Failed to read current framing")?;
+            let preset = LensPreset::from_current(&status.current, name);
+            camera.info.status = Some(status);
+            camera.info.lens_presets.insert(slot, preset.clone());
+
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after saving preset: {}", e);
+            }
+
+            Ok(preset)
+        }.await;
+
+        self.audit.record(source, "save_lens_preset", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Recall a preset slot on one camera
+    pub async fn recall_lens_preset(
+        &mut self,
+        camera_id: &str,
+        slot: u32,
+        source: ActionSource,
+    ) -> Result<SettingsOutcome> {
+        let payload = serde_json::json!({ "slot": slot });
+        let result: Result<SettingsOutcome> = async {
+            let preset = self.lens_preset(camera_id, slot)?;
+            Self::apply_camera_settings_via(ManagerMut::Borrowed(self), camera_id, preset.to_settings()).await
+        }.await;
+
+        self.audit.record(source, "recall_lens_preset", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Recall the same preset slot on several cameras at once
+    pub async fn group_recall_lens_preset(
+        &mut self,
+        camera_ids: &[String],
+        slot: u32,
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        let payload = serde_json::json!({ "slot": slot });
+        let started = Instant::now();
+
+        let result: Result<Vec<GroupCommandResult>> = async {
+            check_preset_slot(slot)?;
+
+            let mut settings = HashMap::new();
+            let mut missing = Vec::new();
+            for camera_id in camera_ids {
+                match self.lens_preset(camera_id, slot) {
+                    Ok(preset) => {
+                        settings.insert(camera_id.clone(), preset.to_settings());
+                    }
+                    Err(e) => missing.push(rejected_result(camera_id.clone(), e)),
+                }
+            }
+
+            let mut results = Self::update_settings_per_camera_via(ManagerMut::Borrowed(self), settings, ValidationPolicy::Skip).await?;
+            results.extend(missing);
+            Ok(results)
+        }.await;
+
+        self.metrics.observe_group_operation("group_recall_lens_preset", started.elapsed());
+        self.audit.record_group(source, "group_recall_lens_preset", camera_ids, &payload, &result).await;
+        result
+    }
+
+    /// Delete a preset slot; deleting an empty slot is an error
+    pub async fn delete_lens_preset(
+        &mut self,
+        camera_id: &str,
+        slot: u32,
+        source: ActionSource,
+    ) -> Result<()> {
+        let payload = serde_json::json!({ "slot": slot });
+        let result: Result<()> = async {
+            let camera = self.cameras.get_mut(camera_id)
+                .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+            camera.info.lens_presets.remove(&slot)
+                .ok_or_else(|| anyhow::anyhow!("No preset {} on {}", slot, camera_id))?;
+
+            if let Err(e) = self.save_cameras_to_disk().await {
+                log::warn!("Failed to save cameras to disk after deleting preset: {}", e);
+            }
+
+            Ok(())
+        }.await;
+
+        self.audit.record(source, "delete_lens_preset", &[camera_id.to_string()], &payload, &result).await;
+        result
+    }
+
+    /// Preset slots of a camera
+    pub fn get_lens_presets(&self, camera_id: &str) -> Result<BTreeMap<u32, LensPreset>> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+        Ok(camera.info.lens_presets.clone())
+    }
+
+    fn lens_preset(&self, camera_id: &str, slot: u32) -> Result<LensPreset> {
+        self.get_lens_presets(camera_id)?
+            .remove(&slot)
+            .ok_or_else(|| anyhow::anyhow!("No preset {} on {}", slot, camera_id))
+    }
+
+    // MARK: - Validation
+
+    /// Capabilities cached at connect time, fetched from the camera if missing
+    pub(crate) async fn camera_capabilities(&self, camera_id: &str) -> Option<Vec<Capability>> {
+        Self::camera_capabilities_via(ManagerRef::Borrowed(self), camera_id).await
+    }
+
+    async fn check_camera_settings(
+        &self,
+        camera_id: &str,
+        mut settings: CameraSettingsRequest,
+        policy: ValidationPolicy,
+    ) -> Result<(CameraSettingsRequest, Vec<FieldIssue>)> {
+        let camera = self.cameras.get(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+        let current_lens = camera.info.status.as_ref().map(|s| s.current.lens.clone());
+        let capabilities = self.camera_capabilities(camera_id).await;
+
+        let issues = validation::validate_camera_settings(
+            &mut settings, capabilities.as_deref(), current_lens.as_deref(), policy,
+        );
+        Ok((settings, issues))
+    }
+
+    async fn check_stream_settings(
+        &self,
+        camera_id: &str,
+        mut settings: StreamStartRequest,
+        policy: ValidationPolicy,
+    ) -> Result<(StreamStartRequest, Vec<FieldIssue>)> {
+        if !self.cameras.contains_key(camera_id) {
+            anyhow::bail!("Camera not found: {}", camera_id);
+        }
+        let capabilities = self.camera_capabilities(camera_id).await;
+
+        let issues = validation::validate_stream_settings(&mut settings, capabilities.as_deref(), policy);
+        Ok((settings, issues))
+    }
+
+    /// Dry-run camera settings validation for several cameras (nothing is sent)
+    pub async fn validate_camera_settings(
+        &self,
+        camera_ids: &[String],
+        settings: &CameraSettingsRequest,
+        policy: ValidationPolicy,
+    ) -> Vec<SettingsValidation> {
+        let mut reports = Vec::new();
+        for camera_id in camera_ids {
+            let issues = match self.check_camera_settings(camera_id, settings.clone(), policy).await {
+                Ok((_, issues)) => issues,
+                Err(e) => vec![FieldIssue {
+                    field: "camera_id".to_string(),
+                    severity: IssueSeverity::Error,
+                    message: e.to_string(),
+                }],
+            };
+            reports.push(SettingsValidation { camera_id: camera_id.clone(), issues });
+        }
+        reports
+    }
+
+    /// Dry-run stream settings validation for several cameras (nothing is sent)
+    pub async fn validate_stream_settings(
+        &self,
+        camera_ids: &[String],
+        settings: &StreamStartRequest,
+        policy: ValidationPolicy,
+    ) -> Vec<SettingsValidation> {
+        let mut reports = Vec::new();
+        for camera_id in camera_ids {
+            let issues = match self.check_stream_settings(camera_id, settings.clone(), policy).await {
+                Ok((_, issues)) => issues,
+                Err(e) => vec![FieldIssue {
+                    field: "camera_id".to_string(),
+                    severity: IssueSeverity::Error,
+                    message: e.to_string(),
+                }],
+            };
+            reports.push(SettingsValidation { camera_id: camera_id.clone(), issues });
+        }
+        reports
+    }
+
+    /// Validate, then convert/snap the shutter; fails with a [`SettingsRejection`] on errors
+    async fn resolve_camera_settings(
+        &self,
+        camera_id: &str,
+        settings: CameraSettingsRequest,
+        policy: ValidationPolicy,
+    ) -> Result<(CameraSettingsRequest, SettingsOutcome)> {
+        let (settings, issues) = self.check_camera_settings(camera_id, settings, policy).await?;
+        if validation::has_errors(&issues) {
+            return Err(SettingsRejection::new(camera_id, issues).into());
+        }
+
+        let mains = self.effective_mains_frequency(camera_id).await;
+        let (settings, shutter_adjustment) =
+            anti_banding::resolve_shutter(settings, self.current_fps(camera_id), mains, self.current_shutter_mode(camera_id))?;
+
+        for issue in &issues {
+            log::warn!("{} {}: {}", camera_id, issue.field, issue.message);
+        }
+
+        Ok((settings, SettingsOutcome { shutter_adjustment, warnings: issues }))
+    }
+
+    /// Validate stream settings; fails with a [`SettingsRejection`] on errors
+    async fn resolve_stream_settings(
+        &self,
+        camera_id: &str,
+        settings: StreamStartRequest,
+        policy: ValidationPolicy,
+    ) -> Result<StreamStartRequest> {
+        let (settings, issues) = self.check_stream_settings(camera_id, settings, policy).await?;
+        if validation::has_errors(&issues) {
+            return Err(SettingsRejection::new(camera_id, issues).into());
+        }
+
+        for issue in &issues {
+            log::warn!("{} {}: {}", camera_id, issue.field, issue.message);
+        }
+
+        Ok(settings)
+    }
+
+    /// Validate stream settings and snapshot the client to send them to
+    async fn prepare_stream(
+        &self,
+        camera_id: &str,
+        settings: StreamStartRequest,
+        delivery: StreamDelivery,
+    ) -> Result<PreparedStream> {
+        let settings = self.resolve_stream_settings(camera_id, settings, ValidationPolicy::Skip).await?;
+        let client = self.camera_client(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+
+        Ok(PreparedStream {
+            camera_id: camera_id.to_string(),
+            client,
+            settings,
+            delivery,
+            streaming: self.is_stream_requested(camera_id),
+        })
+    }
+
+    /// Persist stream settings a camera accepted (saving to disk is up to the caller)
+    fn remember_sent_stream(&mut self, stream: PreparedStream) {
+        match stream.delivery {
+            StreamDelivery::Start => self.mark_stream_requested(&stream.camera_id, true),
+            StreamDelivery::Update => {}
+            StreamDelivery::Bitrate => return,
+        }
+        self.remember_stream_settings(&stream.camera_id, stream.settings);
+    }
+
+    // MARK: - Persisted Settings
+
+    /// Get persisted settings for a camera
+    pub fn get_persisted_settings(&self, camera_id: &str) -> Option<(Option<StreamStartRequest>, Option<CameraSettingsRequest>)> {
+        self.persisted_settings.get(camera_id).cloned()
+    }
+
+    // MARK: - Start/Stop All Operations
+
+    /// Start all cameras with their persisted settings (or default settings if not available)
+    pub async fn start_all_cameras(&self, source: ActionSource) -> Result<Vec<GroupCommandResult>> {
+        Self::start_all_cameras_via(ManagerRef::Borrowed(self), source).await
+    }
+
+    /// Start the given cameras, each with its own persisted settings (or defaults)
+    pub async fn start_cameras_with_persisted_settings(
+        &self,
+        camera_ids: &[String],
+        source: ActionSource,
+    ) -> Result<Vec<GroupCommandResult>> {
+        Self::start_cameras_with_persisted_settings_via(ManagerRef::Borrowed(self), camera_ids, source).await
+    }
+
+    /// Persisted stream settings (or defaults) checked against the link
+    /// budgets, and the clients to start them on
+    async fn prepare_persisted_starts(&self, camera_ids: &[String]) -> PreparedStarts {
+        let mut requests: HashMap<String, StreamStartRequest> = camera_ids.iter()
+            .filter(|id| self.cameras.contains_key(*id))
+            .map(|id| (id.clone(), self.stream_settings_or_default(id)))
+            .collect();
+        let (warnings, refused) = self.enforce_bandwidth_budgets(&mut requests).await;
+
+        let to_start: Vec<String> = camera_ids.iter()
+            .filter(|id| !refused.iter().any(|r| &r.camera_id == *id))
+            .cloned()
+            .collect();
+
+        PreparedStarts {
+            clients: self.group_clients(&to_start),
+            semaphore: self.operation_semaphore.clone(),
+            requests,
+            warnings,
+            refused,
+        }
+    }
+
+    /// Stop all cameras
+    pub async fn stop_all_cameras(&self, source: ActionSource) -> Result<Vec<GroupCommandResult>> {
+        Self::stop_all_cameras_via(ManagerRef::Borrowed(self), source).await
+    }
+}
+
+// MARK: - Manager Access
+
+/// The manager a read-only operation runs on: borrowed by a `&self` method,
+/// or behind the engines' lock
+pub(crate) enum ManagerRef<'a> {
+    Borrowed(&'a CameraManager),
+    Locked(&'a RwLock<CameraManager>),
+}
+
+impl ManagerRef<'_> {
+    /// Holding the guard across camera I/O stalls every other user of the lock
+    async fn read(&self) -> ManagerReadGuard<'_> {
+        match self {
+            ManagerRef::Borrowed(manager) => ManagerReadGuard::Borrowed(manager),
+            ManagerRef::Locked(lock) => ManagerReadGuard::Locked(lock.read().await),
+        }
+    }
+}
+
+/// The manager an operation that remembers its results runs on: borrowed by
+/// a `&mut self` method, or behind the engines' lock
+pub(crate) enum ManagerMut<'a> {
+    Borrowed(&'a mut CameraManager),
+    Locked(&'a RwLock<CameraManager>),
+}
+
+impl ManagerMut<'_> {
+    /// Holding the guard across camera I/O stalls every other user of the lock
+    async fn read(&self) -> ManagerReadGuard<'_> {
+        match self {
+            ManagerMut::Borrowed(manager) => ManagerReadGuard::Borrowed(manager),
+            ManagerMut::Locked(lock) => ManagerReadGuard::Locked(lock.read().await),
+        }
+    }
+
+    /// Borrows `self` mutably, so no read guard can still be held
+    async fn write(&mut self) -> ManagerWriteGuard<'_> {
+        match self {
+            ManagerMut::Borrowed(manager) => ManagerWriteGuard::Borrowed(manager),
+            ManagerMut::Locked(lock) => ManagerWriteGuard::Locked(lock.write().await),
+        }
+    }
+
+    /// Hand the access to a nested operation
+    fn reborrow(&mut self) -> ManagerMut<'_> {
+        match self {
+            ManagerMut::Borrowed(manager) => ManagerMut::Borrowed(manager),
+            ManagerMut::Locked(lock) => ManagerMut::Locked(lock),
+        }
+    }
+}
+
+enum ManagerReadGuard<'a> {
+    Borrowed(&'a CameraManager),
+    Locked(RwLockReadGuard<'a, CameraManager>),
+}
+
+impl Deref for ManagerReadGuard<'_> {
+    type Target = CameraManager;
+
+    fn deref(&self) -> &CameraManager {
+        match self {
+            ManagerReadGuard::Borrowed(manager) => manager,
+            ManagerReadGuard::Locked(guard) => guard,
+        }
+    }
+}
+
+enum ManagerWriteGuard<'a> {
+    Borrowed(&'a mut CameraManager),
+    Locked(RwLockWriteGuard<'a, CameraManager>),
+}
+
+impl Deref for ManagerWriteGuard<'_> {
+    type Target = CameraManager;
+
+    fn deref(&self) -> &CameraManager {
+        match self {
+            ManagerWriteGuard::Borrowed(manager) => manager,
+            ManagerWriteGuard::Locked(guard) => guard,
+        }
+    }
+}
+
+impl DerefMut for ManagerWriteGuard<'_> {
+    fn deref_mut(&mut self) -> &mut CameraManager {
+        match self {
+            ManagerWriteGuard::Borrowed(manager) => manager,
+            ManagerWriteGuard::Locked(guard) => guard,
+        }
+    }
+}
+
+fn check_preset_slot(slot: u32) -> Result<()> {
+    if !(1..=MAX_LENS_PRESET_SLOT).contains(&slot) {
+        anyhow::bail!("Preset slot must be between 1 and {}, got {}", MAX_LENS_PRESET_SLOT, slot);
+    }
+    Ok(())
+}
+
+/// Run `operation` on every camera in parallel, at most `semaphore` permits at once
+async fn fan_out<F, Fut>(
+    clients: Vec<(String, Option<Arc<RwLock<CameraClient>>>)>,
+    semaphore: Arc<Semaphore>,
+    operation: F,
+) -> Result<Vec<GroupCommandResult>>
+where
+    F: Fn(String, Arc<RwLock<CameraClient>>) -> Fut + Send + Sync + 'static,
+    Fut: std::future::Future<Output = Result<()>> + Send,
+{
+    let operation = Arc::new(operation);
+    let mut tasks = Vec::new();
+
+    for (camera_id, client) in clients {
+        let Some(client) = client else {
+            // Camera not found, add error result
+            let error_msg = format!("Camera not found: {}", camera_id);
+            tasks.push(tokio::spawn(async move {
+                GroupCommandResult {
+                    camera_id,
+                    success: false,
+                    error: Some(error_msg),
+                    shutter_adjustment: None,
+                    issues: Vec::new(),
+                }
+            }));
+            continue;
+        };
+
+        let operation = operation.clone();
+        let semaphore = semaphore.clone();
+
+        // Spawn task with semaphore for bounded concurrency
+        tasks.push(tokio::spawn(async move {
+            // Acquire semaphore permit
+            let _permit = semaphore.acquire().await.unwrap();
+
+            // Execute operation
+            let result = operation(camera_id.clone(), client).await;
+
+            // Return result
+            GroupCommandResult {
+                camera_id,
+                success: result.is_ok(),
+                error: result.err().map(|e| e.to_string()),
+                shutter_adjustment: None,
+                issues: Vec::new(),
+            }
+        }));
+    }
+
+    // Wait for all tasks to complete
+    let mut results = Vec::new();
+    for task in tasks {
+        match task.await {
+            Ok(result) => results.push(result),
+            Err(e) => {
+                log::error!("Group operation task failed: {}", e);
+            }
+        }
+    }
+
+    Ok(results)
+}
+
+/// How new stream settings reach a camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum StreamDelivery {
+    /// Start the stream with them; the app refuses while it is already streaming
+    Start,
+    /// Update the stream in place if the controller started it, else only persist them
+    Update,
+    /// Change only the bitrate of the running stream, without persisting it
+    Bitrate,
+}
+
+impl StreamDelivery {
+    /// Audit action of a stream change delivered this way
+    fn action(self) -> &'static str {
+        match self {
+            StreamDelivery::Start => "start_stream",
+            StreamDelivery::Update => "update_stream_settings",
+            StreamDelivery::Bitrate => "update_stream_bitrate",
+        }
+    }
+}
+
+/// Stream settings resolved for one camera, with the client to send them to
+struct PreparedStream {
+    camera_id: String,
+    client: Arc<RwLock<CameraClient>>,
+    settings: StreamStartRequest,
+    delivery: StreamDelivery,
+    // The controller started the camera's stream
+    streaming: bool,
+}
+
+impl PreparedStream {
+    async fn send(&self) -> Result<()> {
+        let client = self.client.read().await;
+        match self.delivery {
+            StreamDelivery::Start => client.start_stream(self.settings.clone()).await,
+            StreamDelivery::Update if self.streaming => client.update_stream(self.settings.clone().into()).await,
+            StreamDelivery::Update => Ok(()),
+            StreamDelivery::Bitrate => {
+                client.update_stream(StreamUpdateRequest { bitrate: Some(self.settings.bitrate), ..Default::default() }).await
+            }
+        }
+    }
+}
+
+/// Profile resolved for its cameras, with the clients to send it to
+struct PreparedProfile {
+    camera_ids: Vec<String>,
+    // Masked camera settings, if the profile sets any
+    settings: Option<PreparedSettings>,
+    streams: Vec<PreparedStream>,
+    // Stream changes refused by validation
+    refused: Vec<(String, anyhow::Error)>,
+}
+
+impl PreparedProfile {
+    /// Send the camera settings, then the stream changes; returns one result
+    /// per camera, and the camera and stream settings the cameras accepted
+    async fn send(self) -> Result<(Vec<GroupCommandResult>, HashMap<String, CameraSettingsRequest>, Vec<PreparedStream>)> {
+        let mut results: HashMap<String, GroupCommandResult> = HashMap::new();
+        let mut applied = HashMap::new();
+
+        if let Some(settings) = self.settings {
+            let (settings_results, accepted) = settings.send().await?;
+            for result in settings_results {
+                results.insert(result.camera_id.clone(), result);
+            }
+            applied = accepted;
+        }
+
+        for (camera_id, e) in self.refused {
+            add_stream_error(&mut results, &camera_id, e);
+        }
+
+        let mut streams = Vec::new();
+        for stream in self.streams {
+            match stream.send().await {
+                Ok(()) => streams.push(stream),
+                Err(e) => add_stream_error(&mut results, &stream.camera_id, e),
+            }
+        }
+
+        Ok((profile_results(&self.camera_ids, results), applied, streams))
+    }
+}
+
+/// Camera settings resolved per camera, with the clients to send them to
+struct PreparedSettings {
+    clients: Vec<(String, Option<Arc<RwLock<CameraClient>>>)>,
+    semaphore: Arc<Semaphore>,
+    settings: HashMap<String, CameraSettingsRequest>,
+    outcomes: HashMap<String, SettingsOutcome>,
+    // Cameras refused by validation
+    rejected: Vec<GroupCommandResult>,
+}
+
+impl PreparedSettings {
+    /// Send the settings in parallel; returns one result per camera and the
+    /// settings the cameras accepted
+    async fn send(self) -> Result<(Vec<GroupCommandResult>, HashMap<String, CameraSettingsRequest>)> {
+        let PreparedSettings { clients, semaphore, settings, mut outcomes, rejected } = self;
+
+        let settings = Arc::new(settings);
+        let to_send = settings.clone();
+        let mut results = fan_out(clients, semaphore, move |camera_id, client| {
+            let camera_settings = to_send.get(&camera_id).cloned();
+            async move {
+                let camera_settings = camera_settings
+                    .ok_or_else(|| anyhow::anyhow!("No settings for camera: {}", camera_id))?;
+                client.read().await.update_camera_settings(camera_settings).await
+            }
+        }).await?;
+
+        let mut applied = HashMap::new();
+        for result in &mut results {
+            if let Some(outcome) = outcomes.remove(&result.camera_id) {
+                result.shutter_adjustment = outcome.shutter_adjustment;
+                result.issues = outcome.warnings;
+            }
+            if let (true, Some(camera_settings)) = (result.success, settings.get(&result.camera_id)) {
+                applied.insert(result.camera_id.clone(), camera_settings.clone());
+            }
+        }
+        results.extend(rejected);
+
+        Ok((results, applied))
+    }
+}
+
+/// Persisted stream settings of a group start, with the clients to start them on
+struct PreparedStarts {
+    clients: Vec<(String, Option<Arc<RwLock<CameraClient>>>)>,
+    semaphore: Arc<Semaphore>,
+    requests: HashMap<String, StreamStartRequest>,
+    warnings: HashMap<String, Vec<FieldIssue>>,
+    // Cameras refused by the bandwidth budgets
+    refused: Vec<GroupCommandResult>,
+}
+
+impl PreparedStarts {
+    /// Start the streams in parallel; returns the results of the cameras
+    /// started (or failed) and of those refused by the budgets
+    async fn send(self) -> Result<(Vec<GroupCommandResult>, Vec<GroupCommandResult>)> {
+        let PreparedStarts { clients, semaphore, requests, mut warnings, refused } = self;
+
+        let requests = Arc::new(requests);
+        let mut results = fan_out(clients, semaphore, move |camera_id, client| {
+            let stream_settings = requests.get(&camera_id).cloned().unwrap_or_default();
+            async move {
+                client.read().await.start_stream(stream_settings).await
+            }
+        }).await?;
+
+        for result in &mut results {
+            result.issues = warnings.remove(&result.camera_id).unwrap_or_default();
+        }
+
+        Ok((results, refused))
+    }
+}
+
+/// Fold a failed stream change into a camera's profile result
+fn add_stream_error(results: &mut HashMap<String, GroupCommandResult>, camera_id: &str, e: anyhow::Error) {
+    let result = results.entry(camera_id.to_string())
+        .or_insert_with(|| succeeded_result(camera_id.to_string()));
+    result.success = false;
+    result.error = Some(match result.error.take() {
+        Some(error) => format!("{}; stream: {}", error, e),
+        None => format!("stream: {}", e),
+    });
+    if let Some(rejection) = e.downcast_ref::<SettingsRejection>() {
+        result.issues.extend(rejection.issues.iter().cloned());
+    }
+}
+
+/// One result per camera of a profile, in request order
+fn profile_results(camera_ids: &[String], mut results: HashMap<String, GroupCommandResult>) -> Vec<GroupCommandResult> {
+    camera_ids.iter()
+        .map(|camera_id| results.remove(camera_id).unwrap_or_else(|| succeeded_result(camera_id.clone())))
+        .collect()
+}
+
+fn succeeded_result(camera_id: String) -> GroupCommandResult {
+    GroupCommandResult {
+        camera_id,
+        success: true,
+        error: None,
+        shutter_adjustment: None,
+        issues: Vec::new(),
+    }
+}
+
+/// Failed group result for a camera refused before sending, keeping validation issues
+fn rejected_result(camera_id: String, error: anyhow::Error) -> GroupCommandResult {
+    let issues = error.downcast_ref::<SettingsRejection>()
+        .map(|rejection| rejection.issues.clone())
+        .unwrap_or_default();
+
+    GroupCommandResult {
+        camera_id,
+        success: false,
+        error: Some(error.to_string()),
+        shutter_adjustment: None,
+        issues,
+    }
+}
+
+/// Derive the passphrase key on a blocking thread; PBKDF2 takes a noticeable
+/// moment and would otherwise stall the other cameras' tasks
+async fn open_passphrase_vault(passphrase: String, info: Option<TokenKeyInfo>) -> Result<TokenVault> {
+    tokio::task::spawn_blocking(move || TokenVault::open_passphrase(&passphrase, info.as_ref()))
+        .await
+        .context("Passphrase key derivation was interrupted")?
+}
+
+impl Drop for CameraManager {
+    fn drop(&mut self) {
+        // Clean up cameras
+        for (_, _camera) in self.cameras.drain() {
+            // WebSocket disconnection happens in camera client drop
+        }
+
+        // Stop discovery
+        if let Some(discovery) = &self.discovery {
+            discovery.stop();
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Camera event bus
+//!
+//! [`CameraManager`](crate::CameraManager) publishes telemetry, connection-state and
+//! discovery events on a broadcast channel, along with every audited control action
+//! and every alert or frontend event sent through its notifier. Background engines
+//! (automation rules, monitoring, session recording) subscribe with
+//! [`CameraManager::subscribe`](crate::CameraManager::subscribe).
+
+use serde::Serialize;
+use std::sync::Arc;
+use tokio::sync::broadcast;
+
+use crate::audit::AuditEntry;
+use crate::models::*;
+use crate::platform::Notifier;
+
+/// Capacity of the broadcast channel (1 Hz telemetry per camera leaves plenty of slack)
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Event published by the manager, its camera clients and mDNS discovery
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum CameraEvent {
+    /// Telemetry sample received over a camera's WebSocket
+    Telemetry {
+        camera_id: String,
+        telemetry: WebSocketTelemetryMessage,
+    },
+    /// Telemetry WebSocket of a camera connected or dropped
+    ConnectionChanged {
+        camera_id: String,
+        state: ConnectionState,
+    },
+    /// A camera started advertising over mDNS
+    Discovered { camera: DiscoveredCamera },
+    /// A camera stopped advertising over mDNS
+    DiscoveryLost { alias: String },
+    /// A control action was journaled in the audit log
+    Action { entry: AuditEntry },
+    /// A notification was shown to the operator
+    Alert { title: String, body: String },
+    /// A structured event was published to the frontend (`abr-step`, `battery-alert`...)
+    Notice { event: String, payload: serde_json::Value },
+}
+
+impl CameraEvent {
+    /// Managed camera the event refers to, if any
+    ///
+    /// Discovery events map to the `ip:port` id a manually added camera would use.
+    pub fn camera_id(&self) -> Option<String> {
+        match self {
+            CameraEvent::Telemetry { camera_id, .. }
+            | CameraEvent::ConnectionChanged { camera_id, .. } => Some(camera_id.clone()),
+            CameraEvent::Discovered { camera } => Some(format!("{}:{}", camera.ip, camera.port)),
+            CameraEvent::Action { entry } => match entry.camera_ids.as_slice() {
+                [camera_id] => Some(camera_id.clone()),
+                _ => None,
+            },
+            CameraEvent::Notice { payload, .. } => payload.get("camera_id")
+                .and_then(|id| id.as_str())
+                .map(str::to_string),
+            CameraEvent::DiscoveryLost { .. } | CameraEvent::Alert { .. } => None,
+        }
+    }
+}
+
+/// Sending half of the event bus
+pub type EventSender = broadcast::Sender<CameraEvent>;
+
+/// Create a new event bus
+pub fn channel() -> EventSender {
+    broadcast::channel(EVENT_CHANNEL_CAPACITY).0
+}
+
+/// [`Notifier`] that also publishes what it shows and emits on the event bus
+pub(crate) struct BusNotifier {
+    inner: Arc<dyn Notifier>,
+    events: EventSender,
+}
+
+impl BusNotifier {
+    pub fn new(inner: Arc<dyn Notifier>, events: EventSender) -> Self {
+        Self { inner, events }
+    }
+}
+
+impl Notifier for BusNotifier {
+    fn notify(&self, title: &str, body: &str) {
+        self.inner.notify(title, body);
+        let _ = self.events.send(CameraEvent::Alert {
+            title: title.to_string(),
+            body: body.to_string(),
+        });
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        self.inner.emit(event, payload.clone());
+        let _ = self.events.send(CameraEvent::Notice {
+            event: event.to_string(),
+            payload,
+        });
+    }
+}
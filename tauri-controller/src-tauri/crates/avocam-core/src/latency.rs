@@ -0,0 +1,415 @@
+//! Round-trip latency and clock offset per camera
+//!
+//! Camera clients time every HTTP call and a periodic WebSocket ping/pong, and
+//! the [`LatencyMonitor`] keeps the last, smoothed and histogram view of both.
+//! Telemetry that carries the camera's own timestamp (NTP time-stamping) gives
+//! a clock offset estimate against the controller. Cameras whose control
+//! latency goes over the threshold from the app settings are flagged, and the
+//! change is emitted as a `latency-flag` event.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::models::*;
+use crate::platform::{LogNotifier, Notifier};
+
+/// Upper bounds (milliseconds) of the RTT histogram; the last count is everything above
+const RTT_BUCKETS_MS: [f64; 10] = [5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0, 2500.0, 5000.0];
+
+/// Weight of a new sample in the smoothed RTT
+const RTT_SMOOTHING: f64 = 0.2;
+
+/// Clock offset samples kept for the estimate
+const OFFSET_WINDOW: usize = 30;
+
+/// Which channel a round trip was measured on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LatencyChannel {
+    /// Time to response headers of a control call
+    Http,
+    /// WebSocket ping to pong
+    WebSocket,
+}
+
+/// RTT counts per bucket (not cumulative)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LatencyHistogram {
+    pub bounds_ms: Vec<f64>,
+    /// One count per bound, plus a last one for samples above every bound
+    pub counts: Vec<u64>,
+}
+
+impl Default for LatencyHistogram {
+    fn default() -> Self {
+        Self {
+            bounds_ms: RTT_BUCKETS_MS.to_vec(),
+            counts: vec![0; RTT_BUCKETS_MS.len() + 1],
+        }
+    }
+}
+
+impl LatencyHistogram {
+    fn observe(&mut self, rtt_ms: f64) {
+        let bucket = RTT_BUCKETS_MS.iter()
+            .position(|&bound| rtt_ms <= bound)
+            .unwrap_or(RTT_BUCKETS_MS.len());
+        self.counts[bucket] += 1;
+    }
+}
+
+/// Round trips measured on one channel
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RttSeries {
+    pub last_ms: Option<f64>,
+    pub smoothed_ms: Option<f64>,
+    pub min_ms: Option<f64>,
+    pub max_ms: Option<f64>,
+    pub samples: u64,
+    pub last_at: Option<DateTime<Utc>>,
+    pub histogram: LatencyHistogram,
+}
+
+impl RttSeries {
+    fn observe(&mut self, rtt_ms: f64, at: DateTime<Utc>) {
+        self.last_ms = Some(rtt_ms);
+        self.smoothed_ms = Some(match self.smoothed_ms {
+            Some(smoothed) => smoothed + RTT_SMOOTHING * (rtt_ms - smoothed),
+            None => rtt_ms,
+        });
+        self.min_ms = Some(self.min_ms.map_or(rtt_ms, |min| min.min(rtt_ms)));
+        self.max_ms = Some(self.max_ms.map_or(rtt_ms, |max| max.max(rtt_ms)));
+        self.samples += 1;
+        self.last_at = Some(at);
+        self.histogram.observe(rtt_ms);
+    }
+}
+
+/// Camera clock minus controller clock
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClockOffset {
+    /// Positive when the camera clock is ahead
+    pub offset_ms: f64,
+    /// Spread between the best and the median sample (network jitter)
+    pub jitter_ms: f64,
+    pub samples: usize,
+    pub measured_at: DateTime<Utc>,
+}
+
+/// Latency view of one camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraLatency {
+    pub camera_id: String,
+    pub http: RttSeries,
+    pub websocket: RttSeries,
+    /// None until telemetry with a camera timestamp arrives
+    pub clock_offset: Option<ClockOffset>,
+    /// Smoothed control latency is over the threshold
+    pub high_latency: bool,
+}
+
+#[derive(Default)]
+struct CameraState {
+    http: RttSeries,
+    websocket: RttSeries,
+    // Camera timestamp minus the estimated send time, per telemetry sample
+    offsets: VecDeque<(DateTime<Utc>, f64)>,
+    high_latency: bool,
+}
+
+impl CameraState {
+    /// Both channels share the link, so the worse of the two is the control latency
+    fn control_latency_ms(&self) -> Option<f64> {
+        match (self.http.smoothed_ms, self.websocket.smoothed_ms) {
+            (Some(http), Some(ws)) => Some(http.max(ws)),
+            (http, ws) => http.or(ws),
+        }
+    }
+
+    /// Network delay only ever makes a sample smaller, so the largest sample of
+    /// the window is the least delayed one (as in NTP's minimum-delay filter)
+    fn clock_offset(&self) -> Option<ClockOffset> {
+        let (measured_at, _) = *self.offsets.back()?;
+        let mut samples: Vec<f64> = self.offsets.iter().map(|(_, offset)| *offset).collect();
+        samples.sort_by(f64::total_cmp);
+        let best = *samples.last()?;
+        let median = samples[samples.len() / 2];
+
+        Some(ClockOffset {
+            offset_ms: best,
+            jitter_ms: best - median,
+            samples: samples.len(),
+            measured_at,
+        })
+    }
+
+    fn snapshot(&self, camera_id: &str) -> CameraLatency {
+        CameraLatency {
+            camera_id: camera_id.to_string(),
+            http: self.http.clone(),
+            websocket: self.websocket.clone(),
+            clock_offset: self.clock_offset(),
+            high_latency: self.high_latency,
+        }
+    }
+}
+
+/// Per-camera latency series, shared with the camera clients
+pub struct LatencyMonitor {
+    cameras: Mutex<HashMap<String, CameraState>>,
+    threshold_ms: Mutex<f64>,
+    notifier: Mutex<Arc<dyn Notifier>>,
+}
+
+impl Default for LatencyMonitor {
+    fn default() -> Self {
+        Self {
+            cameras: Mutex::new(HashMap::new()),
+            threshold_ms: Mutex::new(default_latency_threshold_ms() as f64),
+            notifier: Mutex::new(Arc::new(LogNotifier)),
+        }
+    }
+}
+
+impl LatencyMonitor {
+    pub fn set_notifier(&self, notifier: Arc<dyn Notifier>) {
+        *self.notifier.lock().unwrap() = notifier;
+    }
+
+    /// Control latency above which a camera is flagged
+    pub fn set_threshold_ms(&self, threshold_ms: u32) {
+        *self.threshold_ms.lock().unwrap() = threshold_ms as f64;
+    }
+
+    pub fn observe_rtt(&self, camera_id: &str, channel: LatencyChannel, rtt: Duration) {
+        let rtt_ms = rtt.as_secs_f64() * 1000.0;
+        let threshold_ms = *self.threshold_ms.lock().unwrap();
+
+        let flag_change = {
+            let mut cameras = self.cameras.lock().unwrap();
+            let camera = cameras.entry(camera_id.to_string()).or_default();
+            match channel {
+                LatencyChannel::Http => camera.http.observe(rtt_ms, Utc::now()),
+                LatencyChannel::WebSocket => camera.websocket.observe(rtt_ms, Utc::now()),
+            }
+
+            let control_ms = camera.control_latency_ms().unwrap_or(rtt_ms);
+            let high_latency = control_ms > threshold_ms;
+            (high_latency != camera.high_latency).then(|| {
+                camera.high_latency = high_latency;
+                (high_latency, control_ms)
+            })
+        };
+
+        if let Some((high_latency, control_ms)) = flag_change {
+            if high_latency {
+                log::warn!("Control latency of {} is {:.0} ms (threshold {:.0} ms)", camera_id, control_ms, threshold_ms);
+            } else {
+                log::info!("Control latency of {} back to {:.0} ms", camera_id, control_ms);
+            }
+            let notifier = self.notifier.lock().unwrap().clone();
+            notifier.emit("latency-flag", serde_json::json!({
+                "camera_id": camera_id,
+                "high_latency": high_latency,
+                "latency_ms": control_ms,
+                "threshold_ms": threshold_ms,
+            }));
+        }
+    }
+
+    /// Add a clock offset sample from telemetry stamped by the camera
+    pub fn observe_telemetry(&self, camera_id: &str, telemetry: &WebSocketTelemetryMessage) {
+        self.observe_telemetry_at(camera_id, telemetry, Utc::now());
+    }
+
+    fn observe_telemetry_at(&self, camera_id: &str, telemetry: &WebSocketTelemetryMessage, received_at: DateTime<Utc>) {
+        let Some(camera_time) = telemetry.timestamp else {
+            return;
+        };
+
+        let mut cameras = self.cameras.lock().unwrap();
+        let camera = cameras.entry(camera_id.to_string()).or_default();
+
+        // The sample left the camera about half a round trip ago
+        let one_way_ms = camera.websocket.smoothed_ms.unwrap_or(0.0) / 2.0;
+        let offset_ms = (camera_time - received_at).num_microseconds().unwrap_or(i64::MAX) as f64 / 1000.0 + one_way_ms;
+
+        camera.offsets.push_back((received_at, offset_ms));
+        while camera.offsets.len() > OFFSET_WINDOW {
+            camera.offsets.pop_front();
+        }
+    }
+
+    pub fn stats(&self, camera_id: &str) -> Option<CameraLatency> {
+        let cameras = self.cameras.lock().unwrap();
+        cameras.get(camera_id).map(|camera| camera.snapshot(camera_id))
+    }
+
+    /// Every measured camera, sorted by id
+    pub fn all_stats(&self) -> Vec<CameraLatency> {
+        let cameras = self.cameras.lock().unwrap();
+        let mut stats: Vec<CameraLatency> = cameras.iter()
+            .map(|(camera_id, camera)| camera.snapshot(camera_id))
+            .collect();
+        stats.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        stats
+    }
+
+    pub fn forget_camera(&self, camera_id: &str) {
+        self.cameras.lock().unwrap().remove(camera_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    #[derive(Default)]
+    struct RecordingNotifier {
+        events: Mutex<Vec<serde_json::Value>>,
+    }
+
+    impl Notifier for RecordingNotifier {
+        fn notify(&self, _title: &str, _body: &str) {}
+
+        fn emit(&self, event: &str, payload: serde_json::Value) {
+            assert_eq!(event, "latency-flag");
+            self.events.lock().unwrap().push(payload);
+        }
+    }
+
+    fn stamped(camera_time: Option<DateTime<Utc>>) -> WebSocketTelemetryMessage {
+        WebSocketTelemetryMessage { timestamp: camera_time, ..test_support::telemetry() }
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// Telemetry from a camera `ahead_ms` ahead of the controller, received after `delay_ms`
+    fn receive(monitor: &LatencyMonitor, sent_at: DateTime<Utc>, ahead_ms: i64, delay_ms: i64) {
+        let camera_time = sent_at + chrono::Duration::milliseconds(ahead_ms);
+        let received_at = sent_at + chrono::Duration::milliseconds(delay_ms);
+        monitor.observe_telemetry_at("cam", &stamped(Some(camera_time)), received_at);
+    }
+
+    #[test]
+    fn rtt_series_tracks_last_smoothed_extremes_and_buckets() {
+        let monitor = LatencyMonitor::default();
+        for rtt in [10, 20, 300] {
+            monitor.observe_rtt("cam", LatencyChannel::Http, ms(rtt));
+        }
+
+        let http = monitor.stats("cam").unwrap().http;
+        assert_eq!(http.last_ms, Some(300.0));
+        assert_eq!(http.min_ms, Some(10.0));
+        assert_eq!(http.max_ms, Some(300.0));
+        assert_eq!(http.samples, 3);
+        // 10 → 12 → 69.6
+        assert!((http.smoothed_ms.unwrap() - 69.6).abs() < 1e-9);
+        assert_eq!(http.histogram.counts[1], 1); // <= 10 ms
+        assert_eq!(http.histogram.counts[2], 1); // <= 25 ms
+        assert_eq!(http.histogram.counts[6], 1); // <= 500 ms
+        assert_eq!(monitor.stats("cam").unwrap().websocket.samples, 0);
+
+        monitor.observe_rtt("cam", LatencyChannel::WebSocket, ms(9000));
+        assert_eq!(monitor.stats("cam").unwrap().websocket.histogram.counts[RTT_BUCKETS_MS.len()], 1);
+    }
+
+    #[test]
+    fn clock_offset_uses_the_least_delayed_sample() {
+        let monitor = LatencyMonitor::default();
+        // 40 ms round trips: samples are assumed 20 ms old on arrival
+        monitor.observe_rtt("cam", LatencyChannel::WebSocket, ms(40));
+        let start = Utc::now();
+        for (i, delay_ms) in [60, 20, 35, 90, 25].into_iter().enumerate() {
+            receive(&monitor, start + chrono::Duration::seconds(i as i64), 500, delay_ms);
+        }
+
+        let offset = monitor.stats("cam").unwrap().clock_offset.unwrap();
+        assert!((offset.offset_ms - 500.0).abs() < 1e-6);
+        // Median sample is the 35 ms one, 15 ms more delayed than the best
+        assert!((offset.jitter_ms - 15.0).abs() < 1e-6);
+        assert_eq!(offset.samples, 5);
+        assert_eq!(offset.measured_at, start + chrono::Duration::seconds(4) + chrono::Duration::milliseconds(25));
+    }
+
+    #[test]
+    fn camera_behind_the_controller_has_a_negative_offset() {
+        let monitor = LatencyMonitor::default();
+        receive(&monitor, Utc::now(), -1200, 0);
+
+        let offset = monitor.stats("cam").unwrap().clock_offset.unwrap();
+        assert!((offset.offset_ms + 1200.0).abs() < 1e-6);
+        assert_eq!(offset.jitter_ms, 0.0);
+    }
+
+    #[test]
+    fn offset_window_drops_old_samples() {
+        let monitor = LatencyMonitor::default();
+        let start = Utc::now();
+        // An early, very good sample falls out of the window
+        receive(&monitor, start, 500, 0);
+        for i in 1..=OFFSET_WINDOW as i64 {
+            receive(&monitor, start + chrono::Duration::seconds(i), 500, 10);
+        }
+
+        let offset = monitor.stats("cam").unwrap().clock_offset.unwrap();
+        assert_eq!(offset.samples, OFFSET_WINDOW);
+        assert!((offset.offset_ms - 490.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn telemetry_without_a_camera_timestamp_gives_no_offset() {
+        let monitor = LatencyMonitor::default();
+        monitor.observe_telemetry("cam", &stamped(None));
+        assert!(monitor.stats("cam").is_none());
+
+        monitor.observe_rtt("cam", LatencyChannel::Http, ms(10));
+        assert!(monitor.stats("cam").unwrap().clock_offset.is_none());
+    }
+
+    #[test]
+    fn high_latency_flag_is_emitted_on_changes_only() {
+        let notifier = Arc::new(RecordingNotifier::default());
+        let monitor = LatencyMonitor::default();
+        monitor.set_notifier(notifier.clone());
+        monitor.set_threshold_ms(100);
+
+        monitor.observe_rtt("cam", LatencyChannel::Http, ms(50));
+        // Control latency is the worse channel
+        monitor.observe_rtt("cam", LatencyChannel::WebSocket, ms(150));
+        monitor.observe_rtt("cam", LatencyChannel::WebSocket, ms(150));
+        assert!(monitor.stats("cam").unwrap().high_latency);
+
+        // Smoothing needs a few good samples to clear the flag
+        for _ in 0..10 {
+            monitor.observe_rtt("cam", LatencyChannel::WebSocket, ms(20));
+        }
+        assert!(!monitor.stats("cam").unwrap().high_latency);
+
+        let events = notifier.events.lock().unwrap();
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["high_latency"], true);
+        assert_eq!(events[0]["latency_ms"], 150.0);
+        assert_eq!(events[1]["high_latency"], false);
+        assert_eq!(events[1]["camera_id"], "cam");
+    }
+
+    #[test]
+    fn forgotten_cameras_leave_the_stats() {
+        let monitor = LatencyMonitor::default();
+        monitor.observe_rtt("b", LatencyChannel::Http, ms(10));
+        monitor.observe_rtt("a", LatencyChannel::Http, ms(10));
+        let ids: Vec<String> = monitor.all_stats().into_iter().map(|s| s.camera_id).collect();
+        assert_eq!(ids, ["a", "b"]);
+
+        monitor.forget_camera("a");
+        assert!(monitor.stats("a").is_none());
+        assert_eq!(monitor.all_stats().len(), 1);
+    }
+}
@@ -0,0 +1,89 @@
+//! AvoCam controller core
+//!
+//! Tauri-free building blocks of the AvoCam controller:
+//!
+//! - [`models`]: data structures matching the iOS API contracts
+//! - [`events`]: telemetry/connection/discovery event bus
+//! - [`abr`]: controller-side adaptive bitrate ladder driven by telemetry
+//! - [`anti_banding`]: 50/60 Hz flicker-free shutter snapping and shutter angles
+//! - [`audit`]: append-only journal of every control action
+//! - [`bandwidth`]: per-link bandwidth budgets and bitrate allocation
+//! - [`battery`]: battery runtime prediction and show-end alerts
+//! - [`automation`]: event-triggered "when X then Y" rules
+//! - [`session`]: session recorder writing telemetry, alerts and actions to disk
+//! - [`report`]: post-show session reports (HTML and Markdown)
+//! - [`scheduler`]: time-based stream start/stop and profile changes
+//! - [`camera_client`]: HTTP/WebSocket client for a single camera
+//! - [`camera_discovery`]: mDNS/Bonjour discovery of `_avolocam._tcp.local.` services
+//! - [`camera_manager`]: multi-camera coordination, persistence and group control
+//! - [`pairing`]: PIN/QR pairing issuing per-controller camera tokens
+//! - [`matching`]: fleet white balance and exposure matching
+//! - [`profiles`]: portable profile libraries and the shared profiles folder
+//! - [`ramp`]: smooth timed ramps of zoom, white balance, exposure and torch
+//! - [`tls`]: https/wss with trust-on-first-use certificate pinning
+//! - [`token_vault`]: camera tokens encrypted at rest, masked for the frontend
+//! - [`thermal`]: temperature-driven stream step-down and recovery
+//! - [`latency`]: HTTP/WebSocket round-trip times and camera clock offset
+//! - [`metrics`]: Prometheus `/metrics` exporter for fleet telemetry
+//! - [`validation`]: capability-aware checks of camera/stream settings before sending
+//! - [`platform`]: traits the host application implements (storage paths, notifications)
+//!
+//! The desktop app wraps [`CameraManager`] in thin `#[tauri::command]` adapters,
+//! but the core runs equally well headless, in tests or behind another frontend:
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use avocam_core::{ActionSource, CameraManager, DirectoryPaths, LogNotifier};
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let mut manager = CameraManager::new();
+//! manager.set_notifier(Arc::new(LogNotifier));
+//! manager.set_path_provider(Arc::new(DirectoryPaths::new("/tmp/avocam"))).await?;
+//!
+//! let results = manager.start_all_cameras(ActionSource::Cli).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod abr;
+pub mod anti_banding;
+pub mod audit;
+pub mod bandwidth;
+pub mod battery;
+pub mod automation;
+pub mod camera_client;
+pub mod camera_discovery;
+pub mod camera_manager;
+pub mod events;
+pub mod latency;
+pub mod matching;
+pub mod metrics;
+pub mod models;
+pub mod pairing;
+pub mod platform;
+pub mod profiles;
+pub mod ramp;
+pub mod report;
+pub mod scheduler;
+pub mod session;
+pub mod thermal;
+pub mod tls;
+pub mod token_vault;
+pub mod validation;
+
+#[cfg(test)]
+mod test_support;
+
+pub use abr::AbrEngine;
+pub use audit::ActionSource;
+pub use automation::AutomationEngine;
+pub use camera_client::CameraClient;
+pub use camera_discovery::CameraDiscovery;
+pub use camera_manager::CameraManager;
+pub use events::CameraEvent;
+pub use metrics::{ControllerMetrics, MetricsExporter};
+pub use platform::{DirectoryPaths, LogNotifier, Notifier, PathProvider};
+pub use ramp::RampEngine;
+pub use scheduler::Scheduler;
+pub use session::SessionRecorder;
+pub use thermal::ThermalGuard;
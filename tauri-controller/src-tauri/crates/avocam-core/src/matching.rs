@@ -0,0 +1,821 @@
+//! Multi-camera image matching
+//!
+//! Fleet operations that measure one or more cameras and push matched manual
+//! settings to the rest through a single group update: white balance from
+//! `measure_white_balance`, exposure from the reference's `StatusResponse.current`.
+
+use anyhow::Result;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::audit::ActionSource;
+use crate::camera_client::CameraClient;
+use crate::camera_manager::{CameraManager, ManagerMut, ManagerRef};
+use crate::models::*;
+use crate::validation::{WB_KELVIN_RANGE, WB_TINT_RANGE};
+
+/// ISO range assumed for cameras that do not report theirs (older app versions)
+const DEFAULT_ISO_RANGE: IsoRange = IsoRange { min: 25, max: 3200 };
+/// Shortest shutter considered when darkening a too-bright camera
+const MIN_SHUTTER_S: f64 = 1.0 / 10_000.0;
+/// Exposure error (in stops) still considered a match
+const EXPOSURE_TOLERANCE_STOPS: f64 = 1.0 / 6.0;
+
+// MARK: - White Balance
+
+/// Fleet white balance matching request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhiteBalanceMatchRequest {
+    /// Cameras to set (the reference camera is always included)
+    pub camera_ids: Vec<String>,
+    /// Copy this camera's measurement to every camera; `None` measures each
+    /// camera on the shared grey card and locks it to its own reading
+    pub reference_camera_id: Option<String>,
+    /// In reference mode, also measure the targets to report their residual
+    #[serde(default)]
+    pub measure_targets: bool,
+}
+
+/// Outcome for one camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhiteBalanceMatchResult {
+    pub camera_id: String,
+    pub measured: Option<WhiteBalanceMeasureResponse>,
+    pub applied_kelvin: Option<u32>,
+    pub applied_tint: Option<f64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+impl WhiteBalanceMatchResult {
+    // Applied minus measured, when both are known
+    fn residual(&self) -> Option<(f64, f64)> {
+        let measured = self.measured.as_ref()?;
+        let kelvin = self.applied_kelvin? as f64 - measured.scene_cct_k as f64;
+        let tint = self.applied_tint? - measured.tint;
+        Some((kelvin, tint))
+    }
+}
+
+/// Max-min range across cameras
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct WhiteBalanceSpread {
+    pub kelvin: f64,
+    pub tint: f64,
+}
+
+impl WhiteBalanceSpread {
+    fn of(values: impl IntoIterator<Item = (f64, f64)>) -> Option<Self> {
+        let values: Vec<(f64, f64)> = values.into_iter().collect();
+        if values.is_empty() {
+            return None;
+        }
+
+        let range = |pick: fn(&(f64, f64)) -> f64| {
+            let min = values.iter().map(pick).fold(f64::INFINITY, f64::min);
+            let max = values.iter().map(pick).fold(f64::NEG_INFINITY, f64::max);
+            max - min
+        };
+
+        Some(Self {
+            kelvin: range(|v| v.0),
+            tint: range(|v| v.1),
+        })
+    }
+}
+
+/// Measured vs applied white balance across the fleet
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhiteBalanceMatchReport {
+    pub reference_camera_id: Option<String>,
+    pub cameras: Vec<WhiteBalanceMatchResult>,
+    /// Spread of the raw measurements (how far apart the cameras were)
+    pub measured_spread: Option<WhiteBalanceSpread>,
+    /// Spread of the values the cameras were locked to
+    pub applied_spread: Option<WhiteBalanceSpread>,
+    /// Spread of applied-minus-measured offsets (how differently the cameras
+    /// still see the scene); only known in reference mode with
+    /// `measure_targets`, where the targets' readings are independent of the
+    /// applied values
+    pub residual_spread: Option<WhiteBalanceSpread>,
+}
+
+/// Matched manual white balance per camera from the measurements, and the
+/// settings to lock the cameras to
+fn plan_white_balance(
+    camera_ids: &[String],
+    reference_camera_id: Option<&str>,
+    measurements: &HashMap<String, Result<WhiteBalanceMeasureResponse>>,
+) -> Result<(Vec<WhiteBalanceMatchResult>, HashMap<String, CameraSettingsRequest>)> {
+    // Reference mode: everyone copies the reference reading
+    let shared = match reference_camera_id {
+        Some(reference) => match measurements.get(reference) {
+            Some(Ok(measured)) => Some(measured.clone()),
+            Some(Err(e)) => anyhow::bail!("Failed to measure reference camera {}: {}", reference, e),
+            None => anyhow::bail!("Reference camera {} was not measured", reference),
+        },
+        None => None,
+    };
+
+    let mut results = Vec::new();
+    let mut settings = HashMap::new();
+
+    for camera_id in camera_ids {
+        let (measured, error) = match measurements.get(camera_id) {
+            Some(Ok(measured)) => (Some(measured.clone()), None),
+            Some(Err(e)) => (None, Some(e.to_string())),
+            None => (None, None),
+        };
+
+        let target = shared.clone().or_else(|| measured.clone());
+        let (applied_kelvin, applied_tint) = match &target {
+            Some(target) => {
+                let kelvin = target.scene_cct_k.clamp(WB_KELVIN_RANGE.0, WB_KELVIN_RANGE.1);
+                let tint = target.tint.clamp(WB_TINT_RANGE.0, WB_TINT_RANGE.1);
+
+                settings.insert(camera_id.clone(), CameraSettingsRequest {
+                    wb_mode: Some(WhiteBalanceMode::Manual),
+                    wb_kelvin: Some(kelvin),
+                    wb_tint: Some(tint),
+                    ..CameraSettingsRequest::default()
+                });
+                (Some(kelvin), Some(tint))
+            }
+            None => (None, None),
+        };
+
+        results.push(WhiteBalanceMatchResult {
+            camera_id: camera_id.clone(),
+            measured,
+            applied_kelvin,
+            applied_tint,
+            // Failed to measure in grey card mode: left in auto WB
+            success: target.is_some(),
+            error,
+        });
+    }
+
+    Ok((results, settings))
+}
+
+/// Fold the group update outcome into the results and compute the spreads
+fn white_balance_report(
+    reference_camera_id: Option<String>,
+    measure_targets: bool,
+    mut results: Vec<WhiteBalanceMatchResult>,
+    applied: Vec<GroupCommandResult>,
+) -> WhiteBalanceMatchReport {
+    for outcome in applied {
+        if let Some(result) = results.iter_mut().find(|r| r.camera_id == outcome.camera_id) {
+            if !outcome.success {
+                result.success = false;
+                result.error = outcome.error;
+                result.applied_kelvin = None;
+                result.applied_tint = None;
+            }
+        }
+    }
+
+    let measured_spread = WhiteBalanceSpread::of(
+        results.iter()
+            .filter_map(|r| r.measured.as_ref())
+            .map(|m| (m.scene_cct_k as f64, m.tint)),
+    );
+    let applied_spread = WhiteBalanceSpread::of(
+        results.iter().filter_map(|r| Some((r.applied_kelvin? as f64, r.applied_tint?))),
+    );
+    // In grey card mode every camera is locked to its own reading, so the
+    // offsets are zero by construction and say nothing about the match
+    let residual_spread = (reference_camera_id.is_some() && measure_targets)
+        .then(|| WhiteBalanceSpread::of(results.iter().filter_map(|r| r.residual())))
+        .flatten();
+
+    WhiteBalanceMatchReport {
+        reference_camera_id,
+        cameras: results,
+        measured_spread,
+        applied_spread,
+        residual_spread,
+    }
+}
+
+impl CameraManager {
+    /// Measure white balance and lock every camera to matched manual values
+    ///
+    /// Measuring switches a camera to auto WB for about two seconds, so all
+    /// measurements run in parallel before the single group update. The manager
+    /// lock is only taken to snapshot clients and to record the update, never
+    /// across a measurement.
+    pub async fn match_white_balance(
+        manager: &RwLock<Self>,
+        request: WhiteBalanceMatchRequest,
+        source: ActionSource,
+    ) -> Result<WhiteBalanceMatchReport> {
+        let mut camera_ids = request.camera_ids.clone();
+        if let Some(reference) = &request.reference_camera_id {
+            if !camera_ids.contains(reference) {
+                camera_ids.insert(0, reference.clone());
+            }
+        }
+
+        if camera_ids.is_empty() {
+            anyhow::bail!("No cameras selected for white balance matching");
+        }
+
+        let to_measure: Vec<String> = match &request.reference_camera_id {
+            Some(reference) if !request.measure_targets => vec![reference.clone()],
+            _ => camera_ids.clone(),
+        };
+
+        let clients: Vec<(String, Option<Arc<RwLock<CameraClient>>>)> = {
+            let manager = manager.read().await;
+            to_measure.iter().map(|id| (id.clone(), manager.camera_client(id))).collect()
+        };
+        let measurements: HashMap<String, Result<WhiteBalanceMeasureResponse>> = {
+            let futures = clients.into_iter().map(|(id, client)| async move {
+                let measured = match client {
+                    Some(client) => client.read().await.measure_white_balance().await,
+                    None => Err(anyhow::anyhow!("Camera not found: {}", id)),
+                };
+                (id, measured)
+            });
+            join_all(futures).await.into_iter().collect()
+        };
+
+        let (results, settings) = plan_white_balance(&camera_ids, request.reference_camera_id.as_deref(), &measurements)?;
+        let applied = Self::group_update_settings_per_camera_via(ManagerMut::Locked(manager), settings, ValidationPolicy::Skip, source).await?;
+        let report = white_balance_report(request.reference_camera_id, request.measure_targets, results, applied);
+
+        log::info!("Matched white balance on {}/{} cameras",
+            report.cameras.iter().filter(|r| r.success).count(), report.cameras.len());
+
+        Ok(report)
+    }
+}
+
+// MARK: - Exposure
+
+/// Usable ISO range of a camera
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IsoRange {
+    pub min: u32,
+    pub max: u32,
+}
+
+/// Fleet exposure matching request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureMatchRequest {
+    /// Camera whose current ISO/shutter define the target exposure
+    pub reference_camera_id: String,
+    /// Cameras to set (the reference is locked to its own values as well)
+    pub camera_ids: Vec<String>,
+    /// Mains frequency for every target; defaults to each camera's anti-banding setting
+    pub mains_frequency: Option<MainsFrequency>,
+    /// Per-camera ISO range overrides, keyed by camera id (narrowed to the range
+    /// the camera reports)
+    #[serde(default)]
+    pub iso_ranges: HashMap<String, IsoRange>,
+}
+
+/// Planned and applied exposure for one camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureMatchResult {
+    pub camera_id: String,
+    pub fps: Option<u32>,
+    pub applied_iso: Option<u32>,
+    pub applied_shutter_s: Option<f64>,
+    /// Applied exposure minus target exposure, in stops (positive = brighter)
+    pub error_stops: Option<f64>,
+    /// Whether the target is reachable within tolerance under this camera's constraints
+    pub reachable: bool,
+    /// `None` when no mains frequency was given
+    pub flicker_safe: Option<bool>,
+    /// Why the target could not be reached exactly (fps cap, ISO limits, anti-flicker)
+    pub limits: Vec<String>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+/// Exposure matching outcome, with unreachable cameras listed explicitly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExposureMatchReport {
+    pub reference_camera_id: String,
+    pub reference_iso: u32,
+    pub reference_shutter_s: f64,
+    pub tolerance_stops: f64,
+    pub cameras: Vec<ExposureMatchResult>,
+    /// Cameras that could not reach the target exposure within tolerance
+    pub unreachable: Vec<String>,
+}
+
+/// ISO range the camera reports for its current format and lens
+fn reported_iso_range(capabilities: &[Capability], resolution: &str, lens: &str) -> Option<IsoRange> {
+    capabilities.iter()
+        .filter(|c| c.resolution == resolution)
+        .filter(|c| c.lens.as_deref().is_none_or(|l| l == lens))
+        .find_map(|c| Some(IsoRange { min: c.min_iso?, max: c.max_iso? }))
+        .filter(|range| range.min > 0 && range.min <= range.max)
+}
+
+/// ISO range to plan with: the override within the reported range, the reported
+/// range, or [`DEFAULT_ISO_RANGE`] when the camera reports none
+fn effective_iso_range(requested: Option<IsoRange>, reported: Option<IsoRange>) -> IsoRange {
+    match (requested, reported) {
+        (Some(requested), Some(reported)) => {
+            let narrowed = IsoRange {
+                min: requested.min.max(reported.min),
+                max: requested.max.min(reported.max),
+            };
+            if narrowed.min <= narrowed.max { narrowed } else { reported }
+        }
+        (Some(requested), None) => requested,
+        (None, Some(reported)) => reported,
+        (None, None) => DEFAULT_ISO_RANGE,
+    }
+}
+
+struct ExposurePlan {
+    iso: u32,
+    shutter_s: f64,
+    error_stops: f64,
+    flicker_safe: Option<bool>,
+    limits: Vec<String>,
+}
+
+/// Choose ISO/shutter giving `iso * shutter_s == exposure` within the camera's limits,
+/// keeping the shutter as close to the reference as possible
+fn plan_exposure(
+    exposure: f64,
+    reference_shutter_s: f64,
+    fps: u32,
+    mains: Option<MainsFrequency>,
+    iso_range: IsoRange,
+) -> ExposurePlan {
+    let mut limits = Vec::new();
+    let max_shutter = 1.0 / fps.max(1) as f64;
+    let (iso_min, iso_max) = (iso_range.min as f64, iso_range.max as f64);
+
+    // Ideal shutter: the reference's, within the frame interval, then moved
+    // just enough to bring ISO into range
+    let mut shutter = reference_shutter_s.clamp(MIN_SHUTTER_S, max_shutter);
+    if reference_shutter_s > max_shutter {
+        limits.push(format!("shutter capped at 1/{} s by {} fps", fps, fps));
+    }
+    if exposure / shutter > iso_max {
+        shutter = (exposure / iso_max).min(max_shutter);
+    } else if exposure / shutter < iso_min {
+        shutter = (exposure / iso_min).max(MIN_SHUTTER_S);
+    }
+
+    let finish = |shutter: f64| {
+        let iso = (exposure / shutter).clamp(iso_min, iso_max).round();
+        let error_stops = (iso * shutter / exposure).log2();
+        (iso as u32, error_stops)
+    };
+
+    let mut flicker_safe = None;
+    if let Some(mains) = mains {
+        // Flicker-free shutters are whole multiples of the light's period (1 / 2f)
+        let period = mains.flicker_period_s();
+        let max_multiple = (max_shutter / period + 1e-9).floor();
+
+        if max_multiple >= 1.0 {
+            let ideal = shutter / period;
+            shutter = [ideal.floor(), ideal.ceil()]
+                .into_iter()
+                .map(|n| n.clamp(1.0, max_multiple) * period)
+                .min_by(|a, b| finish(*a).1.abs().total_cmp(&finish(*b).1.abs()))
+                .unwrap_or(period);
+            flicker_safe = Some(true);
+        } else {
+            limits.push(format!("{} fps is too fast for a flicker-free shutter at {} Hz", fps, mains.hz()));
+            flicker_safe = Some(false);
+        }
+    }
+
+    let (iso, error_stops) = finish(shutter);
+    if error_stops.abs() > EXPOSURE_TOLERANCE_STOPS {
+        let bound = if error_stops < 0.0 { iso_range.max } else { iso_range.min };
+        limits.push(format!("ISO limited to {}", bound));
+    }
+
+    ExposurePlan { iso, shutter_s: shutter, error_stops, flicker_safe, limits }
+}
+
+impl CameraManager {
+    /// Lock every camera to manual ISO/shutter equivalent (in stops) to the
+    /// reference camera's current exposure
+    ///
+    /// Each target keeps its shutter as close to the reference as its frame rate
+    /// and anti-flicker constraint allow and makes up the difference with ISO.
+    /// Cameras that still miss the target are applied best-effort and listed in
+    /// [`ExposureMatchReport::unreachable`]. The manager lock is only taken to
+    /// snapshot clients and to record the update, never across a status read.
+    pub async fn match_exposure(
+        manager: &RwLock<Self>,
+        request: ExposureMatchRequest,
+        source: ActionSource,
+    ) -> Result<ExposureMatchReport> {
+        let reference_id = request.reference_camera_id.clone();
+        let mut camera_ids = request.camera_ids.clone();
+        camera_ids.retain(|id| *id != reference_id);
+
+        let (reference_client, clients) = {
+            let manager = manager.read().await;
+            let clients: Vec<(String, Option<Arc<RwLock<CameraClient>>>)> = camera_ids.iter()
+                .map(|id| (id.clone(), manager.camera_client(id)))
+                .collect();
+            (manager.camera_client(&reference_id), clients)
+        };
+
+        let reference = match reference_client {
+            Some(client) => client.read().await.get_status().await,
+            None => Err(anyhow::anyhow!("Camera not found: {}", reference_id)),
+        };
+        let reference = reference
+            .map_err(|e| anyhow::anyhow!("Failed to read reference camera {}: {}", reference_id, e))?;
+
+        let reference_iso = reference.current.iso;
+        let reference_shutter_s = reference.current.shutter_s;
+        if reference_iso == 0 || reference_shutter_s <= 0.0 {
+            anyhow::bail!(
+                "Reference camera {} reports no exposure (iso {}, shutter {} s)",
+                reference_id, reference_iso, reference_shutter_s
+            );
+        }
+        let exposure = reference_iso as f64 * reference_shutter_s;
+
+        let statuses: HashMap<String, Result<StatusResponse>> = {
+            let futures = clients.into_iter().map(|(id, client)| async move {
+                let status = match client {
+                    Some(client) => client.read().await.get_status().await,
+                    None => Err(anyhow::anyhow!("Camera not found: {}", id)),
+                };
+                (id, status)
+            });
+            join_all(futures).await.into_iter().collect()
+        };
+
+        // The reference is locked as-is so its auto exposure cannot drift away
+        let mut results = vec![ExposureMatchResult {
+            camera_id: reference_id.clone(),
+            fps: Some(reference.current.fps),
+            applied_iso: Some(reference_iso),
+            applied_shutter_s: Some(reference_shutter_s),
+            error_stops: Some(0.0),
+            reachable: true,
+            flicker_safe: None,
+            limits: Vec::new(),
+            success: true,
+            error: None,
+        }];
+        let mut settings = HashMap::from([(
+            reference_id.clone(),
+            manual_exposure(reference_iso, reference_shutter_s),
+        )]);
+
+        for camera_id in &camera_ids {
+            let status = match &statuses[camera_id] {
+                Ok(status) => status,
+                Err(e) => {
+                    results.push(ExposureMatchResult {
+                        camera_id: camera_id.clone(),
+                        fps: None,
+                        applied_iso: None,
+                        applied_shutter_s: None,
+                        error_stops: None,
+                        reachable: false,
+                        flicker_safe: None,
+                        limits: Vec::new(),
+                        success: false,
+                        error: Some(e.to_string()),
+                    });
+                    continue;
+                }
+            };
+
+            let fps = status.current.fps;
+            let capabilities = match status.capabilities.is_empty() {
+                false => status.capabilities.clone(),
+                true => Self::camera_capabilities_via(ManagerRef::Locked(manager), camera_id).await.unwrap_or_default(),
+            };
+            let iso_range = effective_iso_range(
+                request.iso_ranges.get(camera_id).copied(),
+                reported_iso_range(&capabilities, &status.current.resolution, &status.current.lens),
+            );
+            let mains = match request.mains_frequency {
+                Some(mains) => Some(mains),
+                None => manager.read().await.effective_mains_frequency(camera_id).await,
+            };
+            let plan = plan_exposure(exposure, reference_shutter_s, fps, mains, iso_range);
+
+            settings.insert(camera_id.clone(), manual_exposure(plan.iso, plan.shutter_s));
+            results.push(ExposureMatchResult {
+                camera_id: camera_id.clone(),
+                fps: Some(fps),
+                applied_iso: Some(plan.iso),
+                applied_shutter_s: Some(plan.shutter_s),
+                error_stops: Some(plan.error_stops),
+                reachable: plan.error_stops.abs() <= EXPOSURE_TOLERANCE_STOPS,
+                flicker_safe: plan.flicker_safe,
+                limits: plan.limits,
+                success: true,
+                error: None,
+            });
+        }
+
+        let applied = Self::group_update_settings_per_camera_via(ManagerMut::Locked(manager), settings, ValidationPolicy::Skip, source).await?;
+        for outcome in applied {
+            let Some(result) = results.iter_mut().find(|r| r.camera_id == outcome.camera_id) else {
+                continue;
+            };
+
+            if !outcome.success {
+                result.success = false;
+                result.error = outcome.error;
+                continue;
+            }
+
+            // Anti-banding may still have moved the shutter (e.g. the reference's own)
+            if let (Some(adjustment), Some(iso)) = (outcome.shutter_adjustment, result.applied_iso) {
+                if adjustment.snapped {
+                    let error_stops = (iso as f64 * adjustment.applied_shutter_s / exposure).log2();
+                    result.applied_shutter_s = Some(adjustment.applied_shutter_s);
+                    result.error_stops = Some(error_stops);
+                    result.reachable = error_stops.abs() <= EXPOSURE_TOLERANCE_STOPS;
+                    result.limits.push("shutter snapped for anti-banding".to_string());
+                }
+                result.flicker_safe = adjustment.flicker_safe;
+            }
+        }
+
+        let unreachable: Vec<String> = results.iter()
+            .filter(|r| !r.reachable)
+            .map(|r| r.camera_id.clone())
+            .collect();
+
+        if !unreachable.is_empty() {
+            log::warn!("Exposure target not reachable on: {}", unreachable.join(", "));
+        }
+
+        Ok(ExposureMatchReport {
+            reference_camera_id: reference_id,
+            reference_iso,
+            reference_shutter_s,
+            tolerance_stops: EXPOSURE_TOLERANCE_STOPS,
+            cameras: results,
+            unreachable,
+        })
+    }
+}
+
+fn manual_exposure(iso: u32, shutter_s: f64) -> CameraSettingsRequest {
+    CameraSettingsRequest {
+        iso_mode: Some(ExposureMode::Manual),
+        iso: Some(iso),
+        shutter_mode: Some(ExposureMode::Manual),
+        shutter_s: Some(shutter_s),
+        ..CameraSettingsRequest::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    const RANGE: IsoRange = IsoRange { min: 25, max: 3200 };
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    fn measured(readings: &[(&str, Option<(u32, f64)>)]) -> HashMap<String, Result<WhiteBalanceMeasureResponse>> {
+        readings.iter()
+            .map(|(id, reading)| {
+                let result = reading
+                    .map(|(scene_cct_k, tint)| WhiteBalanceMeasureResponse { scene_cct_k, tint })
+                    .ok_or_else(|| anyhow::anyhow!("measure timed out"));
+                (id.to_string(), result)
+            })
+            .collect()
+    }
+
+    fn applied(results: &[WhiteBalanceMatchResult]) -> Vec<(u32, f64)> {
+        results.iter().filter_map(|r| Some((r.applied_kelvin?, r.applied_tint?))).collect()
+    }
+
+    fn succeeded(camera_ids: &[String]) -> Vec<GroupCommandResult> {
+        camera_ids.iter()
+            .map(|id| GroupCommandResult {
+                camera_id: id.clone(),
+                success: true,
+                error: None,
+                shutter_adjustment: None,
+                issues: Vec::new(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn grey_card_locks_each_camera_to_its_own_reading() {
+        let camera_ids = ids(&["a", "b", "c"]);
+        let measurements = measured(&[("a", Some((5600, 2.0))), ("b", Some((5200, -3.0))), ("c", None)]);
+
+        let (results, settings) = plan_white_balance(&camera_ids, None, &measurements).unwrap();
+
+        assert_eq!(applied(&results), vec![(5600, 2.0), (5200, -3.0)]);
+        assert_eq!(settings.len(), 2);
+        assert_eq!(settings["a"].wb_mode, Some(WhiteBalanceMode::Manual));
+        assert!(!results[2].success && results[2].error.is_some(), "c stays in auto WB");
+    }
+
+    #[test]
+    fn reference_mode_copies_the_clamped_reference_reading() {
+        let camera_ids = ids(&["ref", "b"]);
+        let measurements = measured(&[("ref", Some((WB_KELVIN_RANGE.1 + 1000, WB_TINT_RANGE.0 - 10.0)))]);
+
+        let (results, settings) = plan_white_balance(&camera_ids, Some("ref"), &measurements).unwrap();
+
+        let clamped = (WB_KELVIN_RANGE.1, WB_TINT_RANGE.0);
+        assert_eq!(applied(&results), vec![clamped, clamped]);
+        assert_eq!(settings["b"].wb_kelvin, Some(WB_KELVIN_RANGE.1));
+        assert!(results[1].measured.is_none() && results[1].success);
+    }
+
+    #[test]
+    fn failed_reference_measurement_aborts_the_match() {
+        let camera_ids = ids(&["ref", "b"]);
+        let measurements = measured(&[("ref", None), ("b", Some((5000, 0.0)))]);
+
+        let error = plan_white_balance(&camera_ids, Some("ref"), &measurements).unwrap_err();
+        assert!(error.to_string().contains("reference camera ref"), "{}", error);
+    }
+
+    #[test]
+    fn residual_compares_the_shared_lock_with_each_targets_own_reading() {
+        let camera_ids = ids(&["ref", "b", "c"]);
+        let measurements = measured(&[("ref", Some((5600, 0.0))), ("b", Some((5400, 4.0))), ("c", Some((5900, -2.0)))]);
+
+        let (results, _) = plan_white_balance(&camera_ids, Some("ref"), &measurements).unwrap();
+        let report = white_balance_report(Some("ref".to_string()), true, results, succeeded(&camera_ids));
+
+        let residual = report.residual_spread.expect("targets measured");
+        assert_eq!((residual.kelvin, residual.tint), (500.0, 6.0));
+        let applied = report.applied_spread.unwrap();
+        assert_eq!((applied.kelvin, applied.tint), (0.0, 0.0));
+        assert_eq!(report.measured_spread.unwrap().kelvin, 500.0);
+    }
+
+    #[test]
+    fn residual_is_unknown_without_independent_target_readings() {
+        let camera_ids = ids(&["ref", "b"]);
+        let measurements = measured(&[("ref", Some((5600, 0.0)))]);
+        let (results, _) = plan_white_balance(&camera_ids, Some("ref"), &measurements).unwrap();
+        let report = white_balance_report(Some("ref".to_string()), false, results, succeeded(&camera_ids));
+        assert!(report.residual_spread.is_none());
+
+        let measurements = measured(&[("ref", Some((5600, 0.0))), ("b", Some((5000, 3.0)))]);
+        let (results, _) = plan_white_balance(&camera_ids, None, &measurements).unwrap();
+        let report = white_balance_report(None, true, results, succeeded(&camera_ids));
+        assert!(report.residual_spread.is_none(), "grey card offsets are zero by construction");
+        let applied = report.applied_spread.unwrap();
+        assert_eq!((applied.kelvin, applied.tint), (600.0, 3.0));
+    }
+
+    #[test]
+    fn cameras_refusing_the_update_are_reported_unapplied() {
+        let camera_ids = ids(&["a", "b"]);
+        let measurements = measured(&[("a", Some((5600, 0.0))), ("b", Some((4000, 0.0)))]);
+        let (results, _) = plan_white_balance(&camera_ids, None, &measurements).unwrap();
+
+        let mut outcomes = succeeded(&camera_ids);
+        outcomes[1].success = false;
+        outcomes[1].error = Some("timeout".to_string());
+        let report = white_balance_report(None, true, results, outcomes);
+
+        assert!(!report.cameras[1].success);
+        assert_eq!(report.cameras[1].error.as_deref(), Some("timeout"));
+        assert_eq!(report.cameras[1].applied_kelvin, None);
+        assert_eq!(report.applied_spread.unwrap().kelvin, 0.0);
+    }
+
+    fn capability(resolution: &str, lens: Option<&str>, iso: Option<(u32, u32)>) -> Capability {
+        Capability {
+            lens: lens.map(str::to_string),
+            min_iso: iso.map(|(min, _)| min),
+            max_iso: iso.map(|(_, max)| max),
+            ..test_support::capability(resolution, &[25, 30])
+        }
+    }
+
+    #[test]
+    fn plan_keeps_reference_exposure_when_reachable() {
+        let plan = plan_exposure(200.0 * 0.02, 0.02, 25, None, RANGE);
+
+        assert_eq!(plan.iso, 200);
+        assert!((plan.shutter_s - 0.02).abs() < 1e-9);
+        assert!(plan.error_stops.abs() < 1e-9);
+        assert_eq!(plan.flicker_safe, None);
+        assert!(plan.limits.is_empty());
+    }
+
+    #[test]
+    fn plan_caps_shutter_at_frame_interval() {
+        // 1/25 s at ISO 100 on a 50 fps camera: half the shutter, double the ISO
+        let plan = plan_exposure(100.0 * 0.04, 0.04, 50, None, RANGE);
+
+        assert_eq!(plan.iso, 200);
+        assert!((plan.shutter_s - 0.02).abs() < 1e-9);
+        assert!(plan.error_stops.abs() < 1e-9);
+        assert_eq!(plan.limits, vec!["shutter capped at 1/50 s by 50 fps".to_string()]);
+    }
+
+    #[test]
+    fn plan_reports_clamped_iso_range() {
+        // ISO 3200 at 1/50 s needs ISO 1920 at 1/30 s; this camera stops at 1600
+        let range = IsoRange { min: 32, max: 1600 };
+        let plan = plan_exposure(3200.0 * 0.02, 0.02, 30, None, range);
+
+        assert_eq!(plan.iso, 1600);
+        assert!((plan.shutter_s - 1.0 / 30.0).abs() < 1e-9);
+        assert!(plan.error_stops < -EXPOSURE_TOLERANCE_STOPS);
+        assert_eq!(plan.limits, vec!["ISO limited to 1600".to_string()]);
+    }
+
+    #[test]
+    fn plan_reports_too_bright_at_minimum_iso() {
+        let range = IsoRange { min: 32, max: 1600 };
+        let plan = plan_exposure(0.001, 0.02, 25, None, range);
+
+        assert_eq!(plan.iso, 32);
+        assert!((plan.shutter_s - MIN_SHUTTER_S).abs() < 1e-12);
+        assert!(plan.error_stops > EXPOSURE_TOLERANCE_STOPS);
+        assert_eq!(plan.limits, vec!["ISO limited to 32".to_string()]);
+    }
+
+    #[test]
+    fn plan_snaps_shutter_to_flicker_period_within_iso_range() {
+        // 1/60 s is not flicker-free at 50 Hz; 1/100 s would need ISO 400 (above
+        // this camera's 300), so 1/50 s at ISO 200 wins
+        let range = IsoRange { min: 25, max: 300 };
+        let plan = plan_exposure(4.0, 1.0 / 60.0, 25, Some(MainsFrequency::Hz50), range);
+
+        assert!((plan.shutter_s - 0.02).abs() < 1e-9);
+        assert_eq!(plan.iso, 200);
+        assert_eq!(plan.flicker_safe, Some(true));
+        assert!(plan.limits.is_empty());
+    }
+
+    #[test]
+    fn plan_flags_frame_rates_too_fast_for_flicker_free_shutter() {
+        let plan = plan_exposure(4.0, 0.02, 240, Some(MainsFrequency::Hz60), RANGE);
+
+        assert_eq!(plan.flicker_safe, Some(false));
+        assert!(plan.limits.iter().any(|l| l.contains("too fast")));
+    }
+
+    #[test]
+    fn reported_range_follows_current_format_and_lens() {
+        let capabilities = vec![
+            capability("3840x2160", Some("wide"), Some((40, 2000))),
+            capability("1920x1080", Some("telephoto"), Some((50, 1500))),
+            capability("1920x1080", Some("wide"), Some((32, 3072))),
+        ];
+
+        assert_eq!(
+            reported_iso_range(&capabilities, "1920x1080", "wide"),
+            Some(IsoRange { min: 32, max: 3072 }),
+        );
+        assert_eq!(
+            reported_iso_range(&capabilities, "1920x1080", "telephoto"),
+            Some(IsoRange { min: 50, max: 1500 }),
+        );
+        assert_eq!(reported_iso_range(&capabilities, "1280x720", "wide"), None);
+    }
+
+    #[test]
+    fn reported_range_is_none_for_older_cameras() {
+        let capabilities = vec![capability("1920x1080", None, None)];
+
+        assert_eq!(reported_iso_range(&capabilities, "1920x1080", "wide"), None);
+    }
+
+    #[test]
+    fn effective_range_prefers_reported_and_narrows_overrides() {
+        let reported = IsoRange { min: 32, max: 3072 };
+
+        assert_eq!(effective_iso_range(None, None), DEFAULT_ISO_RANGE);
+        assert_eq!(effective_iso_range(None, Some(reported)), reported);
+        assert_eq!(
+            effective_iso_range(Some(IsoRange { min: 100, max: 6400 }), Some(reported)),
+            IsoRange { min: 100, max: 3072 },
+        );
+        // An override outside the camera's range is ignored
+        assert_eq!(effective_iso_range(Some(IsoRange { min: 4000, max: 6400 }), Some(reported)), reported);
+        assert_eq!(effective_iso_range(Some(IsoRange { min: 100, max: 800 }), None), IsoRange { min: 100, max: 800 });
+    }
+}
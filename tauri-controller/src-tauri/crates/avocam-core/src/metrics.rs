@@ -0,0 +1,529 @@
+//! Prometheus/OpenMetrics exporter for fleet telemetry
+//!
+//! [`ControllerMetrics`] is fed by the [`CameraManager`](crate::CameraManager) and its
+//! camera clients (latest telemetry, connection changes, HTTP errors, group operation
+//! latency). [`MetricsExporter`] serves it in the Prometheus text format on a local
+//! `GET /metrics` endpoint when enabled in the app settings.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+use crate::models::*;
+
+/// Upper bounds (seconds) of the group operation latency histogram
+const LATENCY_BUCKETS: [f64; 9] = [0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+type TelemetryGauge = (&'static str, &'static str, fn(&WebSocketTelemetryMessage) -> f64);
+
+/// Per-camera gauges taken from the latest telemetry sample: (name, help, value)
+const TELEMETRY_GAUGES: [TelemetryGauge; 8] = [
+    ("avocam_camera_fps", "Measured output frame rate", |t| t.fps),
+    ("avocam_camera_bitrate_bps", "Measured output bitrate in bits per second", |t| t.bitrate as f64),
+    ("avocam_camera_queue_ms", "Encoder/network queue latency in milliseconds", |t| t.queue_ms as f64),
+    ("avocam_camera_battery_ratio", "Battery level (0-1)", |t| t.battery),
+    ("avocam_camera_temperature_celsius", "Device temperature", |t| t.temp_c),
+    ("avocam_camera_wifi_rssi_dbm", "Wi-Fi signal strength", |t| t.wifi_rssi as f64),
+    ("avocam_camera_cpu_usage_percent", "Device CPU usage", |t| t.cpu_usage),
+    ("avocam_camera_dropped_frames", "Frames dropped since the stream started", |t| t.dropped_frames as f64),
+];
+
+// MARK: - Metrics Registry
+
+#[derive(Debug, Clone)]
+struct CameraSeries {
+    alias: String,
+    connection_state: ConnectionState,
+    telemetry: Option<WebSocketTelemetryMessage>,
+    telemetry_at: Option<DateTime<Utc>>,
+    // Telemetry WebSocket (re)connections; every one after the first is a reconnect
+    connects: u64,
+}
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    buckets: [u64; LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    fn observe(&mut self, value: f64) {
+        for (bucket, bound) in self.buckets.iter_mut().zip(LATENCY_BUCKETS) {
+            if value <= bound {
+                *bucket += 1;
+            }
+        }
+        self.count += 1;
+        self.sum += value;
+    }
+}
+
+/// Controller-side metrics shared by the manager, its clients and the exporter
+#[derive(Default)]
+pub struct ControllerMetrics {
+    cameras: Mutex<HashMap<String, CameraSeries>>,
+    // Keyed by (camera_id, code); code is the HTTP status or a transport failure kind
+    http_errors: Mutex<HashMap<(String, String), u64>>,
+    group_operations: Mutex<HashMap<String, Histogram>>,
+}
+
+impl ControllerMetrics {
+    /// Start exporting series for a camera (also used to refresh its alias)
+    pub fn register_camera(&self, camera_id: &str, alias: &str) {
+        let mut cameras = self.cameras.lock().unwrap();
+        cameras
+            .entry(camera_id.to_string())
+            .and_modify(|series| series.alias = alias.to_string())
+            .or_insert_with(|| CameraSeries {
+                alias: alias.to_string(),
+                connection_state: ConnectionState::Connecting,
+                telemetry: None,
+                telemetry_at: None,
+                connects: 0,
+            });
+    }
+
+    /// Drop every series of a removed camera
+    pub fn forget_camera(&self, camera_id: &str) {
+        self.cameras.lock().unwrap().remove(camera_id);
+        self.http_errors.lock().unwrap().retain(|(id, _), _| id != camera_id);
+    }
+
+    pub fn observe_telemetry(&self, camera_id: &str, telemetry: &WebSocketTelemetryMessage) {
+        if let Some(series) = self.cameras.lock().unwrap().get_mut(camera_id) {
+            series.telemetry = Some(telemetry.clone());
+            series.telemetry_at = Some(Utc::now());
+        }
+    }
+
+    /// Latest telemetry of a camera and when it arrived
+    pub fn latest_telemetry(&self, camera_id: &str) -> Option<(WebSocketTelemetryMessage, DateTime<Utc>)> {
+        let cameras = self.cameras.lock().unwrap();
+        let series = cameras.get(camera_id)?;
+        Some((series.telemetry.clone()?, series.telemetry_at?))
+    }
+
+    pub fn observe_connection(&self, camera_id: &str, state: ConnectionState) {
+        if let Some(series) = self.cameras.lock().unwrap().get_mut(camera_id) {
+            if state == ConnectionState::Connected {
+                series.connects += 1;
+            }
+            series.connection_state = state;
+        }
+    }
+
+    /// Count a failed HTTP call (`code` is the status code, or `timeout`/`connect`/`transport`)
+    pub fn record_http_error(&self, camera_id: &str, code: &str) {
+        *self.http_errors.lock().unwrap()
+            .entry((camera_id.to_string(), code.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    /// Record how long a group operation took end to end
+    pub fn observe_group_operation(&self, operation: &str, elapsed: Duration) {
+        self.group_operations.lock().unwrap()
+            .entry(operation.to_string())
+            .or_default()
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Render every series in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let cameras = self.cameras.lock().unwrap().clone();
+
+        let mut ids: Vec<&String> = cameras.keys().collect();
+        ids.sort();
+
+        // Per-camera gauges from the latest telemetry sample
+        for (name, help, value) in TELEMETRY_GAUGES {
+            write_header(&mut out, name, help, "gauge");
+            for id in &ids {
+                let series = &cameras[*id];
+                if let Some(telemetry) = &series.telemetry {
+                    write_sample(&mut out, name, &camera_labels(id, &series.alias), value(telemetry));
+                }
+            }
+        }
+
+        write_header(&mut out, "avocam_camera_streaming", "Whether the camera is sending NDI (1) or idle (0)", "gauge");
+        for id in &ids {
+            let series = &cameras[*id];
+            if let Some(telemetry) = &series.telemetry {
+                let streaming = telemetry.ndi_state == NdiState::Streaming;
+                write_sample(&mut out, "avocam_camera_streaming", &camera_labels(id, &series.alias), streaming as u8 as f64);
+            }
+        }
+
+        write_header(&mut out, "avocam_camera_telemetry_timestamp_seconds", "Unix time of the latest telemetry sample", "gauge");
+        for id in &ids {
+            let series = &cameras[*id];
+            if let Some(at) = series.telemetry_at {
+                write_sample(&mut out, "avocam_camera_telemetry_timestamp_seconds", &camera_labels(id, &series.alias), at.timestamp_millis() as f64 / 1000.0);
+            }
+        }
+
+        write_header(&mut out, "avocam_camera_connection_state", "Telemetry connection state (1 for the current state)", "gauge");
+        for id in &ids {
+            let series = &cameras[*id];
+            for state in [ConnectionState::Connected, ConnectionState::Disconnected, ConnectionState::Connecting, ConnectionState::Error] {
+                let labels = format!("{},state=\"{}\"", camera_labels(id, &series.alias), connection_state_label(state));
+                write_sample(&mut out, "avocam_camera_connection_state", &labels, (series.connection_state == state) as u8 as f64);
+            }
+        }
+
+        // Controller-side counters
+        write_header(&mut out, "avocam_camera_reconnects_total", "Telemetry WebSocket reconnections", "counter");
+        for id in &ids {
+            let series = &cameras[*id];
+            write_sample(&mut out, "avocam_camera_reconnects_total", &camera_labels(id, &series.alias), series.connects.saturating_sub(1) as f64);
+        }
+
+        write_header(&mut out, "avocam_http_errors_total", "Failed camera HTTP calls by status code or failure kind", "counter");
+        let mut http_errors: Vec<((String, String), u64)> = self.http_errors.lock().unwrap()
+            .iter()
+            .map(|(key, count)| (key.clone(), *count))
+            .collect();
+        http_errors.sort();
+        for ((id, code), count) in http_errors {
+            let alias = cameras.get(&id).map(|s| s.alias.as_str()).unwrap_or_default();
+            let labels = format!("{},code=\"{}\"", camera_labels(&id, alias), escape_label(&code));
+            write_sample(&mut out, "avocam_http_errors_total", &labels, count as f64);
+        }
+
+        let name = "avocam_group_operation_duration_seconds";
+        write_header(&mut out, name, "End-to-end latency of group operations", "histogram");
+        let mut operations: Vec<(String, Histogram)> = self.group_operations.lock().unwrap()
+            .iter()
+            .map(|(op, histogram)| (op.clone(), histogram.clone()))
+            .collect();
+        operations.sort_by(|a, b| a.0.cmp(&b.0));
+        for (operation, histogram) in operations {
+            let op = escape_label(&operation);
+            for (bound, count) in LATENCY_BUCKETS.iter().zip(histogram.buckets) {
+                let labels = format!("operation=\"{}\",le=\"{}\"", op, bound);
+                write_sample(&mut out, &format!("{}_bucket", name), &labels, count as f64);
+            }
+            let labels = format!("operation=\"{}\",le=\"+Inf\"", op);
+            write_sample(&mut out, &format!("{}_bucket", name), &labels, histogram.count as f64);
+            let labels = format!("operation=\"{}\"", op);
+            write_sample(&mut out, &format!("{}_sum", name), &labels, histogram.sum);
+            write_sample(&mut out, &format!("{}_count", name), &labels, histogram.count as f64);
+        }
+
+        out
+    }
+}
+
+fn write_header(out: &mut String, name: &str, help: &str, kind: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn write_sample(out: &mut String, name: &str, labels: &str, value: f64) {
+    let _ = writeln!(out, "{}{{{}}} {}", name, labels, value);
+}
+
+fn camera_labels(camera_id: &str, alias: &str) -> String {
+    format!("camera_id=\"{}\",alias=\"{}\"", escape_label(camera_id), escape_label(alias))
+}
+
+fn connection_state_label(state: ConnectionState) -> &'static str {
+    match state {
+        ConnectionState::Connected => "connected",
+        ConnectionState::Disconnected => "disconnected",
+        ConnectionState::Connecting => "connecting",
+        ConnectionState::Error => "error",
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// MARK: - HTTP Endpoint
+
+/// Serves [`ControllerMetrics`] on `http://127.0.0.1:<port>/metrics`
+pub struct MetricsExporter {
+    metrics: Arc<ControllerMetrics>,
+    server: tokio::sync::Mutex<Option<JoinHandle<()>>>,
+}
+
+impl MetricsExporter {
+    pub fn new(metrics: Arc<ControllerMetrics>) -> Self {
+        Self {
+            metrics,
+            server: tokio::sync::Mutex::new(None),
+        }
+    }
+
+    /// Start, restart or stop the endpoint to match the settings
+    pub async fn apply_config(&self, config: &MetricsConfig) -> Result<()> {
+        let mut server = self.server.lock().await;
+
+        if let Some(handle) = server.take() {
+            handle.abort();
+            log::info!("Metrics endpoint stopped");
+        }
+
+        if !config.enabled {
+            return Ok(());
+        }
+
+        // Loopback only: the NOC scrapes through the host, cameras never see it
+        let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, config.port));
+        let listener = TcpListener::bind(addr).await
+            .with_context(|| format!("Failed to bind metrics endpoint on {}", addr))?;
+
+        log::info!("Metrics endpoint listening on http://{}/metrics", addr);
+
+        let metrics = self.metrics.clone();
+        *server = Some(tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, _)) => {
+                        let metrics = metrics.clone();
+                        tokio::spawn(async move {
+                            if let Err(e) = handle_connection(stream, &metrics).await {
+                                log::debug!("Metrics request failed: {}", e);
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        log::warn!("Metrics endpoint accept failed: {}", e);
+                    }
+                }
+            }
+        }));
+
+        Ok(())
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, metrics: &ControllerMetrics) -> Result<()> {
+    let request = tokio::time::timeout(REQUEST_TIMEOUT, read_request_head(&mut stream)).await
+        .context("Timed out reading request")??;
+
+    let mut parts = request.lines().next().unwrap_or_default().split_whitespace();
+    let method = parts.next().unwrap_or_default();
+    let path = parts.next().unwrap_or_default();
+
+    let (status, content_type, body) = match (method, path.split('?').next().unwrap_or_default()) {
+        ("GET", "/metrics") => ("200 OK", "text/plain; version=0.0.4; charset=utf-8", metrics.render()),
+        ("GET", _) => ("404 Not Found", "text/plain; charset=utf-8", "Not found\n".to_string()),
+        _ => ("405 Method Not Allowed", "text/plain; charset=utf-8", "Method not allowed\n".to_string()),
+    };
+
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> Result<String> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 1024];
+
+    while !buffer.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+        if buffer.len() > MAX_REQUEST_SIZE {
+            anyhow::bail!("Request too large");
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&buffer).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+
+    fn telemetry(fps: f64, ndi_state: NdiState) -> WebSocketTelemetryMessage {
+        WebSocketTelemetryMessage { fps, bitrate: 8_000_000, ndi_state, ..test_support::telemetry() }
+    }
+
+    /// Sample lines of one metric family, without the HELP/TYPE header
+    fn samples<'a>(rendered: &'a str, name: &str) -> Vec<&'a str> {
+        rendered.lines()
+            .filter(|line| line.strip_prefix(name).is_some_and(|rest| rest.starts_with('{')))
+            .collect()
+    }
+
+    #[test]
+    fn every_family_has_help_and_type_headers() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("a", "Cam A");
+        metrics.observe_telemetry("a", &telemetry(30.0, NdiState::Streaming));
+        metrics.observe_group_operation("group_start_stream", Duration::from_millis(200));
+        metrics.record_http_error("a", "500");
+        let rendered = metrics.render();
+
+        let families = TELEMETRY_GAUGES.iter().map(|(name, _, _)| (*name, "gauge")).chain([
+            ("avocam_camera_streaming", "gauge"),
+            ("avocam_camera_telemetry_timestamp_seconds", "gauge"),
+            ("avocam_camera_connection_state", "gauge"),
+            ("avocam_camera_reconnects_total", "counter"),
+            ("avocam_http_errors_total", "counter"),
+            ("avocam_group_operation_duration_seconds", "histogram"),
+        ]);
+        for (name, kind) in families {
+            assert!(rendered.contains(&format!("# HELP {} ", name)), "missing HELP for {}", name);
+            assert!(rendered.contains(&format!("# TYPE {} {}\n", name, kind)), "missing TYPE for {}", name);
+        }
+        assert!(rendered.ends_with('\n'));
+    }
+
+    #[test]
+    fn telemetry_gauges_carry_camera_labels() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("b", "Cam B");
+        metrics.register_camera("a", "Cam A");
+        metrics.observe_telemetry("a", &telemetry(29.97, NdiState::Streaming));
+        metrics.observe_telemetry("b", &telemetry(25.0, NdiState::Idle));
+        let rendered = metrics.render();
+
+        assert_eq!(samples(&rendered, "avocam_camera_fps"), [
+            "avocam_camera_fps{camera_id=\"a\",alias=\"Cam A\"} 29.97",
+            "avocam_camera_fps{camera_id=\"b\",alias=\"Cam B\"} 25",
+        ]);
+        assert_eq!(samples(&rendered, "avocam_camera_streaming"), [
+            "avocam_camera_streaming{camera_id=\"a\",alias=\"Cam A\"} 1",
+            "avocam_camera_streaming{camera_id=\"b\",alias=\"Cam B\"} 0",
+        ]);
+        assert_eq!(samples(&rendered, "avocam_camera_bitrate_bps")[0], "avocam_camera_bitrate_bps{camera_id=\"a\",alias=\"Cam A\"} 8000000");
+    }
+
+    #[test]
+    fn cameras_without_telemetry_have_no_telemetry_samples() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("a", "Cam A");
+        let rendered = metrics.render();
+
+        assert!(samples(&rendered, "avocam_camera_fps").is_empty());
+        assert!(samples(&rendered, "avocam_camera_telemetry_timestamp_seconds").is_empty());
+        assert_eq!(samples(&rendered, "avocam_camera_connection_state").len(), 4);
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("a", "Stage \"left\"\\wide\nangle");
+        metrics.observe_telemetry("a", &telemetry(30.0, NdiState::Idle));
+
+        assert_eq!(
+            samples(&metrics.render(), "avocam_camera_fps")[0],
+            "avocam_camera_fps{camera_id=\"a\",alias=\"Stage \\\"left\\\"\\\\wide\\nangle\"} 30"
+        );
+    }
+
+    #[test]
+    fn connection_state_is_one_hot_and_reconnects_skip_the_first_connect() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("a", "Cam A");
+        for state in [ConnectionState::Connected, ConnectionState::Disconnected, ConnectionState::Connected, ConnectionState::Connected] {
+            metrics.observe_connection("a", state);
+        }
+        let rendered = metrics.render();
+
+        assert_eq!(samples(&rendered, "avocam_camera_connection_state"), [
+            "avocam_camera_connection_state{camera_id=\"a\",alias=\"Cam A\",state=\"connected\"} 1",
+            "avocam_camera_connection_state{camera_id=\"a\",alias=\"Cam A\",state=\"disconnected\"} 0",
+            "avocam_camera_connection_state{camera_id=\"a\",alias=\"Cam A\",state=\"connecting\"} 0",
+            "avocam_camera_connection_state{camera_id=\"a\",alias=\"Cam A\",state=\"error\"} 0",
+        ]);
+        assert_eq!(samples(&rendered, "avocam_camera_reconnects_total"), [
+            "avocam_camera_reconnects_total{camera_id=\"a\",alias=\"Cam A\"} 2",
+        ]);
+    }
+
+    #[test]
+    fn http_errors_are_counted_per_code_and_forgotten_with_the_camera() {
+        let metrics = ControllerMetrics::default();
+        metrics.register_camera("a", "Cam A");
+        metrics.record_http_error("a", "timeout");
+        metrics.record_http_error("a", "500");
+        metrics.record_http_error("a", "500");
+
+        assert_eq!(samples(&metrics.render(), "avocam_http_errors_total"), [
+            "avocam_http_errors_total{camera_id=\"a\",alias=\"Cam A\",code=\"500\"} 2",
+            "avocam_http_errors_total{camera_id=\"a\",alias=\"Cam A\",code=\"timeout\"} 1",
+        ]);
+
+        metrics.forget_camera("a");
+        let rendered = metrics.render();
+        assert!(samples(&rendered, "avocam_http_errors_total").is_empty());
+        assert!(samples(&rendered, "avocam_camera_connection_state").is_empty());
+    }
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let metrics = ControllerMetrics::default();
+        for ms in [30, 300, 300, 40_000] {
+            metrics.observe_group_operation("group_start_stream", Duration::from_millis(ms));
+        }
+        let rendered = metrics.render();
+
+        let buckets = samples(&rendered, "avocam_group_operation_duration_seconds_bucket");
+        assert_eq!(buckets.len(), LATENCY_BUCKETS.len() + 1);
+        assert_eq!(buckets[0], "avocam_group_operation_duration_seconds_bucket{operation=\"group_start_stream\",le=\"0.05\"} 1");
+        assert_eq!(buckets[2], "avocam_group_operation_duration_seconds_bucket{operation=\"group_start_stream\",le=\"0.25\"} 1");
+        assert_eq!(buckets[3], "avocam_group_operation_duration_seconds_bucket{operation=\"group_start_stream\",le=\"0.5\"} 3");
+        assert_eq!(buckets[8], "avocam_group_operation_duration_seconds_bucket{operation=\"group_start_stream\",le=\"30\"} 3");
+        assert_eq!(buckets[9], "avocam_group_operation_duration_seconds_bucket{operation=\"group_start_stream\",le=\"+Inf\"} 4");
+        assert_eq!(samples(&rendered, "avocam_group_operation_duration_seconds_count"), [
+            "avocam_group_operation_duration_seconds_count{operation=\"group_start_stream\"} 4",
+        ]);
+        assert_eq!(samples(&rendered, "avocam_group_operation_duration_seconds_sum"), [
+            "avocam_group_operation_duration_seconds_sum{operation=\"group_start_stream\"} 40.63",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn endpoint_serves_metrics_and_rejects_other_requests() {
+        let metrics = Arc::new(ControllerMetrics::default());
+        metrics.register_camera("a", "Cam A");
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server_metrics = metrics.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let _ = handle_connection(stream, &server_metrics).await;
+            }
+        });
+
+        async fn request(addr: SocketAddr, head: &str) -> String {
+            let mut stream = TcpStream::connect(addr).await.unwrap();
+            stream.write_all(head.as_bytes()).await.unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).await.unwrap();
+            response
+        }
+
+        let response = request(addr, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: text/plain; version=0.0.4; charset=utf-8\r\n"));
+        assert!(response.ends_with(&metrics.render()));
+
+        assert!(request(addr, "GET / HTTP/1.1\r\n\r\n").await.starts_with("HTTP/1.1 404"));
+        assert!(request(addr, "POST /metrics HTTP/1.1\r\n\r\n").await.starts_with("HTTP/1.1 405"));
+    }
+}
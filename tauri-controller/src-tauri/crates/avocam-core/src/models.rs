@@ -0,0 +1,849 @@
+//! Data models matching iOS API contracts
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// MARK: - Camera Status
+
+/// `GET /api/v1/status` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusResponse {
+    pub alias: String,
+    pub ndi_state: NdiState,
+    pub current: CurrentSettings,
+    pub telemetry: Telemetry,
+    pub capabilities: Vec<Capability>,
+}
+
+/// Whether the camera is currently sending NDI
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum NdiState {
+    Streaming,
+    Idle,
+}
+
+/// Settings currently active on the camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentSettings {
+    pub resolution: String,
+    pub fps: u32,
+    pub bitrate: u32,
+    pub codec: String,
+    pub wb_mode: WhiteBalanceMode,
+    pub wb_kelvin: Option<u32>,
+    pub wb_tint: Option<f64>,
+    pub iso_mode: ExposureMode,
+    pub iso: u32,
+    pub shutter_mode: ExposureMode,
+    pub shutter_s: f64,
+    pub focus_mode: FocusMode,
+    pub zoom_factor: f64,
+    pub camera_position: String,
+    pub lens: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum WhiteBalanceMode {
+    Auto,
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FocusMode {
+    Auto,
+    Manual,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ExposureMode {
+    Auto,
+    Manual,
+}
+
+/// Telemetry snapshot embedded in the status response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Telemetry {
+    pub fps: f64,
+    pub bitrate: u32,
+    pub battery: f64,
+    pub temp_c: f64,
+    pub wifi_rssi: i32,
+    pub cpu_usage: f64,
+    pub queue_ms: Option<u32>,
+    pub dropped_frames: Option<u32>,
+    pub charging_state: Option<ChargingState>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ChargingState {
+    Charging,
+    Full,
+    Unplugged,
+}
+
+/// Battery model a camera is in; each has its own learned drain
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum BatteryRegime {
+    StreamingUnplugged,
+    StreamingCharging,
+    IdleUnplugged,
+    IdleCharging,
+}
+
+impl BatteryRegime {
+    pub fn from_telemetry(telemetry: &WebSocketTelemetryMessage) -> Self {
+        let streaming = telemetry.ndi_state == NdiState::Streaming;
+        let unplugged = telemetry.charging_state == ChargingState::Unplugged;
+        match (streaming, unplugged) {
+            (true, true) => Self::StreamingUnplugged,
+            (true, false) => Self::StreamingCharging,
+            (false, true) => Self::IdleUnplugged,
+            (false, false) => Self::IdleCharging,
+        }
+    }
+}
+
+/// Predicted battery runtime, from the recent slope of the current regime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatteryRuntime {
+    pub level: f64,
+    pub charging_state: Option<ChargingState>,
+    pub regime: BatteryRegime,
+    /// Level lost per hour (0-1, negative while charging); unknown until enough samples
+    pub drain_per_hour: Option<f64>,
+    /// `None` while the battery is not draining or the drain is still unknown
+    pub minutes_left: Option<f64>,
+    pub predicted_empty_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Predicted to run out before the configured show end
+    pub dies_before_show_end: bool,
+}
+
+/// Supported frame rates and codecs for one resolution (optionally per lens)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Capability {
+    pub resolution: String,
+    pub fps: Vec<u32>,
+    pub codec: Vec<String>,
+    pub lens: Option<String>,
+    pub max_zoom: Option<f64>,
+    /// ISO range of the format (`activeFormat.minISO/maxISO`); absent on older cameras
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_iso: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_iso: Option<u32>,
+}
+
+// MARK: - Stream Control
+
+/// `POST /api/v1/stream/start` body
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct StreamStartRequest {
+    pub resolution: String,
+    pub framerate: u32,
+    pub bitrate: u32,
+    pub codec: String,
+}
+
+impl Default for StreamStartRequest {
+    /// 1080p30 H.264 at 10 Mb/s, used when a camera has no persisted stream settings
+    fn default() -> Self {
+        Self {
+            resolution: "1920x1080".to_string(),
+            framerate: 30,
+            bitrate: 10_000_000,
+            codec: "h264".to_string(),
+        }
+    }
+}
+
+/// `POST /api/v1/stream/update` body; changes a running stream in place
+/// without restarting NDI, only `Some` fields are changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct StreamUpdateRequest {
+    pub resolution: Option<String>,
+    pub framerate: Option<u32>,
+    pub bitrate: Option<u32>,
+    pub codec: Option<String>,
+}
+
+impl From<StreamStartRequest> for StreamUpdateRequest {
+    fn from(stream: StreamStartRequest) -> Self {
+        Self {
+            resolution: Some(stream.resolution),
+            framerate: Some(stream.framerate),
+            bitrate: Some(stream.bitrate),
+            codec: Some(stream.codec),
+        }
+    }
+}
+
+// MARK: - Camera Control
+
+/// `POST /api/v1/camera` body; only `Some` fields are changed on the camera
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CameraSettingsRequest {
+    pub wb_mode: Option<WhiteBalanceMode>,
+    pub wb_kelvin: Option<u32>,
+    pub wb_tint: Option<f64>,
+    pub iso_mode: Option<ExposureMode>,
+    pub iso: Option<u32>,
+    pub shutter_mode: Option<ExposureMode>,
+    pub shutter_s: Option<f64>,
+    pub focus_mode: Option<FocusMode>,
+    pub zoom_factor: Option<f64>,
+    pub lens: Option<String>,
+    pub camera_position: Option<String>,
+    pub orientation_lock: Option<String>,
+    pub torch_level: Option<f32>,  // NDI tally torch brightness (0.01-1.0)
+    /// Controller-side only: converted to `shutter_s` for the current fps before sending
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutter_angle_deg: Option<f64>,
+}
+
+/// Mains frequency of the venue lighting, used for anti-banding shutter constraints
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum MainsFrequency {
+    #[serde(rename = "50hz")]
+    Hz50,
+    #[serde(rename = "60hz")]
+    Hz60,
+}
+
+impl MainsFrequency {
+    pub fn hz(self) -> u32 {
+        match self {
+            MainsFrequency::Hz50 => 50,
+            MainsFrequency::Hz60 => 60,
+        }
+    }
+
+    /// Light intensity period (lamps flicker at twice the mains frequency)
+    pub fn flicker_period_s(self) -> f64 {
+        1.0 / (2 * self.hz()) as f64
+    }
+}
+
+/// How the controller turned a requested manual shutter into the value sent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutterAdjustment {
+    pub requested_shutter_s: Option<f64>,
+    pub requested_angle_deg: Option<f64>,
+    pub fps: u32,
+    pub mains_frequency: Option<MainsFrequency>,
+    pub applied_shutter_s: f64,
+    /// The applied value differs from the requested (or angle-derived) one
+    pub snapped: bool,
+    /// `None` without a mains frequency; `false` when the fps leaves no flicker-free
+    /// shutter or the shutter is shorter than one flicker period
+    pub flicker_safe: Option<bool>,
+}
+
+// MARK: - Profiles
+
+/// Named set of camera (and optionally stream) settings that can be applied to several cameras
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraProfile {
+    pub name: String,
+    pub settings: CameraSettingsRequest,
+    /// Resolution/fps/bitrate/codec to apply along with the camera settings
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stream: Option<StreamStartRequest>,
+    /// Fields to apply; empty applies every field the profile sets
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<ProfileField>,
+    #[serde(default)]
+    pub metadata: ProfileMetadata,
+    /// Loaded from the shared profiles folder (read-only)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub shared: bool,
+}
+
+/// Authoring details carried along when profiles are exported
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileMetadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Device the look was built for, e.g. "iPhone 15 Pro"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_model: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Entry of a profile field mask: a single field or a group of fields
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ProfileField {
+    WbMode,
+    WbKelvin,
+    WbTint,
+    IsoMode,
+    Iso,
+    ShutterMode,
+    ShutterS,
+    ShutterAngleDeg,
+    FocusMode,
+    ZoomFactor,
+    Lens,
+    CameraPosition,
+    OrientationLock,
+    TorchLevel,
+    Resolution,
+    Framerate,
+    Bitrate,
+    Codec,
+    /// `wb_mode`, `wb_kelvin`, `wb_tint`
+    WhiteBalance,
+    /// ISO and shutter fields
+    Exposure,
+    /// `zoom_factor`, `lens`, `camera_position`
+    Framing,
+    /// Every stream field
+    Stream,
+}
+
+impl ProfileField {
+    /// Whether this mask entry covers `field`
+    fn covers(self, field: ProfileField) -> bool {
+        use ProfileField::*;
+        match self {
+            WhiteBalance => matches!(field, WbMode | WbKelvin | WbTint),
+            Exposure => matches!(field, IsoMode | Iso | ShutterMode | ShutterS | ShutterAngleDeg),
+            Framing => matches!(field, ZoomFactor | Lens | CameraPosition),
+            Stream => matches!(field, Resolution | Framerate | Bitrate | Codec),
+            _ => self == field,
+        }
+    }
+}
+
+impl CameraProfile {
+    /// Whether the field mask lets `field` through
+    pub fn includes(&self, field: ProfileField) -> bool {
+        self.fields.is_empty() || self.fields.iter().any(|f| f.covers(field))
+    }
+
+    /// Camera settings restricted to the field mask, or `None` if nothing is left
+    pub fn masked_settings(&self) -> Option<CameraSettingsRequest> {
+        let source = &self.settings;
+        let mut masked = CameraSettingsRequest::default();
+        let mut any = false;
+
+        macro_rules! copy_field {
+            ($field:ident, $mask:ident) => {
+                if source.$field.is_some() && self.includes(ProfileField::$mask) {
+                    masked.$field = source.$field.clone();
+                    any = true;
+                }
+            };
+        }
+
+        copy_field!(wb_mode, WbMode);
+        copy_field!(wb_kelvin, WbKelvin);
+        copy_field!(wb_tint, WbTint);
+        copy_field!(iso_mode, IsoMode);
+        copy_field!(iso, Iso);
+        copy_field!(shutter_mode, ShutterMode);
+        copy_field!(shutter_s, ShutterS);
+        copy_field!(shutter_angle_deg, ShutterAngleDeg);
+        copy_field!(focus_mode, FocusMode);
+        copy_field!(zoom_factor, ZoomFactor);
+        copy_field!(lens, Lens);
+        copy_field!(camera_position, CameraPosition);
+        copy_field!(orientation_lock, OrientationLock);
+        copy_field!(torch_level, TorchLevel);
+
+        any.then_some(masked)
+    }
+
+    /// Masked stream fields laid over `current`, or `None` without a stream section
+    pub fn masked_stream(&self, current: &StreamStartRequest) -> Option<StreamStartRequest> {
+        let stream = self.stream.as_ref()?;
+        let mut merged = current.clone();
+
+        if self.includes(ProfileField::Resolution) {
+            merged.resolution = stream.resolution.clone();
+        }
+        if self.includes(ProfileField::Framerate) {
+            merged.framerate = stream.framerate;
+        }
+        if self.includes(ProfileField::Bitrate) {
+            merged.bitrate = stream.bitrate;
+        }
+        if self.includes(ProfileField::Codec) {
+            merged.codec = stream.codec.clone();
+        }
+
+        Some(merged)
+    }
+}
+
+// MARK: - Lens Presets
+
+/// Highest lens preset slot number (slots start at 1)
+pub const MAX_LENS_PRESET_SLOT: u32 = 99;
+
+/// Numbered framing preset of one camera, like a PTZ preset
+///
+/// Manual focus position is not exposed by the camera API yet, so only the
+/// focus mode is stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LensPreset {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub zoom_factor: f64,
+    pub lens: String,
+    pub camera_position: String,
+    pub focus_mode: FocusMode,
+    pub saved_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl LensPreset {
+    /// Capture the framing a camera currently reports
+    pub fn from_current(current: &CurrentSettings, name: Option<String>) -> Self {
+        Self {
+            name,
+            zoom_factor: current.zoom_factor,
+            lens: current.lens.clone(),
+            camera_position: current.camera_position.clone(),
+            focus_mode: current.focus_mode,
+            saved_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Settings request that recalls this preset (framing fields only)
+    pub fn to_settings(&self) -> CameraSettingsRequest {
+        CameraSettingsRequest {
+            zoom_factor: Some(self.zoom_factor),
+            lens: Some(self.lens.clone()),
+            camera_position: Some(self.camera_position.clone()),
+            focus_mode: Some(self.focus_mode),
+            ..Default::default()
+        }
+    }
+}
+
+// MARK: - WebSocket Messages
+
+/// Telemetry message sent from iOS camera to controller via WebSocket (1Hz)
+/// This is the Server→Client direction of the WebSocket protocol
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WebSocketTelemetryMessage {
+    pub fps: f64,
+    pub bitrate: u32,
+    pub queue_ms: u32,
+    pub battery: f64,
+    pub temp_c: f64,
+    pub wifi_rssi: i32,
+    pub cpu_usage: f64,
+    pub ndi_state: NdiState,
+    pub dropped_frames: u32,
+    pub charging_state: ChargingState,
+    /// Camera clock when the sample was taken (NTP time-stamping), if sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Command message to be sent from controller to iOS camera via WebSocket
+/// This is the Client→Server direction of the WebSocket protocol
+///
+/// **Status:** Defined but not yet implemented (LOT C - Image Quality & Ops)
+///
+/// **Purpose:** Enable low-latency camera control commands via WebSocket
+/// instead of HTTP POST. Useful for real-time adjustments like manual focus/zoom.
+///
+/// **Example payload:**
+/// ```json
+/// {
+///   "op": "set",
+///   "camera": {
+///     "focus_mode": "manual",
+///     "zoom_factor": 2.0
+///   }
+/// }
+/// ```
+///
+/// **To implement:**
+/// 1. Modify `CameraClient::connect_websocket()` to support bidirectional communication
+/// 2. Add iOS WebSocket handler for incoming commands in `WebSocketHandler.swift`
+/// 3. Add Tauri command `send_camera_command_ws()` for frontend to use
+///
+/// See [DEAD_CODE_ANALYSIS.md](../../../../DEAD_CODE_ANALYSIS.md#1-websocketcommandmessage) for full implementation guide
+#[allow(dead_code)]
+pub struct WebSocketCommandMessage {
+    pub op: String,
+    pub camera: Option<CameraSettingsRequest>,
+}
+
+// MARK: - White Balance Measure
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WhiteBalanceMeasureResponse {
+    pub scene_cct_k: u32,  // Physical scene illumination temperature
+    pub tint: f64,
+}
+
+// MARK: - Diagnostics
+
+/// One point of the camera's temperature history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemperatureSample {
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub temp_c: f64,
+}
+
+/// `GET /api/v1/diag` payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiagnosticsResponse {
+    /// Frames dropped since the stream started
+    pub dropped_frames: u64,
+    /// Frames waiting in the encoder/NDI send queue
+    pub queue_depth: u32,
+    /// Oldest first
+    #[serde(default)]
+    pub temperature_timeline: Vec<TemperatureSample>,
+}
+
+/// Log archive fetched from one camera of a group download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogDownloadResult {
+    pub camera_id: String,
+    pub path: Option<std::path::PathBuf>,
+    pub bytes: u64,
+    pub error: Option<String>,
+}
+
+/// Timestamped folder holding the log archives of a group download
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LogBundle {
+    pub folder: std::path::PathBuf,
+    pub results: Vec<LogDownloadResult>,
+}
+
+// MARK: - Pairing
+
+/// How the operator proves they hold the phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PairingMethod {
+    /// The phone shows a short PIN the operator types into the controller
+    Pin,
+    /// The controller shows a QR code the phone scans
+    Qr,
+}
+
+/// `POST /api/v1/pair/request` body (unauthenticated)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingRequest {
+    pub controller_id: String,
+    pub controller_name: String,
+    pub method: PairingMethod,
+}
+
+/// `POST /api/v1/pair/request` response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingResponse {
+    pub pairing_id: String,
+    pub expires_in_s: u32,
+}
+
+/// `POST /api/v1/pair/confirm` body; `code` is the PIN or the QR code secret
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingConfirmRequest {
+    pub pairing_id: String,
+    pub code: String,
+}
+
+/// `POST /api/v1/pair/confirm` response: a token issued to this controller only
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingConfirmResponse {
+    pub token: String,
+}
+
+/// `POST /api/v1/pair/revoke` body (authenticated with the token being revoked)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingRevokeRequest {
+    pub controller_id: String,
+}
+
+// MARK: - Error Response
+
+/// Uniform JSON error returned by the camera API
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub code: String,
+    pub message: String,
+}
+
+// MARK: - Camera Info
+
+/// Controller-side view of a managed camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraInfo {
+    pub id: String,
+    pub alias: String,
+    pub ip: String,
+    pub port: u16,
+    /// Masked when serialized; the full token stays in the core
+    #[serde(serialize_with = "crate::token_vault::serialize_masked")]
+    pub token: String,
+    #[serde(default)]
+    pub scheme: CameraScheme,
+    /// SHA-256 of the pinned TLS certificate (https cameras, once trusted)
+    #[serde(default)]
+    pub cert_fingerprint: Option<String>,
+    pub status: Option<StatusResponse>,
+    pub connection_state: ConnectionState,
+    /// Per-camera anti-banding override of the app-level mains frequency
+    #[serde(default)]
+    pub mains_frequency: Option<MainsFrequency>,
+    /// Lens presets keyed by slot number
+    #[serde(default)]
+    pub lens_presets: BTreeMap<u32, LensPreset>,
+    /// Predicted battery runtime (not persisted)
+    #[serde(default)]
+    pub battery_runtime: Option<BatteryRuntime>,
+}
+
+/// Transport of a camera's control API and telemetry feed
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum CameraScheme {
+    /// `http://` and `ws://`
+    #[default]
+    Http,
+    /// `https://` and `wss://` with a pinned self-signed certificate
+    Https,
+}
+
+/// Controller connection state for a camera
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConnectionState {
+    Connected,
+    Disconnected,
+    Connecting,
+    Error,
+}
+
+// MARK: - Discovery
+
+/// Camera advertised over mDNS
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveredCamera {
+    pub alias: String,
+    pub ip: String,
+    pub port: u16,
+    pub txt_records: std::collections::HashMap<String, String>,
+}
+
+// MARK: - Group Control
+
+/// Per-camera outcome of a group operation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GroupCommandResult {
+    pub camera_id: String,
+    pub success: bool,
+    pub error: Option<String>,
+    /// Manual shutter conversion/snapping applied to this camera, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shutter_adjustment: Option<ShutterAdjustment>,
+    /// Validation errors (camera skipped) or warnings (value clamped)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub issues: Vec<FieldIssue>,
+}
+
+// MARK: - Validation
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueSeverity {
+    Error,
+    Warning,
+}
+
+/// Problem with one field of a settings request, checked against the camera's capabilities
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldIssue {
+    pub field: String,
+    pub severity: IssueSeverity,
+    pub message: String,
+}
+
+/// What to do with out-of-range values in group operations
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationPolicy {
+    /// Skip any camera whose settings have errors
+    #[default]
+    Skip,
+    /// Clamp numeric values into range (reported as warnings); skip only on
+    /// errors that cannot be clamped, such as an unsupported codec
+    Clamp,
+}
+
+/// What the controller adjusted or flagged while applying camera settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SettingsOutcome {
+    pub shutter_adjustment: Option<ShutterAdjustment>,
+    pub warnings: Vec<FieldIssue>,
+}
+
+// MARK: - App Settings
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertSettings {
+    pub enabled: bool,
+    #[serde(rename = "temperatureThreshold")]
+    pub temperature_threshold: f64,
+    #[serde(rename = "cpuThreshold")]
+    pub cpu_threshold: f64,
+    #[serde(rename = "batteryLowThreshold")]
+    pub battery_low_threshold: f64,
+    #[serde(rename = "batteryCriticalThreshold")]
+    pub battery_critical_threshold: f64,
+}
+
+/// Controller application settings (`settings.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppSettings {
+    pub alerts: AlertsConfig,
+    // Optional for backward compatibility with older settings.json files
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    /// Venue mains frequency for anti-banding; cameras may override it
+    #[serde(default, rename = "mainsFrequency")]
+    pub mains_frequency: Option<MainsFrequency>,
+    /// Read-only folder of shared profile libraries, loaded alongside `profiles.json`
+    #[serde(default, rename = "sharedProfilesDir")]
+    pub shared_profiles_dir: Option<std::path::PathBuf>,
+    /// Link capacities shared by sets of cameras, checked on group start
+    #[serde(default, rename = "linkBudgets")]
+    pub link_budgets: Vec<LinkBudget>,
+    #[serde(default, rename = "budgetPolicy")]
+    pub budget_policy: BudgetPolicy,
+    /// Cameras predicted to run out of battery before this time raise an alert
+    #[serde(default, rename = "showEndTime")]
+    pub show_end_time: Option<chrono::DateTime<chrono::Utc>>,
+    /// Cameras whose control round trip is slower than this are flagged
+    #[serde(default = "default_latency_threshold_ms", rename = "latencyThresholdMs")]
+    pub latency_threshold_ms: u32,
+}
+
+/// User-declared capacity of one access point (or any group of cameras sharing a link)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkBudget {
+    pub name: String,
+    #[serde(rename = "capacityBps")]
+    pub capacity_bps: u64,
+    #[serde(rename = "cameraIds")]
+    pub camera_ids: Vec<String>,
+    /// Share of the capacity kept free for retransmits and other traffic
+    #[serde(default = "default_reserve_ratio", rename = "reserveRatio")]
+    pub reserve_ratio: f64,
+}
+
+fn default_reserve_ratio() -> f64 {
+    0.25
+}
+
+pub fn default_latency_threshold_ms() -> u32 {
+    250
+}
+
+impl LinkBudget {
+    /// Capacity left for streams once the reserve is set aside
+    pub fn usable_bps(&self) -> u64 {
+        (self.capacity_bps as f64 * (1.0 - self.reserve_ratio.clamp(0.0, 1.0))) as u64
+    }
+}
+
+/// What group start does when the planned total exceeds a link budget
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BudgetPolicy {
+    /// Start anyway, with a warning on each camera of the link
+    #[default]
+    Warn,
+    /// Leave the link's starting cameras out
+    Refuse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AlertsConfig {
+    pub temperature: AlertSettings,
+    pub cpu: AlertSettings,
+    #[serde(rename = "batteryLow")]
+    pub battery_low: AlertSettings,
+    #[serde(rename = "batteryCritical")]
+    pub battery_critical: AlertSettings,
+}
+
+/// Local Prometheus `/metrics` endpoint (off by default)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: 9464,
+        }
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            alerts: AlertsConfig {
+                temperature: AlertSettings {
+                    enabled: true,
+                    temperature_threshold: 40.0,
+                    cpu_threshold: 0.0,
+                    battery_low_threshold: 0.0,
+                    battery_critical_threshold: 0.0,
+                },
+                cpu: AlertSettings {
+                    enabled: true,
+                    temperature_threshold: 0.0,
+                    cpu_threshold: 100.0,
+                    battery_low_threshold: 0.0,
+                    battery_critical_threshold: 0.0,
+                },
+                battery_low: AlertSettings {
+                    enabled: true,
+                    temperature_threshold: 0.0,
+                    cpu_threshold: 0.0,
+                    battery_low_threshold: 25.0,
+                    battery_critical_threshold: 0.0,
+                },
+                battery_critical: AlertSettings {
+                    enabled: true,
+                    temperature_threshold: 0.0,
+                    cpu_threshold: 0.0,
+                    battery_low_threshold: 0.0,
+                    battery_critical_threshold: 10.0,
+                },
+            },
+            metrics: MetricsConfig::default(),
+            mains_frequency: None,
+            shared_profiles_dir: None,
+            link_budgets: Vec::new(),
+            budget_policy: BudgetPolicy::default(),
+            show_end_time: None,
+            latency_threshold_ms: default_latency_threshold_ms(),
+        }
+    }
+}
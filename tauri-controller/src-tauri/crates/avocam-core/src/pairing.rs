@@ -0,0 +1,118 @@
+//! Pairing flow issuing per-controller camera tokens
+//!
+//! Instead of typing a bearer token, the operator pairs the controller with a
+//! camera:
+//!
+//! 1. The controller sends `POST /api/v1/pair/request` with its id and name.
+//! 2. With [`PairingMethod::Pin`] the phone shows a short PIN that the operator
+//!    types into the controller. With [`PairingMethod::Qr`] the controller shows
+//!    a QR code holding a random secret, and the phone scans it.
+//! 3. The controller sends `POST /api/v1/pair/confirm` with the PIN or secret
+//!    and receives a token issued to this controller only.
+//!
+//! `POST /api/v1/pair/revoke` invalidates the token of one controller without
+//! affecting the others.
+//!
+//! The iOS app only implements PIN pairing for now. QR pairing is kept on the
+//! controller side for cameras that can scan, and the app refuses it with
+//! `UNSUPPORTED_METHOD`.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::camera_client::CameraClient;
+use crate::models::*;
+
+/// Random bytes behind a controller id or a QR code secret
+const ID_BYTES: usize = 16;
+
+/// A pairing waiting for the PIN (or for the phone to scan the QR code)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PairingSession {
+    pub pairing_id: String,
+    pub camera_id: String,
+    pub method: PairingMethod,
+    /// Text to render as a QR code ([`PairingMethod::Qr`] only)
+    pub qr_payload: Option<String>,
+    pub expires_at: DateTime<Utc>,
+}
+
+pub(crate) struct PendingPairing {
+    pub ip: String,
+    pub port: u16,
+    pub scheme: CameraScheme,
+    pub method: PairingMethod,
+    // QR code secret
+    pub code: Option<String>,
+    // Keeps the certificate trusted when the pairing started (https cameras)
+    pub client: Arc<CameraClient>,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Pairings started but not confirmed yet
+#[derive(Default)]
+pub(crate) struct PairingRegistry {
+    pending: HashMap<String, PendingPairing>,
+}
+
+impl PairingRegistry {
+    pub fn insert(&mut self, pairing_id: String, pairing: PendingPairing) {
+        let now = Utc::now();
+        self.pending.retain(|_, pending| pending.expires_at > now);
+        self.pending.insert(pairing_id, pairing);
+    }
+
+    pub fn get(&self, pairing_id: &str) -> Result<&PendingPairing> {
+        let pairing = self.pending.get(pairing_id)
+            .ok_or_else(|| anyhow::anyhow!("Pairing not found: {}", pairing_id))?;
+        if pairing.expires_at <= Utc::now() {
+            anyhow::bail!("Pairing {} has expired; start again", pairing_id);
+        }
+        Ok(pairing)
+    }
+
+    pub fn remove(&mut self, pairing_id: &str) {
+        self.pending.remove(pairing_id);
+    }
+}
+
+/// Session shown to the operator for a pairing the camera accepted
+pub(crate) fn session(
+    camera_id: &str,
+    controller_id: &str,
+    method: PairingMethod,
+    response: &PairingResponse,
+    code: Option<&str>,
+) -> PairingSession {
+    PairingSession {
+        pairing_id: response.pairing_id.clone(),
+        camera_id: camera_id.to_string(),
+        method,
+        qr_payload: code.map(|code| serde_json::json!({
+            "pairing_id": response.pairing_id,
+            "code": code,
+            "controller_id": controller_id,
+            "controller_name": controller_name(),
+        }).to_string()),
+        expires_at: Utc::now() + Duration::seconds(response.expires_in_s as i64),
+    }
+}
+
+/// Random hex id (controller ids, QR code secrets)
+pub(crate) fn random_id() -> Result<String> {
+    let mut bytes = [0u8; ID_BYTES];
+    SystemRandom::new().fill(&mut bytes)
+        .map_err(|_| anyhow::anyhow!("System random generator unavailable"))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// Name the phone shows for this controller
+pub(crate) fn controller_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .unwrap_or_else(|_| "AvoCam Controller".to_string())
+}
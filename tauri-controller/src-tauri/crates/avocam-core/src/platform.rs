@@ -0,0 +1,124 @@
+//! Host integration points injected into the core
+//!
+//! The core never talks to a windowing toolkit or an OS-specific app directory
+//! directly. Instead the host (Tauri app, CLI, test harness) provides:
+//!
+//! - a [`PathProvider`] telling the manager where to keep its JSON files
+//! - a [`Notifier`] used to surface notifications and events to the user
+
+use anyhow::{Context, Result};
+use std::path::PathBuf;
+
+// MARK: - Persistence Paths
+
+/// Resolves where persisted controller data lives
+///
+/// Only [`PathProvider::data_dir`] is required; individual file locations default
+/// to well-known names inside that directory and can be overridden when needed.
+pub trait PathProvider: Send + Sync {
+    /// Directory holding all controller data (created by the implementation if missing)
+    fn data_dir(&self) -> Result<PathBuf>;
+
+    /// Persisted camera list (`cameras.json`)
+    fn cameras_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("cameras.json"))
+    }
+
+    /// Saved camera profiles (`profiles.json`)
+    fn profiles_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("profiles.json"))
+    }
+
+    /// Application settings (`settings.json`)
+    fn settings_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("settings.json"))
+    }
+
+    /// Automation rules (`automation_rules.json`)
+    fn automation_rules_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("automation_rules.json"))
+    }
+
+    /// Scheduled actions and their run history (`schedules.json`)
+    fn schedules_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("schedules.json"))
+    }
+
+    /// Adaptive bitrate ladder and opted-in cameras (`abr.json`)
+    fn abr_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("abr.json"))
+    }
+
+    /// Thermal guard stages and cool-down policy (`thermal.json`)
+    fn thermal_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("thermal.json"))
+    }
+
+    /// Cameras the thermal guard has stepped down, with their original stream (`thermal_state.json`)
+    fn thermal_state_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("thermal_state.json"))
+    }
+
+    /// Recorded sessions, one JSONL file each (`sessions/`, created on demand)
+    fn sessions_dir(&self) -> Result<PathBuf> {
+        let dir = self.data_dir()?.join("sessions");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create sessions directory {:?}", dir))?;
+        Ok(dir)
+    }
+
+    /// Machine-local key encrypting camera tokens (`token.key`, created on demand)
+    fn token_key_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("token.key"))
+    }
+
+    /// Append-only journal of control actions (`audit.jsonl`)
+    fn audit_log_file(&self) -> Result<PathBuf> {
+        Ok(self.data_dir()?.join("audit.jsonl"))
+    }
+}
+
+/// [`PathProvider`] rooted at a fixed directory (headless use, tests, CLI tools)
+#[derive(Debug, Clone)]
+pub struct DirectoryPaths {
+    root: PathBuf,
+}
+
+impl DirectoryPaths {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+}
+
+impl PathProvider for DirectoryPaths {
+    fn data_dir(&self) -> Result<PathBuf> {
+        std::fs::create_dir_all(&self.root)
+            .with_context(|| format!("Failed to create data directory {:?}", self.root))?;
+        Ok(self.root.clone())
+    }
+}
+
+// MARK: - Notifications
+
+/// Sink for user-facing notifications and frontend events
+pub trait Notifier: Send + Sync {
+    /// Show a notification to the operator (OS notification, toast, log line...)
+    fn notify(&self, title: &str, body: &str);
+
+    /// Publish a structured event to the frontend (ignored by default)
+    fn emit(&self, _event: &str, _payload: serde_json::Value) {}
+}
+
+/// [`Notifier`] that writes notifications to the log (default for headless use)
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LogNotifier;
+
+impl Notifier for LogNotifier {
+    fn notify(&self, title: &str, body: &str) {
+        log::info!("Notification: {} - {}", title, body);
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        log::debug!("Event {}: {}", event, payload);
+    }
+}
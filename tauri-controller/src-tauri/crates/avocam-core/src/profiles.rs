@@ -0,0 +1,655 @@
+//! Portable profile libraries
+//!
+//! Profiles are exported to (and imported from) a JSON library file carrying
+//! their metadata. The same format is read from the optional shared profiles
+//! folder, so a DoP can distribute looks by dropping library files (or a plain
+//! `profiles.json`) into a synced directory on every operator laptop.
+//!
+//! [`CameraManager::preview_profile`] dry-runs a profile against each camera's
+//! live settings before anything is applied.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::anti_banding;
+use crate::camera_manager::CameraManager;
+use crate::models::*;
+
+/// Version written to exported libraries
+pub const PROFILE_LIBRARY_VERSION: u32 = 1;
+
+/// Exported profiles file (also accepts a bare `profiles.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProfileLibrary {
+    #[serde(default)]
+    pub format_version: u32,
+    #[serde(default)]
+    pub exported_at: Option<DateTime<Utc>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exported_by: Option<String>,
+    pub profiles: Vec<CameraProfile>,
+}
+
+/// What to do when an imported profile has the name of an existing one
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConflictResolution {
+    /// Import under a free name, e.g. "Stage (2)"
+    #[default]
+    Rename,
+    Overwrite,
+    Skip,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RenamedProfile {
+    pub from: String,
+    pub to: String,
+}
+
+/// Outcome of an import, by profile name
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfileImportReport {
+    pub imported: Vec<String>,
+    pub renamed: Vec<RenamedProfile>,
+    pub overwritten: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// Read a library file
+pub async fn read_library(path: &Path) -> Result<ProfileLibrary> {
+    let json = tokio::fs::read_to_string(path).await
+        .with_context(|| format!("Failed to read profile library {:?}", path))?;
+
+    serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse profile library {:?}", path))
+}
+
+/// Write a library file with the given profiles
+pub async fn write_library(path: &Path, profiles: Vec<CameraProfile>, exported_by: Option<String>) -> Result<()> {
+    let library = ProfileLibrary {
+        format_version: PROFILE_LIBRARY_VERSION,
+        exported_at: Some(Utc::now()),
+        exported_by,
+        profiles,
+    };
+
+    let json = serde_json::to_string_pretty(&library)
+        .context("Failed to serialize profile library")?;
+
+    tokio::fs::write(path, json).await
+        .with_context(|| format!("Failed to write profile library {:?}", path))
+}
+
+/// Profiles of every `*.json` library in the shared folder, marked read-only
+///
+/// Unreadable files are skipped with a warning; the first profile of a given name wins.
+pub async fn load_shared_profiles(dir: &Path) -> Result<Vec<CameraProfile>> {
+    let mut entries = tokio::fs::read_dir(dir).await
+        .with_context(|| format!("Failed to read shared profiles folder {:?}", dir))?;
+
+    let mut paths = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")) {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    let mut names = HashSet::new();
+    let mut profiles = Vec::new();
+    for path in paths {
+        match read_library(&path).await {
+            Ok(library) => {
+                for mut profile in library.profiles {
+                    if names.insert(profile.name.clone()) {
+                        profile.shared = true;
+                        profiles.push(profile);
+                    }
+                }
+            }
+            Err(e) => log::warn!("Skipping shared profile file: {:#}", e),
+        }
+    }
+
+    log::info!("Loaded {} shared profiles from {:?}", profiles.len(), dir);
+    Ok(profiles)
+}
+
+/// First "`name` (n)" not in `taken`
+pub fn free_name(name: &str, taken: &HashSet<String>) -> String {
+    (2..)
+        .map(|n| format!("{} ({})", name, n))
+        .find(|candidate| !taken.contains(candidate))
+        .unwrap_or_else(|| name.to_string())
+}
+
+// MARK: - Preview
+
+/// Relative tolerance below which float settings count as unchanged
+const FLOAT_TOLERANCE: f64 = 1e-3;
+
+/// One field a profile would change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    /// Live value (`null` when the camera does not report it)
+    pub from: serde_json::Value,
+    pub to: serde_json::Value,
+}
+
+/// What applying a profile would do to one camera
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProfilePreview {
+    pub camera_id: String,
+    pub changes: Vec<FieldChange>,
+    /// Switches to another lens
+    pub lens_switch: bool,
+    /// Switches between front and back camera
+    pub camera_switch: bool,
+    /// Stream settings change while the camera is streaming (updated in place;
+    /// a resolution or frame rate change briefly reconfigures capture)
+    pub stream_restart: bool,
+    /// Fields the camera's capabilities cannot honour (errors) or would adjust (warnings)
+    pub issues: Vec<FieldIssue>,
+    /// Live settings could not be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn compare<T: Serialize + PartialEq>(
+    changes: &mut Vec<FieldChange>,
+    field: &str,
+    current: Option<T>,
+    target: Option<T>,
+) {
+    let Some(target) = target else {
+        return;
+    };
+    if current.as_ref() == Some(&target) {
+        return;
+    }
+
+    changes.push(FieldChange {
+        field: field.to_string(),
+        from: serde_json::to_value(current).unwrap_or_default(),
+        to: serde_json::to_value(target).unwrap_or_default(),
+    });
+}
+
+fn compare_float(changes: &mut Vec<FieldChange>, field: &str, current: Option<f64>, target: Option<f64>) {
+    if let (Some(current), Some(target)) = (current, target) {
+        if (current - target).abs() <= FLOAT_TOLERANCE * current.abs().max(target.abs()) {
+            return;
+        }
+    }
+    compare(changes, field, current, target);
+}
+
+impl CameraManager {
+    /// Dry-run a profile: field-level changes against each camera's live settings,
+    /// lens/camera switches, stream restarts and capability issues. Nothing is sent.
+    pub async fn preview_profile(&self, profile_name: &str, camera_ids: &[String]) -> Result<Vec<ProfilePreview>> {
+        let profile = self.get_profiles().await?
+            .into_iter()
+            .find(|p| p.name == profile_name)
+            .ok_or_else(|| anyhow::anyhow!("Profile not found: {}", profile_name))?;
+
+        let mut previews = Vec::new();
+        for camera_id in camera_ids {
+            let preview = match self.preview_camera(&profile, camera_id).await {
+                Ok(preview) => preview,
+                Err(e) => ProfilePreview {
+                    camera_id: camera_id.clone(),
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                },
+            };
+            previews.push(preview);
+        }
+
+        Ok(previews)
+    }
+
+    async fn preview_camera(&self, profile: &CameraProfile, camera_id: &str) -> Result<ProfilePreview> {
+        let client = self.camera_client(camera_id)
+            .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+        let status = client.read().await.get_status().await
+            .context("Failed to read live settings")?;
+
+        let streaming = status.ndi_state == NdiState::Streaming || self.is_stream_requested(camera_id);
+        let mut preview = diff_live_settings(profile, &status.current, streaming);
+        preview.camera_id = camera_id.to_string();
+
+        if let Some(settings) = profile.masked_settings() {
+            let reports = self.validate_camera_settings(&[camera_id.to_string()], &settings, ValidationPolicy::Skip).await;
+            preview.issues.extend(reports.into_iter().flat_map(|r| r.issues));
+        }
+
+        if let Some(stream) = profile.masked_stream(&live_stream(&status.current)) {
+            let reports = self.validate_stream_settings(&[camera_id.to_string()], &stream, ValidationPolicy::Skip).await;
+            preview.issues.extend(reports.into_iter().flat_map(|r| r.issues));
+        }
+
+        Ok(preview)
+    }
+}
+
+fn live_stream(live: &CurrentSettings) -> StreamStartRequest {
+    StreamStartRequest {
+        resolution: live.resolution.clone(),
+        framerate: live.fps,
+        bitrate: live.bitrate,
+        codec: live.codec.clone(),
+    }
+}
+
+/// Changes, switches and stream restart the masked profile implies for a camera's
+/// live settings (capability issues are left to the caller)
+fn diff_live_settings(profile: &CameraProfile, live: &CurrentSettings, streaming: bool) -> ProfilePreview {
+    let mut preview = ProfilePreview::default();
+    let changes = &mut preview.changes;
+
+    if let Some(settings) = profile.masked_settings() {
+        compare(changes, "wb_mode", Some(live.wb_mode), settings.wb_mode);
+        compare(changes, "wb_kelvin", live.wb_kelvin, settings.wb_kelvin);
+        compare_float(changes, "wb_tint", live.wb_tint, settings.wb_tint);
+        compare(changes, "iso_mode", Some(live.iso_mode), settings.iso_mode);
+        compare(changes, "iso", Some(live.iso), settings.iso);
+        compare(changes, "shutter_mode", Some(live.shutter_mode), settings.shutter_mode);
+        compare_float(changes, "shutter_s", Some(live.shutter_s), settings.shutter_s);
+        compare_float(
+            changes,
+            "shutter_s",
+            Some(live.shutter_s),
+            settings.shutter_angle_deg.map(|angle| anti_banding::shutter_from_angle(angle, live.fps)),
+        );
+        compare(changes, "focus_mode", Some(live.focus_mode), settings.focus_mode);
+        compare_float(changes, "zoom_factor", Some(live.zoom_factor), settings.zoom_factor);
+        compare(changes, "lens", Some(&live.lens), settings.lens.as_ref());
+        compare(changes, "camera_position", Some(&live.camera_position), settings.camera_position.as_ref());
+        // Not part of the reported status
+        compare(changes, "orientation_lock", None, settings.orientation_lock.as_ref());
+        compare(changes, "torch_level", None, settings.torch_level);
+
+        preview.lens_switch = settings.lens.as_ref().is_some_and(|lens| *lens != live.lens);
+        preview.camera_switch = settings.camera_position.as_ref()
+            .is_some_and(|position| *position != live.camera_position);
+    }
+
+    let live_stream = live_stream(live);
+    if let Some(stream) = profile.masked_stream(&live_stream) {
+        let changes = &mut preview.changes;
+        compare(changes, "resolution", Some(&live_stream.resolution), Some(&stream.resolution));
+        compare(changes, "framerate", Some(live_stream.framerate), Some(stream.framerate));
+        compare(changes, "bitrate", Some(live_stream.bitrate), Some(stream.bitrate));
+        compare(changes, "codec", Some(&live_stream.codec), Some(&stream.codec));
+
+        preview.stream_restart = streaming && stream != live_stream;
+    }
+
+    preview
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::platform::DirectoryPaths;
+    use std::sync::Arc;
+
+    fn profile(name: &str) -> CameraProfile {
+        CameraProfile {
+            name: name.to_string(),
+            settings: CameraSettingsRequest {
+                wb_mode: Some(WhiteBalanceMode::Manual),
+                wb_kelvin: Some(4300),
+                iso: Some(400),
+                shutter_s: Some(0.01),
+                zoom_factor: Some(2.0),
+                lens: Some("tele".to_string()),
+                ..Default::default()
+            },
+            stream: Some(StreamStartRequest {
+                resolution: "3840x2160".to_string(),
+                framerate: 50,
+                bitrate: 40_000_000,
+                codec: "hevc".to_string(),
+            }),
+            fields: Vec::new(),
+            metadata: ProfileMetadata::default(),
+            shared: false,
+        }
+    }
+
+    fn masked(fields: &[ProfileField]) -> CameraProfile {
+        CameraProfile { fields: fields.to_vec(), ..profile("Masked") }
+    }
+
+    // MARK: - Field Mask
+
+    #[test]
+    fn empty_mask_applies_every_field_set() {
+        let profile = profile("Stage");
+        let settings = profile.masked_settings().unwrap();
+
+        assert_eq!(settings.wb_kelvin, Some(4300));
+        assert_eq!(settings.iso, Some(400));
+        assert_eq!(settings.lens.as_deref(), Some("tele"));
+        assert_eq!(settings.focus_mode, None);
+        assert_eq!(profile.masked_stream(&StreamStartRequest::default()), profile.stream);
+    }
+
+    #[test]
+    fn single_fields_and_groups_select_settings() {
+        let settings = masked(&[ProfileField::Iso, ProfileField::WhiteBalance]).masked_settings().unwrap();
+        assert_eq!(settings.wb_mode, Some(WhiteBalanceMode::Manual));
+        assert_eq!(settings.wb_kelvin, Some(4300));
+        assert_eq!(settings.iso, Some(400));
+        assert_eq!(settings.shutter_s, None);
+        assert_eq!(settings.zoom_factor, None);
+        assert_eq!(settings.lens, None);
+
+        let settings = masked(&[ProfileField::Exposure, ProfileField::Framing]).masked_settings().unwrap();
+        assert_eq!(settings.iso, Some(400));
+        assert_eq!(settings.shutter_s, Some(0.01));
+        assert_eq!(settings.zoom_factor, Some(2.0));
+        assert_eq!(settings.lens.as_deref(), Some("tele"));
+        assert_eq!(settings.wb_kelvin, None);
+    }
+
+    #[test]
+    fn mask_without_camera_fields_sends_no_settings() {
+        assert!(masked(&[ProfileField::Stream]).masked_settings().is_none());
+        // Masked-in fields the profile does not set do not count either
+        assert!(masked(&[ProfileField::TorchLevel]).masked_settings().is_none());
+    }
+
+    #[test]
+    fn masked_stream_keeps_current_values_outside_the_mask() {
+        let current = StreamStartRequest {
+            resolution: "1920x1080".to_string(),
+            framerate: 25,
+            bitrate: 10_000_000,
+            codec: "h264".to_string(),
+        };
+
+        let stream = masked(&[ProfileField::Bitrate, ProfileField::Codec]).masked_stream(&current).unwrap();
+        assert_eq!(stream.resolution, "1920x1080");
+        assert_eq!(stream.framerate, 25);
+        assert_eq!(stream.bitrate, 40_000_000);
+        assert_eq!(stream.codec, "hevc");
+
+        assert_eq!(masked(&[ProfileField::Iso]).masked_stream(&current), Some(current.clone()));
+        assert_eq!(CameraProfile { stream: None, ..profile("No stream") }.masked_stream(&current), None);
+    }
+
+    #[test]
+    fn mask_is_stored_in_snake_case() {
+        let json = serde_json::to_value(masked(&[ProfileField::WhiteBalance, ProfileField::ShutterAngleDeg])).unwrap();
+        assert_eq!(json["fields"], serde_json::json!(["white_balance", "shutter_angle_deg"]));
+
+        // Profiles saved before masks existed apply everything
+        let json = serde_json::json!({ "name": "Old", "settings": { "iso": 800 } });
+        let old: CameraProfile = serde_json::from_value(json).unwrap();
+        assert!(old.fields.is_empty());
+        assert!(old.includes(ProfileField::Iso));
+    }
+
+    // MARK: - Import/Export
+
+    async fn manager(dir: &Path) -> CameraManager {
+        let mut manager = CameraManager::new();
+        manager.set_path_provider(Arc::new(DirectoryPaths::new(dir))).await.unwrap();
+        manager
+    }
+
+    fn names(profiles: &[CameraProfile]) -> Vec<&str> {
+        profiles.iter().map(|p| p.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn library_round_trips_profiles_and_metadata() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("looks.json");
+        let mut stage = profile("Stage");
+        stage.metadata.author = Some("DoP".to_string());
+        stage.fields = vec![ProfileField::WhiteBalance];
+
+        write_library(&path, vec![stage], Some("FOH laptop".to_string())).await.unwrap();
+        let library = read_library(&path).await.unwrap();
+
+        assert_eq!(library.format_version, PROFILE_LIBRARY_VERSION);
+        assert!(library.exported_at.is_some());
+        assert_eq!(library.exported_by.as_deref(), Some("FOH laptop"));
+        assert_eq!(names(&library.profiles), ["Stage"]);
+        assert_eq!(library.profiles[0].metadata.author.as_deref(), Some("DoP"));
+        assert_eq!(library.profiles[0].fields, [ProfileField::WhiteBalance]);
+        assert_eq!(library.profiles[0].settings.wb_kelvin, Some(4300));
+    }
+
+    #[tokio::test]
+    async fn bare_profiles_file_reads_as_a_library() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("profiles.json");
+        std::fs::write(&path, r#"{ "profiles": [{ "name": "Old", "settings": { "iso": 800 } }] }"#).unwrap();
+
+        let library = read_library(&path).await.unwrap();
+        assert_eq!(library.format_version, 0);
+        assert_eq!(library.exported_at, None);
+        assert_eq!(names(&library.profiles), ["Old"]);
+    }
+
+    #[test]
+    fn free_name_skips_taken_suffixes() {
+        let taken: HashSet<String> = ["Stage", "Stage (2)", "Stage (3)"].iter().map(|n| n.to_string()).collect();
+        assert_eq!(free_name("Stage", &taken), "Stage (4)");
+        assert_eq!(free_name("Booth", &taken), "Booth (2)");
+    }
+
+    #[tokio::test]
+    async fn shared_folder_profiles_are_read_only_and_first_name_wins() {
+        let dir = tempfile::tempdir().unwrap();
+        write_library(&dir.path().join("a.json"), vec![profile("Stage")], None).await.unwrap();
+        let mut later = profile("Stage");
+        later.settings.iso = Some(1600);
+        write_library(&dir.path().join("b.json"), vec![later, profile("Booth")], None).await.unwrap();
+        std::fs::write(dir.path().join("c.json"), "not a library").unwrap();
+        std::fs::write(dir.path().join("notes.txt"), "ignored").unwrap();
+
+        let shared = load_shared_profiles(dir.path()).await.unwrap();
+        assert_eq!(names(&shared), ["Stage", "Booth"]);
+        assert_eq!(shared[0].settings.iso, Some(400));
+        assert!(shared.iter().all(|p| p.shared));
+    }
+
+    #[tokio::test]
+    async fn import_resolves_name_conflicts() {
+        let dir = tempfile::tempdir().unwrap();
+        let library = dir.path().join("looks.json");
+        let mut imported_stage = profile("Stage");
+        imported_stage.settings.iso = Some(1600);
+        write_library(&library, vec![imported_stage, profile("Booth")], None).await.unwrap();
+
+        for (resolution, expected_names, stage_iso) in [
+            (ConflictResolution::Rename, vec!["Stage", "Stage (2)", "Booth"], 400),
+            (ConflictResolution::Overwrite, vec!["Stage", "Booth"], 1600),
+            (ConflictResolution::Skip, vec!["Stage", "Booth"], 400),
+        ] {
+            let data = tempfile::tempdir().unwrap();
+            let mut manager = manager(data.path()).await;
+            manager.save_profile(profile("Stage")).await.unwrap();
+
+            let report = manager.import_profiles(&library, resolution).await.unwrap();
+            assert_eq!(report.imported, ["Booth"], "{:?}", resolution);
+            match resolution {
+                ConflictResolution::Rename => {
+                    assert_eq!(report.renamed[0].from, "Stage");
+                    assert_eq!(report.renamed[0].to, "Stage (2)");
+                }
+                ConflictResolution::Overwrite => assert_eq!(report.overwritten, ["Stage"]),
+                ConflictResolution::Skip => assert_eq!(report.skipped, ["Stage"]),
+            }
+
+            let profiles = manager.get_profiles().await.unwrap();
+            let mut actual = names(&profiles);
+            actual.sort();
+            let mut expected = expected_names;
+            expected.sort();
+            assert_eq!(actual, expected, "{:?}", resolution);
+            let stage = profiles.iter().find(|p| p.name == "Stage").unwrap();
+            assert_eq!(stage.settings.iso, Some(stage_iso), "{:?}", resolution);
+        }
+    }
+
+    #[tokio::test]
+    async fn import_does_not_shadow_shared_profiles() {
+        let data = tempfile::tempdir().unwrap();
+        let shared = tempfile::tempdir().unwrap();
+        write_library(&shared.path().join("team.json"), vec![profile("Stage")], None).await.unwrap();
+        let mut manager = manager(data.path()).await;
+        let mut settings = manager.get_app_settings().await.unwrap();
+        settings.shared_profiles_dir = Some(shared.path().to_path_buf());
+        manager.save_app_settings(settings).await.unwrap();
+
+        let library = data.path().join("looks.json");
+        write_library(&library, vec![profile("Stage")], None).await.unwrap();
+        let report = manager.import_profiles(&library, ConflictResolution::Rename).await.unwrap();
+        assert_eq!(report.renamed[0].to, "Stage (2)");
+
+        let profiles = manager.get_profiles().await.unwrap();
+        assert_eq!(names(&profiles), ["Stage (2)", "Stage"]);
+        assert!(!profiles[0].shared);
+        assert!(profiles[1].shared);
+        assert!(manager.delete_profile("Stage").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn export_writes_local_and_shared_profiles_as_editable() {
+        let data = tempfile::tempdir().unwrap();
+        let shared = tempfile::tempdir().unwrap();
+        write_library(&shared.path().join("team.json"), vec![profile("Team look")], None).await.unwrap();
+        let mut manager = manager(data.path()).await;
+        let mut settings = manager.get_app_settings().await.unwrap();
+        settings.shared_profiles_dir = Some(shared.path().to_path_buf());
+        manager.save_app_settings(settings).await.unwrap();
+        manager.save_profile(profile("Stage")).await.unwrap();
+
+        let destination = data.path().join("export.json");
+        let names_to_export = ["Stage".to_string(), "Team look".to_string()];
+        assert_eq!(manager.export_profiles(&names_to_export, &destination, None).await.unwrap(), 2);
+
+        let library = read_library(&destination).await.unwrap();
+        assert_eq!(names(&library.profiles), ["Stage", "Team look"]);
+        assert!(library.profiles.iter().all(|p| !p.shared));
+        assert!(library.profiles[0].metadata.created_at.is_some());
+
+        let missing = ["Nope".to_string()];
+        assert!(manager.export_profiles(&missing, &destination, None).await.is_err());
+    }
+
+    // MARK: - Preview
+
+    fn live() -> CurrentSettings {
+        CurrentSettings {
+            resolution: "1920x1080".to_string(),
+            fps: 25,
+            bitrate: 10_000_000,
+            codec: "h264".to_string(),
+            wb_mode: WhiteBalanceMode::Manual,
+            wb_kelvin: Some(5600),
+            wb_tint: Some(0.0),
+            iso_mode: ExposureMode::Manual,
+            iso: 400,
+            shutter_mode: ExposureMode::Manual,
+            shutter_s: 0.02,
+            focus_mode: FocusMode::Auto,
+            zoom_factor: 2.0,
+            camera_position: "back".to_string(),
+            lens: "wide".to_string(),
+        }
+    }
+
+    fn camera_only(settings: CameraSettingsRequest) -> CameraProfile {
+        CameraProfile { settings, stream: None, ..profile("Preview") }
+    }
+
+    fn changed_fields(preview: &ProfilePreview) -> Vec<&str> {
+        preview.changes.iter().map(|c| c.field.as_str()).collect()
+    }
+
+    #[test]
+    fn preview_lists_only_fields_that_change() {
+        let preview = diff_live_settings(&profile("Stage"), &live(), false);
+
+        assert_eq!(changed_fields(&preview), ["wb_kelvin", "shutter_s", "lens", "resolution", "framerate", "bitrate", "codec"]);
+        let kelvin = &preview.changes[0];
+        assert_eq!(kelvin.from, serde_json::json!(5600));
+        assert_eq!(kelvin.to, serde_json::json!(4300));
+        assert!(preview.lens_switch);
+        assert!(!preview.camera_switch);
+        assert!(preview.issues.is_empty());
+    }
+
+    #[test]
+    fn float_settings_within_tolerance_are_unchanged() {
+        let profile = camera_only(CameraSettingsRequest {
+            zoom_factor: Some(2.0005),
+            shutter_s: Some(0.02),
+            wb_tint: Some(0.0),
+            ..Default::default()
+        });
+        assert!(diff_live_settings(&profile, &live(), false).changes.is_empty());
+
+        let profile = camera_only(CameraSettingsRequest { zoom_factor: Some(2.1), ..Default::default() });
+        assert_eq!(changed_fields(&diff_live_settings(&profile, &live(), false)), ["zoom_factor"]);
+    }
+
+    #[test]
+    fn shutter_angle_is_compared_at_the_live_frame_rate() {
+        let profile = camera_only(CameraSettingsRequest { shutter_angle_deg: Some(180.0), ..Default::default() });
+        assert!(diff_live_settings(&profile, &live(), false).changes.is_empty());
+
+        let at_50 = CurrentSettings { fps: 50, ..live() };
+        let preview = diff_live_settings(&profile, &at_50, false);
+        assert_eq!(changed_fields(&preview), ["shutter_s"]);
+        assert_eq!(preview.changes[0].to, serde_json::json!(0.01));
+    }
+
+    #[test]
+    fn unreported_fields_are_always_changes() {
+        let profile = camera_only(CameraSettingsRequest { torch_level: Some(0.5), ..Default::default() });
+        let preview = diff_live_settings(&profile, &live(), false);
+
+        assert_eq!(changed_fields(&preview), ["torch_level"]);
+        assert_eq!(preview.changes[0].from, serde_json::Value::Null);
+    }
+
+    #[test]
+    fn camera_switch_is_flagged() {
+        let profile = camera_only(CameraSettingsRequest { camera_position: Some("front".to_string()), ..Default::default() });
+        let preview = diff_live_settings(&profile, &live(), false);
+
+        assert!(preview.camera_switch);
+        assert!(!preview.lens_switch);
+    }
+
+    #[test]
+    fn stream_restarts_only_when_streaming_and_the_stream_changes() {
+        let stage = profile("Stage");
+        assert!(diff_live_settings(&stage, &live(), true).stream_restart);
+        assert!(!diff_live_settings(&stage, &live(), false).stream_restart);
+
+        // Masked to camera fields: the stream section is left alone
+        let masked = CameraProfile { fields: vec![ProfileField::Iso], ..profile("Stage") };
+        let preview = diff_live_settings(&masked, &live(), true);
+        assert!(!preview.stream_restart);
+        assert!(preview.changes.is_empty());
+
+        let same_stream = CameraProfile { stream: Some(live_stream(&live())), ..camera_only(CameraSettingsRequest::default()) };
+        assert!(!diff_live_settings(&same_stream, &live(), true).stream_restart);
+    }
+}
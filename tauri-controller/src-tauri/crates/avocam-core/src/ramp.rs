@@ -0,0 +1,593 @@
+//! Timed settings ramps
+//!
+//! Interpolates `zoom_factor`, `wb_kelvin`, `iso`, `shutter_s` and `torch_level`
+//! from their current value to a target over a duration with an easing curve,
+//! sending intermediate updates at a fixed rate. Every camera of a ramp is driven
+//! by the same clock so group ramps stay in sync.
+//!
+//! Every step is validated and anti-banded like any other change. The audit log
+//! records the ramp's start and its landing on the targets, which is persisted
+//! through [`CameraManager::group_update_settings_per_camera`]; the steps in
+//! between are not journaled. The manager lock is held only while resolving
+//! settings, never across camera I/O. A cancelled ramp leaves the camera at its
+//! last step.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::audit::ActionSource;
+use crate::camera_manager::{CameraManager, ManagerMut, ManagerRef};
+use crate::models::*;
+use crate::validation;
+
+pub const DEFAULT_RAMP_RATE_HZ: f64 = 10.0;
+/// The app answers `RATE_LIMITED` to camera updates closer than this
+const APP_CAMERA_UPDATE_INTERVAL_MS: f64 = 50.0;
+const MAX_RAMP_RATE_HZ: f64 = 1000.0 / APP_CAMERA_UPDATE_INTERVAL_MS;
+const MIN_RAMP_RATE_HZ: f64 = 1.0;
+
+// MARK: - Ramp Model
+
+/// Easing curve applied to ramp progress
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    #[default]
+    EaseInOut,
+}
+
+impl Easing {
+    /// Eased progress for linear progress `t` in `[0, 1]`
+    pub fn apply(self, t: f64) -> f64 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// Camera setting a ramp can drive
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RampParameter {
+    ZoomFactor,
+    WbKelvin,
+    Iso,
+    ShutterS,
+    TorchLevel,
+}
+
+impl RampParameter {
+    /// Value the camera currently reports (torch level is not part of the status)
+    fn current(self, current: &CurrentSettings) -> Option<f64> {
+        match self {
+            RampParameter::ZoomFactor => Some(current.zoom_factor),
+            RampParameter::WbKelvin => current.wb_kelvin.map(f64::from),
+            RampParameter::Iso => Some(current.iso as f64),
+            RampParameter::ShutterS => Some(current.shutter_s),
+            RampParameter::TorchLevel => None,
+        }
+    }
+
+    /// Value at eased progress `p`
+    ///
+    /// Zoom, ISO and shutter move evenly in ratio (stops), white balance evenly
+    /// in mired, so the change looks uniform on air.
+    fn interpolate(self, from: f64, to: f64, p: f64) -> f64 {
+        match self {
+            RampParameter::ZoomFactor | RampParameter::Iso | RampParameter::ShutterS => {
+                from * (to / from).powf(p)
+            }
+            RampParameter::WbKelvin => {
+                let (from_mired, to_mired) = (1e6 / from, 1e6 / to);
+                1e6 / (from_mired + (to_mired - from_mired) * p)
+            }
+            RampParameter::TorchLevel => from + (to - from) * p,
+        }
+    }
+
+    /// Set the value (and the manual mode it needs) on a settings request
+    fn apply(self, settings: &mut CameraSettingsRequest, value: f64) {
+        match self {
+            RampParameter::ZoomFactor => settings.zoom_factor = Some(value),
+            RampParameter::WbKelvin => {
+                settings.wb_mode = Some(WhiteBalanceMode::Manual);
+                settings.wb_kelvin = Some(value.round() as u32);
+            }
+            RampParameter::Iso => {
+                settings.iso_mode = Some(ExposureMode::Manual);
+                settings.iso = Some(value.round() as u32);
+            }
+            RampParameter::ShutterS => {
+                settings.shutter_mode = Some(ExposureMode::Manual);
+                settings.shutter_s = Some(value);
+            }
+            RampParameter::TorchLevel => settings.torch_level = Some(value as f32),
+        }
+    }
+}
+
+/// One parameter of a ramp
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampTarget {
+    pub parameter: RampParameter,
+    pub target: f64,
+    /// Start value; defaults to the camera's current value
+    #[serde(default)]
+    pub from: Option<f64>,
+}
+
+/// Ramp of one or more parameters on one or more cameras in sync
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampRequest {
+    pub camera_ids: Vec<String>,
+    pub targets: Vec<RampTarget>,
+    pub duration_ms: u64,
+    #[serde(default)]
+    pub easing: Easing,
+    /// Updates per second (1-20, default 10)
+    #[serde(default)]
+    pub rate_hz: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RampState {
+    Running,
+    Completed,
+    Cancelled,
+    Failed { error: String },
+}
+
+/// Latest ramp of a camera, as listed to the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RampStatus {
+    pub ramp_id: String,
+    pub camera_id: String,
+    pub parameters: Vec<RampParameter>,
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    /// Linear progress in `[0, 1]`
+    pub progress: f64,
+    pub state: RampState,
+}
+
+// MARK: - Ramp Engine
+
+/// Per-camera plan of a running ramp
+struct CameraRamp {
+    camera_id: String,
+    // (parameter, from, to)
+    values: Vec<(RampParameter, f64, f64)>,
+    cancelled: Arc<AtomicBool>,
+    last_sent: Vec<f64>,
+}
+
+impl CameraRamp {
+    fn settings_at(&self, p: f64) -> (CameraSettingsRequest, Vec<f64>) {
+        let mut settings = CameraSettingsRequest::default();
+        let mut sent = Vec::with_capacity(self.values.len());
+
+        for &(parameter, from, to) in &self.values {
+            let value = parameter.interpolate(from, to, p);
+            parameter.apply(&mut settings, value);
+            sent.push(value);
+        }
+
+        (settings, sent)
+    }
+
+    fn target_settings(&self) -> CameraSettingsRequest {
+        self.settings_at(1.0).0
+    }
+}
+
+#[derive(Default)]
+struct RampRegistry {
+    next_id: u64,
+    statuses: HashMap<String, RampStatus>,
+    // Cancellation flag of each camera's running ramp
+    cancel_flags: HashMap<String, Arc<AtomicBool>>,
+}
+
+/// Runs settings ramps through the camera clients of the manager
+pub struct RampEngine {
+    manager: Arc<RwLock<CameraManager>>,
+    registry: Arc<RwLock<RampRegistry>>,
+}
+
+impl RampEngine {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            registry: Arc::new(RwLock::new(RampRegistry::default())),
+        }
+    }
+
+    /// Start a ramp and return its id
+    ///
+    /// Targets are validated for every camera first; a camera already ramping has
+    /// its previous ramp cancelled.
+    pub async fn start_ramp(&self, request: RampRequest, source: ActionSource) -> Result<String> {
+        let result = self.plan_and_spawn(&request, source).await;
+
+        self.manager.read().await.audit()
+            .record(source, "start_ramp", &request.camera_ids, &request, &result).await;
+        result
+    }
+
+    async fn plan_and_spawn(&self, request: &RampRequest, source: ActionSource) -> Result<String> {
+        if request.camera_ids.is_empty() || request.targets.is_empty() {
+            anyhow::bail!("A ramp needs at least one camera and one target");
+        }
+        let rate_hz = request.rate_hz.unwrap_or(DEFAULT_RAMP_RATE_HZ);
+        if !rate_hz.is_finite() {
+            anyhow::bail!("Invalid ramp rate: {}", rate_hz);
+        }
+        let rate_hz = rate_hz.clamp(MIN_RAMP_RATE_HZ, MAX_RAMP_RATE_HZ);
+
+        let plans = self.plan(request).await?;
+
+        let mut registry = self.registry.write().await;
+        registry.next_id += 1;
+        let ramp_id = format!("ramp-{}", registry.next_id);
+        let started_at = Utc::now();
+
+        for plan in &plans {
+            if let Some(previous) = registry.cancel_flags.insert(plan.camera_id.clone(), plan.cancelled.clone()) {
+                previous.store(true, Ordering::SeqCst);
+            }
+            registry.statuses.insert(plan.camera_id.clone(), RampStatus {
+                ramp_id: ramp_id.clone(),
+                camera_id: plan.camera_id.clone(),
+                parameters: request.targets.iter().map(|t| t.parameter).collect(),
+                started_at,
+                duration_ms: request.duration_ms,
+                progress: 0.0,
+                state: RampState::Running,
+            });
+        }
+        drop(registry);
+
+        log::info!("Starting {} on {} camera(s) over {} ms at {:.0} Hz",
+            ramp_id, plans.len(), request.duration_ms, rate_hz);
+
+        tokio::spawn(run_ramp(
+            self.manager.clone(),
+            self.registry.clone(),
+            ramp_id.clone(),
+            plans,
+            Duration::from_millis(request.duration_ms),
+            request.easing,
+            Duration::from_secs_f64(1.0 / rate_hz),
+            source,
+        ));
+
+        Ok(ramp_id)
+    }
+
+    /// Resolve start values, clients and validate targets for each camera
+    async fn plan(&self, request: &RampRequest) -> Result<Vec<CameraRamp>> {
+        let mut target_settings = CameraSettingsRequest::default();
+        for target in &request.targets {
+            target.parameter.apply(&mut target_settings, target.target);
+        }
+        if !(target_settings.shutter_s.unwrap_or(1.0) > 0.0
+            && target_settings.zoom_factor.unwrap_or(1.0) > 0.0)
+        {
+            anyhow::bail!("Ramp targets for zoom and shutter must be positive");
+        }
+
+        // Snapshot clients and persisted torch levels, then release the lock
+        // before asking every camera for its current values
+        let cameras = {
+            let manager = self.manager.read().await;
+
+            // Reject the whole ramp rather than running it on a subset of the group
+            let reports = manager
+                .validate_camera_settings(&request.camera_ids, &target_settings, ValidationPolicy::Skip)
+                .await;
+            if let Some(report) = reports.into_iter().find(|r| !r.is_valid()) {
+                return Err(validation::SettingsRejection::new(&report.camera_id, report.issues).into());
+            }
+
+            request.camera_ids.iter()
+                .map(|camera_id| {
+                    let client = manager.camera_client(camera_id)
+                        .ok_or_else(|| anyhow::anyhow!("Camera not found: {}", camera_id))?;
+                    let persisted_torch = manager.get_persisted_settings(camera_id)
+                        .and_then(|(_, settings)| settings.and_then(|s| s.torch_level))
+                        .map(f64::from);
+                    Ok((camera_id.clone(), client, persisted_torch))
+                })
+                .collect::<Result<Vec<_>>>()?
+        };
+
+        let statuses = join_all(cameras.iter().map(|(_, client, _)| async move {
+            client.read().await.get_status().await.ok().map(|s| s.current)
+        })).await;
+
+        let mut plans = Vec::new();
+        for ((camera_id, _, persisted_torch), current) in cameras.into_iter().zip(statuses) {
+            let mut values = Vec::new();
+            for target in &request.targets {
+                let from = target.from
+                    .or_else(|| current.as_ref().and_then(|c| target.parameter.current(c)))
+                    .or(if target.parameter == RampParameter::TorchLevel { persisted_torch } else { None })
+                    .ok_or_else(|| anyhow::anyhow!(
+                        "Unknown start value of {:?} on {}; pass `from`", target.parameter, camera_id))?;
+
+                // Auto modes report 0 for ISO/shutter, which cannot be ramped in stops
+                if from <= 0.0 && target.parameter != RampParameter::TorchLevel {
+                    anyhow::bail!("Start value of {:?} on {} is {}; set it manually or pass `from`",
+                        target.parameter, camera_id, from);
+                }
+                values.push((target.parameter, from, target.target));
+            }
+
+            plans.push(CameraRamp {
+                camera_id,
+                last_sent: Vec::new(),
+                values,
+                cancelled: Arc::new(AtomicBool::new(false)),
+            });
+        }
+
+        Ok(plans)
+    }
+
+    /// Cancel the running ramp of each camera, leaving it at its last step
+    pub async fn cancel_ramp(&self, camera_ids: &[String], source: ActionSource) -> Result<()> {
+        let mut registry = self.registry.write().await;
+        let mut cancelled = Vec::new();
+
+        for camera_id in camera_ids {
+            if let Some(flag) = registry.cancel_flags.remove(camera_id) {
+                flag.store(true, Ordering::SeqCst);
+                cancelled.push(camera_id.clone());
+            }
+            if let Some(status) = registry.statuses.get_mut(camera_id) {
+                if status.state == RampState::Running {
+                    status.state = RampState::Cancelled;
+                }
+            }
+        }
+        drop(registry);
+
+        let result = if cancelled.is_empty() {
+            Err(anyhow::anyhow!("No ramp in progress on the given cameras"))
+        } else {
+            Ok(())
+        };
+
+        self.manager.read().await.audit()
+            .record(source, "cancel_ramp", camera_ids, &serde_json::Value::Null, &result).await;
+        result
+    }
+
+    /// Latest ramp of every camera that ran one
+    pub async fn get_ramp_statuses(&self) -> Vec<RampStatus> {
+        let mut statuses: Vec<RampStatus> = self.registry.read().await.statuses.values().cloned().collect();
+        statuses.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        statuses
+    }
+
+    /// Whether a camera has a ramp in progress
+    pub async fn is_ramping(&self, camera_id: &str) -> bool {
+        self.registry.read().await.cancel_flags.contains_key(camera_id)
+    }
+}
+
+// MARK: - Ramp Driver
+
+#[allow(clippy::too_many_arguments)]
+async fn run_ramp(
+    manager: Arc<RwLock<CameraManager>>,
+    registry: Arc<RwLock<RampRegistry>>,
+    ramp_id: String,
+    mut plans: Vec<CameraRamp>,
+    duration: Duration,
+    easing: Easing,
+    step_interval: Duration,
+    source: ActionSource,
+) {
+    let started = Instant::now();
+    let mut interval = tokio::time::interval(step_interval);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
+    loop {
+        interval.tick().await;
+        plans.retain(|plan| !plan.cancelled.load(Ordering::SeqCst));
+        if plans.is_empty() {
+            log::info!("{} cancelled", ramp_id);
+            return;
+        }
+
+        let elapsed = started.elapsed();
+        if elapsed >= duration {
+            break;
+        }
+        let t = elapsed.as_secs_f64() / duration.as_secs_f64();
+        let p = easing.apply(t);
+
+        // One clock for every camera: all steps of a tick are sent together
+        let steps: HashMap<String, CameraSettingsRequest> = plans.iter_mut()
+            .filter_map(|plan| {
+                let (settings, sent) = plan.settings_at(p);
+                if sent == plan.last_sent {
+                    return None;
+                }
+                plan.last_sent = sent;
+                Some((plan.camera_id.clone(), settings))
+            })
+            .collect();
+        if !steps.is_empty() {
+            match CameraManager::send_transient_settings_via(ManagerRef::Locked(&manager), steps).await {
+                Ok(results) => {
+                    for result in results.iter().filter(|r| !r.success) {
+                        log::warn!("Ramp step failed on {}: {}",
+                            result.camera_id, result.error.as_deref().unwrap_or("unknown error"));
+                    }
+                }
+                Err(e) => log::warn!("{} step failed: {}", ramp_id, e),
+            }
+        }
+
+        let mut registry = registry.write().await;
+        for plan in &plans {
+            if let Some(status) = registry.statuses.get_mut(&plan.camera_id) {
+                if status.ramp_id == ramp_id {
+                    status.progress = t;
+                }
+            }
+        }
+    }
+
+    // A ramp cancelled during the last tick stays at its last step
+    plans.retain(|plan| !plan.cancelled.load(Ordering::SeqCst));
+    if plans.is_empty() {
+        log::info!("{} cancelled", ramp_id);
+        return;
+    }
+
+    // Land exactly on the targets through the regular (validated, persisted, audited) path
+    let targets: HashMap<String, CameraSettingsRequest> = plans.iter()
+        .map(|plan| (plan.camera_id.clone(), plan.target_settings()))
+        .collect();
+    let result = CameraManager::group_update_settings_per_camera_via(
+        ManagerMut::Locked(&manager), targets, ValidationPolicy::Skip, source,
+    ).await;
+
+    let mut registry = registry.write().await;
+    for plan in &plans {
+        // A newer ramp on this camera owns its status
+        let Some(status) = registry.statuses.get_mut(&plan.camera_id).filter(|s| s.ramp_id == ramp_id) else {
+            continue;
+        };
+
+        let error = match &result {
+            Ok(results) => results.iter()
+                .find(|r| r.camera_id == plan.camera_id)
+                .and_then(|r| r.error.clone()),
+            Err(e) => Some(e.to_string()),
+        };
+        status.progress = 1.0;
+        status.state = match error {
+            Some(error) => RampState::Failed { error },
+            None => RampState::Completed,
+        };
+        registry.cancel_flags.remove(&plan.camera_id);
+    }
+
+    log::info!("{} finished", ramp_id);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {}, got {}", expected, actual);
+    }
+
+    fn ramp(values: Vec<(RampParameter, f64, f64)>) -> CameraRamp {
+        CameraRamp {
+            camera_id: "cam".to_string(),
+            values,
+            cancelled: Arc::new(AtomicBool::new(false)),
+            last_sent: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn easing_curves_keep_endpoints() {
+        for easing in [Easing::Linear, Easing::EaseIn, Easing::EaseOut, Easing::EaseInOut] {
+            assert_close(easing.apply(0.0), 0.0);
+            assert_close(easing.apply(1.0), 1.0);
+            // Out-of-range progress is clamped
+            assert_close(easing.apply(-0.5), 0.0);
+            assert_close(easing.apply(1.5), 1.0);
+        }
+    }
+
+    #[test]
+    fn easing_curves_shape_progress() {
+        assert_close(Easing::Linear.apply(0.25), 0.25);
+        assert_close(Easing::EaseIn.apply(0.5), 0.125);
+        assert_close(Easing::EaseOut.apply(0.5), 0.875);
+        assert_close(Easing::EaseInOut.apply(0.5), 0.5);
+        assert!(Easing::EaseInOut.apply(0.25) < 0.25);
+        assert!(Easing::EaseInOut.apply(0.75) > 0.75);
+    }
+
+    #[test]
+    fn ratio_parameters_move_evenly_in_stops() {
+        // ISO 100 → 400 is two stops: one stop halfway
+        assert_close(RampParameter::Iso.interpolate(100.0, 400.0, 0.5), 200.0);
+        assert_close(RampParameter::ShutterS.interpolate(1.0 / 100.0, 1.0 / 25.0, 0.5), 1.0 / 50.0);
+        assert_close(RampParameter::ZoomFactor.interpolate(1.0, 4.0, 0.5), 2.0);
+    }
+
+    #[test]
+    fn white_balance_moves_evenly_in_mired() {
+        // 3200 K = 312.5 mired, 5600 K ≈ 178.6 mired
+        let mid = RampParameter::WbKelvin.interpolate(3200.0, 5600.0, 0.5);
+        assert_close(1e6 / mid, (1e6 / 3200.0 + 1e6 / 5600.0) / 2.0);
+    }
+
+    #[test]
+    fn torch_level_moves_linearly() {
+        assert_close(RampParameter::TorchLevel.interpolate(0.0, 1.0, 0.3), 0.3);
+    }
+
+    #[test]
+    fn interpolation_hits_endpoints() {
+        let parameters = [
+            (RampParameter::ZoomFactor, 1.0, 3.0),
+            (RampParameter::WbKelvin, 3200.0, 5600.0),
+            (RampParameter::Iso, 100.0, 800.0),
+            (RampParameter::ShutterS, 0.01, 0.04),
+            (RampParameter::TorchLevel, 0.0, 0.8),
+        ];
+        for (parameter, from, to) in parameters {
+            assert_close(parameter.interpolate(from, to, 0.0), from);
+            assert_close(parameter.interpolate(from, to, 1.0), to);
+        }
+    }
+
+    #[test]
+    fn settings_set_manual_modes_and_land_on_targets() {
+        let ramp = ramp(vec![
+            (RampParameter::Iso, 100.0, 400.0),
+            (RampParameter::WbKelvin, 3200.0, 5600.0),
+            (RampParameter::ShutterS, 0.01, 0.02),
+        ]);
+
+        let (start, sent) = ramp.settings_at(0.0);
+        assert_eq!(start.iso, Some(100));
+        assert_eq!(start.iso_mode, Some(ExposureMode::Manual));
+        assert_eq!(start.wb_kelvin, Some(3200));
+        assert_eq!(start.wb_mode, Some(WhiteBalanceMode::Manual));
+        assert_eq!(start.shutter_mode, Some(ExposureMode::Manual));
+        assert_eq!(sent.len(), 3);
+
+        let target = ramp.target_settings();
+        assert_eq!(target.iso, Some(400));
+        assert_eq!(target.wb_kelvin, Some(5600));
+        assert_close(target.shutter_s.unwrap(), 0.02);
+        assert_eq!(target.zoom_factor, None);
+    }
+}
@@ -0,0 +1,662 @@
+//! Post-show session reports
+//!
+//! Builds a [`SessionReport`] from a recorded session: per-camera uptime,
+//! reconnects and downtime, fps stability against the configured framerate,
+//! dropped frames, bitrate, minimum battery and peak temperature, plus the
+//! alerts fired and control actions taken. Reports render to Markdown (QA
+//! notes) or a self-contained HTML page (client deliverables).
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::models::*;
+use crate::session::{SessionInfo, SessionRecord, SessionRecordKind, SessionRecorder};
+
+/// A sample within this share of the configured framerate counts as on target
+const FPS_TOLERANCE: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+/// Min / mean / max of a series
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Stats {
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+}
+
+impl Stats {
+    fn of(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        Some(Self {
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+/// One camera over the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CameraReport {
+    pub camera_id: String,
+    pub alias: Option<String>,
+    pub first_seen: DateTime<Utc>,
+    /// Connected time from first sighting to the end of the session
+    pub uptime_s: i64,
+    pub downtime_s: i64,
+    pub reconnects: u32,
+    /// Time spent sending NDI, from telemetry
+    pub streaming_s: i64,
+    /// Framerate the camera was configured with, when known
+    pub configured_fps: Option<u32>,
+    /// Measured fps while streaming
+    pub fps: Option<Stats>,
+    pub fps_std_dev: Option<f64>,
+    /// Share of streaming samples within 5% of the configured framerate
+    pub fps_on_target: Option<f64>,
+    pub dropped_frames: u64,
+    pub bitrate_bps: Option<Stats>,
+    pub min_battery: Option<f64>,
+    pub peak_temp_c: Option<f64>,
+    pub alerts: usize,
+}
+
+/// An alert or notice fired during the session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportAlert {
+    pub timestamp: DateTime<Utc>,
+    pub camera_id: Option<String>,
+    pub title: String,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionReport {
+    pub session: SessionInfo,
+    pub generated_at: DateTime<Utc>,
+    pub duration_s: i64,
+    pub cameras: Vec<CameraReport>,
+    pub alerts: Vec<ReportAlert>,
+    pub actions: usize,
+    pub failed_actions: usize,
+    /// Events lost because the recorder fell behind
+    pub skipped_events: u64,
+}
+
+// MARK: - Building
+
+#[derive(Default)]
+struct CameraAccumulator {
+    first_seen: Option<DateTime<Utc>>,
+    // Set while disconnected
+    down_since: Option<DateTime<Utc>>,
+    downtime_s: i64,
+    reconnects: u32,
+    last_sample: Option<(DateTime<Utc>, bool)>,
+    streaming_s: i64,
+    configured_fps: Option<u32>,
+    fps: Vec<f64>,
+    bitrate: Vec<f64>,
+    last_dropped: Option<u32>,
+    dropped_frames: u64,
+    min_battery: Option<f64>,
+    peak_temp_c: Option<f64>,
+    alerts: usize,
+}
+
+impl CameraAccumulator {
+    fn seen(&mut self, at: DateTime<Utc>) {
+        self.first_seen.get_or_insert(at);
+    }
+}
+
+/// Framerate set by a stream action (applies to every camera it targeted)
+fn configured_framerate(entry: &crate::audit::AuditEntry) -> Option<u32> {
+    let request = &entry.request;
+    let framerate = request.get("framerate")
+        .or_else(|| request.get("request").and_then(|r| r.get("framerate")))?;
+    framerate.as_u64().map(|fps| fps as u32)
+}
+
+/// Build a report from the session records; `fallback_fps` fills in cameras
+/// whose framerate was not set during the session.
+pub fn build_report(
+    session: SessionInfo,
+    records: &[SessionRecord],
+    fallback_fps: &HashMap<String, u32>,
+    aliases: &HashMap<String, String>,
+) -> SessionReport {
+    let end = records.last().map(|r| r.timestamp).unwrap_or(session.started_at);
+    let mut cameras: BTreeMap<String, CameraAccumulator> = BTreeMap::new();
+    let mut alerts = Vec::new();
+    let (mut actions, mut failed_actions, mut skipped_events) = (0, 0, 0);
+
+    for record in records {
+        let at = record.timestamp;
+        match &record.kind {
+            SessionRecordKind::Telemetry { camera_id, telemetry: t } => {
+                let camera = cameras.entry(camera_id.clone()).or_default();
+                camera.seen(at);
+                let streaming = t.ndi_state == NdiState::Streaming;
+
+                if let Some((previous_at, was_streaming)) = camera.last_sample {
+                    if was_streaming && streaming {
+                        camera.streaming_s += (at - previous_at).num_seconds();
+                    }
+                }
+                camera.last_sample = Some((at, streaming));
+
+                if streaming {
+                    camera.fps.push(t.fps);
+                    camera.bitrate.push(t.bitrate as f64);
+                }
+                // dropped_frames is cumulative per stream; a smaller value means a restart
+                let new_drops = match camera.last_dropped {
+                    Some(last) if t.dropped_frames >= last => t.dropped_frames - last,
+                    _ => t.dropped_frames,
+                };
+                camera.dropped_frames += new_drops as u64;
+                camera.last_dropped = Some(t.dropped_frames);
+                camera.min_battery = Some(camera.min_battery.map_or(t.battery, |b| b.min(t.battery)));
+                camera.peak_temp_c = Some(camera.peak_temp_c.map_or(t.temp_c, |c| c.max(t.temp_c)));
+            }
+            SessionRecordKind::ConnectionChanged { camera_id, state } => {
+                let camera = cameras.entry(camera_id.clone()).or_default();
+                camera.seen(at);
+                match state {
+                    ConnectionState::Connected => {
+                        if let Some(since) = camera.down_since.take() {
+                            camera.downtime_s += (at - since).num_seconds();
+                            camera.reconnects += 1;
+                        }
+                    }
+                    ConnectionState::Disconnected | ConnectionState::Error => {
+                        camera.down_since.get_or_insert(at);
+                        camera.last_sample = None;
+                    }
+                    ConnectionState::Connecting => {}
+                }
+            }
+            SessionRecordKind::Action { entry } => {
+                actions += 1;
+                if !entry.outcome.is_success() {
+                    failed_actions += 1;
+                }
+                if let Some(fps) = configured_framerate(entry) {
+                    for camera_id in &entry.camera_ids {
+                        cameras.entry(camera_id.clone()).or_default().configured_fps = Some(fps);
+                    }
+                }
+            }
+            SessionRecordKind::Alert { title, body } => alerts.push(ReportAlert {
+                timestamp: at,
+                camera_id: None,
+                title: title.clone(),
+                detail: body.clone(),
+            }),
+            SessionRecordKind::Notice { event, payload } => {
+                let camera_id = payload.get("camera_id").and_then(|id| id.as_str()).map(str::to_string);
+                if let Some(camera_id) = &camera_id {
+                    cameras.entry(camera_id.clone()).or_default().alerts += 1;
+                }
+                let detail = payload.get("reason").and_then(|r| r.as_str())
+                    .map(str::to_string)
+                    .unwrap_or_else(|| payload.to_string());
+                alerts.push(ReportAlert { timestamp: at, camera_id, title: event.clone(), detail });
+            }
+            SessionRecordKind::Gap { skipped } => skipped_events += skipped,
+            SessionRecordKind::SessionStarted { .. } | SessionRecordKind::SessionStopped => {}
+        }
+    }
+
+    let cameras = cameras.into_iter()
+        .filter_map(|(camera_id, mut camera)| {
+            let first_seen = camera.first_seen?;
+            if let Some(since) = camera.down_since.take() {
+                camera.downtime_s += (end - since).num_seconds();
+            }
+            let configured_fps = camera.configured_fps.or_else(|| fallback_fps.get(&camera_id).copied());
+            let fps_std_dev = Stats::of(&camera.fps).map(|stats| {
+                (camera.fps.iter().map(|fps| (fps - stats.mean).powi(2)).sum::<f64>() / camera.fps.len() as f64).sqrt()
+            });
+            let fps_on_target = configured_fps.filter(|_| !camera.fps.is_empty()).map(|target| {
+                let target = target as f64;
+                let on_target = camera.fps.iter().filter(|fps| (*fps - target).abs() <= target * FPS_TOLERANCE).count();
+                on_target as f64 / camera.fps.len() as f64
+            });
+
+            Some(CameraReport {
+                alias: aliases.get(&camera_id).cloned(),
+                first_seen,
+                uptime_s: ((end - first_seen).num_seconds() - camera.downtime_s).max(0),
+                downtime_s: camera.downtime_s,
+                reconnects: camera.reconnects,
+                streaming_s: camera.streaming_s,
+                configured_fps,
+                fps: Stats::of(&camera.fps),
+                fps_std_dev,
+                fps_on_target,
+                dropped_frames: camera.dropped_frames,
+                bitrate_bps: Stats::of(&camera.bitrate),
+                min_battery: camera.min_battery,
+                peak_temp_c: camera.peak_temp_c,
+                alerts: camera.alerts,
+                camera_id,
+            })
+        })
+        .collect();
+
+    SessionReport {
+        duration_s: (end - session.started_at).num_seconds(),
+        session,
+        generated_at: Utc::now(),
+        cameras,
+        alerts,
+        actions,
+        failed_actions,
+        skipped_events,
+    }
+}
+
+// MARK: - Rendering
+
+fn duration(seconds: i64) -> String {
+    format!("{}h{:02}m{:02}s", seconds / 3600, seconds % 3600 / 60, seconds % 60)
+}
+
+fn percent(ratio: Option<f64>) -> String {
+    ratio.map(|r| format!("{:.0}%", r * 100.0)).unwrap_or_else(|| "–".to_string())
+}
+
+fn or_dash<T>(value: Option<T>, format: impl Fn(T) -> String) -> String {
+    value.map(format).unwrap_or_else(|| "–".to_string())
+}
+
+fn title(report: &SessionReport) -> String {
+    match &report.session.name {
+        Some(name) => format!("Session report: {}", name),
+        None => format!("Session report {}", report.session.id),
+    }
+}
+
+/// Table rows shared by both formats: header, then one row per camera
+fn camera_table(report: &SessionReport) -> Vec<Vec<String>> {
+    let mut rows = vec![[
+        "Camera", "Uptime", "Downtime", "Reconnects", "Streaming", "FPS (set)", "FPS min/avg/max",
+        "FPS σ", "On target", "Dropped", "Bitrate min/avg/max", "Min battery", "Peak temp", "Alerts",
+    ].iter().map(|h| h.to_string()).collect()];
+
+    for camera in &report.cameras {
+        rows.push(vec![
+            camera.alias.clone().unwrap_or_else(|| camera.camera_id.clone()),
+            duration(camera.uptime_s),
+            duration(camera.downtime_s),
+            camera.reconnects.to_string(),
+            duration(camera.streaming_s),
+            or_dash(camera.configured_fps, |fps| fps.to_string()),
+            or_dash(camera.fps, |s| format!("{:.1} / {:.1} / {:.1}", s.min, s.mean, s.max)),
+            or_dash(camera.fps_std_dev, |sd| format!("{:.2}", sd)),
+            percent(camera.fps_on_target),
+            camera.dropped_frames.to_string(),
+            or_dash(camera.bitrate_bps, |s| format!("{:.1} / {:.1} / {:.1} Mb/s", s.min / 1e6, s.mean / 1e6, s.max / 1e6)),
+            percent(camera.min_battery),
+            or_dash(camera.peak_temp_c, |t| format!("{:.1} °C", t)),
+            camera.alerts.to_string(),
+        ]);
+    }
+
+    rows
+}
+
+fn alert_rows(report: &SessionReport) -> Vec<[String; 4]> {
+    report.alerts.iter()
+        .map(|alert| [
+            alert.timestamp.format("%H:%M:%S").to_string(),
+            alert.camera_id.clone().unwrap_or_default(),
+            alert.title.clone(),
+            alert.detail.clone(),
+        ])
+        .collect()
+}
+
+fn summary_lines(report: &SessionReport) -> Vec<String> {
+    let mut lines = vec![
+        format!("Started: {}", report.session.started_at.format("%Y-%m-%d %H:%M:%S UTC")),
+        format!("Duration: {}", duration(report.duration_s)),
+        format!("Cameras: {}", report.cameras.len()),
+        format!("Control actions: {} ({} failed)", report.actions, report.failed_actions),
+        format!("Alerts: {}", report.alerts.len()),
+    ];
+    if report.skipped_events > 0 {
+        lines.push(format!("Events lost by the recorder: {}", report.skipped_events));
+    }
+    lines
+}
+
+pub fn render_markdown(report: &SessionReport) -> String {
+    let md_cell = |cell: &str| cell.replace('|', "\\|").replace('<', "&lt;").replace('\n', " ");
+    let mut out = String::new();
+
+    let _ = writeln!(out, "# {}\n", title(report));
+    for line in summary_lines(report) {
+        let _ = writeln!(out, "- {}", line);
+    }
+
+    let _ = writeln!(out, "\n## Cameras\n");
+    for (i, row) in camera_table(report).iter().enumerate() {
+        let cells: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+        let _ = writeln!(out, "| {} |", cells.join(" | "));
+        if i == 0 {
+            let _ = writeln!(out, "|{}", "---|".repeat(row.len()));
+        }
+    }
+
+    let _ = writeln!(out, "\n## Alerts\n");
+    let rows = alert_rows(report);
+    if rows.is_empty() {
+        let _ = writeln!(out, "No alerts fired.");
+    } else {
+        let _ = writeln!(out, "| Time | Camera | Alert | Detail |\n|---|---|---|---|");
+        for row in rows {
+            let cells: Vec<String> = row.iter().map(|c| md_cell(c)).collect();
+            let _ = writeln!(out, "| {} |", cells.join(" | "));
+        }
+    }
+
+    let _ = writeln!(out, "\n_Generated {}_", report.generated_at.format("%Y-%m-%d %H:%M:%S UTC"));
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(report: &SessionReport) -> String {
+    let mut out = String::new();
+    let title = escape_html(&title(report));
+
+    let _ = writeln!(out, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>", title);
+    let _ = writeln!(out, "<style>\
+        body {{ font-family: -apple-system, 'Segoe UI', sans-serif; margin: 2rem; color: #222; }}\
+        table {{ border-collapse: collapse; margin: 1rem 0; font-size: 0.9rem; }}\
+        th, td {{ border: 1px solid #ccc; padding: 0.3rem 0.6rem; text-align: left; }}\
+        th {{ background: #f2f2f2; }}\
+        </style>\n</head>\n<body>");
+    let _ = writeln!(out, "<h1>{}</h1>\n<ul>", title);
+    for line in summary_lines(report) {
+        let _ = writeln!(out, "<li>{}</li>", escape_html(&line));
+    }
+    let _ = writeln!(out, "</ul>\n<h2>Cameras</h2>\n<table>");
+    for (i, row) in camera_table(report).iter().enumerate() {
+        let tag = if i == 0 { "th" } else { "td" };
+        let cells: String = row.iter().map(|c| format!("<{0}>{1}</{0}>", tag, escape_html(c))).collect();
+        let _ = writeln!(out, "<tr>{}</tr>", cells);
+    }
+    let _ = writeln!(out, "</table>\n<h2>Alerts</h2>");
+
+    let rows = alert_rows(report);
+    if rows.is_empty() {
+        let _ = writeln!(out, "<p>No alerts fired.</p>");
+    } else {
+        let _ = writeln!(out, "<table>\n<tr><th>Time</th><th>Camera</th><th>Alert</th><th>Detail</th></tr>");
+        for row in rows {
+            let cells: String = row.iter().map(|c| format!("<td>{}</td>", escape_html(c))).collect();
+            let _ = writeln!(out, "<tr>{}</tr>", cells);
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    let _ = writeln!(
+        out,
+        "<p><small>Generated {}</small></p>\n</body>\n</html>",
+        report.generated_at.format("%Y-%m-%d %H:%M:%S UTC"),
+    );
+    out
+}
+
+// MARK: - Recorder Integration
+
+impl SessionRecorder {
+    /// Report of a recorded session; cameras still managed fill in aliases and framerates
+    pub async fn session_report(&self, id: &str) -> Result<SessionReport> {
+        let session = self.list_sessions().await?
+            .into_iter()
+            .find(|s| s.id == id)
+            .ok_or_else(|| anyhow::anyhow!("Session not found: {}", id))?;
+        let records = self.open_session(id).await?;
+
+        let (mut fallback_fps, mut aliases) = (HashMap::new(), HashMap::new());
+        {
+            let manager = self.manager().read().await;
+            for camera_id in records.iter().filter_map(|r| match &r.kind {
+                SessionRecordKind::Telemetry { camera_id, .. } => Some(camera_id),
+                _ => None,
+            }) {
+                if fallback_fps.contains_key(camera_id) {
+                    continue;
+                }
+                fallback_fps.insert(camera_id.clone(), manager.current_fps(camera_id));
+                if let Some(alias) = manager.camera_alias(camera_id) {
+                    aliases.insert(camera_id.clone(), alias);
+                }
+            }
+        }
+
+        Ok(build_report(session, &records, &fallback_fps, &aliases))
+    }
+
+    /// Render a session report to `destination` as HTML or Markdown
+    pub async fn export_session_report(&self, id: &str, format: ReportFormat, destination: &Path) -> Result<()> {
+        let report = self.session_report(id).await?;
+        let content = match format {
+            ReportFormat::Html => render_html(&report),
+            ReportFormat::Markdown => render_markdown(&report),
+        };
+
+        tokio::fs::write(destination, content).await
+            .with_context(|| format!("Failed to write session report to {:?}", destination))?;
+
+        log::info!("Wrote {:?} report of session {} to {:?}", format, id, destination);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::audit::{ActionSource, AuditEntry, AuditOutcome};
+    use chrono::Duration;
+
+    fn start() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-05-01T19:00:00Z").unwrap().with_timezone(&Utc)
+    }
+
+    fn session() -> SessionInfo {
+        SessionInfo {
+            id: "20260501-190000".to_string(),
+            name: Some("Gala".to_string()),
+            started_at: start(),
+            ended_at: None,
+            size_bytes: 0,
+            active: false,
+        }
+    }
+
+    fn at(seconds: i64, kind: SessionRecordKind) -> SessionRecord {
+        SessionRecord { timestamp: start() + Duration::seconds(seconds), kind }
+    }
+
+    fn sample(seconds: i64, camera_id: &str, fps: f64, dropped_frames: u32, streaming: bool) -> SessionRecord {
+        at(seconds, SessionRecordKind::Telemetry {
+            camera_id: camera_id.to_string(),
+            telemetry: WebSocketTelemetryMessage {
+                fps,
+                battery: 0.9 - seconds as f64 / 1000.0,
+                temp_c: 30.0 + seconds as f64 / 10.0,
+                ndi_state: if streaming { NdiState::Streaming } else { NdiState::Idle },
+                dropped_frames,
+                ..test_support::telemetry()
+            },
+        })
+    }
+
+    fn connection(seconds: i64, camera_id: &str, state: ConnectionState) -> SessionRecord {
+        at(seconds, SessionRecordKind::ConnectionChanged { camera_id: camera_id.to_string(), state })
+    }
+
+    fn action(seconds: i64, camera_id: &str, request: serde_json::Value, success: bool) -> SessionRecord {
+        at(seconds, SessionRecordKind::Action {
+            entry: AuditEntry {
+                timestamp: start() + Duration::seconds(seconds),
+                source: ActionSource::Ui,
+                action: "start_stream".to_string(),
+                camera_ids: vec![camera_id.to_string()],
+                request,
+                outcome: if success { AuditOutcome::Success } else { AuditOutcome::Failure { error: "timed out".to_string() } },
+            },
+        })
+    }
+
+    /// Camera "a" streams at 25 fps, drops out for 30 s and restarts its stream;
+    /// camera "b" only idles and is still disconnected when the session ends
+    fn show() -> Vec<SessionRecord> {
+        vec![
+            at(0, SessionRecordKind::SessionStarted { name: Some("Gala".to_string()) }),
+            connection(0, "a", ConnectionState::Connected),
+            action(0, "a", serde_json::json!({ "framerate": 25, "resolution": "1920x1080" }), true),
+            sample(0, "a", 25.0, 0, true),
+            sample(10, "a", 25.0, 2, true),
+            sample(20, "a", 24.0, 5, true),
+            sample(30, "a", 12.0, 9, true),
+            connection(40, "a", ConnectionState::Disconnected),
+            connection(70, "a", ConnectionState::Connected),
+            sample(70, "a", 25.0, 1, true),
+            sample(80, "a", 25.0, 1, true),
+            sample(20, "b", 0.0, 0, false),
+            connection(50, "b", ConnectionState::Error),
+            action(60, "b", serde_json::json!({}), false),
+            at(75, SessionRecordKind::Notice {
+                event: "thermal-throttle".to_string(),
+                payload: serde_json::json!({ "camera_id": "a", "reason": "Device is hot" }),
+            }),
+            at(85, SessionRecordKind::Alert { title: "Battery Warning".to_string(), body: "Cam B is low".to_string() }),
+            at(90, SessionRecordKind::Gap { skipped: 3 }),
+            at(100, SessionRecordKind::SessionStopped),
+        ]
+    }
+
+    fn report() -> SessionReport {
+        let fallback_fps = HashMap::from([("a".to_string(), 30), ("b".to_string(), 30)]);
+        let aliases = HashMap::from([("a".to_string(), "Stage | Left".to_string())]);
+        build_report(session(), &show(), &fallback_fps, &aliases)
+    }
+
+    #[test]
+    fn uptime_downtime_and_reconnects() {
+        let report = report();
+        assert_eq!(report.duration_s, 100);
+        let a = &report.cameras[0];
+        assert_eq!(a.camera_id, "a");
+        assert_eq!(a.downtime_s, 30);
+        assert_eq!(a.uptime_s, 70);
+        assert_eq!(a.reconnects, 1);
+        // 0-30 s and 70-80 s; the outage does not count as streaming
+        assert_eq!(a.streaming_s, 40);
+
+        // Still down at the end of the session
+        let b = &report.cameras[1];
+        assert_eq!(b.first_seen, start() + Duration::seconds(20));
+        assert_eq!(b.downtime_s, 50);
+        assert_eq!(b.uptime_s, 30);
+        assert_eq!(b.reconnects, 0);
+        assert_eq!(b.streaming_s, 0);
+    }
+
+    #[test]
+    fn fps_stability_against_the_configured_framerate() {
+        let report = report();
+        let a = &report.cameras[0];
+        assert_eq!(a.configured_fps, Some(25), "set during the session, not the fallback");
+        let fps = a.fps.unwrap();
+        assert_eq!((fps.min, fps.max), (12.0, 25.0));
+        assert!((fps.mean - 22.666).abs() < 0.01);
+        assert!(a.fps_std_dev.unwrap() > 4.0);
+        // 24 fps is within 5% of 25, 12 fps is not
+        assert!((a.fps_on_target.unwrap() - 5.0 / 6.0).abs() < 1e-9);
+
+        let b = &report.cameras[1];
+        assert_eq!(b.configured_fps, Some(30));
+        assert!(b.fps.is_none());
+        assert!(b.fps_on_target.is_none());
+    }
+
+    #[test]
+    fn dropped_frames_survive_stream_restarts() {
+        // 9 before the restart, then 1 after the counter reset
+        assert_eq!(report().cameras[0].dropped_frames, 10);
+    }
+
+    #[test]
+    fn battery_temperature_alerts_and_actions() {
+        let report = report();
+        let a = &report.cameras[0];
+        assert!((a.min_battery.unwrap() - 0.82).abs() < 1e-9);
+        assert!((a.peak_temp_c.unwrap() - 38.0).abs() < 1e-9);
+        assert_eq!(a.alerts, 1);
+        assert_eq!(a.alias.as_deref(), Some("Stage | Left"));
+
+        assert_eq!(report.alerts.len(), 2);
+        assert_eq!(report.alerts[0].camera_id.as_deref(), Some("a"));
+        assert_eq!(report.alerts[0].detail, "Device is hot");
+        assert_eq!(report.alerts[1].camera_id, None);
+        assert_eq!((report.actions, report.failed_actions), (2, 1));
+        assert_eq!(report.skipped_events, 3);
+    }
+
+    #[test]
+    fn markdown_escapes_table_cells() {
+        let markdown = render_markdown(&report());
+
+        assert!(markdown.starts_with("# Session report: Gala\n"));
+        assert!(markdown.contains("- Control actions: 2 (1 failed)\n"));
+        assert!(markdown.contains("- Events lost by the recorder: 3\n"));
+        assert!(markdown.contains("| Stage \\| Left | 0h01m10s | 0h00m30s | 1 | 0h00m40s | 25 |"));
+        assert!(markdown.contains("| thermal-throttle | Device is hot |"));
+    }
+
+    #[test]
+    fn html_escapes_and_handles_sessions_without_alerts() {
+        let records = vec![sample(0, "<script>", 25.0, 0, true)];
+        let report = build_report(session(), &records, &HashMap::new(), &HashMap::new());
+        let html = render_html(&report);
+
+        assert!(html.contains("<td>&lt;script&gt;</td>"));
+        assert!(!html.contains("<script>"));
+        assert!(html.contains("<p>No alerts fired.</p>"));
+        assert!(render_markdown(&report).contains("No alerts fired."));
+    }
+
+    #[test]
+    fn empty_session_has_no_cameras() {
+        let report = build_report(session(), &[], &HashMap::new(), &HashMap::new());
+        assert_eq!(report.duration_s, 0);
+        assert!(report.cameras.is_empty());
+        assert!(render_html(&report).contains("<title>Session report: Gala</title>"));
+    }
+}
@@ -0,0 +1,540 @@
+//! Time-based scheduler for stream start/stop and profile changes
+//!
+//! Schedules are persisted in `schedules.json` together with their run history.
+//! Times are interpreted in the controller's local time zone, e.g.:
+//!
+//! - start all cameras at 09:55 (weekdays) with their persisted settings
+//! - apply profile "Evening" to the stage cameras at 18:00
+//! - stop all at 12:30 (one-off)
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime, TimeZone, Utc, Weekday};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::audit::ActionSource;
+use crate::camera_manager::{CameraManager, ManagerMut, ManagerRef};
+use crate::models::*;
+use crate::platform::PathProvider;
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+const MAX_HISTORY_ENTRIES: usize = 200;
+// Runs starting later than this after their slot are reported as late
+const LATE_THRESHOLD_S: i64 = 60;
+
+// MARK: - Schedule Model
+
+/// A persisted scheduled action
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Schedule {
+    /// Assigned on first save when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub action: ScheduleAction,
+    pub recurrence: Recurrence,
+    #[serde(default)]
+    pub missed_run_policy: MissedRunPolicy,
+    /// Next planned execution (maintained by the scheduler)
+    #[serde(default)]
+    pub next_run: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub last_run: Option<DateTime<Utc>>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// What a schedule does when it fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleAction {
+    /// Start every camera with its persisted stream settings
+    StartAll,
+    StopAll,
+    /// Start the given cameras with their persisted stream settings
+    StartCameras { camera_ids: Vec<String> },
+    StopCameras { camera_ids: Vec<String> },
+    ApplyProfile { profile_name: String, camera_ids: Vec<String> },
+}
+
+/// When a schedule fires
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Recurrence {
+    /// Single run at an absolute instant
+    Once { at: DateTime<Utc> },
+    /// Every selected weekday at a local time of day (`HH:MM:SS`)
+    Weekly { days: Vec<Weekday>, time: NaiveTime },
+}
+
+/// What to do with runs that were due while the controller was not running
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum MissedRunPolicy {
+    /// Drop missed runs and wait for the next slot
+    #[default]
+    Skip,
+    /// Run once immediately, however late
+    RunOnce,
+    /// Run once immediately if no more than `grace_s` late, otherwise skip
+    RunIfWithin { grace_s: u64 },
+}
+
+impl Recurrence {
+    /// First occurrence strictly after `after`
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            Recurrence::Once { at } => (*at > after).then_some(*at),
+            Recurrence::Weekly { days, time } => {
+                let local_after = after.with_timezone(&Local);
+
+                // Scan one week ahead (plus today) for the next matching weekday slot
+                (0..=7).find_map(|offset| {
+                    let date = local_after.date_naive() + ChronoDuration::days(offset);
+                    if !days.contains(&date.weekday()) {
+                        return None;
+                    }
+
+                    Local.from_local_datetime(&date.and_time(*time))
+                        .earliest()
+                        .map(|dt| dt.with_timezone(&Utc))
+                        .filter(|dt| *dt > after)
+                })
+            }
+        }
+    }
+}
+
+/// Next run of a schedule being saved or enabled; an enabled schedule that
+/// would never fire (past one-off, weekly without days) is rejected
+fn planned_next_run(recurrence: &Recurrence, enabled: bool, now: DateTime<Utc>) -> Result<Option<DateTime<Utc>>> {
+    if let Recurrence::Weekly { days, .. } = recurrence {
+        if days.is_empty() {
+            anyhow::bail!("Weekly schedule needs at least one day");
+        }
+    }
+
+    let next_run = recurrence.next_after(now);
+    if enabled && next_run.is_none() {
+        anyhow::bail!("Schedule time is in the past");
+    }
+    Ok(next_run)
+}
+
+// MARK: - Run History
+
+/// Outcome of one scheduled (or skipped) run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRun {
+    pub schedule_id: String,
+    pub schedule_name: String,
+    pub scheduled_for: DateTime<Utc>,
+    /// `None` when the run was skipped by the missed-run policy
+    pub executed_at: Option<DateTime<Utc>>,
+    /// Executed after a restart or otherwise well past its slot
+    pub late: bool,
+    pub results: Vec<GroupCommandResult>,
+    pub error: Option<String>,
+}
+
+/// Planned future run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpcomingRun {
+    pub schedule_id: String,
+    pub schedule_name: String,
+    pub at: DateTime<Utc>,
+}
+
+/// Upcoming and past runs, as listed to the UI
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduleRunsOverview {
+    pub upcoming: Vec<UpcomingRun>,
+    /// Newest first
+    pub past: Vec<ScheduleRun>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct SchedulesPersistence {
+    schedules: Vec<Schedule>,
+    #[serde(default)]
+    history: Vec<ScheduleRun>,
+}
+
+// MARK: - Scheduler
+
+#[derive(Default)]
+struct SchedulerState {
+    data: SchedulesPersistence,
+    schedules_file_path: Option<PathBuf>,
+}
+
+/// Runs persisted schedules through the camera manager
+pub struct Scheduler {
+    manager: Arc<RwLock<CameraManager>>,
+    state: Arc<RwLock<SchedulerState>>,
+}
+
+impl Scheduler {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            state: Arc::new(RwLock::new(SchedulerState::default())),
+        }
+    }
+
+    /// Inject where schedules are persisted, load them and apply the
+    /// missed-run policy to anything that was due while the app was closed
+    pub async fn set_path_provider(&self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        let path = paths.schedules_file()?;
+        let mut state = self.state.write().await;
+
+        if path.exists() {
+            let json = tokio::fs::read_to_string(&path).await
+                .context("Failed to read schedules file")?;
+            state.data = serde_json::from_str(&json)
+                .context("Failed to deserialize schedules")?;
+            log::info!("Loaded {} schedules from {:?}", state.data.schedules.len(), path);
+        } else {
+            log::info!("No schedules file found at {:?}, starting fresh", path);
+        }
+
+        state.schedules_file_path = Some(path);
+
+        let now = Utc::now();
+        let mut skipped = Vec::new();
+
+        for schedule in state.data.schedules.iter_mut().filter(|s| s.enabled) {
+            let Some(due) = schedule.next_run else {
+                schedule.next_run = schedule.recurrence.next_after(now);
+                continue;
+            };
+
+            if due > now {
+                continue;
+            }
+
+            let keep = match schedule.missed_run_policy {
+                MissedRunPolicy::Skip => false,
+                MissedRunPolicy::RunOnce => true,
+                MissedRunPolicy::RunIfWithin { grace_s } => (now - due).num_seconds() <= grace_s as i64,
+            };
+
+            if !keep {
+                log::info!("Skipping missed run of schedule '{}' due at {}", schedule.name, due);
+                skipped.push(ScheduleRun {
+                    schedule_id: schedule.id.clone(),
+                    schedule_name: schedule.name.clone(),
+                    scheduled_for: due,
+                    executed_at: None,
+                    late: true,
+                    results: Vec::new(),
+                    error: Some("Missed while controller was not running".to_string()),
+                });
+                schedule.next_run = schedule.recurrence.next_after(now);
+                schedule.enabled = schedule.next_run.is_some();
+            }
+            // Otherwise leave next_run in the past so the first tick runs it
+        }
+
+        for run in skipped {
+            push_history(&mut state.data, run);
+        }
+
+        save_schedules_to_disk(&state).await
+    }
+
+    /// Check for due schedules every second in the background
+    pub fn start(&self) {
+        let manager = self.manager.clone();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+
+            loop {
+                interval.tick().await;
+                run_due_schedules(&manager, &state).await;
+            }
+        });
+
+        log::info!("Scheduler started");
+    }
+
+    // MARK: - Schedule Management
+
+    pub async fn get_schedules(&self) -> Vec<Schedule> {
+        self.state.read().await.data.schedules.clone()
+    }
+
+    /// Create or replace a schedule (by id), returning the stored schedule
+    pub async fn save_schedule(&self, mut schedule: Schedule) -> Result<Schedule> {
+        schedule.next_run = planned_next_run(&schedule.recurrence, schedule.enabled, Utc::now())?;
+
+        let mut state = self.state.write().await;
+
+        if schedule.id.is_empty() {
+            schedule.id = Uuid::new_v4().to_string();
+        }
+
+        if let Some(existing) = state.data.schedules.iter_mut().find(|s| s.id == schedule.id) {
+            schedule.last_run = existing.last_run;
+            *existing = schedule.clone();
+            log::info!("Updated schedule: {}", schedule.name);
+        } else {
+            state.data.schedules.push(schedule.clone());
+            log::info!("Created schedule: {}", schedule.name);
+        }
+
+        save_schedules_to_disk(&state).await?;
+        Ok(schedule)
+    }
+
+    pub async fn delete_schedule(&self, schedule_id: &str) -> Result<()> {
+        let mut state = self.state.write().await;
+
+        let initial_len = state.data.schedules.len();
+        state.data.schedules.retain(|s| s.id != schedule_id);
+
+        if state.data.schedules.len() == initial_len {
+            anyhow::bail!("Schedule not found: {}", schedule_id);
+        }
+
+        save_schedules_to_disk(&state).await?;
+        log::info!("Deleted schedule: {}", schedule_id);
+        Ok(())
+    }
+
+    pub async fn set_schedule_enabled(&self, schedule_id: &str, enabled: bool) -> Result<()> {
+        let mut state = self.state.write().await;
+
+        let schedule = state.data.schedules.iter_mut()
+            .find(|s| s.id == schedule_id)
+            .ok_or_else(|| anyhow::anyhow!("Schedule not found: {}", schedule_id))?;
+
+        // Re-enabling never replays runs missed while disabled
+        schedule.next_run = planned_next_run(&schedule.recurrence, enabled, Utc::now())?;
+        schedule.enabled = enabled;
+
+        save_schedules_to_disk(&state).await
+    }
+
+    /// Next `limit` planned runs across all enabled schedules, plus the run history
+    pub async fn get_runs(&self, limit: usize) -> ScheduleRunsOverview {
+        let state = self.state.read().await;
+
+        let mut upcoming = Vec::new();
+        for schedule in state.data.schedules.iter().filter(|s| s.enabled) {
+            let mut next = schedule.next_run;
+
+            // Each schedule contributes at most `limit` runs before merging
+            for _ in 0..limit {
+                let Some(at) = next else {
+                    break;
+                };
+
+                upcoming.push(UpcomingRun {
+                    schedule_id: schedule.id.clone(),
+                    schedule_name: schedule.name.clone(),
+                    at,
+                });
+                next = schedule.recurrence.next_after(at);
+            }
+        }
+
+        upcoming.sort_by_key(|u| u.at);
+        upcoming.truncate(limit);
+
+        ScheduleRunsOverview {
+            upcoming,
+            past: state.data.history.iter().rev().cloned().collect(),
+        }
+    }
+}
+
+async fn save_schedules_to_disk(state: &SchedulerState) -> Result<()> {
+    let Some(path) = &state.schedules_file_path else {
+        return Ok(()); // No persistence path set
+    };
+
+    let json = serde_json::to_string_pretty(&state.data)
+        .context("Failed to serialize schedules")?;
+
+    tokio::fs::write(path, json).await
+        .context("Failed to write schedules to disk")?;
+
+    Ok(())
+}
+
+fn push_history(data: &mut SchedulesPersistence, run: ScheduleRun) {
+    data.history.push(run);
+    if data.history.len() > MAX_HISTORY_ENTRIES {
+        let excess = data.history.len() - MAX_HISTORY_ENTRIES;
+        data.history.drain(..excess);
+    }
+}
+
+// MARK: - Execution
+
+async fn run_due_schedules(
+    manager: &Arc<RwLock<CameraManager>>,
+    state: &Arc<RwLock<SchedulerState>>,
+) {
+    let now = Utc::now();
+
+    // Collect due schedules and advance them before executing, so the state
+    // lock isn't held across camera requests
+    let due: Vec<(Schedule, DateTime<Utc>)> = {
+        let mut state = state.write().await;
+        let mut due = Vec::new();
+
+        for schedule in state.data.schedules.iter_mut().filter(|s| s.enabled) {
+            let Some(at) = schedule.next_run.filter(|at| *at <= now) else {
+                continue;
+            };
+
+            schedule.last_run = Some(now);
+            schedule.next_run = schedule.recurrence.next_after(now);
+            if schedule.next_run.is_none() {
+                schedule.enabled = false; // One-off schedule has fired
+            }
+
+            due.push((schedule.clone(), at));
+        }
+
+        due
+    };
+
+    if due.is_empty() {
+        return;
+    }
+
+    for (schedule, scheduled_for) in due {
+        log::info!("Running schedule '{}' (due {})", schedule.name, scheduled_for);
+
+        let result = execute_action(manager, &schedule.action).await;
+        let executed_at = Utc::now();
+
+        if let Err(e) = &result {
+            log::error!("Schedule '{}' failed: {}", schedule.name, e);
+        }
+
+        let (results, error) = match result {
+            Ok(results) => (results, None),
+            Err(e) => (Vec::new(), Some(e.to_string())),
+        };
+
+        let run = ScheduleRun {
+            schedule_id: schedule.id.clone(),
+            schedule_name: schedule.name.clone(),
+            scheduled_for,
+            executed_at: Some(executed_at),
+            late: (executed_at - scheduled_for).num_seconds() > LATE_THRESHOLD_S,
+            results,
+            error,
+        };
+
+        if let Ok(payload) = serde_json::to_value(&run) {
+            manager.read().await.notifier().emit("schedule-run", payload);
+        }
+
+        push_history(&mut state.write().await.data, run);
+    }
+
+    if let Err(e) = save_schedules_to_disk(&*state.read().await).await {
+        log::warn!("Failed to save schedules to disk: {}", e);
+    }
+}
+
+async fn execute_action(
+    manager: &Arc<RwLock<CameraManager>>,
+    action: &ScheduleAction,
+) -> Result<Vec<GroupCommandResult>> {
+    match action {
+        ScheduleAction::StartAll => CameraManager::start_all_cameras_via(ManagerRef::Locked(manager), ActionSource::Scheduler).await,
+        ScheduleAction::StopAll => CameraManager::stop_all_cameras_via(ManagerRef::Locked(manager), ActionSource::Scheduler).await,
+        ScheduleAction::StartCameras { camera_ids } => {
+            CameraManager::start_cameras_with_persisted_settings_via(ManagerRef::Locked(manager), camera_ids, ActionSource::Scheduler).await
+        }
+        ScheduleAction::StopCameras { camera_ids } => {
+            CameraManager::group_stop_stream_via(ManagerRef::Locked(manager), camera_ids, ActionSource::Scheduler).await
+        }
+        ScheduleAction::ApplyProfile { profile_name, camera_ids } => {
+            CameraManager::apply_profile_via(ManagerMut::Locked(manager), profile_name, camera_ids, ActionSource::Scheduler).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EVERY_DAY: [Weekday; 7] = [
+        Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat, Weekday::Sun,
+    ];
+
+    fn local(day: u32, hour: u32, minute: u32) -> DateTime<Utc> {
+        Local.with_ymd_and_hms(2026, 6, day, hour, minute, 0).unwrap().with_timezone(&Utc)
+    }
+
+    fn weekly(days: &[Weekday], hour: u32, minute: u32) -> Recurrence {
+        Recurrence::Weekly { days: days.to_vec(), time: NaiveTime::from_hms_opt(hour, minute, 0).unwrap() }
+    }
+
+    #[test]
+    fn once_fires_only_before_its_instant() {
+        let at = local(3, 12, 30);
+        let once = Recurrence::Once { at };
+
+        assert_eq!(once.next_after(local(3, 9, 0)), Some(at));
+        assert_eq!(once.next_after(at), None);
+        assert_eq!(once.next_after(local(4, 9, 0)), None);
+    }
+
+    #[test]
+    fn daily_fires_today_or_tomorrow() {
+        let daily = weekly(&EVERY_DAY, 9, 55);
+
+        // Wednesday 3 June
+        assert_eq!(daily.next_after(local(3, 8, 0)), Some(local(3, 9, 55)));
+        assert_eq!(daily.next_after(local(3, 9, 55)), Some(local(4, 9, 55)));
+        assert_eq!(daily.next_after(local(3, 23, 0)), Some(local(4, 9, 55)));
+    }
+
+    #[test]
+    fn weekly_wraps_into_next_week() {
+        let mondays = weekly(&[Weekday::Mon], 9, 0);
+        // Saturday 6 June -> Monday 8 June
+        assert_eq!(mondays.next_after(local(6, 12, 0)), Some(local(8, 9, 0)));
+
+        // Same weekday, slot already passed: a full week later
+        let saturdays = weekly(&[Weekday::Sat], 10, 0);
+        assert_eq!(saturdays.next_after(local(6, 12, 0)), Some(local(13, 10, 0)));
+    }
+
+    #[test]
+    fn weekly_without_days_is_rejected() {
+        let never = weekly(&[], 9, 0);
+
+        assert_eq!(never.next_after(local(3, 8, 0)), None);
+        let error = planned_next_run(&never, true, local(3, 8, 0)).unwrap_err();
+        assert!(error.to_string().contains("at least one day"));
+    }
+
+    #[test]
+    fn past_once_is_rejected_only_when_enabled() {
+        let once = Recurrence::Once { at: local(3, 12, 0) };
+
+        let error = planned_next_run(&once, true, local(3, 13, 0)).unwrap_err();
+        assert_eq!(error.to_string(), "Schedule time is in the past");
+        assert_eq!(planned_next_run(&once, false, local(3, 13, 0)).unwrap(), None);
+        assert_eq!(planned_next_run(&once, true, local(3, 11, 0)).unwrap(), Some(local(3, 12, 0)));
+    }
+}
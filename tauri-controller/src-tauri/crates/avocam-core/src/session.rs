@@ -0,0 +1,421 @@
+//! Session recorder for post-show analysis
+//!
+//! While a session is running, every telemetry sample, connection change,
+//! alert, frontend notice and audited control action of every camera is
+//! appended to `sessions/<id>.jsonl` in the app data dir. Recording happens in
+//! the backend, so it keeps running when the UI reloads. Sessions can be
+//! listed, read back, exported as CSV and deleted.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{broadcast, RwLock};
+
+use crate::audit::{csv_field, AuditEntry};
+use crate::camera_manager::CameraManager;
+use crate::events::CameraEvent;
+use crate::models::*;
+use crate::platform::PathProvider;
+
+// MARK: - Records
+
+/// One line of a session file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    pub kind: SessionRecordKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SessionRecordKind {
+    SessionStarted { name: Option<String> },
+    SessionStopped,
+    Telemetry { camera_id: String, telemetry: WebSocketTelemetryMessage },
+    ConnectionChanged { camera_id: String, state: ConnectionState },
+    Action { entry: AuditEntry },
+    Alert { title: String, body: String },
+    Notice { event: String, payload: serde_json::Value },
+    /// The recorder fell behind and lost events
+    Gap { skipped: u64 },
+}
+
+impl SessionRecordKind {
+    fn from_event(event: CameraEvent) -> Option<Self> {
+        match event {
+            CameraEvent::Telemetry { camera_id, telemetry } => Some(Self::Telemetry { camera_id, telemetry }),
+            CameraEvent::ConnectionChanged { camera_id, state } => Some(Self::ConnectionChanged { camera_id, state }),
+            CameraEvent::Action { entry } => Some(Self::Action { entry }),
+            CameraEvent::Alert { title, body } => Some(Self::Alert { title, body }),
+            CameraEvent::Notice { event, payload } => Some(Self::Notice { event, payload }),
+            CameraEvent::Discovered { .. } | CameraEvent::DiscoveryLost { .. } => None,
+        }
+    }
+}
+
+/// Summary of a recorded (or running) session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub started_at: DateTime<Utc>,
+    /// Last write to the file; `None` while recording
+    pub ended_at: Option<DateTime<Utc>>,
+    pub size_bytes: u64,
+    pub active: bool,
+}
+
+// MARK: - Recorder
+
+struct ActiveSession {
+    id: String,
+    name: Option<String>,
+    started_at: DateTime<Utc>,
+    path: PathBuf,
+    file: tokio::fs::File,
+}
+
+#[derive(Default)]
+struct RecorderState {
+    sessions_dir: Option<PathBuf>,
+    active: Option<ActiveSession>,
+}
+
+/// Records the event bus to session files
+pub struct SessionRecorder {
+    manager: Arc<RwLock<CameraManager>>,
+    state: Arc<RwLock<RecorderState>>,
+}
+
+impl SessionRecorder {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            state: Arc::new(RwLock::new(RecorderState::default())),
+        }
+    }
+
+    pub(crate) fn manager(&self) -> &Arc<RwLock<CameraManager>> {
+        &self.manager
+    }
+
+    /// Inject where session files are kept
+    pub async fn set_path_provider(&self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        let dir = paths.sessions_dir()?;
+        log::info!("Recording sessions to {:?}", dir);
+        self.state.write().await.sessions_dir = Some(dir);
+        Ok(())
+    }
+
+    /// Subscribe to the event bus and write events while a session runs
+    pub async fn start(&self) {
+        let mut events = self.manager.read().await.subscribe();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let kind = match events.recv().await {
+                    Ok(event) => match SessionRecordKind::from_event(event) {
+                        Some(kind) => kind,
+                        None => continue,
+                    },
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Session recorder lagged, skipped {} events", skipped);
+                        SessionRecordKind::Gap { skipped }
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                if let Some(session) = &mut state.write().await.active {
+                    if let Err(e) = write_record(&mut session.file, kind).await {
+                        log::warn!("Failed to write session record: {}", e);
+                    }
+                }
+            }
+
+            log::warn!("Session recorder event loop ended");
+        });
+
+        log::info!("Session recorder started");
+    }
+
+    // MARK: - Sessions
+
+    /// Start recording to a new timestamped file (fails if one is already running)
+    pub async fn start_session(&self, name: Option<String>) -> Result<SessionInfo> {
+        let mut state = self.state.write().await;
+        if let Some(session) = &state.active {
+            anyhow::bail!("Session {} is already recording", session.id);
+        }
+        let dir = state.sessions_dir.clone()
+            .ok_or_else(|| anyhow::anyhow!("Sessions path not set"))?;
+
+        let started_at = Utc::now();
+        let (id, path, mut file) = create_session_file(&dir, &started_at.format("%Y%m%d-%H%M%S").to_string()).await?;
+
+        write_record(&mut file, SessionRecordKind::SessionStarted { name: name.clone() }).await?;
+        log::info!("Started session {} ({:?})", id, path);
+
+        let info = SessionInfo {
+            id: id.clone(),
+            name: name.clone(),
+            started_at,
+            ended_at: None,
+            size_bytes: 0,
+            active: true,
+        };
+        state.active = Some(ActiveSession { id, name, started_at, path, file });
+        Ok(info)
+    }
+
+    /// Stop the running session, returning its summary
+    pub async fn stop_session(&self) -> Result<SessionInfo> {
+        let mut state = self.state.write().await;
+        let mut session = state.active.take()
+            .ok_or_else(|| anyhow::anyhow!("No session is recording"))?;
+
+        write_record(&mut session.file, SessionRecordKind::SessionStopped).await?;
+        log::info!("Stopped session {}", session.id);
+
+        Ok(SessionInfo {
+            id: session.id,
+            name: session.name,
+            started_at: session.started_at,
+            ended_at: Some(Utc::now()),
+            size_bytes: file_size(&session.path).await,
+            active: false,
+        })
+    }
+
+    pub async fn active_session(&self) -> Option<SessionInfo> {
+        let state = self.state.read().await;
+        let session = state.active.as_ref()?;
+
+        Some(SessionInfo {
+            id: session.id.clone(),
+            name: session.name.clone(),
+            started_at: session.started_at,
+            ended_at: None,
+            size_bytes: file_size(&session.path).await,
+            active: true,
+        })
+    }
+
+    /// Every session on disk, newest first
+    pub async fn list_sessions(&self) -> Result<Vec<SessionInfo>> {
+        let state = self.state.read().await;
+        let Some(dir) = &state.sessions_dir else {
+            return Ok(Vec::new());
+        };
+        let active_id = state.active.as_ref().map(|s| s.id.clone());
+
+        let mut entries = tokio::fs::read_dir(dir).await
+            .with_context(|| format!("Failed to read sessions directory {:?}", dir))?;
+
+        let mut sessions = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+                continue;
+            };
+            if path.extension().is_none_or(|ext| ext != "jsonl") {
+                continue;
+            }
+
+            match read_header(&path).await {
+                Ok((started_at, name)) => {
+                    let metadata = entry.metadata().await?;
+                    let active = active_id.as_deref() == Some(id.as_str());
+                    sessions.push(SessionInfo {
+                        id,
+                        name,
+                        started_at,
+                        ended_at: (!active).then(|| metadata.modified().ok().map(DateTime::<Utc>::from)).flatten(),
+                        size_bytes: metadata.len(),
+                        active,
+                    });
+                }
+                Err(e) => log::warn!("Skipping session file {:?}: {:#}", path, e),
+            }
+        }
+
+        sessions.sort_by_key(|s| std::cmp::Reverse(s.started_at));
+        Ok(sessions)
+    }
+
+    /// All records of a session, oldest first
+    pub async fn open_session(&self, id: &str) -> Result<Vec<SessionRecord>> {
+        let path = self.session_path(id).await?;
+        read_records(&path).await
+    }
+
+    /// Write a session as CSV (one row per record), returning the row count
+    pub async fn export_session_csv(&self, id: &str, destination: &Path) -> Result<usize> {
+        let records = self.open_session(id).await?;
+        let content = records_to_csv(&records)?;
+
+        tokio::fs::write(destination, content).await
+            .with_context(|| format!("Failed to write session export to {:?}", destination))?;
+
+        log::info!("Exported {} session records to {:?}", records.len(), destination);
+        Ok(records.len())
+    }
+
+    pub async fn delete_session(&self, id: &str) -> Result<()> {
+        if self.state.read().await.active.as_ref().is_some_and(|s| s.id == id) {
+            anyhow::bail!("Session {} is still recording", id);
+        }
+
+        let path = self.session_path(id).await?;
+        tokio::fs::remove_file(&path).await
+            .with_context(|| format!("Failed to delete session {:?}", path))?;
+
+        log::info!("Deleted session {}", id);
+        Ok(())
+    }
+
+    async fn session_path(&self, id: &str) -> Result<PathBuf> {
+        // Ids are generated timestamps; anything else could escape the directory
+        if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            anyhow::bail!("Invalid session id: {}", id);
+        }
+
+        let state = self.state.read().await;
+        let dir = state.sessions_dir.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Sessions path not set"))?;
+        let path = dir.join(format!("{}.jsonl", id));
+        if !path.exists() {
+            anyhow::bail!("Session not found: {}", id);
+        }
+
+        Ok(path)
+    }
+}
+
+// MARK: - Files
+
+/// Create a new session file named after `base_id`, suffixing `-2`, `-3`, ...
+/// when a session was already started in the same second
+async fn create_session_file(dir: &Path, base_id: &str) -> Result<(String, PathBuf, tokio::fs::File)> {
+    let mut id = base_id.to_string();
+    let mut attempt = 1;
+    loop {
+        let path = dir.join(format!("{}.jsonl", id));
+        match tokio::fs::OpenOptions::new().write(true).create_new(true).open(&path).await {
+            Ok(file) => return Ok((id, path, file)),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                attempt += 1;
+                id = format!("{}-{}", base_id, attempt);
+            }
+            Err(e) => return Err(e).with_context(|| format!("Failed to create session file {:?}", path)),
+        }
+    }
+}
+
+async fn write_record(file: &mut tokio::fs::File, kind: SessionRecordKind) -> Result<()> {
+    let record = SessionRecord { timestamp: Utc::now(), kind };
+    let mut line = serde_json::to_string(&record)
+        .context("Failed to serialize session record")?;
+    line.push('\n');
+
+    file.write_all(line.as_bytes()).await
+        .context("Failed to append to session file")?;
+    // Flush per record so a crash mid-show loses nothing already received
+    file.flush().await
+        .context("Failed to flush session file")?;
+    Ok(())
+}
+
+async fn file_size(path: &Path) -> u64 {
+    tokio::fs::metadata(path).await.map(|m| m.len()).unwrap_or(0)
+}
+
+/// Start time and name from the first line of a session file
+async fn read_header(path: &Path) -> Result<(DateTime<Utc>, Option<String>)> {
+    let file = tokio::fs::File::open(path).await
+        .context("Failed to open session file")?;
+    let mut first_line = String::new();
+    BufReader::new(file).read_line(&mut first_line).await
+        .context("Failed to read session file")?;
+
+    let record: SessionRecord = serde_json::from_str(&first_line)
+        .context("Missing session header")?;
+    match record.kind {
+        SessionRecordKind::SessionStarted { name } => Ok((record.timestamp, name)),
+        _ => anyhow::bail!("Missing session header"),
+    }
+}
+
+async fn read_records(path: &Path) -> Result<Vec<SessionRecord>> {
+    let content = tokio::fs::read_to_string(path).await
+        .with_context(|| format!("Failed to read session {:?}", path))?;
+
+    Ok(content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str::<SessionRecord>(line) {
+            Ok(record) => Some(record),
+            Err(e) => {
+                log::warn!("Skipping malformed session record: {}", e);
+                None
+            }
+        })
+        .collect())
+}
+
+fn records_to_csv(records: &[SessionRecord]) -> Result<String> {
+    let mut out = String::from(
+        "timestamp,type,camera_id,fps,bitrate,queue_ms,battery,temp_c,wifi_rssi,cpu_usage,ndi_state,dropped_frames,charging_state,details\n",
+    );
+
+    for record in records {
+        let kind = serde_json::to_value(&record.kind)?;
+        let record_type = kind.get("type").and_then(|t| t.as_str()).unwrap_or_default().to_string();
+
+        let mut row = vec![record.timestamp.to_rfc3339(), record_type];
+        match &record.kind {
+            SessionRecordKind::Telemetry { camera_id, telemetry: t } => {
+                row.push(camera_id.clone());
+                row.extend([
+                    t.fps.to_string(),
+                    t.bitrate.to_string(),
+                    t.queue_ms.to_string(),
+                    t.battery.to_string(),
+                    t.temp_c.to_string(),
+                    t.wifi_rssi.to_string(),
+                    t.cpu_usage.to_string(),
+                    serde_json::to_value(t.ndi_state)?.as_str().unwrap_or_default().to_string(),
+                    t.dropped_frames.to_string(),
+                    serde_json::to_value(t.charging_state)?.as_str().unwrap_or_default().to_string(),
+                    String::new(),
+                ]);
+            }
+            other => {
+                let camera_id = match other {
+                    SessionRecordKind::ConnectionChanged { camera_id, .. } => camera_id.clone(),
+                    SessionRecordKind::Action { entry } => entry.camera_ids.join(";"),
+                    _ => String::new(),
+                };
+                // Everything but the type goes into the details column
+                let mut details = kind;
+                if let Some(object) = details.as_object_mut() {
+                    object.remove("type");
+                }
+
+                row.push(camera_id);
+                row.extend(std::iter::repeat_n(String::new(), 10));
+                row.push(if details.as_object().is_some_and(|o| o.is_empty()) { String::new() } else { details.to_string() });
+            }
+        }
+
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&fields.join(","));
+        out.push('\n');
+    }
+
+    Ok(out)
+}
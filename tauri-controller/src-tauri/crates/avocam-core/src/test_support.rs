@@ -0,0 +1,33 @@
+//! Fixtures shared by the unit tests
+
+use crate::models::*;
+
+/// Telemetry of a healthy streaming camera; tests override the fields they exercise
+pub(crate) fn telemetry() -> WebSocketTelemetryMessage {
+    WebSocketTelemetryMessage {
+        fps: 30.0,
+        bitrate: 10_000_000,
+        queue_ms: 20,
+        battery: 80.0,
+        temp_c: 35.0,
+        wifi_rssi: -55,
+        cpu_usage: 30.0,
+        ndi_state: NdiState::Streaming,
+        dropped_frames: 0,
+        charging_state: ChargingState::Unplugged,
+        timestamp: None,
+    }
+}
+
+/// An H.264 capability with no lens, zoom or ISO limits; tests override the fields they exercise
+pub(crate) fn capability(resolution: &str, fps: &[u32]) -> Capability {
+    Capability {
+        resolution: resolution.to_string(),
+        fps: fps.to_vec(),
+        codec: vec!["h264".to_string()],
+        lens: None,
+        max_zoom: None,
+        min_iso: None,
+        max_iso: None,
+    }
+}
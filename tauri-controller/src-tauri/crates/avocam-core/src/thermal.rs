@@ -0,0 +1,739 @@
+//! Thermal guard
+//!
+//! Tracks a smoothed `temp_c` and its trend per camera. Past each configured
+//! stage the stream is stepped down (fps, bitrate, resolution, within the
+//! camera's capabilities) or moved to a cool-down profile. Once the phone has
+//! stayed below the restore temperature for the cool-down time, the original
+//! [`StreamStartRequest`] is restored. Both update the running stream in
+//! place, so the program feed is never cut. Every transition is logged, kept in the
+//! camera's history and emitted as a `thermal-transition` event.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{broadcast, RwLock};
+
+use crate::audit::ActionSource;
+use crate::camera_manager::{CameraManager, ManagerMut, ManagerRef, StreamDelivery};
+use crate::events::CameraEvent;
+use crate::models::*;
+use crate::platform::PathProvider;
+
+/// Transitions kept per camera
+const MAX_TRANSITIONS: usize = 20;
+
+// MARK: - Configuration
+
+/// One step-down stage; limits of lower stages still apply
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ThermalStage {
+    /// Smoothed temperature at which the stage is entered
+    pub above_c: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fps: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_bitrate_bps: Option<u32>,
+    /// Largest resolution allowed, e.g. "1280x720"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_resolution: Option<String>,
+    /// Apply this profile instead of the limits above
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+}
+
+/// Thermal policy shared by every camera (`thermal.json`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThermalConfig {
+    /// Auto step-down is opt-in; temperatures are tracked either way
+    pub enabled: bool,
+    pub stages: Vec<ThermalStage>,
+    /// Weight of the newest sample in the moving averages (0-1]
+    pub smoothing: f64,
+    /// Minimum time in a stage before escalating further
+    pub min_stage_s: u64,
+    /// Restore only below this smoothed temperature...
+    pub restore_below_c: f64,
+    /// ...once it has stayed there (and stopped rising) this long
+    pub cooldown_s: u64,
+}
+
+impl Default for ThermalConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            stages: vec![
+                ThermalStage { above_c: 43.0, max_bitrate_bps: Some(6_000_000), ..Default::default() },
+                ThermalStage { above_c: 45.0, max_fps: Some(25), ..Default::default() },
+                ThermalStage { above_c: 47.0, max_resolution: Some("1280x720".to_string()), ..Default::default() },
+            ],
+            smoothing: 0.2,
+            min_stage_s: 30,
+            restore_below_c: 40.0,
+            cooldown_s: 300,
+        }
+    }
+}
+
+/// One stage change, emitted as a `thermal-transition` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalTransition {
+    pub camera_id: String,
+    /// 0 is normal operation, n is the n-th configured stage
+    pub from_stage: usize,
+    pub to_stage: usize,
+    pub temp_c: f64,
+    pub trend_c_per_min: f64,
+    /// Stream settings applied by the transition
+    pub stream: Option<StreamStartRequest>,
+    pub profile: Option<String>,
+    pub reason: String,
+    pub error: Option<String>,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Live thermal view of a camera
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThermalCameraStatus {
+    pub camera_id: String,
+    pub temp_c: f64,
+    pub trend_c_per_min: f64,
+    pub stage: usize,
+    /// Settings restored after cooling down
+    pub original_stream: Option<StreamStartRequest>,
+    /// Seconds spent below the restore temperature so far
+    pub cooling_for_s: Option<u64>,
+    pub transitions: Vec<ThermalTransition>,
+}
+
+// MARK: - Engine
+
+/// Step-down of a camera that must survive a controller restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Throttle {
+    stage: usize,
+    /// Stream restored after cooling down
+    original: StreamStartRequest,
+    /// Stream the guard last persisted (a different persisted value means the operator changed it)
+    applied: Option<StreamStartRequest>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StageChange {
+    Escalate(usize),
+    Restore,
+}
+
+struct CameraThermal {
+    temp_c: f64,
+    trend_c_per_min: f64,
+    last_sample: Instant,
+    stage: usize,
+    stage_since: Instant,
+    cool_since: Option<Instant>,
+    original: Option<StreamStartRequest>,
+    applied: Option<StreamStartRequest>,
+    transitions: VecDeque<ThermalTransition>,
+}
+
+impl CameraThermal {
+    fn new(temp_c: f64, throttle: Option<Throttle>, now: Instant) -> Self {
+        let (stage, original, applied) = match throttle {
+            Some(throttle) => (throttle.stage, Some(throttle.original), throttle.applied),
+            None => (0, None, None),
+        };
+
+        Self {
+            temp_c,
+            trend_c_per_min: 0.0,
+            last_sample: now,
+            stage,
+            stage_since: now,
+            cool_since: None,
+            original,
+            applied,
+            transitions: VecDeque::new(),
+        }
+    }
+
+    fn update(&mut self, temp_c: f64, alpha: f64, now: Instant) {
+        let elapsed_min = now.duration_since(self.last_sample).as_secs_f64().max(0.1) / 60.0;
+        let alpha = alpha.clamp(0.01, 1.0);
+        let previous = self.temp_c;
+
+        self.temp_c += alpha * (temp_c - self.temp_c);
+        self.trend_c_per_min += alpha * ((self.temp_c - previous) / elapsed_min - self.trend_c_per_min);
+        self.last_sample = now;
+    }
+
+    /// Stage change due now: escalate while streaming once `min_stage_s` has
+    /// passed in the current stage, restore after `cooldown_s` below the
+    /// restore temperature without warming up
+    fn stage_change(&mut self, config: &ThermalConfig, streaming: bool, now: Instant) -> Option<StageChange> {
+        let target_stage = config.stages.iter().filter(|s| self.temp_c >= s.above_c).count();
+
+        if self.stage > 0 {
+            if self.temp_c < config.restore_below_c && self.trend_c_per_min <= 0.0 {
+                self.cool_since.get_or_insert(now);
+            } else {
+                self.cool_since = None;
+            }
+        }
+
+        let escalate = streaming
+            && target_stage > self.stage
+            && (self.stage == 0 || now.duration_since(self.stage_since) >= Duration::from_secs(config.min_stage_s));
+        let restore = self.stage > 0
+            && self.cool_since.is_some_and(|since| now.duration_since(since) >= Duration::from_secs(config.cooldown_s));
+
+        if escalate {
+            Some(StageChange::Escalate(target_stage))
+        } else if restore {
+            Some(StageChange::Restore)
+        } else {
+            None
+        }
+    }
+
+    fn enter_stage(&mut self, stage: usize, now: Instant) {
+        self.stage = stage;
+        self.stage_since = now;
+        self.cool_since = None;
+        if stage == 0 {
+            self.original = None;
+            self.applied = None;
+        }
+    }
+
+    fn throttle(&self) -> Option<Throttle> {
+        let original = self.original.clone().filter(|_| self.stage > 0)?;
+        Some(Throttle { stage: self.stage, original, applied: self.applied.clone() })
+    }
+
+    fn push_transition(&mut self, transition: ThermalTransition) {
+        if self.transitions.len() == MAX_TRANSITIONS {
+            self.transitions.pop_front();
+        }
+        self.transitions.push_back(transition);
+    }
+}
+
+#[derive(Default)]
+struct ThermalState {
+    config: ThermalConfig,
+    config_file_path: Option<PathBuf>,
+    state_file_path: Option<PathBuf>,
+    /// Step-downs loaded from disk for cameras not heard from yet
+    restored: HashMap<String, Throttle>,
+    cameras: HashMap<String, CameraThermal>,
+}
+
+/// Steps hot cameras down and restores them once cool
+pub struct ThermalGuard {
+    manager: Arc<RwLock<CameraManager>>,
+    state: Arc<RwLock<ThermalState>>,
+}
+
+impl ThermalGuard {
+    pub fn new(manager: Arc<RwLock<CameraManager>>) -> Self {
+        Self {
+            manager,
+            state: Arc::new(RwLock::new(ThermalState::default())),
+        }
+    }
+
+    /// Inject where the thermal policy and step-downs are persisted and load them
+    pub async fn set_path_provider(&self, paths: Arc<dyn PathProvider>) -> Result<()> {
+        let path = paths.thermal_file()?;
+        let state_path = paths.thermal_state_file()?;
+        let mut state = self.state.write().await;
+
+        if path.exists() {
+            let json = tokio::fs::read_to_string(&path).await
+                .context("Failed to read thermal policy file")?;
+            state.config = serde_json::from_str(&json)
+                .context("Failed to deserialize thermal policy")?;
+            log::info!("Loaded thermal policy with {} stages from {:?}", state.config.stages.len(), path);
+        } else {
+            log::info!("No thermal policy file found at {:?}, using defaults", path);
+        }
+
+        if state_path.exists() {
+            let json = tokio::fs::read_to_string(&state_path).await
+                .context("Failed to read thermal state file")?;
+            state.restored = serde_json::from_str(&json)
+                .context("Failed to deserialize thermal state")?;
+            log::info!("Loaded {} stepped-down cameras from {:?}", state.restored.len(), state_path);
+        }
+
+        state.config_file_path = Some(path);
+        state.state_file_path = Some(state_path);
+        Ok(())
+    }
+
+    /// Subscribe to telemetry and guard temperatures in the background
+    pub async fn start(&self) {
+        let mut events = self.manager.read().await.subscribe();
+        let manager = self.manager.clone();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(CameraEvent::Telemetry { camera_id, telemetry }) => {
+                        handle_telemetry(&manager, &state, camera_id, telemetry).await;
+                    }
+                    Ok(_) => {}
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("Thermal guard lagged, skipped {} events", skipped);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+
+            log::warn!("Thermal guard event loop ended");
+        });
+
+        log::info!("Thermal guard started");
+    }
+
+    // MARK: - Settings
+
+    pub async fn get_config(&self) -> ThermalConfig {
+        self.state.read().await.config.clone()
+    }
+
+    pub async fn save_config(&self, mut config: ThermalConfig) -> Result<()> {
+        config.stages.sort_by(|a, b| a.above_c.total_cmp(&b.above_c));
+        if let Some(first) = config.stages.first() {
+            if config.restore_below_c >= first.above_c {
+                anyhow::bail!(
+                    "Restore temperature ({} °C) must be below the first stage ({} °C)",
+                    config.restore_below_c, first.above_c,
+                );
+            }
+        }
+
+        let mut state = self.state.write().await;
+        state.config = config;
+        save_config_to_disk(&state).await
+    }
+
+    pub async fn get_status(&self) -> Vec<ThermalCameraStatus> {
+        let state = self.state.read().await;
+
+        let mut statuses: Vec<ThermalCameraStatus> = state.cameras.iter()
+            .map(|(camera_id, camera)| ThermalCameraStatus {
+                camera_id: camera_id.clone(),
+                temp_c: camera.temp_c,
+                trend_c_per_min: camera.trend_c_per_min,
+                stage: camera.stage,
+                original_stream: camera.original.clone(),
+                cooling_for_s: camera.cool_since.map(|since| since.elapsed().as_secs()),
+                transitions: camera.transitions.iter().cloned().collect(),
+            })
+            .collect();
+        statuses.sort_by(|a, b| a.camera_id.cmp(&b.camera_id));
+        statuses
+    }
+}
+
+async fn save_config_to_disk(state: &ThermalState) -> Result<()> {
+    let Some(path) = &state.config_file_path else {
+        return Ok(()); // No persistence path set
+    };
+
+    let json = serde_json::to_string_pretty(&state.config)
+        .context("Failed to serialize thermal policy")?;
+
+    tokio::fs::write(path, json).await
+        .context("Failed to write thermal policy to disk")?;
+
+    Ok(())
+}
+
+/// Persist every stepped-down camera with its original stream, so a restarted
+/// controller still brings it back once cool
+async fn save_throttles_to_disk(state: &ThermalState) -> Result<()> {
+    let Some(path) = &state.state_file_path else {
+        return Ok(()); // No persistence path set
+    };
+
+    let mut throttles = state.restored.clone();
+    for (camera_id, camera) in &state.cameras {
+        match camera.throttle() {
+            Some(throttle) => throttles.insert(camera_id.clone(), throttle),
+            None => throttles.remove(camera_id),
+        };
+    }
+
+    let json = serde_json::to_string_pretty(&throttles)
+        .context("Failed to serialize thermal state")?;
+
+    tokio::fs::write(path, json).await
+        .context("Failed to write thermal state to disk")?;
+
+    Ok(())
+}
+
+// MARK: - Stepping
+
+fn pixels(resolution: &str) -> Option<u64> {
+    let (width, height) = resolution.split_once('x')?;
+    Some(width.trim().parse::<u64>().ok()? * height.trim().parse::<u64>().ok()?)
+}
+
+/// `original` limited by every stage up to `stages`, snapped to supported modes
+fn limited_stream(original: &StreamStartRequest, stages: &[ThermalStage], capabilities: &[Capability]) -> StreamStartRequest {
+    let mut stream = original.clone();
+
+    for stage in stages {
+        if let Some(max_bitrate) = stage.max_bitrate_bps {
+            stream.bitrate = stream.bitrate.min(max_bitrate);
+        }
+
+        if let Some(max_pixels) = stage.max_resolution.as_deref().and_then(pixels) {
+            if pixels(&stream.resolution).is_some_and(|p| p > max_pixels) {
+                let smaller = capabilities.iter()
+                    .filter(|c| c.codec.contains(&stream.codec))
+                    .filter_map(|c| pixels(&c.resolution).map(|p| (p, c)))
+                    .filter(|(p, _)| *p <= max_pixels)
+                    .max_by_key(|(p, _)| *p);
+                match smaller {
+                    Some((_, capability)) => stream.resolution = capability.resolution.clone(),
+                    None if capabilities.is_empty() => stream.resolution = stage.max_resolution.clone().unwrap_or_default(),
+                    None => {}
+                }
+            }
+        }
+
+        if let Some(max_fps) = stage.max_fps {
+            if stream.framerate > max_fps {
+                let supported: Vec<u32> = capabilities.iter()
+                    .filter(|c| c.resolution == stream.resolution)
+                    .flat_map(|c| c.fps.iter().copied())
+                    .collect();
+                stream.framerate = supported.iter().copied().filter(|&fps| fps <= max_fps).max()
+                    .or_else(|| supported.iter().copied().min())
+                    .unwrap_or(max_fps)
+                    .min(stream.framerate);
+            }
+        }
+    }
+
+    // Keep the frame rate valid if the resolution changed
+    let supported: Vec<u32> = capabilities.iter()
+        .filter(|c| c.resolution == stream.resolution)
+        .flat_map(|c| c.fps.iter().copied())
+        .collect();
+    if !supported.is_empty() && !supported.contains(&stream.framerate) {
+        if let Some(fps) = supported.iter().copied().filter(|&fps| fps <= stream.framerate).max()
+            .or_else(|| supported.iter().copied().min())
+        {
+            stream.framerate = fps;
+        }
+    }
+
+    stream
+}
+
+async fn handle_telemetry(
+    manager: &Arc<RwLock<CameraManager>>,
+    engine_state: &Arc<RwLock<ThermalState>>,
+    camera_id: String,
+    telemetry: WebSocketTelemetryMessage,
+) {
+    let (current, stream_requested) = {
+        let manager = manager.read().await;
+        (manager.stream_settings_or_default(&camera_id), manager.is_stream_requested(&camera_id))
+    };
+    let now = Instant::now();
+
+    let mut guard = engine_state.write().await;
+    let state = &mut *guard;
+    let config = state.config.clone();
+
+    if !state.cameras.contains_key(&camera_id) {
+        let throttle = state.restored.remove(&camera_id);
+        state.cameras.insert(camera_id.clone(), CameraThermal::new(telemetry.temp_c, throttle, now));
+    }
+    let Some(camera) = state.cameras.get_mut(&camera_id) else {
+        return;
+    };
+    camera.update(telemetry.temp_c, config.smoothing, now);
+
+    if !config.enabled {
+        return;
+    }
+
+    // The operator changed the stream while stepped down: their settings win.
+    // ABR rungs are never persisted, so they do not count as a change.
+    if camera.stage > 0 && camera.applied.as_ref().is_some_and(|applied| *applied != current) {
+        let transition = transition(&camera_id, camera, 0, None, None, "stream changed by the operator".to_string());
+        log::info!("Thermal guard on {}: stage {} -> 0 ({})", camera_id, camera.stage, transition.reason);
+        camera.enter_stage(0, now);
+        camera.push_transition(transition.clone());
+        if let Err(e) = save_throttles_to_disk(state).await {
+            log::warn!("Failed to save thermal state: {}", e);
+        }
+        drop(guard);
+        manager.read().await.notifier().emit("thermal-transition", serde_json::to_value(&transition).unwrap_or_default());
+        return;
+    }
+
+    let streaming = telemetry.ndi_state == NdiState::Streaming || stream_requested;
+    let Some(change) = camera.stage_change(&config, streaming, now) else {
+        return;
+    };
+    let from_stage = camera.stage;
+
+    match change {
+        StageChange::Escalate(target_stage) => {
+            let original = camera.original.get_or_insert_with(|| current.clone()).clone();
+            let reason = format!(
+                "{:.1} °C >= {:.1} °C ({:+.2} °C/min)",
+                camera.temp_c, config.stages[target_stage - 1].above_c, camera.trend_c_per_min,
+            );
+            camera.enter_stage(target_stage, now);
+            drop(guard);
+
+            let stages = &config.stages[..target_stage];
+            let profile = stages.last().and_then(|s| s.profile.clone());
+
+            let (stream, result) = match &profile {
+                Some(name) => {
+                    let result = CameraManager::apply_profile_via(ManagerMut::Locked(manager), name, std::slice::from_ref(&camera_id), ActionSource::Automation).await
+                        .and_then(|results| match results.into_iter().find(|r| !r.success) {
+                            Some(failed) => Err(anyhow::anyhow!(failed.error.unwrap_or_default())),
+                            None => Ok(()),
+                        });
+                    (manager.read().await.stream_settings_or_default(&camera_id), result)
+                }
+                None => {
+                    let capabilities = CameraManager::camera_capabilities_via(ManagerRef::Locked(manager), &camera_id).await.unwrap_or_default();
+                    let stream = limited_stream(&original, stages, &capabilities);
+                    let result = if stream == current {
+                        Ok(())
+                    } else {
+                        CameraManager::send_stream_via(ManagerMut::Locked(manager), &camera_id, stream.clone(), StreamDelivery::Update, ActionSource::Automation).await
+                    };
+                    (stream, result)
+                }
+            };
+
+            record_transition(manager, engine_state, &camera_id, from_stage, target_stage, stream, profile, reason, result).await;
+        }
+        StageChange::Restore => {
+            let original = camera.original.clone().unwrap_or_else(|| current.clone());
+            let reason = format!(
+                "below {:.1} °C for {} s ({:.1} °C)",
+                config.restore_below_c, config.cooldown_s, camera.temp_c,
+            );
+            camera.enter_stage(0, now);
+            drop(guard);
+
+            let result = if original == current {
+                Ok(())
+            } else {
+                CameraManager::send_stream_via(ManagerMut::Locked(manager), &camera_id, original.clone(), StreamDelivery::Update, ActionSource::Automation).await
+            };
+
+            record_transition(manager, engine_state, &camera_id, from_stage, 0, original, None, reason, result).await;
+        }
+    }
+}
+
+fn transition(
+    camera_id: &str,
+    camera: &CameraThermal,
+    to_stage: usize,
+    stream: Option<StreamStartRequest>,
+    profile: Option<String>,
+    reason: String,
+) -> ThermalTransition {
+    ThermalTransition {
+        camera_id: camera_id.to_string(),
+        from_stage: camera.stage,
+        to_stage,
+        temp_c: camera.temp_c,
+        trend_c_per_min: camera.trend_c_per_min,
+        stream,
+        profile,
+        reason,
+        error: None,
+        timestamp: Utc::now(),
+    }
+}
+
+/// Log, store, persist and emit a transition that has been applied (or failed to)
+#[allow(clippy::too_many_arguments)]
+async fn record_transition(
+    manager: &RwLock<CameraManager>,
+    engine_state: &Arc<RwLock<ThermalState>>,
+    camera_id: &str,
+    from_stage: usize,
+    to_stage: usize,
+    stream: StreamStartRequest,
+    profile: Option<String>,
+    reason: String,
+    result: Result<()>,
+) {
+    let persisted = manager.read().await.stream_settings_or_default(camera_id);
+    let mut state = engine_state.write().await;
+    let Some(camera) = state.cameras.get_mut(camera_id) else {
+        return;
+    };
+
+    let mut transition = transition(camera_id, camera, to_stage, Some(stream.clone()), profile, reason);
+    transition.from_stage = from_stage;
+
+    match result {
+        Ok(()) => {
+            log::info!("Thermal guard on {}: stage {} -> {} ({})", camera_id, from_stage, to_stage, transition.reason);
+            camera.applied = (to_stage > 0).then_some(stream);
+        }
+        Err(e) => {
+            log::warn!("Thermal guard on {}: stage {} -> {} failed: {}", camera_id, from_stage, to_stage, e);
+            transition.error = Some(e.to_string());
+            // Compare against what is actually persisted from now on
+            camera.applied = (to_stage > 0).then_some(persisted);
+        }
+    }
+
+    camera.push_transition(transition.clone());
+    if let Err(e) = save_throttles_to_disk(&state).await {
+        log::warn!("Failed to save thermal state: {}", e);
+    }
+    drop(state);
+
+    if let Ok(payload) = serde_json::to_value(&transition) {
+        manager.read().await.notifier().emit("thermal-transition", payload);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::capability;
+
+    fn stream(resolution: &str, framerate: u32, bitrate: u32) -> StreamStartRequest {
+        StreamStartRequest { resolution: resolution.to_string(), framerate, bitrate, codec: "h264".to_string() }
+    }
+
+    fn config() -> ThermalConfig {
+        ThermalConfig { enabled: true, smoothing: 1.0, ..Default::default() }
+    }
+
+    #[test]
+    fn limited_stream_applies_every_stage_up_to_the_current_one() {
+        let capabilities = [capability("1920x1080", &[25, 30, 60]), capability("1280x720", &[30, 60])];
+        let original = stream("1920x1080", 60, 10_000_000);
+        let stages = ThermalConfig::default().stages;
+
+        assert_eq!(limited_stream(&original, &stages[..1], &capabilities), stream("1920x1080", 60, 6_000_000));
+        assert_eq!(limited_stream(&original, &stages[..2], &capabilities), stream("1920x1080", 25, 6_000_000));
+        // 720p has no 25 fps mode, so it falls back to its slowest supported rate
+        assert_eq!(limited_stream(&original, &stages, &capabilities), stream("1280x720", 30, 6_000_000));
+    }
+
+    #[test]
+    fn limited_stream_never_raises_settings() {
+        let capabilities = [capability("1280x720", &[24, 30])];
+        let original = stream("1280x720", 24, 4_000_000);
+
+        assert_eq!(limited_stream(&original, &ThermalConfig::default().stages, &capabilities), original);
+    }
+
+    #[test]
+    fn limited_stream_uses_stage_limits_without_capabilities() {
+        let original = stream("3840x2160", 60, 20_000_000);
+        let stages = [ThermalStage {
+            above_c: 40.0,
+            max_fps: Some(30),
+            max_bitrate_bps: Some(8_000_000),
+            max_resolution: Some("1920x1080".to_string()),
+            profile: None,
+        }];
+
+        assert_eq!(limited_stream(&original, &stages, &[]), stream("1920x1080", 30, 8_000_000));
+    }
+
+    #[test]
+    fn limited_stream_picks_the_largest_supported_resolution_under_the_limit() {
+        let capabilities = [
+            capability("1920x1080", &[30]),
+            capability("1280x720", &[30]),
+            capability("960x540", &[30]),
+        ];
+        let stages = [ThermalStage { above_c: 40.0, max_resolution: Some("1600x900".to_string()), ..Default::default() }];
+
+        assert_eq!(
+            limited_stream(&stream("1920x1080", 30, 10_000_000), &stages, &capabilities),
+            stream("1280x720", 30, 10_000_000),
+        );
+    }
+
+    #[test]
+    fn escalates_while_streaming_and_waits_min_stage_between_stages() {
+        let config = config();
+        let start = Instant::now();
+        let mut camera = CameraThermal::new(44.0, None, start);
+
+        assert_eq!(camera.stage_change(&config, false, start), None, "not streaming");
+        assert_eq!(camera.stage_change(&config, true, start), Some(StageChange::Escalate(1)));
+        camera.enter_stage(1, start);
+
+        camera.update(48.0, 1.0, start + Duration::from_secs(1));
+        let early = start + Duration::from_secs(config.min_stage_s - 1);
+        assert_eq!(camera.stage_change(&config, true, early), None);
+        let later = start + Duration::from_secs(config.min_stage_s);
+        assert_eq!(camera.stage_change(&config, true, later), Some(StageChange::Escalate(3)));
+    }
+
+    #[test]
+    fn restores_after_cooling_down_without_warming_up() {
+        let config = config();
+        let start = Instant::now();
+        let mut camera = CameraThermal::new(46.0, None, start);
+        camera.original = Some(stream("1920x1080", 30, 10_000_000));
+        camera.enter_stage(2, start);
+
+        let mut now = start + Duration::from_secs(60);
+        camera.update(39.0, 1.0, now);
+        assert_eq!(camera.stage_change(&config, true, now), None, "cool-down starts");
+
+        // Warming up again resets the cool-down, even below the restore temperature
+        now += Duration::from_secs(config.cooldown_s);
+        camera.update(39.5, 1.0, now);
+        assert_eq!(camera.stage_change(&config, true, now), None);
+        assert!(camera.cool_since.is_none());
+
+        now += Duration::from_secs(60);
+        camera.update(39.0, 1.0, now);
+        assert_eq!(camera.stage_change(&config, true, now), None);
+        now += Duration::from_secs(config.cooldown_s);
+        camera.update(39.0, 1.0, now);
+        assert_eq!(camera.stage_change(&config, true, now), Some(StageChange::Restore));
+
+        camera.enter_stage(0, now);
+        assert!(camera.original.is_none() && camera.throttle().is_none());
+    }
+
+    #[test]
+    fn a_restored_throttle_resumes_its_stage_and_original() {
+        let original = stream("1920x1080", 30, 10_000_000);
+        let applied = stream("1920x1080", 30, 6_000_000);
+        let throttle = Throttle { stage: 1, original: original.clone(), applied: Some(applied.clone()) };
+        let json = serde_json::to_string(&throttle).unwrap();
+
+        let camera = CameraThermal::new(38.0, serde_json::from_str(&json).unwrap(), Instant::now());
+        assert_eq!(camera.stage, 1);
+        assert_eq!(camera.original, Some(original));
+        assert_eq!(camera.applied, Some(applied));
+        assert!(camera.throttle().is_some());
+    }
+}
@@ -0,0 +1,147 @@
+//! TLS with trust-on-first-use certificate pinning
+//!
+//! Cameras serve a self-signed certificate, so there is no CA to verify it
+//! against. Instead the SHA-256 fingerprint of the certificate seen on the
+//! first connection is pinned and stored with the camera in `cameras.json`.
+//! Later handshakes (HTTP and WebSocket alike) must present the same
+//! certificate or fail with [`CertificateMismatch`], before any bearer token
+//! is sent. Re-trusting a camera clears its pin.
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{self, CryptoProvider};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::sync::{Arc, Mutex};
+
+/// The camera presented a certificate other than the pinned one
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("Certificate of {camera_id} changed (pinned {expected}, presented {presented}). \
+         If the camera was reset or its certificate regenerated, re-trust it.")]
+pub struct CertificateMismatch {
+    pub camera_id: String,
+    pub expected: String,
+    pub presented: String,
+}
+
+/// `AB:CD:...` SHA-256 fingerprint of a DER certificate
+pub fn fingerprint(der: &[u8]) -> String {
+    Sha256::digest(der).iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[derive(Debug, Default)]
+struct PinState {
+    pinned: Option<String>,
+    // Fingerprint presented by the last rejected handshake
+    mismatch: Option<String>,
+}
+
+/// Pinned fingerprint of one camera, shared by its HTTP and WebSocket connections
+#[derive(Debug)]
+pub(crate) struct CertificatePin {
+    camera_id: String,
+    state: Mutex<PinState>,
+}
+
+impl CertificatePin {
+    /// `pinned` is None for a camera that was never trusted
+    pub fn new(camera_id: &str, pinned: Option<String>) -> Self {
+        Self {
+            camera_id: camera_id.to_string(),
+            state: Mutex::new(PinState { pinned, mismatch: None }),
+        }
+    }
+
+    pub fn pinned(&self) -> Option<String> {
+        self.state.lock().unwrap().pinned.clone()
+    }
+
+    /// Mismatch behind the last failed handshake, if that is why it failed
+    pub fn take_mismatch(&self) -> Option<CertificateMismatch> {
+        let mut state = self.state.lock().unwrap();
+        let presented = state.mismatch.take()?;
+        Some(CertificateMismatch {
+            camera_id: self.camera_id.clone(),
+            expected: state.pinned.clone().unwrap_or_default(),
+            presented,
+        })
+    }
+
+    fn check(&self, presented: String) -> bool {
+        let mut state = self.state.lock().unwrap();
+        match &state.pinned {
+            Some(pinned) if *pinned == presented => true,
+            Some(_) => {
+                state.mismatch = Some(presented);
+                false
+            }
+            None => {
+                log::info!("Trusting certificate {} of {} on first use", presented, self.camera_id);
+                state.pinned = Some(presented);
+                true
+            }
+        }
+    }
+}
+
+/// Accepts exactly the pinned certificate (or the first one seen), whatever its
+/// issuer, validity dates or name; handshake signatures are still verified
+#[derive(Debug)]
+struct PinnedCertVerifier {
+    pin: Arc<CertificatePin>,
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if self.pin.check(fingerprint(end_entity)) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General("certificate fingerprint does not match the pinned one".to_string()))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls12_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        crypto::verify_tls13_signature(message, cert, dss, &self.provider.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// Client TLS configuration verifying the server against `pin`
+pub(crate) fn pinned_client_config(pin: Arc<CertificatePin>) -> ClientConfig {
+    let provider = Arc::new(crypto::ring::default_provider());
+
+    ClientConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .expect("Default TLS versions are supported by the ring provider")
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedCertVerifier { pin, provider }))
+        .with_no_client_auth()
+}
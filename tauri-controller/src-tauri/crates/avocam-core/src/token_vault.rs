@@ -0,0 +1,326 @@
+//! Encrypted at-rest storage of camera tokens
+//!
+//! Tokens are written to `cameras.json` encrypted with ChaCha20-Poly1305. The
+//! key comes either from a machine-local key file (`token.key`, created on
+//! first use) or from a user passphrase (PBKDF2-HMAC-SHA256). Only the key
+//! parameters are stored with the cameras: the source, the passphrase salt and
+//! an encrypted check value telling a wrong key from a corrupt token. Tokens
+//! never leave the core in full; [`mask_token`] is what the frontend sees.
+//!
+//! The key file only keeps tokens out of a `cameras.json` that is shared or
+//! backed up on its own. It sits in the same data directory, so anyone who
+//! copies that directory can decrypt the tokens. Protecting against that takes
+//! a passphrase, or storing the key in the OS keychain instead, which this
+//! module does not do.
+
+use anyhow::{Context, Result};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::pbkdf2;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroU32;
+use std::path::Path;
+
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const PBKDF2_ITERATIONS: u32 = 600_000;
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Version prefix of encrypted values
+const CIPHERTEXT_PREFIX: &str = "v1:";
+
+/// Encrypted with the key to recognise it later
+const CHECK_PLAINTEXT: &str = "avocam-token-key";
+
+/// Where the token encryption key comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TokenKeySource {
+    /// Random key in `token.key` next to `cameras.json`
+    #[default]
+    KeyFile,
+    /// Derived from a passphrase entered at each launch
+    Passphrase,
+}
+
+/// Key parameters stored in `cameras.json` (never the key itself)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenKeyInfo {
+    pub source: TokenKeySource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    /// [`CHECK_PLAINTEXT`] encrypted with the key
+    pub check: String,
+}
+
+/// Token storage state shown in the settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenStorageStatus {
+    pub source: TokenKeySource,
+    /// Cameras are waiting for the passphrase
+    pub locked: bool,
+    pub locked_cameras: usize,
+}
+
+/// Encrypts and decrypts camera tokens with one key
+pub struct TokenVault {
+    key: LessSafeKey,
+    info: TokenKeyInfo,
+}
+
+impl TokenVault {
+    /// Key from `path`, created if missing; `info` (from `cameras.json`) is checked against it
+    pub fn open_key_file(path: &Path, info: Option<&TokenKeyInfo>) -> Result<Self> {
+        let key = if path.exists() {
+            let key = std::fs::read(path)
+                .with_context(|| format!("Failed to read token key file {:?}", path))?;
+            if key.len() != KEY_LEN {
+                anyhow::bail!("Token key file {:?} is corrupt", path);
+            }
+            key
+        } else if info.is_some() {
+            anyhow::bail!("Token key file {:?} is missing; the saved camera tokens cannot be decrypted", path);
+        } else {
+            let mut key = vec![0u8; KEY_LEN];
+            random_bytes(&mut key)?;
+            write_private(path, &key)?;
+            log::info!("Created token key file {:?}", path);
+            key
+        };
+
+        Self::with_key(&key, TokenKeySource::KeyFile, None, None, info)
+            .with_context(|| format!("Token key file {:?} does not match cameras.json", path))
+    }
+
+    /// Key derived from `passphrase`, with the salt of `info` or a new one
+    pub fn open_passphrase(passphrase: &str, info: Option<&TokenKeyInfo>) -> Result<Self> {
+        let (salt, iterations) = match info {
+            Some(info) => {
+                let salt = info.salt.as_deref()
+                    .context("Passphrase salt missing from cameras.json")?;
+                let salt = BASE64.decode(salt).context("Passphrase salt is corrupt")?;
+                (salt, info.iterations.unwrap_or(PBKDF2_ITERATIONS))
+            }
+            None => {
+                if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+                    anyhow::bail!("Passphrase must be at least {} characters", MIN_PASSPHRASE_LEN);
+                }
+                let mut salt = vec![0u8; SALT_LEN];
+                random_bytes(&mut salt)?;
+                (salt, PBKDF2_ITERATIONS)
+            }
+        };
+
+        let mut key = [0u8; KEY_LEN];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(iterations).context("Invalid PBKDF2 iteration count")?,
+            &salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+
+        Self::with_key(&key, TokenKeySource::Passphrase, Some(BASE64.encode(&salt)), Some(iterations), info)
+            .context("Wrong passphrase")
+    }
+
+    fn with_key(
+        key: &[u8],
+        source: TokenKeySource,
+        salt: Option<String>,
+        iterations: Option<u32>,
+        info: Option<&TokenKeyInfo>,
+    ) -> Result<Self> {
+        let unbound = UnboundKey::new(&CHACHA20_POLY1305, key)
+            .map_err(|_| anyhow::anyhow!("Invalid token key"))?;
+        let mut vault = Self {
+            key: LessSafeKey::new(unbound),
+            info: TokenKeyInfo { source, salt, iterations, check: String::new() },
+        };
+
+        match info {
+            Some(info) => {
+                if vault.decrypt(&info.check).ok().as_deref() != Some(CHECK_PLAINTEXT) {
+                    anyhow::bail!("Token key check failed");
+                }
+                vault.info.check = info.check.clone();
+            }
+            None => vault.info.check = vault.encrypt(CHECK_PLAINTEXT)?,
+        }
+
+        Ok(vault)
+    }
+
+    pub fn info(&self) -> &TokenKeyInfo {
+        &self.info
+    }
+
+    /// `v1:` + base64(nonce || ciphertext || tag)
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let mut nonce = [0u8; NONCE_LEN];
+        random_bytes(&mut nonce)?;
+
+        let mut sealed = plaintext.as_bytes().to_vec();
+        self.key.seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow::anyhow!("Failed to encrypt token"))?;
+
+        let mut out = nonce.to_vec();
+        out.extend_from_slice(&sealed);
+        Ok(format!("{}{}", CIPHERTEXT_PREFIX, BASE64.encode(out)))
+    }
+
+    pub fn decrypt(&self, ciphertext: &str) -> Result<String> {
+        let encoded = ciphertext.strip_prefix(CIPHERTEXT_PREFIX)
+            .context("Unsupported token encryption version")?;
+        let data = BASE64.decode(encoded).context("Encrypted token is corrupt")?;
+        if data.len() < NONCE_LEN {
+            anyhow::bail!("Encrypted token is corrupt");
+        }
+
+        let (nonce, sealed) = data.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| anyhow::anyhow!("Encrypted token is corrupt"))?;
+        let mut sealed = sealed.to_vec();
+        let plaintext = self.key.open_in_place(nonce, Aad::empty(), &mut sealed)
+            .map_err(|_| anyhow::anyhow!("Failed to decrypt token"))?;
+
+        String::from_utf8(plaintext.to_vec()).context("Decrypted token is not UTF-8")
+    }
+}
+
+/// Token as shown to the frontend: only the last 4 characters of long tokens
+pub fn mask_token(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    if chars.is_empty() {
+        return String::new();
+    }
+    let visible = if chars.len() >= 12 { 4 } else { 0 };
+    let tail: String = chars[chars.len() - visible..].iter().collect();
+    format!("{}{}", "•".repeat(8), tail)
+}
+
+/// Serialize a token masked, so it never crosses the frontend boundary in full
+pub(crate) fn serialize_masked<S: serde::Serializer>(token: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&mask_token(token))
+}
+
+fn random_bytes(buffer: &mut [u8]) -> Result<()> {
+    SystemRandom::new().fill(buffer)
+        .map_err(|_| anyhow::anyhow!("System random generator unavailable"))
+}
+
+/// Write a file only the current user can read
+fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+
+    let mut file = options.open(path)
+        .with_context(|| format!("Failed to create {:?}", path))?;
+    file.write_all(contents)
+        .with_context(|| format!("Failed to write {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOKEN: &str = "secret-token-1234";
+
+    fn key_file_vault(dir: &Path) -> TokenVault {
+        TokenVault::open_key_file(&dir.join("token.key"), None).unwrap()
+    }
+
+    #[test]
+    fn encrypt_decrypt_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = key_file_vault(dir.path());
+
+        let sealed = vault.encrypt(TOKEN).unwrap();
+        assert!(sealed.starts_with(CIPHERTEXT_PREFIX));
+        assert!(!sealed.contains(TOKEN));
+        assert_eq!(vault.decrypt(&sealed).unwrap(), TOKEN);
+        // Fresh nonce per encryption
+        assert_ne!(vault.encrypt(TOKEN).unwrap(), sealed);
+    }
+
+    #[test]
+    fn key_file_is_reopened_with_its_check_value() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = key_file_vault(dir.path());
+        let sealed = vault.encrypt(TOKEN).unwrap();
+
+        let reopened = TokenVault::open_key_file(&dir.path().join("token.key"), Some(vault.info())).unwrap();
+        assert_eq!(reopened.decrypt(&sealed).unwrap(), TOKEN);
+
+        // Another machine's key file does not match the saved check value
+        let other = tempfile::tempdir().unwrap();
+        key_file_vault(other.path());
+        assert!(TokenVault::open_key_file(&other.path().join("token.key"), Some(vault.info())).is_err());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected_by_check_value() {
+        let vault = TokenVault::open_passphrase("correct horse", None).unwrap();
+        let sealed = vault.encrypt(TOKEN).unwrap();
+        let info = vault.info().clone();
+        assert_eq!(info.source, TokenKeySource::Passphrase);
+
+        let error = TokenVault::open_passphrase("wrong horse", Some(&info)).err().unwrap();
+        assert_eq!(error.to_string(), "Wrong passphrase");
+
+        let reopened = TokenVault::open_passphrase("correct horse", Some(&info)).unwrap();
+        assert_eq!(reopened.decrypt(&sealed).unwrap(), TOKEN);
+    }
+
+    #[test]
+    fn short_new_passphrase_is_refused() {
+        assert!(TokenVault::open_passphrase("short", None).is_err());
+    }
+
+    #[test]
+    fn tampered_ciphertext_fails_to_open() {
+        let dir = tempfile::tempdir().unwrap();
+        let vault = key_file_vault(dir.path());
+        let sealed = vault.encrypt(TOKEN).unwrap();
+
+        let mut data = BASE64.decode(sealed.strip_prefix(CIPHERTEXT_PREFIX).unwrap()).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0x01;
+        let tampered = format!("{}{}", CIPHERTEXT_PREFIX, BASE64.encode(&data));
+
+        assert!(vault.decrypt(&tampered).is_err());
+        assert!(vault.decrypt("v1:AAAA").is_err());
+        assert!(vault.decrypt("v2:whatever").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn key_file_is_private() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        key_file_vault(dir.path());
+
+        let metadata = std::fs::metadata(dir.path().join("token.key")).unwrap();
+        assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        assert_eq!(metadata.len(), KEY_LEN as u64);
+    }
+
+    #[test]
+    fn masks_all_but_the_tail_of_long_tokens() {
+        assert_eq!(mask_token(TOKEN), "••••••••1234");
+        assert_eq!(mask_token("short"), "••••••••");
+        assert_eq!(mask_token(""), "");
+    }
+}
@@ -0,0 +1,429 @@
+//! Capability-aware validation of camera and stream settings
+//!
+//! Requests are checked against the camera's reported [`Capability`] list
+//! (resolutions, fps, codecs, lenses, max zoom) and the ranges the iOS app
+//! accepts before anything is sent, so the phone never has to reject or
+//! silently clamp a value.
+
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+use crate::models::*;
+
+/// Scene CCT range the cameras accept for manual white balance
+pub const WB_KELVIN_RANGE: (u32, u32) = (2000, 10000);
+/// Tint range of `AVCaptureDevice` temperature/tint values
+pub const WB_TINT_RANGE: (f64, f64) = (-150.0, 150.0);
+/// NDI tally torch brightness range
+pub const TORCH_LEVEL_RANGE: (f32, f32) = (0.01, 1.0);
+const MIN_ZOOM: f64 = 1.0;
+const CAMERA_POSITIONS: [&str; 2] = ["front", "back"];
+
+/// Per-camera result of a dry-run validation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SettingsValidation {
+    pub camera_id: String,
+    pub issues: Vec<FieldIssue>,
+}
+
+impl SettingsValidation {
+    pub fn is_valid(&self) -> bool {
+        !has_errors(&self.issues)
+    }
+}
+
+/// Settings refused for a camera, with the issues that caused it
+#[derive(Debug, Clone, thiserror::Error)]
+#[error("{message}")]
+pub struct SettingsRejection {
+    pub message: String,
+    pub issues: Vec<FieldIssue>,
+}
+
+impl SettingsRejection {
+    pub fn new(camera_id: &str, issues: Vec<FieldIssue>) -> Self {
+        let errors: Vec<String> = issues.iter()
+            .filter(|i| i.severity == IssueSeverity::Error)
+            .map(|i| format!("{}: {}", i.field, i.message))
+            .collect();
+
+        Self {
+            message: format!("Invalid settings for {}: {}", camera_id, errors.join("; ")),
+            issues,
+        }
+    }
+}
+
+pub fn has_errors(issues: &[FieldIssue]) -> bool {
+    issues.iter().any(|i| i.severity == IssueSeverity::Error)
+}
+
+fn error(field: &str, message: String) -> FieldIssue {
+    FieldIssue { field: field.to_string(), severity: IssueSeverity::Error, message }
+}
+
+fn warning(field: &str, message: String) -> FieldIssue {
+    FieldIssue { field: field.to_string(), severity: IssueSeverity::Warning, message }
+}
+
+/// Flag (or, under [`ValidationPolicy::Clamp`], clamp) a value outside `[min, max]`
+fn check_range<T: PartialOrd + Copy + Display>(
+    issues: &mut Vec<FieldIssue>,
+    policy: ValidationPolicy,
+    field: &str,
+    value: &mut T,
+    min: T,
+    max: T,
+) {
+    if *value >= min && *value <= max {
+        return;
+    }
+
+    let clamped = if *value < min { min } else { max };
+    match policy {
+        ValidationPolicy::Skip => {
+            issues.push(error(field, format!("{} is outside {}..={}", value, min, max)));
+        }
+        ValidationPolicy::Clamp => {
+            issues.push(warning(field, format!("{} clamped to {}", value, clamped)));
+            *value = clamped;
+        }
+    }
+}
+
+// MARK: - Camera Settings
+
+/// Validate camera settings in place; `current_lens` is used for zoom limits
+/// when the request does not switch lens
+pub fn validate_camera_settings(
+    settings: &mut CameraSettingsRequest,
+    capabilities: Option<&[Capability]>,
+    current_lens: Option<&str>,
+    policy: ValidationPolicy,
+) -> Vec<FieldIssue> {
+    let mut issues = Vec::new();
+
+    if let Some(kelvin) = settings.wb_kelvin.as_mut() {
+        check_range(&mut issues, policy, "wb_kelvin", kelvin, WB_KELVIN_RANGE.0, WB_KELVIN_RANGE.1);
+    }
+    if let Some(tint) = settings.wb_tint.as_mut() {
+        check_range(&mut issues, policy, "wb_tint", tint, WB_TINT_RANGE.0, WB_TINT_RANGE.1);
+    }
+    if settings.wb_mode == Some(WhiteBalanceMode::Manual) && settings.wb_kelvin.is_none() {
+        issues.push(warning("wb_kelvin", "manual white balance without wb_kelvin keeps the current gains".to_string()));
+    }
+
+    if settings.iso == Some(0) {
+        issues.push(error("iso", "must be greater than 0".to_string()));
+    }
+    if let Some(shutter_s) = settings.shutter_s {
+        if !(shutter_s > 0.0 && shutter_s.is_finite()) {
+            issues.push(error("shutter_s", format!("must be a positive duration, got {}", shutter_s)));
+        }
+    }
+    if let Some(angle) = settings.shutter_angle_deg.as_mut() {
+        if *angle <= 0.0 {
+            issues.push(error("shutter_angle_deg", format!("must be positive, got {}", angle)));
+        } else {
+            check_range(&mut issues, policy, "shutter_angle_deg", angle, 0.0, 360.0);
+        }
+    }
+
+    if let Some(torch_level) = settings.torch_level.as_mut() {
+        check_range(&mut issues, policy, "torch_level", torch_level, TORCH_LEVEL_RANGE.0, TORCH_LEVEL_RANGE.1);
+    }
+
+    if let Some(position) = &settings.camera_position {
+        if !CAMERA_POSITIONS.contains(&position.as_str()) {
+            issues.push(error("camera_position", format!("unknown position '{}' (expected front or back)", position)));
+        }
+    }
+
+    let capabilities = capabilities.filter(|c| !c.is_empty());
+
+    // Lens must be one the camera reports
+    if let (Some(lens), Some(capabilities)) = (&settings.lens, capabilities) {
+        let lenses: Vec<&str> = capabilities.iter().filter_map(|c| c.lens.as_deref()).collect();
+        if !lenses.is_empty() && !lenses.contains(&lens.as_str()) {
+            issues.push(error("lens", format!("'{}' not available (supported: {})", lens, join_unique(&lenses))));
+        }
+    }
+
+    if let Some(zoom) = settings.zoom_factor.as_mut() {
+        let lens = settings.lens.as_deref().or(current_lens);
+        let max_zoom = capabilities.and_then(|caps| {
+            caps.iter()
+                .filter(|c| c.lens.is_none() || lens.is_none() || c.lens.as_deref() == lens)
+                .filter_map(|c| c.max_zoom)
+                .reduce(f64::max)
+        });
+
+        match max_zoom {
+            Some(max_zoom) => check_range(&mut issues, policy, "zoom_factor", zoom, MIN_ZOOM, max_zoom),
+            // No reported maximum: only the lower bound applies
+            None if *zoom < MIN_ZOOM => check_range(&mut issues, policy, "zoom_factor", zoom, MIN_ZOOM, MIN_ZOOM),
+            None => {}
+        }
+    }
+
+    if capabilities.is_none() && (settings.lens.is_some() || settings.zoom_factor.is_some()) {
+        issues.push(warning("capabilities", "camera capabilities unknown; lens and max zoom not checked".to_string()));
+    }
+
+    issues
+}
+
+// MARK: - Stream Settings
+
+/// Validate stream settings in place against the camera's capabilities
+pub fn validate_stream_settings(
+    settings: &mut StreamStartRequest,
+    capabilities: Option<&[Capability]>,
+    policy: ValidationPolicy,
+) -> Vec<FieldIssue> {
+    let mut issues = Vec::new();
+
+    if settings.bitrate == 0 {
+        issues.push(error("bitrate", "must be greater than 0".to_string()));
+    }
+    if settings.framerate == 0 {
+        issues.push(error("framerate", "must be greater than 0".to_string()));
+    }
+
+    let Some(capabilities) = capabilities.filter(|c| !c.is_empty()) else {
+        issues.push(warning("capabilities", "camera capabilities unknown; resolution, fps and codec not checked".to_string()));
+        return issues;
+    };
+
+    let matching: Vec<&Capability> = capabilities.iter()
+        .filter(|c| c.resolution == settings.resolution)
+        .collect();
+
+    if matching.is_empty() {
+        let resolutions: Vec<&str> = capabilities.iter().map(|c| c.resolution.as_str()).collect();
+        issues.push(error("resolution", format!("'{}' not supported (supported: {})", settings.resolution, join_unique(&resolutions))));
+        return issues;
+    }
+
+    let mut fps: Vec<u32> = matching.iter().flat_map(|c| c.fps.iter().copied()).collect();
+    fps.sort_unstable();
+    fps.dedup();
+
+    if settings.framerate > 0 && !fps.is_empty() && !fps.contains(&settings.framerate) {
+        let supported = fps.iter().map(|f| f.to_string()).collect::<Vec<_>>().join(", ");
+        match policy {
+            ValidationPolicy::Skip => {
+                issues.push(error("framerate", format!("{} fps not supported at {} (supported: {})",
+                    settings.framerate, settings.resolution, supported)));
+            }
+            ValidationPolicy::Clamp => {
+                let nearest = fps.iter()
+                    .copied()
+                    .min_by_key(|f| f.abs_diff(settings.framerate))
+                    .unwrap_or(settings.framerate);
+                issues.push(warning("framerate", format!("{} fps not supported at {}, using {}",
+                    settings.framerate, settings.resolution, nearest)));
+                settings.framerate = nearest;
+            }
+        }
+    }
+
+    let codecs: Vec<&str> = matching.iter().flat_map(|c| c.codec.iter().map(String::as_str)).collect();
+    if !codecs.is_empty() && !codecs.iter().any(|c| c.eq_ignore_ascii_case(&settings.codec)) {
+        issues.push(error("codec", format!("'{}' not supported at {} (supported: {})",
+            settings.codec, settings.resolution, join_unique(&codecs))));
+    }
+
+    issues
+}
+
+fn join_unique(values: &[&str]) -> String {
+    let mut unique: Vec<&str> = values.to_vec();
+    unique.sort_unstable();
+    unique.dedup();
+    unique.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use IssueSeverity::{Error, Warning};
+    use ValidationPolicy::{Clamp, Skip};
+
+    fn capability(resolution: &str, fps: &[u32], codecs: &[&str], lens: Option<&str>, max_zoom: Option<f64>) -> Capability {
+        Capability {
+            codec: codecs.iter().map(|c| c.to_string()).collect(),
+            lens: lens.map(str::to_string),
+            max_zoom,
+            ..test_support::capability(resolution, fps)
+        }
+    }
+
+    fn capabilities() -> Vec<Capability> {
+        vec![
+            capability("1920x1080", &[25, 30, 60], &["h264", "hevc"], Some("wide"), Some(10.0)),
+            capability("1280x720", &[30, 60, 120], &["h264"], Some("ultra_wide"), Some(2.0)),
+        ]
+    }
+
+    fn issues(issues: &[FieldIssue]) -> Vec<(&str, IssueSeverity)> {
+        issues.iter().map(|i| (i.field.as_str(), i.severity)).collect()
+    }
+
+    fn stream(resolution: &str, framerate: u32, codec: &str) -> StreamStartRequest {
+        StreamStartRequest { resolution: resolution.to_string(), framerate, bitrate: 8_000_000, codec: codec.to_string() }
+    }
+
+    #[test]
+    fn check_range_accepts_flags_or_clamps() {
+        let cases = [
+            (5600, Skip, 5600, vec![]),
+            (2000, Skip, 2000, vec![]),
+            (10000, Clamp, 10000, vec![]),
+            (1500, Skip, 1500, vec![Error]),
+            (12000, Skip, 12000, vec![Error]),
+            (1500, Clamp, 2000, vec![Warning]),
+            (12000, Clamp, 10000, vec![Warning]),
+        ];
+        for (value, policy, expected, severities) in cases {
+            let mut issues = Vec::new();
+            let mut kelvin = value;
+            check_range(&mut issues, policy, "wb_kelvin", &mut kelvin, WB_KELVIN_RANGE.0, WB_KELVIN_RANGE.1);
+
+            assert_eq!(kelvin, expected, "{} under {:?}", value, policy);
+            assert_eq!(issues.iter().map(|i| i.severity).collect::<Vec<_>>(), severities, "{} under {:?}", value, policy);
+        }
+    }
+
+    #[test]
+    fn camera_settings_in_range_pass_untouched() {
+        let mut settings = CameraSettingsRequest {
+            wb_mode: Some(WhiteBalanceMode::Manual),
+            wb_kelvin: Some(5600),
+            wb_tint: Some(-10.0),
+            iso: Some(400),
+            shutter_s: Some(0.02),
+            torch_level: Some(0.5),
+            camera_position: Some("back".to_string()),
+            lens: Some("wide".to_string()),
+            zoom_factor: Some(4.0),
+            ..Default::default()
+        };
+        let before = serde_json::to_value(&settings).unwrap();
+
+        for policy in [Skip, Clamp] {
+            assert!(validate_camera_settings(&mut settings, Some(&capabilities()), None, policy).is_empty());
+        }
+        assert_eq!(serde_json::to_value(&settings).unwrap(), before);
+    }
+
+    #[test]
+    fn camera_settings_out_of_range_are_rejected_or_clamped() {
+        let request = CameraSettingsRequest {
+            wb_kelvin: Some(20000),
+            wb_tint: Some(-300.0),
+            torch_level: Some(1.5),
+            shutter_angle_deg: Some(400.0),
+            ..Default::default()
+        };
+
+        let mut skipped = request.clone();
+        let found = validate_camera_settings(&mut skipped, None, None, Skip);
+        assert_eq!(issues(&found), vec![
+            ("wb_kelvin", Error), ("wb_tint", Error), ("shutter_angle_deg", Error), ("torch_level", Error),
+        ]);
+        assert_eq!(skipped.wb_kelvin, Some(20000));
+
+        let mut clamped = request;
+        let found = validate_camera_settings(&mut clamped, None, None, Clamp);
+        assert!(!has_errors(&found));
+        assert_eq!(
+            (clamped.wb_kelvin, clamped.wb_tint, clamped.torch_level, clamped.shutter_angle_deg),
+            (Some(10000), Some(-150.0), Some(1.0), Some(360.0)),
+        );
+    }
+
+    #[test]
+    fn invalid_values_are_errors_under_either_policy() {
+        let request = CameraSettingsRequest {
+            iso: Some(0),
+            shutter_s: Some(-0.01),
+            shutter_angle_deg: Some(0.0),
+            camera_position: Some("side".to_string()),
+            ..Default::default()
+        };
+
+        for policy in [Skip, Clamp] {
+            let found = validate_camera_settings(&mut request.clone(), None, None, policy);
+            assert_eq!(issues(&found), vec![
+                ("iso", Error), ("shutter_s", Error), ("shutter_angle_deg", Error), ("camera_position", Error),
+            ], "{:?}", policy);
+        }
+    }
+
+    #[test]
+    fn lens_and_zoom_follow_the_reported_capabilities() {
+        let capabilities = capabilities();
+
+        let mut telephoto = CameraSettingsRequest { lens: Some("telephoto".to_string()), ..Default::default() };
+        assert_eq!(issues(&validate_camera_settings(&mut telephoto, Some(&capabilities), None, Skip)), vec![("lens", Error)]);
+
+        // Max zoom of the lens being switched to, else of the current lens
+        let mut zoom = CameraSettingsRequest { zoom_factor: Some(4.0), ..Default::default() };
+        assert_eq!(issues(&validate_camera_settings(&mut zoom.clone(), Some(&capabilities), Some("ultra_wide"), Skip)), vec![("zoom_factor", Error)]);
+        assert!(validate_camera_settings(&mut zoom.clone(), Some(&capabilities), Some("wide"), Skip).is_empty());
+        validate_camera_settings(&mut zoom, Some(&capabilities), Some("ultra_wide"), Clamp);
+        assert_eq!(zoom.zoom_factor, Some(2.0));
+
+        let mut below_one = CameraSettingsRequest { zoom_factor: Some(0.5), ..Default::default() };
+        assert_eq!(
+            issues(&validate_camera_settings(&mut below_one, Some(&[capability("1920x1080", &[30], &["h264"], None, None)]), None, Skip)),
+            vec![("zoom_factor", Error)],
+        );
+    }
+
+    #[test]
+    fn unknown_capabilities_only_warn_about_lens_and_zoom() {
+        let mut settings = CameraSettingsRequest { lens: Some("telephoto".to_string()), zoom_factor: Some(50.0), ..Default::default() };
+
+        for capabilities in [None, Some(&[][..])] {
+            let found = validate_camera_settings(&mut settings, capabilities, None, Skip);
+            assert_eq!(issues(&found), vec![("capabilities", Warning)]);
+        }
+    }
+
+    #[test]
+    fn stream_settings_are_checked_against_capabilities() {
+        let capabilities = capabilities();
+        let cases = [
+            (stream("1920x1080", 30, "h264"), vec![]),
+            (stream("1920x1080", 60, "HEVC"), vec![]),
+            (stream("3840x2160", 30, "h264"), vec![("resolution", Error)]),
+            (stream("1920x1080", 50, "h264"), vec![("framerate", Error)]),
+            (stream("1280x720", 30, "hevc"), vec![("codec", Error)]),
+        ];
+
+        for (mut request, expected) in cases {
+            let found = validate_stream_settings(&mut request, Some(&capabilities), Skip);
+            assert_eq!(issues(&found), expected, "{:?}", request);
+        }
+    }
+
+    #[test]
+    fn clamp_picks_the_nearest_supported_framerate() {
+        let mut request = stream("1280x720", 100, "h264");
+        let found = validate_stream_settings(&mut request, Some(&capabilities()), Clamp);
+
+        assert_eq!(issues(&found), vec![("framerate", Warning)]);
+        assert_eq!(request.framerate, 120);
+    }
+
+    #[test]
+    fn zero_stream_values_are_errors_even_without_capabilities() {
+        let mut request = StreamStartRequest { bitrate: 0, framerate: 0, ..Default::default() };
+        let found = validate_stream_settings(&mut request, None, Clamp);
+
+        assert_eq!(issues(&found), vec![("bitrate", Error), ("framerate", Error), ("capabilities", Warning)]);
+    }
+}
@@ -0,0 +1,67 @@
+//! ABR steps change the bitrate of a running stream in place: the app refuses
+//! to start a stream that is already running
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::{AbrEngine, ActionSource};
+use common::{MockCamera, TempPaths};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn a_second_start_is_refused_like_the_app() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+
+    manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.expect("start stream");
+    let error = manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.unwrap_err();
+
+    assert!(error.to_string().contains("STREAM_START_FAILED"), "{}", error);
+}
+
+#[tokio::test]
+async fn congestion_steps_the_running_stream_down_without_restarting_it() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.expect("start stream");
+
+    let manager = Arc::new(RwLock::new(manager));
+    let abr = AbrEngine::new(manager.clone());
+    abr.start().await;
+    abr.set_enabled(&id, true).await.expect("enable ABR");
+
+    camera.send_telemetry(500, 0, -50, 35.0);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while camera.state().stream_updates.is_empty() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("ABR step sent");
+
+    {
+        let state = camera.state();
+        assert_eq!(state.stream_updates[0]["bitrate"], 8_000_000);
+        assert!(state.stream_updates[0]["resolution"].is_null());
+        assert!(state.streaming);
+    }
+    assert_eq!(camera.requests_to("POST /api/v1/stream/start"), 1);
+
+    // The rung is not persisted: the operator's bitrate stays the ceiling
+    let (stream, _) = manager.read().await.get_persisted_settings(&id).expect("persisted settings");
+    assert_eq!(stream.expect("stream settings").bitrate, 10_000_000);
+}
@@ -0,0 +1,67 @@
+//! Automation rules change a running stream in place: the app refuses to
+//! start a stream that is already running
+
+mod common;
+
+use avocam_core::automation::{AutomationRule, ComparisonOp, RuleAction, RuleCondition, RuleTrigger, TelemetryMetric};
+use avocam_core::models::*;
+use avocam_core::{ActionSource, AutomationEngine};
+use common::{MockCamera, TempPaths};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn stream_settings_action_updates_the_running_stream_without_restarting_it() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.expect("start stream");
+
+    let manager = Arc::new(RwLock::new(manager));
+    let automation = AutomationEngine::new(manager.clone());
+    automation
+        .save_rule(AutomationRule {
+            id: String::new(),
+            name: "Hot camera".to_string(),
+            enabled: true,
+            trigger: RuleTrigger::Telemetry,
+            conditions: vec![RuleCondition { metric: TelemetryMetric::TempC, op: ComparisonOp::Gt, value: 42.0 }],
+            actions: vec![RuleAction::UpdateStreamSettings {
+                resolution: None,
+                framerate: None,
+                bitrate: Some(6_000_000),
+                codec: None,
+            }],
+            cooldown_s: 60,
+            camera_ids: Vec::new(),
+        })
+        .await
+        .expect("save rule");
+    automation.start().await;
+
+    camera.send_telemetry(10, 0, -50, 45.0);
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while camera.state().stream_updates.is_empty() {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("stream update sent");
+
+    {
+        let state = camera.state();
+        assert_eq!(state.stream_updates[0]["bitrate"], 6_000_000);
+        assert!(state.streaming);
+    }
+    assert_eq!(camera.requests_to("POST /api/v1/stream/start"), 1);
+
+    let (stream, _) = manager.read().await.get_persisted_settings(&id).expect("persisted settings");
+    assert_eq!(stream.expect("stream settings").bitrate, 6_000_000);
+}
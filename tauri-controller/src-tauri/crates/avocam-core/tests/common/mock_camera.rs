@@ -0,0 +1,491 @@
+//! Stand-in for the iOS camera API on a local port
+//!
+//! Serves just enough of `/api/v1` and `/ws` for the manager to connect, and
+//! records every request so tests can assert on what was sent. Responses close
+//! the connection, so each request is parsed on a fresh socket. A TLS camera
+//! serves a self-signed certificate that tests can regenerate, like a reset phone.
+
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::TcpListener;
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use tokio_rustls::rustls::{self, ServerConfig};
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use futures_util::{SinkExt, StreamExt};
+
+/// PIN the phone "shows" for PIN pairing
+pub const PAIRING_PIN: &str = "482913";
+
+/// What the mock camera knows and has received
+#[derive(Debug, Default)]
+pub struct MockState {
+    pub alias: String,
+    /// Bearer tokens accepted by the API (empty = no authentication)
+    pub tokens: HashSet<String>,
+    /// Every request as `METHOD /path`
+    pub requests: Vec<String>,
+    /// Bodies of `POST /api/v1/camera`
+    pub camera_settings: Vec<Value>,
+    /// Whether NDI is sending; the app refuses to start a running stream
+    pub streaming: bool,
+    /// Bodies of `POST /api/v1/stream/update`
+    pub stream_updates: Vec<Value>,
+    /// Lifetime of new pairings
+    pub pairing_ttl: Duration,
+    /// Pairings started and not confirmed yet, by pairing id
+    pub pairings: HashMap<String, MockPairing>,
+    /// Token issued to each paired controller
+    pub paired_tokens: HashMap<String, String>,
+    /// Telemetry pushed to every open WebSocket
+    telemetry: Option<broadcast::Sender<String>>,
+    next_pairing: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct MockPairing {
+    pub controller_id: String,
+    pub method: String,
+    pub expires_at: Instant,
+    /// QR code secret, once the phone has scanned the controller's code
+    pub scanned_code: Option<String>,
+}
+
+/// Self-signed certificate served by a TLS camera
+struct Certificate {
+    config: Arc<ServerConfig>,
+    fingerprint: String,
+}
+
+impl Certificate {
+    fn generate() -> Self {
+        let certified = rcgen::generate_simple_self_signed(vec!["avocam.local".to_string()])
+            .expect("generate certificate");
+        let der: CertificateDer<'static> = certified.cert.der().clone();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+
+        let config = ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+            .with_safe_default_protocol_versions()
+            .expect("TLS versions")
+            .with_no_client_auth()
+            .with_single_cert(vec![der.clone()], key)
+            .expect("server certificate");
+
+        Self {
+            fingerprint: avocam_core::tls::fingerprint(&der),
+            config: Arc::new(config),
+        }
+    }
+}
+
+/// Mock camera listening on `127.0.0.1`
+pub struct MockCamera {
+    pub port: u16,
+    state: Arc<Mutex<MockState>>,
+    certificate: Option<Arc<Mutex<Certificate>>>,
+    task: JoinHandle<()>,
+}
+
+impl MockCamera {
+    /// Start a camera accepting `token` (or anything when empty)
+    pub async fn start(alias: &str, token: &str) -> Self {
+        Self::start_with(alias, token, None).await
+    }
+
+    /// Start a camera serving https/wss with a fresh self-signed certificate
+    pub async fn start_tls(alias: &str, token: &str) -> Self {
+        Self::start_with(alias, token, Some(Certificate::generate())).await
+    }
+
+    async fn start_with(alias: &str, token: &str, certificate: Option<Certificate>) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind mock camera");
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(MockState {
+            alias: alias.to_string(),
+            tokens: [token.to_string()].into_iter().filter(|t| !t.is_empty()).collect(),
+            pairing_ttl: Duration::from_secs(120),
+            telemetry: Some(broadcast::channel(16).0),
+            ..Default::default()
+        }));
+        let certificate = certificate.map(|c| Arc::new(Mutex::new(c)));
+
+        let server_state = state.clone();
+        let server_certificate = certificate.clone();
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let state = server_state.clone();
+                let Some(certificate) = &server_certificate else {
+                    tokio::spawn(handle_connection(stream, state));
+                    continue;
+                };
+
+                // The certificate is read per connection so regenerating it takes effect at once
+                let acceptor = TlsAcceptor::from(certificate.lock().unwrap().config.clone());
+                tokio::spawn(async move {
+                    // A client rejecting the certificate aborts the handshake
+                    if let Ok(stream) = acceptor.accept(stream).await {
+                        handle_connection(stream, state).await;
+                    }
+                });
+            }
+        });
+
+        Self { port, state, certificate, task }
+    }
+
+    /// Fingerprint of the certificate a TLS camera serves
+    pub fn fingerprint(&self) -> String {
+        self.certificate.as_ref().expect("not a TLS camera").lock().unwrap().fingerprint.clone()
+    }
+
+    /// Serve a new self-signed certificate, as after a reset; returns its fingerprint
+    pub fn regenerate_certificate(&self) -> String {
+        let certificate = self.certificate.as_ref().expect("not a TLS camera");
+        *certificate.lock().unwrap() = Certificate::generate();
+        self.fingerprint()
+    }
+
+    pub fn state(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Requests received so far whose line starts with `prefix` (e.g. `POST /api/v1/camera`)
+    pub fn requests_to(&self, prefix: &str) -> usize {
+        self.state().requests.iter().filter(|r| r.starts_with(prefix)).count()
+    }
+
+    /// Push a telemetry sample to the connected controllers, `ndi_state`
+    /// following the stream state
+    pub fn send_telemetry(&self, queue_ms: u32, dropped_frames: u32, wifi_rssi: i32, temp_c: f64) {
+        let state = self.state();
+        let telemetry = json!({
+            "fps": 25.0,
+            "bitrate": 10_000_000,
+            "queue_ms": queue_ms,
+            "battery": 0.8,
+            "temp_c": temp_c,
+            "wifi_rssi": wifi_rssi,
+            "cpu_usage": 0.2,
+            "ndi_state": if state.streaming { "streaming" } else { "idle" },
+            "dropped_frames": dropped_frames,
+            "charging_state": "unplugged",
+        });
+        if let Some(sender) = &state.telemetry {
+            let _ = sender.send(telemetry.to_string());
+        }
+    }
+
+    /// Scan a controller's pairing QR code with the phone
+    pub fn scan_qr(&self, payload: &str) {
+        let payload: Value = serde_json::from_str(payload).expect("QR payload is JSON");
+        let pairing_id = payload["pairing_id"].as_str().expect("pairing_id in QR payload");
+        let code = payload["code"].as_str().expect("code in QR payload");
+
+        let mut state = self.state();
+        let pairing = state.pairings.get_mut(pairing_id).expect("pairing to scan");
+        pairing.scanned_code = Some(code.to_string());
+    }
+}
+
+impl Drop for MockCamera {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+// MARK: - HTTP
+
+struct Request {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    websocket_key: Option<String>,
+    body: Vec<u8>,
+}
+
+async fn read_request<S: AsyncRead + Unpin>(reader: &mut BufReader<S>) -> Option<Request> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?.to_string();
+    let path = parts.next()?.to_string();
+
+    let (mut authorization, mut websocket_key, mut content_length) = (None, None, 0);
+    loop {
+        let mut header = String::new();
+        reader.read_line(&mut header).await.ok()?;
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let (name, value) = header.split_once(':')?;
+        let value = value.trim().to_string();
+        match name.to_ascii_lowercase().as_str() {
+            "authorization" => authorization = Some(value),
+            "sec-websocket-key" => websocket_key = Some(value),
+            "content-length" => content_length = value.parse().unwrap_or(0),
+            _ => {}
+        }
+    }
+
+    let mut body = vec![0u8; content_length];
+    reader.read_exact(&mut body).await.ok()?;
+    Some(Request { method, path, authorization, websocket_key, body })
+}
+
+pub(crate) async fn handle_connection<S>(stream: S, state: Arc<Mutex<MockState>>)
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut reader = BufReader::new(stream);
+    let Some(request) = read_request(&mut reader).await else {
+        return;
+    };
+    state.lock().unwrap().requests.push(format!("{} {}", request.method, request.path));
+
+    let authorized = {
+        let state = state.lock().unwrap();
+        state.tokens.is_empty()
+            || request.authorization.as_deref()
+                .and_then(|value| value.strip_prefix("Bearer "))
+                .is_some_and(|token| state.tokens.contains(token))
+    };
+
+    if request.path == "/ws" {
+        if let (true, Some(key)) = (authorized, &request.websocket_key) {
+            let accept = derive_accept_key(key.as_bytes());
+            // Subscribed before the handshake so nothing sent once connected is missed
+            let mut telemetry = state.lock().unwrap().telemetry.as_ref().expect("telemetry channel").subscribe();
+            let mut stream = reader.into_inner();
+            let handshake = format!(
+                "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\n\r\n",
+                accept,
+            );
+            if stream.write_all(handshake.as_bytes()).await.is_err() {
+                return;
+            }
+            // Pings are answered while reading; telemetry is pushed on request
+            let mut socket = WebSocketStream::from_raw_socket(stream, Role::Server, None).await;
+            loop {
+                tokio::select! {
+                    message = socket.next() => {
+                        if !matches!(message, Some(Ok(_))) {
+                            return;
+                        }
+                    }
+                    Ok(text) = telemetry.recv() => {
+                        if socket.send(Message::Text(text)).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    // Pairing is how a controller gets a token in the first place
+    let public = request.method == "POST"
+        && matches!(request.path.as_str(), "/api/v1/pair/request" | "/api/v1/pair/confirm");
+    let (status, body) = if authorized || public {
+        route(&request, &state)
+    } else {
+        (401, error("UNAUTHORIZED", "Missing or invalid token"))
+    };
+
+    let body = body.to_string();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, reason(status), body.len(), body,
+    );
+    let mut stream = reader.into_inner();
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+fn route(request: &Request, state: &Mutex<MockState>) -> (u16, Value) {
+    let body: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/api/v1/status") => (200, status(&state.lock().unwrap())),
+        ("GET", "/api/v1/capabilities") => (200, capabilities()),
+        ("POST", "/api/v1/camera") => {
+            state.lock().unwrap().camera_settings.push(body);
+            (200, json!({}))
+        }
+        ("POST", "/api/v1/stream/start") => {
+            let mut state = state.lock().unwrap();
+            if state.streaming {
+                return (500, error("STREAM_START_FAILED", "Stream is already active"));
+            }
+            state.streaming = true;
+            (200, json!({}))
+        }
+        ("POST", "/api/v1/stream/stop") => {
+            state.lock().unwrap().streaming = false;
+            (200, json!({}))
+        }
+        ("POST", "/api/v1/stream/update") => {
+            state.lock().unwrap().stream_updates.push(body);
+            (200, json!({}))
+        }
+        ("GET", "/api/v1/diag") => (200, diagnostics()),
+        ("POST", "/api/v1/pair/request") => request_pairing(&body, &mut state.lock().unwrap()),
+        ("POST", "/api/v1/pair/confirm") => confirm_pairing(&body, &mut state.lock().unwrap()),
+        ("POST", "/api/v1/pair/revoke") => {
+            let mut state = state.lock().unwrap();
+            if let Some(token) = body["controller_id"].as_str().and_then(|id| state.paired_tokens.remove(id)) {
+                state.tokens.remove(&token);
+            }
+            (200, json!({}))
+        }
+        _ => (404, error("NOT_FOUND", "No such endpoint")),
+    }
+}
+
+// MARK: - Pairing
+
+fn request_pairing(body: &Value, state: &mut MockState) -> (u16, Value) {
+    let (Some(controller_id), Some(method)) = (body["controller_id"].as_str(), body["method"].as_str()) else {
+        return (400, error("BAD_REQUEST", "controller_id and method are required"));
+    };
+
+    state.next_pairing += 1;
+    let pairing_id = format!("pairing-{}", state.next_pairing);
+    let expires_at = Instant::now() + state.pairing_ttl;
+    state.pairings.insert(pairing_id.clone(), MockPairing {
+        controller_id: controller_id.to_string(),
+        method: method.to_string(),
+        expires_at,
+        scanned_code: None,
+    });
+
+    (200, json!({ "pairing_id": pairing_id, "expires_in_s": state.pairing_ttl.as_secs() }))
+}
+
+fn confirm_pairing(body: &Value, state: &mut MockState) -> (u16, Value) {
+    let pairing_id = body["pairing_id"].as_str().unwrap_or_default();
+    let code = body["code"].as_str().unwrap_or_default();
+    let Some(pairing) = state.pairings.get(pairing_id).cloned() else {
+        return (404, error("PAIRING_NOT_FOUND", "No such pairing"));
+    };
+
+    if pairing.expires_at <= Instant::now() {
+        state.pairings.remove(pairing_id);
+        return (410, error("PAIRING_EXPIRED", "Pairing expired"));
+    }
+    let expected = match pairing.method.as_str() {
+        "qr" => match pairing.scanned_code {
+            Some(code) => code,
+            None => return (409, error("NOT_SCANNED", "Scan the QR code with the camera first")),
+        },
+        _ => PAIRING_PIN.to_string(),
+    };
+    // A wrong code keeps the pairing open for another try
+    if code != expected {
+        return (403, error("INVALID_CODE", "Wrong pairing code"));
+    }
+
+    state.pairings.remove(pairing_id);
+    let token = format!("paired-{}-{}", pairing.controller_id, pairing_id);
+    if let Some(previous) = state.paired_tokens.insert(pairing.controller_id, token.clone()) {
+        state.tokens.remove(&previous);
+    }
+    state.tokens.insert(token.clone());
+    (200, json!({ "token": token }))
+}
+
+/// Status reporting the settings posted so far on top of the defaults
+fn status(state: &MockState) -> Value {
+    let mut current = json!({
+        "resolution": "1920x1080",
+        "fps": 25,
+        "bitrate": 10_000_000,
+        "codec": "h264",
+        "wb_mode": "manual",
+        "wb_kelvin": 5600,
+        "wb_tint": 0.0,
+        "iso_mode": "manual",
+        "iso": 200,
+        "shutter_mode": "manual",
+        "shutter_s": 0.02,
+        "focus_mode": "auto",
+        "zoom_factor": 1.0,
+        "camera_position": "back",
+        "lens": "wide",
+    });
+    for settings in &state.camera_settings {
+        for (key, value) in settings.as_object().into_iter().flatten() {
+            if let Some(field) = current.get_mut(key.as_str()).filter(|_| !value.is_null()) {
+                *field = value.clone();
+            }
+        }
+    }
+
+    json!({
+        "alias": state.alias,
+        "ndi_state": if state.streaming { "streaming" } else { "idle" },
+        "current": current,
+        "telemetry": {
+            "fps": 25.0,
+            "bitrate": 10_000_000,
+            "battery": 0.8,
+            "temp_c": 35.0,
+            "wifi_rssi": -50,
+            "cpu_usage": 0.2,
+            "queue_ms": 10,
+            "dropped_frames": 0,
+            "charging_state": "unplugged",
+        },
+        "capabilities": capabilities(),
+    })
+}
+
+/// Shaped like the phone's encoder output (ISO 8601 dates without fractions)
+fn diagnostics() -> Value {
+    json!({
+        "dropped_frames": 12,
+        "queue_depth": 2,
+        "temperature_timeline": [
+            { "timestamp": "2026-05-01T19:00:00Z", "temp_c": 35.5 },
+            { "timestamp": "2026-05-01T19:00:01Z", "temp_c": 35.75 },
+        ],
+    })
+}
+
+fn capabilities() -> Value {
+    json!([{
+        "resolution": "1920x1080",
+        "fps": [25, 30, 50, 60],
+        "codec": ["h264", "hevc"],
+        "lens": "wide",
+        "max_zoom": 10.0,
+        "min_iso": 32,
+        "max_iso": 3072,
+    }])
+}
+
+pub(crate) fn error(code: &str, message: &str) -> Value {
+    json!({ "code": code, "message": message })
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        410 => "Gone",
+        500 => "Internal Server Error",
+        _ => "Error",
+    }
+}
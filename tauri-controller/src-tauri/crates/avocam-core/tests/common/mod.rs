@@ -0,0 +1,47 @@
+//! Harness shared by the integration tests: a throwaway data directory and a
+//! mock camera, so the core runs headless without Tauri or a phone
+#![allow(dead_code, unused_imports)] // Each test binary uses a different subset
+
+pub mod mock_camera;
+
+use anyhow::Result;
+use avocam_core::{CameraManager, PathProvider};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tempfile::TempDir;
+
+pub use mock_camera::MockCamera;
+
+/// [`PathProvider`] in a temporary directory, deleted when dropped
+pub struct TempPaths {
+    dir: TempDir,
+}
+
+impl TempPaths {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self { dir: TempDir::new().expect("create temp dir") })
+    }
+
+    pub fn path(&self) -> &Path {
+        self.dir.path()
+    }
+}
+
+impl PathProvider for TempPaths {
+    fn data_dir(&self) -> Result<PathBuf> {
+        Ok(self.dir.path().to_path_buf())
+    }
+}
+
+/// Manager persisting into `paths`, loading whatever is saved there
+pub async fn manager(paths: &Arc<TempPaths>) -> CameraManager {
+    let mut manager = CameraManager::new();
+    manager.set_path_provider(paths.clone()).await.expect("set path provider");
+    manager
+}
+
+/// `cameras.json` as written by the manager
+pub fn cameras_file(paths: &TempPaths) -> serde_json::Value {
+    let json = std::fs::read_to_string(paths.path().join("cameras.json")).expect("read cameras.json");
+    serde_json::from_str(&json).expect("parse cameras.json")
+}
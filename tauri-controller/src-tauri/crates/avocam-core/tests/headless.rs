@@ -0,0 +1,117 @@
+//! The core runs without Tauri: a manager with a temporary data directory
+//! connects, controls and persists a mock camera
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::ActionSource;
+use common::{MockCamera, TempPaths};
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn manager_runs_headless_and_persists_cameras() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    assert_eq!(id, format!("127.0.0.1:{}", camera.port));
+
+    let cameras = manager.get_all_cameras().await;
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(cameras[0].alias, "Stage Left");
+    assert_eq!(cameras[0].connection_state, ConnectionState::Connected);
+
+    manager
+        .update_camera_settings(&id, CameraSettingsRequest { iso: Some(400), ..Default::default() }, ActionSource::Cli)
+        .await
+        .expect("update settings");
+    assert_eq!(camera.state().camera_settings.last().unwrap()["iso"], 400);
+
+    let results = manager
+        .group_update_settings(
+            &[id.clone(), "10.0.0.99:8080".to_string()],
+            CameraSettingsRequest { zoom_factor: Some(2.0), ..Default::default() },
+            ValidationPolicy::Skip,
+            ActionSource::Cli,
+        )
+        .await
+        .expect("group update");
+    assert!(results.iter().any(|r| r.camera_id == id && r.success));
+    assert!(results.iter().any(|r| r.camera_id == "10.0.0.99:8080" && !r.success));
+    assert_eq!(camera.state().camera_settings.last().unwrap()["zoom_factor"], 2.0);
+
+    // A new manager on the same directory reconnects the saved camera
+    drop(manager);
+    let manager = common::manager(&paths).await;
+    assert_eq!(manager.camera_alias(&id).as_deref(), Some("Stage Left"));
+    assert!(manager.get_persisted_settings(&id).is_some());
+}
+
+#[tokio::test]
+async fn applied_settings_survive_a_restart_rather_than_the_reported_status() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    manager
+        .update_camera_settings(&id, CameraSettingsRequest { iso: Some(800), ..Default::default() }, ActionSource::Cli)
+        .await
+        .expect("update settings");
+    manager
+        .update_stream_settings(&id, StreamStartRequest { bitrate: 6_000_000, ..Default::default() }, ActionSource::Cli)
+        .await
+        .expect("update stream settings");
+    let saved = manager.get_persisted_settings(&id).expect("persisted settings");
+
+    drop(manager);
+    let manager = common::manager(&paths).await;
+    let loaded = manager.get_persisted_settings(&id).expect("persisted settings after restart");
+    assert_eq!(serde_json::to_value(&loaded).unwrap(), serde_json::to_value(&saved).unwrap());
+
+    let (stream, camera_settings) = loaded;
+    assert_eq!(stream.expect("stream settings").bitrate, 6_000_000);
+    let camera_settings = camera_settings.expect("camera settings");
+    assert_eq!(camera_settings.iso, Some(800));
+    assert_eq!(camera_settings.wb_mode, None);
+}
+
+#[tokio::test]
+async fn wrong_token_is_rejected() {
+    let camera = MockCamera::start("Stage Right", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+
+    let result = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, "wrong".to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await;
+
+    assert!(result.is_err());
+    assert!(manager.get_all_cameras().await.is_empty());
+}
+
+#[tokio::test]
+async fn diagnostics_parse_the_phone_payload() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+
+    let diagnostics = manager.get_diagnostics(&id).await.expect("diagnostics");
+    assert_eq!(diagnostics.dropped_frames, 12);
+    assert_eq!(diagnostics.queue_depth, 2);
+    assert_eq!(diagnostics.temperature_timeline.len(), 2);
+    assert_eq!(diagnostics.temperature_timeline[1].temp_c, 35.75);
+    assert_eq!(camera.requests_to("GET /api/v1/diag"), 1);
+}
@@ -0,0 +1,119 @@
+//! Lens presets capture a camera's live framing, survive a restart and are
+//! recalled on one camera or a group
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::{ActionSource, CameraManager};
+use common::{MockCamera, TempPaths};
+
+const TOKEN: &str = "secret-token-1234";
+
+async fn add(manager: &mut CameraManager, camera: &MockCamera) -> String {
+    manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera")
+}
+
+async fn frame(manager: &mut CameraManager, id: &str, zoom_factor: f64, focus_mode: FocusMode) {
+    let settings = CameraSettingsRequest { zoom_factor: Some(zoom_factor), focus_mode: Some(focus_mode), ..Default::default() };
+    manager.update_camera_settings(id, settings, ActionSource::Cli).await.expect("update settings");
+}
+
+#[tokio::test]
+async fn preset_captures_live_framing_and_survives_a_restart() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = add(&mut manager, &camera).await;
+
+    frame(&mut manager, &id, 2.5, FocusMode::Manual).await;
+    let preset = manager
+        .save_lens_preset(&id, 3, Some("Lectern".to_string()), ActionSource::Cli)
+        .await
+        .expect("save preset");
+    assert_eq!(preset.name.as_deref(), Some("Lectern"));
+    assert_eq!(preset.zoom_factor, 2.5);
+    assert_eq!(preset.focus_mode, FocusMode::Manual);
+    assert_eq!(preset.lens, "wide");
+    assert_eq!(preset.camera_position, "back");
+
+    drop(manager);
+    let manager = common::manager(&paths).await;
+    let presets = manager.get_lens_presets(&id).expect("presets");
+    assert_eq!(presets.keys().copied().collect::<Vec<_>>(), [3]);
+    assert_eq!(presets[&3].zoom_factor, 2.5);
+    assert_eq!(common::cameras_file(&paths)["cameras"][0]["lens_presets"]["3"]["name"], "Lectern");
+}
+
+#[tokio::test]
+async fn recall_sends_only_the_framing_fields() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = add(&mut manager, &camera).await;
+
+    frame(&mut manager, &id, 4.0, FocusMode::Manual).await;
+    manager.save_lens_preset(&id, 1, None, ActionSource::Cli).await.expect("save preset");
+    frame(&mut manager, &id, 1.0, FocusMode::Auto).await;
+
+    manager.recall_lens_preset(&id, 1, ActionSource::Cli).await.expect("recall preset");
+
+    let sent = camera.state().camera_settings.last().unwrap().clone();
+    assert_eq!(sent["zoom_factor"], 4.0);
+    assert_eq!(sent["focus_mode"], "manual");
+    assert_eq!(sent["lens"], "wide");
+    assert_eq!(sent["camera_position"], "back");
+    for field in ["wb_kelvin", "iso", "shutter_s", "torch_level"] {
+        assert!(sent[field].is_null(), "{} should not be recalled", field);
+    }
+}
+
+#[tokio::test]
+async fn slots_are_checked_and_empty_slots_are_errors() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = add(&mut manager, &camera).await;
+
+    for slot in [0, MAX_LENS_PRESET_SLOT + 1] {
+        assert!(manager.save_lens_preset(&id, slot, None, ActionSource::Cli).await.is_err());
+    }
+    assert!(manager.recall_lens_preset(&id, 5, ActionSource::Cli).await.is_err());
+    assert!(manager.delete_lens_preset(&id, 5, ActionSource::Cli).await.is_err());
+
+    manager.save_lens_preset(&id, 5, None, ActionSource::Cli).await.expect("save preset");
+    manager.delete_lens_preset(&id, 5, ActionSource::Cli).await.expect("delete preset");
+    assert!(manager.get_lens_presets(&id).expect("presets").is_empty());
+}
+
+#[tokio::test]
+async fn group_recall_reports_cameras_without_the_slot() {
+    let left = MockCamera::start("Stage Left", TOKEN).await;
+    let right = MockCamera::start("Stage Right", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let left_id = add(&mut manager, &left).await;
+    let right_id = add(&mut manager, &right).await;
+
+    frame(&mut manager, &left_id, 3.0, FocusMode::Auto).await;
+    manager.save_lens_preset(&left_id, 2, None, ActionSource::Cli).await.expect("save preset");
+    let sent_to_right = right.requests_to("POST /api/v1/camera");
+
+    let results = manager
+        .group_recall_lens_preset(&[left_id.clone(), right_id.clone()], 2, ActionSource::Cli)
+        .await
+        .expect("group recall");
+
+    let left_result = results.iter().find(|r| r.camera_id == left_id).unwrap();
+    let right_result = results.iter().find(|r| r.camera_id == right_id).unwrap();
+    assert!(left_result.success);
+    assert!(!right_result.success);
+    assert!(right_result.error.as_deref().unwrap().contains("No preset 2"));
+    assert_eq!(left.state().camera_settings.last().unwrap()["zoom_factor"], 3.0);
+    assert_eq!(right.requests_to("POST /api/v1/camera"), sent_to_right);
+
+    // An invalid slot fails the whole group
+    assert!(manager.group_recall_lens_preset(&[left_id], 0, ActionSource::Cli).await.is_err());
+}
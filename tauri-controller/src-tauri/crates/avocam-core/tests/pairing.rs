@@ -0,0 +1,138 @@
+//! PIN and QR pairing against the mock camera's pairing endpoints
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::pairing::PairingSession;
+use avocam_core::{ActionSource, CameraClient, CameraManager};
+use common::mock_camera::PAIRING_PIN;
+use common::{MockCamera, TempPaths};
+use std::time::Duration;
+
+/// Token of the camera's operator; the controller never sees it
+const OPERATOR_TOKEN: &str = "operator-token-5678";
+
+async fn start_pin_pairing(manager: &mut CameraManager, camera: &MockCamera) -> PairingSession {
+    manager
+        .start_pairing("127.0.0.1".to_string(), camera.port, CameraScheme::Http, PairingMethod::Pin)
+        .await
+        .expect("start pairing")
+}
+
+#[tokio::test]
+async fn pin_pairing_issues_a_controller_token() {
+    let camera = MockCamera::start("Stage Left", OPERATOR_TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+
+    let session = start_pin_pairing(&mut manager, &camera).await;
+    assert_eq!(session.camera_id, format!("127.0.0.1:{}", camera.port));
+    assert!(session.qr_payload.is_none());
+
+    let id = manager
+        .confirm_pairing(&session.pairing_id, Some(PAIRING_PIN.to_string()), ActionSource::Cli)
+        .await
+        .expect("confirm pairing");
+
+    // One token, issued to this controller and used for every call
+    let issued: Vec<String> = camera.state().paired_tokens.values().cloned().collect();
+    assert_eq!(issued.len(), 1);
+    assert!(camera.state().pairings.is_empty());
+    let status = manager.get_camera_status(&id).await.expect("status with the issued token");
+    assert_eq!(status.alias, "Stage Left");
+
+    // Saved encrypted like any other token
+    let saved = std::fs::read_to_string(paths.path().join("cameras.json")).unwrap();
+    assert!(saved.contains("encrypted_token"));
+    assert!(!saved.contains(&issued[0]));
+}
+
+#[tokio::test]
+async fn wrong_pin_can_be_retried() {
+    let camera = MockCamera::start("Stage Left", OPERATOR_TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let session = start_pin_pairing(&mut manager, &camera).await;
+
+    let wrong = manager
+        .confirm_pairing(&session.pairing_id, Some("000000".to_string()), ActionSource::Cli)
+        .await;
+    assert!(wrong.unwrap_err().to_string().contains("INVALID_CODE"));
+    assert!(manager.get_all_cameras().await.is_empty());
+    assert!(camera.state().paired_tokens.is_empty());
+
+    let empty = manager.confirm_pairing(&session.pairing_id, None, ActionSource::Cli).await;
+    assert!(empty.is_err());
+
+    manager
+        .confirm_pairing(&session.pairing_id, Some(PAIRING_PIN.to_string()), ActionSource::Cli)
+        .await
+        .expect("retry with the right PIN");
+    assert_eq!(manager.get_all_cameras().await.len(), 1);
+}
+
+#[tokio::test]
+async fn expired_pairing_is_refused() {
+    let camera = MockCamera::start("Stage Left", OPERATOR_TOKEN).await;
+    camera.state().pairing_ttl = Duration::from_secs(1);
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let session = start_pin_pairing(&mut manager, &camera).await;
+
+    tokio::time::sleep(Duration::from_millis(1100)).await;
+    let result = manager
+        .confirm_pairing(&session.pairing_id, Some(PAIRING_PIN.to_string()), ActionSource::Cli)
+        .await;
+
+    assert!(result.unwrap_err().to_string().contains("expired"));
+    assert!(manager.get_all_cameras().await.is_empty());
+    assert!(camera.state().paired_tokens.is_empty());
+}
+
+#[tokio::test]
+async fn qr_pairing_waits_for_the_scan() {
+    let camera = MockCamera::start("Stage Left", OPERATOR_TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+
+    let session = manager
+        .start_pairing("127.0.0.1".to_string(), camera.port, CameraScheme::Http, PairingMethod::Qr)
+        .await
+        .expect("start pairing");
+    let payload = session.qr_payload.clone().expect("QR payload");
+
+    let early = manager.confirm_pairing(&session.pairing_id, None, ActionSource::Cli).await;
+    assert!(early.unwrap_err().to_string().contains("NOT_SCANNED"));
+
+    camera.scan_qr(&payload);
+    let id = manager
+        .confirm_pairing(&session.pairing_id, None, ActionSource::Cli)
+        .await
+        .expect("confirm after scan");
+    assert!(manager.get_camera_status(&id).await.is_ok());
+}
+
+#[tokio::test]
+async fn revoke_invalidates_only_this_controllers_token() {
+    let camera = MockCamera::start("Stage Left", OPERATOR_TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let session = start_pin_pairing(&mut manager, &camera).await;
+    let id = manager
+        .confirm_pairing(&session.pairing_id, Some(PAIRING_PIN.to_string()), ActionSource::Cli)
+        .await
+        .expect("confirm pairing");
+    let token = camera.state().paired_tokens.values().next().cloned().unwrap();
+
+    manager.revoke_pairing(&id, ActionSource::Cli).await.expect("revoke");
+
+    assert!(manager.get_all_cameras().await.is_empty());
+    assert!(camera.state().paired_tokens.is_empty());
+    assert_eq!(common::cameras_file(&paths)["cameras"].as_array().unwrap().len(), 0);
+
+    // The old token is refused; the operator's still works
+    let revoked = CameraClient::new("127.0.0.1".to_string(), camera.port, token);
+    assert!(revoked.get_status().await.is_err());
+    let operator = CameraClient::new("127.0.0.1".to_string(), camera.port, OPERATOR_TOKEN.to_string());
+    assert!(operator.get_status().await.is_ok());
+}
@@ -0,0 +1,59 @@
+//! Applying a profile updates running streams in place and is journaled once
+
+mod common;
+
+use avocam_core::audit::AuditQuery;
+use avocam_core::models::*;
+use avocam_core::ActionSource;
+use common::{MockCamera, TempPaths};
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn profile_updates_a_running_stream_in_place_with_one_audit_entry() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.expect("start stream");
+
+    manager
+        .save_profile(CameraProfile {
+            name: "Low Light".to_string(),
+            settings: CameraSettingsRequest { iso: Some(800), ..Default::default() },
+            stream: Some(StreamStartRequest { bitrate: 6_000_000, ..Default::default() }),
+            fields: vec![ProfileField::Iso, ProfileField::Bitrate],
+            metadata: Default::default(),
+            shared: false,
+        })
+        .await
+        .expect("save profile");
+
+    let results = manager
+        .apply_profile("Low Light", std::slice::from_ref(&id), ActionSource::Cli)
+        .await
+        .expect("apply profile");
+
+    assert!(results.iter().all(|r| r.success), "{:?}", results);
+    assert_eq!(camera.requests_to("POST /api/v1/stream/start"), 1);
+    {
+        let state = camera.state();
+        assert_eq!(state.camera_settings.last().unwrap()["iso"], 800);
+        assert_eq!(state.stream_updates.len(), 1);
+        assert_eq!(state.stream_updates[0]["bitrate"], 6_000_000);
+        assert!(state.streaming);
+    }
+
+    let (stream, camera_settings) = manager.get_persisted_settings(&id).expect("persisted settings");
+    assert_eq!(stream.expect("stream settings").bitrate, 6_000_000);
+    assert_eq!(camera_settings.expect("camera settings").iso, Some(800));
+
+    let actions: Vec<String> = manager.query_audit_log(&AuditQuery::default()).await.expect("query audit log")
+        .into_iter()
+        .map(|entry| entry.action)
+        .collect();
+    assert_eq!(actions, ["add_camera", "start_stream", "apply_profile"]);
+}
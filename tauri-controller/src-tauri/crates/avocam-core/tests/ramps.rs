@@ -0,0 +1,64 @@
+//! Ramps stay within the app's camera update debounce and journal only their
+//! start and their landing
+
+mod common;
+
+use avocam_core::audit::AuditQuery;
+use avocam_core::models::*;
+use avocam_core::ramp::{Easing, RampParameter, RampRequest, RampState, RampTarget};
+use avocam_core::{ActionSource, RampEngine};
+use common::{MockCamera, TempPaths};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn ramp_is_capped_at_the_app_debounce_and_journals_start_and_landing() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+
+    let manager = Arc::new(RwLock::new(manager));
+    let ramps = RampEngine::new(manager.clone());
+    ramps
+        .start_ramp(
+            RampRequest {
+                camera_ids: vec![id.clone()],
+                targets: vec![RampTarget { parameter: RampParameter::Iso, target: 1600.0, from: Some(100.0) }],
+                duration_ms: 500,
+                easing: Easing::Linear,
+                rate_hz: Some(60.0),
+            },
+            ActionSource::Cli,
+        )
+        .await
+        .expect("start ramp");
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while ramps.get_ramp_statuses().await.iter().any(|s| s.state == RampState::Running) {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("ramp finished");
+
+    assert_eq!(ramps.get_ramp_statuses().await[0].state, RampState::Completed);
+    {
+        let state = camera.state();
+        // 500 ms at no more than 20 Hz, plus the landing
+        assert!(state.camera_settings.len() <= 12, "{} updates", state.camera_settings.len());
+        assert_eq!(state.camera_settings.last().unwrap()["iso"], 1600);
+    }
+
+    let actions: Vec<String> = manager.read().await.query_audit_log(&AuditQuery::default()).await.expect("query audit log")
+        .into_iter()
+        .map(|entry| entry.action)
+        .collect();
+    assert_eq!(actions, ["add_camera", "start_ramp", "group_update_settings_per_camera"]);
+}
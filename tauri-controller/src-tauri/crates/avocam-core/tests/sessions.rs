@@ -0,0 +1,44 @@
+//! Session files are never shared between recordings
+
+mod common;
+
+use avocam_core::{PathProvider, SessionRecorder};
+use chrono::{Duration, Utc};
+use common::TempPaths;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+#[tokio::test]
+async fn sessions_started_in_the_same_second_get_their_own_files() {
+    let paths = TempPaths::new();
+    let manager = Arc::new(RwLock::new(common::manager(&paths).await));
+    let recorder = SessionRecorder::new(manager);
+    recorder.set_path_provider(paths.clone()).await.expect("set path provider");
+    let sessions_dir = paths.sessions_dir().unwrap();
+
+    // Files of earlier sessions for this second (and the next, in case the clock ticks)
+    let now = Utc::now();
+    let taken: Vec<String> = [now, now + Duration::seconds(1)].iter()
+        .map(|t| t.format("%Y%m%d-%H%M%S").to_string())
+        .collect();
+    for id in &taken {
+        std::fs::write(sessions_dir.join(format!("{}.jsonl", id)), "earlier session\n").unwrap();
+    }
+
+    let session = recorder.start_session(Some("second".to_string())).await.expect("start session");
+    recorder.stop_session().await.expect("stop session");
+
+    assert!(taken.iter().any(|id| session.id == format!("{}-2", id)), "unexpected id {}", session.id);
+    for id in &taken {
+        let content = std::fs::read_to_string(sessions_dir.join(format!("{}.jsonl", id))).unwrap();
+        assert_eq!(content, "earlier session\n");
+    }
+    assert_eq!(recorder.open_session(&session.id).await.expect("open session").len(), 2);
+
+    // The next session that second takes the next suffix
+    let base = session.id.trim_end_matches("-2");
+    std::fs::write(sessions_dir.join(format!("{}.jsonl", base)), "earlier session\n").unwrap();
+    let next = recorder.start_session(None).await.expect("start next session");
+    recorder.stop_session().await.expect("stop next session");
+    assert_ne!(next.id, session.id);
+}
@@ -0,0 +1,78 @@
+//! The thermal guard steps a running stream down and restores it in place:
+//! the app refuses to start a stream that is already running
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::thermal::{ThermalConfig, ThermalStage};
+use avocam_core::{ActionSource, ThermalGuard};
+use common::{MockCamera, TempPaths};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::RwLock;
+
+const TOKEN: &str = "secret-token-1234";
+
+async fn wait_for_stream_updates(camera: &MockCamera, count: usize) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while camera.state().stream_updates.len() < count {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await
+    .expect("stream updates sent");
+}
+
+#[tokio::test]
+async fn steps_down_and_restores_the_running_stream_without_restarting_it() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+    manager.start_stream(&id, StreamStartRequest::default(), ActionSource::Cli).await.expect("start stream");
+
+    let manager = Arc::new(RwLock::new(manager));
+    let guard = ThermalGuard::new(manager.clone());
+    guard
+        .save_config(ThermalConfig {
+            enabled: true,
+            stages: vec![ThermalStage {
+                above_c: 43.0,
+                max_bitrate_bps: Some(6_000_000),
+                max_fps: Some(25),
+                ..Default::default()
+            }],
+            smoothing: 1.0,
+            min_stage_s: 0,
+            restore_below_c: 40.0,
+            cooldown_s: 0,
+        })
+        .await
+        .expect("save thermal policy");
+    guard.start().await;
+
+    camera.send_telemetry(10, 0, -50, 45.0);
+    wait_for_stream_updates(&camera, 1).await;
+    {
+        let state = camera.state();
+        assert_eq!(state.stream_updates[0]["bitrate"], 6_000_000);
+        assert_eq!(state.stream_updates[0]["framerate"], 25);
+    }
+
+    camera.send_telemetry(10, 0, -50, 35.0);
+    wait_for_stream_updates(&camera, 2).await;
+    {
+        let state = camera.state();
+        assert_eq!(state.stream_updates[1]["bitrate"], 10_000_000);
+        assert_eq!(state.stream_updates[1]["framerate"], 30);
+        assert!(state.streaming);
+    }
+    assert_eq!(camera.requests_to("POST /api/v1/stream/start"), 1);
+
+    let status = guard.get_status().await;
+    assert_eq!(status[0].stage, 0);
+    assert!(status[0].transitions.iter().all(|t| t.error.is_none()));
+}
@@ -0,0 +1,123 @@
+//! Trust-on-first-use certificate pinning against a TLS mock camera
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::ActionSource;
+use common::{MockCamera, TempPaths};
+
+const TOKEN: &str = "secret-token-1234";
+
+async fn add_tls_camera(manager: &mut avocam_core::CameraManager, camera: &MockCamera) -> String {
+    manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Https, ActionSource::Cli)
+        .await
+        .expect("add camera")
+}
+
+/// Fingerprints saved in `cameras.json`, one per saved camera
+fn saved_fingerprints(paths: &TempPaths) -> Vec<Option<String>> {
+    common::cameras_file(paths)["cameras"].as_array().unwrap().iter()
+        .map(|camera| camera["cert_fingerprint"].as_str().map(str::to_string))
+        .collect()
+}
+
+#[tokio::test]
+async fn first_connection_pins_the_certificate() {
+    let camera = MockCamera::start_tls("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+
+    let id = add_tls_camera(&mut manager, &camera).await;
+
+    let cameras = manager.get_all_cameras().await;
+    assert_eq!(cameras[0].id, id);
+    assert_eq!(cameras[0].cert_fingerprint, Some(camera.fingerprint()));
+    assert_eq!(saved_fingerprints(&paths), vec![Some(camera.fingerprint())]);
+}
+
+#[tokio::test]
+async fn matching_pin_reconnects_on_load() {
+    let camera = MockCamera::start_tls("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let id = add_tls_camera(&mut common::manager(&paths).await, &camera).await;
+    let status_requests = camera.requests_to("GET /api/v1/status");
+
+    let manager = common::manager(&paths).await;
+
+    assert_eq!(manager.camera_alias(&id).as_deref(), Some("Stage Left"));
+    assert!(camera.requests_to("GET /api/v1/status") > status_requests);
+    assert_eq!(saved_fingerprints(&paths), vec![Some(camera.fingerprint())]);
+}
+
+#[tokio::test]
+async fn changed_certificate_leaves_camera_untrusted() {
+    let camera = MockCamera::start_tls("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let id = add_tls_camera(&mut common::manager(&paths).await, &camera).await;
+    let pinned = camera.fingerprint();
+    let status_requests = camera.requests_to("GET /api/v1/status");
+
+    camera.regenerate_certificate();
+    let mut manager = common::manager(&paths).await;
+
+    // Not connected, and no request (so no token) reached the camera
+    assert!(manager.get_all_cameras().await.is_empty());
+    assert_eq!(camera.requests_to("GET /api/v1/status"), status_requests);
+
+    // Saves keep it under its old pin until it is re-trusted
+    let other = MockCamera::start("Stage Right", TOKEN).await;
+    manager
+        .add_camera_manual("127.0.0.1".to_string(), other.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add other camera");
+    let mut fingerprints = saved_fingerprints(&paths);
+    fingerprints.sort();
+    assert_eq!(fingerprints, vec![None, Some(pinned)]);
+    assert!(manager.camera_alias(&id).is_none());
+}
+
+#[tokio::test]
+async fn retrust_pins_the_new_certificate() {
+    let camera = MockCamera::start_tls("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let id = add_tls_camera(&mut common::manager(&paths).await, &camera).await;
+
+    let regenerated = camera.regenerate_certificate();
+    let mut manager = common::manager(&paths).await;
+    assert!(manager.get_all_cameras().await.is_empty());
+
+    let fingerprint = manager.retrust_camera(&id, ActionSource::Cli).await.expect("retrust");
+
+    assert_eq!(fingerprint, regenerated);
+    let cameras = manager.get_all_cameras().await;
+    assert_eq!(cameras.len(), 1);
+    assert_eq!(cameras[0].cert_fingerprint, Some(regenerated.clone()));
+    // Saved once, under the new pin
+    assert_eq!(saved_fingerprints(&paths), vec![Some(regenerated.clone())]);
+
+    // The new pin holds on the next start
+    drop(manager);
+    let manager = common::manager(&paths).await;
+    assert_eq!(manager.camera_alias(&id).as_deref(), Some("Stage Left"));
+}
+
+#[tokio::test]
+async fn failed_retrust_keeps_the_untrusted_camera() {
+    let camera = MockCamera::start_tls("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let id = add_tls_camera(&mut common::manager(&paths).await, &camera).await;
+    let pinned = camera.fingerprint();
+
+    camera.regenerate_certificate();
+    let mut manager = common::manager(&paths).await;
+    camera.state().tokens = ["rotated".to_string()].into();
+
+    assert!(manager.retrust_camera(&id, ActionSource::Cli).await.is_err());
+
+    // Still known, so a later retrust can succeed
+    camera.state().tokens = [TOKEN.to_string()].into();
+    let fingerprint = manager.retrust_camera(&id, ActionSource::Cli).await.expect("retrust");
+    assert_ne!(fingerprint, pinned);
+    assert_eq!(saved_fingerprints(&paths), vec![Some(fingerprint)]);
+}
@@ -0,0 +1,44 @@
+//! Camera tokens saved in plaintext by older versions are encrypted on load
+
+mod common;
+
+use common::{MockCamera, TempPaths};
+use serde_json::json;
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn plaintext_tokens_are_encrypted_on_load() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let id = format!("127.0.0.1:{}", camera.port);
+    let legacy = json!({
+        "cameras": [{
+            "id": id,
+            "alias": "Stage Left",
+            "ip": "127.0.0.1",
+            "port": camera.port,
+            "token": TOKEN,
+        }],
+    });
+    std::fs::write(paths.path().join("cameras.json"), legacy.to_string()).unwrap();
+
+    let manager = common::manager(&paths).await;
+
+    // Connected with the migrated token
+    assert_eq!(manager.camera_alias(&id).as_deref(), Some("Stage Left"));
+
+    let saved = common::cameras_file(&paths);
+    let saved_camera = &saved["cameras"][0];
+    assert!(saved_camera.get("token").is_none());
+    let encrypted = saved_camera["encrypted_token"].as_str().expect("encrypted token");
+    assert!(encrypted.starts_with("v1:"));
+    assert_eq!(saved["token_key"]["source"], "key_file");
+    assert!(!std::fs::read_to_string(paths.path().join("cameras.json")).unwrap().contains(TOKEN));
+    assert!(paths.path().join("token.key").exists());
+
+    // And still connects from the encrypted file
+    drop(manager);
+    let manager = common::manager(&paths).await;
+    assert_eq!(manager.camera_alias(&id).as_deref(), Some("Stage Left"));
+}
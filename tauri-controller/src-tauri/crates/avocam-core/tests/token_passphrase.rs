@@ -0,0 +1,40 @@
+//! Passphrase-protected tokens keep the saved cameras locked until unlocked
+
+mod common;
+
+use avocam_core::models::*;
+use avocam_core::{ActionSource, CameraManager};
+use common::{MockCamera, TempPaths};
+use tokio::sync::RwLock;
+
+const TOKEN: &str = "secret-token-1234";
+
+#[tokio::test]
+async fn saved_cameras_connect_once_the_passphrase_is_entered() {
+    let camera = MockCamera::start("Stage Left", TOKEN).await;
+    let paths = TempPaths::new();
+    let mut manager = common::manager(&paths).await;
+    let id = manager
+        .add_camera_manual("127.0.0.1".to_string(), camera.port, TOKEN.to_string(), CameraScheme::Http, ActionSource::Cli)
+        .await
+        .expect("add camera");
+
+    let manager = RwLock::new(manager);
+    CameraManager::set_token_passphrase(&manager, Some("correct horse".to_string()), ActionSource::Cli)
+        .await
+        .expect("set passphrase");
+    assert_eq!(common::cameras_file(&paths)["token_key"]["source"], "passphrase");
+
+    drop(manager);
+    let manager = RwLock::new(common::manager(&paths).await);
+    assert!(manager.read().await.token_storage_status().locked);
+    assert_eq!(manager.read().await.camera_alias(&id), None);
+
+    assert!(CameraManager::unlock_tokens(&manager, "wrong", ActionSource::Cli).await.is_err());
+    assert!(manager.read().await.token_storage_status().locked);
+
+    let connected = CameraManager::unlock_tokens(&manager, "correct horse", ActionSource::Cli).await.expect("unlock");
+    assert_eq!(connected, 1);
+    assert!(!manager.read().await.token_storage_status().locked);
+    assert_eq!(manager.read().await.camera_alias(&id).as_deref(), Some("Stage Left"));
+}
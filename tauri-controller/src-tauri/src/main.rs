@@ -1,22 +1,48 @@
 // Prevents additional console window on Windows in release builds
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
-mod models;
-mod camera_discovery;
-mod camera_client;
-mod camera_manager;
+mod platform;
 
+use std::collections::BTreeMap;
 use std::sync::Arc;
 use tauri::{Manager, State, AppHandle};
 use tokio::sync::RwLock;
 
-use camera_manager::CameraManager;
-use models::*;
+use avocam_core::abr::{AbrCameraStatus, AbrConfig, AbrSettings};
+use avocam_core::audit::{AuditEntry, AuditQuery, ExportFormat};
+use avocam_core::automation::{AutomationRule, RuleExecution};
+use avocam_core::bandwidth::BudgetReport;
+use avocam_core::latency::CameraLatency;
+use avocam_core::matching::{
+    ExposureMatchReport, ExposureMatchRequest, WhiteBalanceMatchReport, WhiteBalanceMatchRequest,
+};
+use avocam_core::models::*;
+use avocam_core::pairing::PairingSession;
+use avocam_core::profiles::{ConflictResolution, ProfileImportReport, ProfilePreview};
+use avocam_core::ramp::{RampRequest, RampStatus};
+use avocam_core::report::{ReportFormat, SessionReport};
+use avocam_core::scheduler::{Schedule, ScheduleRunsOverview};
+use avocam_core::session::{SessionInfo, SessionRecord};
+use avocam_core::thermal::{ThermalCameraStatus, ThermalConfig};
+use avocam_core::token_vault::TokenStorageStatus;
+use avocam_core::validation::SettingsValidation;
+use avocam_core::{
+    AbrEngine, ActionSource, AutomationEngine, CameraManager, MetricsExporter, PathProvider, RampEngine, Scheduler,
+    SessionRecorder, ThermalGuard,
+};
+use platform::{TauriNotifier, TauriPaths};
 
 // MARK: - Application State
 
 struct AppState {
     camera_manager: Arc<RwLock<CameraManager>>,
+    automation: Arc<AutomationEngine>,
+    scheduler: Arc<Scheduler>,
+    ramps: Arc<RampEngine>,
+    abr: Arc<AbrEngine>,
+    thermal: Arc<ThermalGuard>,
+    sessions: Arc<SessionRecorder>,
+    metrics_exporter: Arc<MetricsExporter>,
 }
 
 // MARK: - Tauri Commands
@@ -36,9 +62,80 @@ async fn add_camera_manual(
     ip: String,
     port: u16,
     token: String,
+    scheme: Option<CameraScheme>,
 ) -> Result<String, String> {
     let mut manager = state.camera_manager.write().await;
-    manager.add_camera_manual(ip, port, token).await
+    manager.add_camera_manual(ip, port, token, scheme.unwrap_or_default(), ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn retrust_camera(
+    state: State<'_, AppState>,
+    camera_id: String,
+) -> Result<String, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.retrust_camera(&camera_id, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn start_pairing(
+    state: State<'_, AppState>,
+    ip: String,
+    port: u16,
+    scheme: Option<CameraScheme>,
+    method: PairingMethod,
+) -> Result<PairingSession, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.start_pairing(ip, port, scheme.unwrap_or_default(), method).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn confirm_pairing(
+    state: State<'_, AppState>,
+    pairing_id: String,
+    pin: Option<String>,
+) -> Result<String, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.confirm_pairing(&pairing_id, pin, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn revoke_pairing(
+    state: State<'_, AppState>,
+    camera_id: String,
+) -> Result<(), String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.revoke_pairing(&camera_id, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_token_storage_status(
+    state: State<'_, AppState>,
+) -> Result<TokenStorageStatus, String> {
+    let manager = state.camera_manager.read().await;
+    Ok(manager.token_storage_status())
+}
+
+#[tauri::command]
+async fn unlock_tokens(
+    state: State<'_, AppState>,
+    passphrase: String,
+) -> Result<usize, String> {
+    CameraManager::unlock_tokens(&state.camera_manager, &passphrase, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_token_passphrase(
+    state: State<'_, AppState>,
+    passphrase: Option<String>,
+) -> Result<(), String> {
+    CameraManager::set_token_passphrase(&state.camera_manager, passphrase, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -48,7 +145,7 @@ async fn remove_camera(
     camera_id: String,
 ) -> Result<(), String> {
     let mut manager = state.camera_manager.write().await;
-    manager.remove_camera(&camera_id).await
+    manager.remove_camera(&camera_id, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -86,7 +183,7 @@ async fn start_stream(
         bitrate,
         codec,
     };
-    manager.start_stream(&camera_id, request).await
+    manager.start_stream(&camera_id, request, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -96,7 +193,7 @@ async fn stop_stream(
     camera_id: String,
 ) -> Result<(), String> {
     let manager = state.camera_manager.read().await;
-    manager.stop_stream(&camera_id).await
+    manager.stop_stream(&camera_id, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -105,9 +202,64 @@ async fn update_camera_settings(
     state: State<'_, AppState>,
     camera_id: String,
     settings: CameraSettingsRequest,
+) -> Result<SettingsOutcome, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.update_camera_settings(&camera_id, settings, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_camera_mains_frequency(
+    state: State<'_, AppState>,
+    camera_id: String,
+    mains_frequency: Option<MainsFrequency>,
+) -> Result<(), String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.set_camera_mains_frequency(&camera_id, mains_frequency, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn save_lens_preset(
+    state: State<'_, AppState>,
+    camera_id: String,
+    slot: u32,
+    name: Option<String>,
+) -> Result<LensPreset, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.save_lens_preset(&camera_id, slot, name, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn recall_lens_preset(
+    state: State<'_, AppState>,
+    camera_id: String,
+    slot: u32,
+) -> Result<SettingsOutcome, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.recall_lens_preset(&camera_id, slot, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_lens_preset(
+    state: State<'_, AppState>,
+    camera_id: String,
+    slot: u32,
 ) -> Result<(), String> {
     let mut manager = state.camera_manager.write().await;
-    manager.update_camera_settings(&camera_id, settings).await
+    manager.delete_lens_preset(&camera_id, slot, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_lens_presets(
+    state: State<'_, AppState>,
+    camera_id: String,
+) -> Result<BTreeMap<u32, LensPreset>, String> {
+    let manager = state.camera_manager.read().await;
+    manager.get_lens_presets(&camera_id)
         .map_err(|e| e.to_string())
 }
 
@@ -127,7 +279,7 @@ async fn update_stream_settings(
         bitrate,
         codec,
     };
-    manager.update_stream_settings(&camera_id, request).await
+    manager.update_stream_settings(&camera_id, request, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -151,6 +303,65 @@ async fn measure_white_balance(
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn get_diagnostics(
+    state: State<'_, AppState>,
+    camera_id: String,
+) -> Result<DiagnosticsResponse, String> {
+    let manager = state.camera_manager.read().await;
+    manager.get_diagnostics(&camera_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn download_logs(
+    state: State<'_, AppState>,
+    camera_id: String,
+    directory: String,
+) -> Result<String, String> {
+    let manager = state.camera_manager.read().await;
+    manager.download_logs(&camera_id, std::path::Path::new(&directory)).await
+        .map(|path| path.display().to_string())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn group_download_logs(
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+    directory: String,
+) -> Result<LogBundle, String> {
+    let manager = state.camera_manager.read().await;
+    manager.group_download_logs(&camera_ids, std::path::Path::new(&directory)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_latency_stats(
+    state: State<'_, AppState>,
+) -> Result<Vec<CameraLatency>, String> {
+    let manager = state.camera_manager.read().await;
+    Ok(manager.get_latency_stats())
+}
+
+#[tauri::command]
+async fn match_white_balance(
+    state: State<'_, AppState>,
+    request: WhiteBalanceMatchRequest,
+) -> Result<WhiteBalanceMatchReport, String> {
+    CameraManager::match_white_balance(&state.camera_manager, request, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn match_exposure(
+    state: State<'_, AppState>,
+    request: ExposureMatchRequest,
+) -> Result<ExposureMatchReport, String> {
+    CameraManager::match_exposure(&state.camera_manager, request, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
 // Group commands
 
 #[tauri::command]
@@ -161,6 +372,7 @@ async fn group_start_stream(
     framerate: u32,
     bitrate: u32,
     codec: String,
+    policy: Option<ValidationPolicy>,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let mut manager = state.camera_manager.write().await;
     let request = StreamStartRequest {
@@ -169,7 +381,7 @@ async fn group_start_stream(
         bitrate,
         codec,
     };
-    manager.group_start_stream(&camera_ids, request).await
+    manager.group_start_stream(&camera_ids, request, policy.unwrap_or_default(), ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -179,7 +391,7 @@ async fn group_stop_stream(
     camera_ids: Vec<String>,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let manager = state.camera_manager.read().await;
-    manager.group_stop_stream(&camera_ids).await
+    manager.group_stop_stream(&camera_ids, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -188,18 +400,52 @@ async fn group_update_settings(
     state: State<'_, AppState>,
     camera_ids: Vec<String>,
     settings: CameraSettingsRequest,
+    policy: Option<ValidationPolicy>,
+) -> Result<Vec<GroupCommandResult>, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.group_update_settings(&camera_ids, settings, policy.unwrap_or_default(), ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn group_recall_lens_preset(
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+    slot: u32,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let mut manager = state.camera_manager.write().await;
-    manager.group_update_settings(&camera_ids, settings).await
+    manager.group_recall_lens_preset(&camera_ids, slot, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
+#[tauri::command]
+async fn validate_camera_settings(
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+    settings: CameraSettingsRequest,
+    policy: Option<ValidationPolicy>,
+) -> Result<Vec<SettingsValidation>, String> {
+    let manager = state.camera_manager.read().await;
+    Ok(manager.validate_camera_settings(&camera_ids, &settings, policy.unwrap_or_default()).await)
+}
+
+#[tauri::command]
+async fn validate_stream_settings(
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+    request: StreamStartRequest,
+    policy: Option<ValidationPolicy>,
+) -> Result<Vec<SettingsValidation>, String> {
+    let manager = state.camera_manager.read().await;
+    Ok(manager.validate_stream_settings(&camera_ids, &request, policy.unwrap_or_default()).await)
+}
+
 #[tauri::command]
 async fn start_all_cameras(
     state: State<'_, AppState>,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let manager = state.camera_manager.read().await;
-    manager.start_all_cameras().await
+    manager.start_all_cameras(ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -208,7 +454,7 @@ async fn stop_all_cameras(
     state: State<'_, AppState>,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let manager = state.camera_manager.read().await;
-    manager.stop_all_cameras().await
+    manager.stop_all_cameras(ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -219,7 +465,7 @@ async fn update_camera_alias(
     alias: String,
 ) -> Result<(), String> {
     let mut manager = state.camera_manager.write().await;
-    manager.update_camera_alias(&camera_id, alias).await
+    manager.update_camera_alias(&camera_id, alias, ActionSource::Ui).await
         .map_err(|e| e.to_string())
 }
 
@@ -230,9 +476,20 @@ async fn save_profile(
     state: State<'_, AppState>,
     name: String,
     settings: CameraSettingsRequest,
+    stream: Option<StreamStartRequest>,
+    fields: Option<Vec<ProfileField>>,
+    metadata: Option<ProfileMetadata>,
 ) -> Result<(), String> {
     let mut manager = state.camera_manager.write().await;
-    manager.save_profile(name, settings).await
+    let profile = CameraProfile {
+        name,
+        settings,
+        stream,
+        fields: fields.unwrap_or_default(),
+        metadata: metadata.unwrap_or_default(),
+        shared: false,
+    };
+    manager.save_profile(profile).await
         .map_err(|e| e.to_string())
 }
 
@@ -262,7 +519,41 @@ async fn apply_profile(
     camera_ids: Vec<String>,
 ) -> Result<Vec<GroupCommandResult>, String> {
     let mut manager = state.camera_manager.write().await;
-    manager.apply_profile(&profile_name, &camera_ids).await
+    manager.apply_profile(&profile_name, &camera_ids, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn preview_profile(
+    state: State<'_, AppState>,
+    profile_name: String,
+    camera_ids: Vec<String>,
+) -> Result<Vec<ProfilePreview>, String> {
+    let manager = state.camera_manager.read().await;
+    manager.preview_profile(&profile_name, &camera_ids).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_profiles(
+    state: State<'_, AppState>,
+    names: Vec<String>,
+    destination: String,
+    exported_by: Option<String>,
+) -> Result<usize, String> {
+    let manager = state.camera_manager.read().await;
+    manager.export_profiles(&names, std::path::Path::new(&destination), exported_by).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn import_profiles(
+    state: State<'_, AppState>,
+    source: String,
+    resolution: Option<ConflictResolution>,
+) -> Result<ProfileImportReport, String> {
+    let mut manager = state.camera_manager.write().await;
+    manager.import_profiles(std::path::Path::new(&source), resolution.unwrap_or_default()).await
         .map_err(|e| e.to_string())
 }
 
@@ -282,8 +573,22 @@ async fn save_app_settings(
     state: State<'_, AppState>,
     settings: AppSettings,
 ) -> Result<(), String> {
+    let metrics = settings.metrics.clone();
     let mut manager = state.camera_manager.write().await;
     manager.save_app_settings(settings).await
+        .map_err(|e| e.to_string())?;
+    drop(manager);
+
+    state.metrics_exporter.apply_config(&metrics).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn plan_bandwidth(
+    state: State<'_, AppState>,
+) -> Result<Vec<BudgetReport>, String> {
+    let manager = state.camera_manager.read().await;
+    manager.plan_bandwidth().await
         .map_err(|e| e.to_string())
 }
 
@@ -292,7 +597,289 @@ async fn delete_cameras_data(
     state: State<'_, AppState>,
 ) -> Result<(), String> {
     let mut manager = state.camera_manager.write().await;
-    manager.delete_cameras_data().await
+    manager.delete_cameras_data(ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+// Audit log commands
+
+#[tauri::command]
+async fn query_audit_log(
+    state: State<'_, AppState>,
+    query: AuditQuery,
+) -> Result<Vec<AuditEntry>, String> {
+    let manager = state.camera_manager.read().await;
+    manager.query_audit_log(&query).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_audit_log(
+    state: State<'_, AppState>,
+    query: AuditQuery,
+    format: ExportFormat,
+    destination: String,
+) -> Result<usize, String> {
+    let manager = state.camera_manager.read().await;
+    manager.export_audit_log(&query, format, std::path::Path::new(&destination)).await
+        .map_err(|e| e.to_string())
+}
+
+// Automation rule commands
+
+#[tauri::command]
+async fn get_automation_rules(
+    state: State<'_, AppState>,
+) -> Result<Vec<AutomationRule>, String> {
+    Ok(state.automation.get_rules().await)
+}
+
+#[tauri::command]
+async fn save_automation_rule(
+    state: State<'_, AppState>,
+    rule: AutomationRule,
+) -> Result<AutomationRule, String> {
+    state.automation.save_rule(rule).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_automation_rule(
+    state: State<'_, AppState>,
+    rule_id: String,
+) -> Result<(), String> {
+    state.automation.delete_rule(&rule_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_automation_rule_enabled(
+    state: State<'_, AppState>,
+    rule_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    state.automation.set_rule_enabled(&rule_id, enabled).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_automation_log(
+    state: State<'_, AppState>,
+) -> Result<Vec<RuleExecution>, String> {
+    Ok(state.automation.get_execution_log().await)
+}
+
+// Scheduler commands
+
+#[tauri::command]
+async fn get_schedules(
+    state: State<'_, AppState>,
+) -> Result<Vec<Schedule>, String> {
+    Ok(state.scheduler.get_schedules().await)
+}
+
+#[tauri::command]
+async fn save_schedule(
+    state: State<'_, AppState>,
+    schedule: Schedule,
+) -> Result<Schedule, String> {
+    state.scheduler.save_schedule(schedule).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_schedule(
+    state: State<'_, AppState>,
+    schedule_id: String,
+) -> Result<(), String> {
+    state.scheduler.delete_schedule(&schedule_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_schedule_enabled(
+    state: State<'_, AppState>,
+    schedule_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    state.scheduler.set_schedule_enabled(&schedule_id, enabled).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_schedule_runs(
+    state: State<'_, AppState>,
+    limit: Option<usize>,
+) -> Result<ScheduleRunsOverview, String> {
+    Ok(state.scheduler.get_runs(limit.unwrap_or(20)).await)
+}
+
+// Ramp commands
+
+#[tauri::command]
+async fn start_ramp(
+    state: State<'_, AppState>,
+    request: RampRequest,
+) -> Result<String, String> {
+    state.ramps.start_ramp(request, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn cancel_ramp(
+    state: State<'_, AppState>,
+    camera_ids: Vec<String>,
+) -> Result<(), String> {
+    state.ramps.cancel_ramp(&camera_ids, ActionSource::Ui).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_ramp_statuses(
+    state: State<'_, AppState>,
+) -> Result<Vec<RampStatus>, String> {
+    Ok(state.ramps.get_ramp_statuses().await)
+}
+
+// Adaptive bitrate commands
+
+#[tauri::command]
+async fn get_abr_settings(
+    state: State<'_, AppState>,
+) -> Result<AbrSettings, String> {
+    Ok(state.abr.get_settings().await)
+}
+
+#[tauri::command]
+async fn save_abr_config(
+    state: State<'_, AppState>,
+    config: AbrConfig,
+) -> Result<(), String> {
+    state.abr.save_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn set_abr_enabled(
+    state: State<'_, AppState>,
+    camera_id: String,
+    enabled: bool,
+) -> Result<(), String> {
+    state.abr.set_enabled(&camera_id, enabled).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_abr_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<AbrCameraStatus>, String> {
+    Ok(state.abr.get_status().await)
+}
+
+// Thermal guard commands
+
+#[tauri::command]
+async fn get_thermal_config(
+    state: State<'_, AppState>,
+) -> Result<ThermalConfig, String> {
+    Ok(state.thermal.get_config().await)
+}
+
+#[tauri::command]
+async fn save_thermal_config(
+    state: State<'_, AppState>,
+    config: ThermalConfig,
+) -> Result<(), String> {
+    state.thermal.save_config(config).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_thermal_status(
+    state: State<'_, AppState>,
+) -> Result<Vec<ThermalCameraStatus>, String> {
+    Ok(state.thermal.get_status().await)
+}
+
+// Session recording commands
+
+#[tauri::command]
+async fn start_session(
+    state: State<'_, AppState>,
+    name: Option<String>,
+) -> Result<SessionInfo, String> {
+    state.sessions.start_session(name).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn stop_session(
+    state: State<'_, AppState>,
+) -> Result<SessionInfo, String> {
+    state.sessions.stop_session().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_active_session(
+    state: State<'_, AppState>,
+) -> Result<Option<SessionInfo>, String> {
+    Ok(state.sessions.active_session().await)
+}
+
+#[tauri::command]
+async fn list_sessions(
+    state: State<'_, AppState>,
+) -> Result<Vec<SessionInfo>, String> {
+    state.sessions.list_sessions().await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn open_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<Vec<SessionRecord>, String> {
+    state.sessions.open_session(&session_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_session_csv(
+    state: State<'_, AppState>,
+    session_id: String,
+    destination: String,
+) -> Result<usize, String> {
+    state.sessions.export_session_csv(&session_id, std::path::Path::new(&destination)).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn delete_session(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<(), String> {
+    state.sessions.delete_session(&session_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn get_session_report(
+    state: State<'_, AppState>,
+    session_id: String,
+) -> Result<SessionReport, String> {
+    state.sessions.session_report(&session_id).await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+async fn export_session_report(
+    state: State<'_, AppState>,
+    session_id: String,
+    format: ReportFormat,
+    destination: String,
+) -> Result<(), String> {
+    state.sessions.export_session_report(&session_id, format, std::path::Path::new(&destination)).await
         .map_err(|e| e.to_string())
 }
 
@@ -356,29 +943,69 @@ fn main() {
         .plugin(tauri_plugin_notification::init())
         .setup(|app| {
             // Initialize camera manager
-            let camera_manager = Arc::new(RwLock::new(CameraManager::new()));
+            let manager = CameraManager::new();
+            let metrics_exporter = Arc::new(MetricsExporter::new(manager.metrics()));
+            let camera_manager = Arc::new(RwLock::new(manager));
+
+            let automation = Arc::new(AutomationEngine::new(camera_manager.clone()));
+            let scheduler = Arc::new(Scheduler::new(camera_manager.clone()));
+            let ramps = Arc::new(RampEngine::new(camera_manager.clone()));
+            let abr = Arc::new(AbrEngine::new(camera_manager.clone()));
+            let thermal = Arc::new(ThermalGuard::new(camera_manager.clone()));
+            let sessions = Arc::new(SessionRecorder::new(camera_manager.clone()));
 
-            // Set up persistence path
+            // Inject notifications and persistence paths, then start automation
+            // before cameras load so their connection events reach the rules.
+            // The scheduler starts last so missed runs see the loaded cameras.
             let manager_clone = camera_manager.clone();
+            let automation_clone = automation.clone();
+            let abr_clone = abr.clone();
+            let thermal_clone = thermal.clone();
+            let sessions_clone = sessions.clone();
+            let scheduler_clone = scheduler.clone();
+            let metrics_exporter_clone = metrics_exporter.clone();
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                // Get app data directory
-                if let Ok(app_data_dir) = app_handle.path().app_data_dir() {
-                    // Create directory if it doesn't exist
-                    if let Err(e) = std::fs::create_dir_all(&app_data_dir) {
-                        log::error!("Failed to create app data directory: {}", e);
-                        return;
-                    }
+                let paths: Arc<dyn PathProvider> = Arc::new(TauriPaths::new(app_handle.clone()));
+                manager_clone.write().await.set_notifier(Arc::new(TauriNotifier::new(app_handle)));
 
-                    // Set persistence path to cameras.json in app data dir
-                    let cameras_file = app_data_dir.join("cameras.json");
-                    log::info!("Setting camera persistence path to: {:?}", cameras_file);
+                if let Err(e) = automation_clone.set_path_provider(paths.clone()).await {
+                    log::error!("Failed to load automation rules: {}", e);
+                }
+                automation_clone.start().await;
+
+                if let Err(e) = abr_clone.set_path_provider(paths.clone()).await {
+                    log::error!("Failed to load ABR settings: {}", e);
+                }
+                abr_clone.start().await;
+
+                if let Err(e) = thermal_clone.set_path_provider(paths.clone()).await {
+                    log::error!("Failed to load thermal policy: {}", e);
+                }
+                thermal_clone.start().await;
+
+                if let Err(e) = sessions_clone.set_path_provider(paths.clone()).await {
+                    log::error!("Failed to set sessions path, session recording disabled: {}", e);
+                }
+                sessions_clone.start().await;
+
+                if let Err(e) = manager_clone.write().await.set_path_provider(paths.clone()).await {
+                    log::error!("Failed to set persistence path, camera persistence disabled: {}", e);
+                }
+
+                if let Err(e) = scheduler_clone.set_path_provider(paths).await {
+                    log::error!("Failed to load schedules: {}", e);
+                }
+                scheduler_clone.start();
 
-                    if let Err(e) = manager_clone.write().await.set_persistence_path(cameras_file).await {
-                        log::error!("Failed to set persistence path: {}", e);
+                // Serve /metrics if enabled in settings
+                match manager_clone.read().await.get_app_settings().await {
+                    Ok(settings) => {
+                        if let Err(e) = metrics_exporter_clone.apply_config(&settings.metrics).await {
+                            log::error!("Failed to start metrics endpoint: {}", e);
+                        }
                     }
-                } else {
-                    log::warn!("Failed to get app data directory, camera persistence disabled");
+                    Err(e) => log::error!("Failed to load app settings: {}", e),
                 }
             });
 
@@ -393,6 +1020,13 @@ fn main() {
             // Set app state
             app.manage(AppState {
                 camera_manager,
+                automation,
+                scheduler,
+                ramps,
+                abr,
+                thermal,
+                sessions,
+                metrics_exporter,
             });
 
             Ok(())
@@ -400,6 +1034,13 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             discover_cameras,
             add_camera_manual,
+            retrust_camera,
+            start_pairing,
+            confirm_pairing,
+            revoke_pairing,
+            get_token_storage_status,
+            unlock_tokens,
+            set_token_passphrase,
             remove_camera,
             get_cameras,
             get_camera_status,
@@ -407,11 +1048,25 @@ fn main() {
             start_stream,
             stop_stream,
             update_camera_settings,
+            set_camera_mains_frequency,
+            save_lens_preset,
+            recall_lens_preset,
+            delete_lens_preset,
+            get_lens_presets,
             update_stream_settings,
             measure_white_balance,
+            get_diagnostics,
+            download_logs,
+            group_download_logs,
+            get_latency_stats,
+            match_white_balance,
+            match_exposure,
             group_start_stream,
             group_stop_stream,
             group_update_settings,
+            group_recall_lens_preset,
+            validate_camera_settings,
+            validate_stream_settings,
             start_all_cameras,
             stop_all_cameras,
             update_camera_alias,
@@ -419,9 +1074,44 @@ fn main() {
             get_profiles,
             delete_profile,
             apply_profile,
+            preview_profile,
+            export_profiles,
+            import_profiles,
             get_app_settings,
             save_app_settings,
+            plan_bandwidth,
             delete_cameras_data,
+            query_audit_log,
+            export_audit_log,
+            get_automation_rules,
+            save_automation_rule,
+            delete_automation_rule,
+            set_automation_rule_enabled,
+            get_automation_log,
+            get_schedules,
+            save_schedule,
+            delete_schedule,
+            set_schedule_enabled,
+            get_schedule_runs,
+            start_ramp,
+            cancel_ramp,
+            get_ramp_statuses,
+            get_abr_settings,
+            save_abr_config,
+            set_abr_enabled,
+            get_abr_status,
+            get_thermal_config,
+            save_thermal_config,
+            get_thermal_status,
+            start_session,
+            stop_session,
+            get_active_session,
+            list_sessions,
+            open_session,
+            export_session_csv,
+            delete_session,
+            get_session_report,
+            export_session_report,
             check_notification_permission,
             request_notification_permission,
             send_test_notification,
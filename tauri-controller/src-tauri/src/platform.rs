@@ -0,0 +1,56 @@
+//! Tauri implementations of the core's host traits
+
+use anyhow::{Context, Result};
+use avocam_core::{Notifier, PathProvider};
+use std::path::PathBuf;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Stores controller data in the platform app data directory
+pub struct TauriPaths {
+    app: AppHandle,
+}
+
+impl TauriPaths {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl PathProvider for TauriPaths {
+    fn data_dir(&self) -> Result<PathBuf> {
+        let app_data_dir = self.app.path().app_data_dir()
+            .context("Failed to get app data directory")?;
+
+        // Create directory if it doesn't exist
+        std::fs::create_dir_all(&app_data_dir)
+            .context("Failed to create app data directory")?;
+
+        Ok(app_data_dir)
+    }
+}
+
+/// Shows OS notifications and forwards events to the webview
+pub struct TauriNotifier {
+    app: AppHandle,
+}
+
+impl TauriNotifier {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl Notifier for TauriNotifier {
+    fn notify(&self, title: &str, body: &str) {
+        if let Err(e) = self.app.notification().builder().title(title).body(body).show() {
+            log::error!("Failed to send notification: {}", e);
+        }
+    }
+
+    fn emit(&self, event: &str, payload: serde_json::Value) {
+        if let Err(e) = self.app.emit(event, payload) {
+            log::error!("Failed to emit event {}: {}", event, e);
+        }
+    }
+}
@@ -13,6 +13,45 @@ export interface AppSettings {
     batteryLow: AlertSettings;
     batteryCritical: AlertSettings;
   };
+  metrics: {
+    enabled: boolean;
+    port: number;
+  };
+  mainsFrequency: '50hz' | '60hz' | null;  // Anti-banding; cameras may override
+  sharedProfilesDir: string | null;  // Read-only folder of shared profile libraries
+  linkBudgets: LinkBudget[];  // Access points (or groups) and their capacity
+  budgetPolicy: BudgetPolicy;  // Group start over budget: warn or leave cameras out
+  showEndTime: string | null;  // ISO 8601; alert when a battery will not last until then
+  latencyThresholdMs: number;  // Flag cameras whose control round trip is slower (default 250)
+}
+
+export interface LinkBudget {
+  name: string;
+  capacityBps: number;
+  cameraIds: string[];
+  reserveRatio: number; // Share of the capacity kept free (default 0.25)
+}
+
+export type BudgetPolicy = 'warn' | 'refuse';
+
+export interface CameraBandwidth {
+  camera_id: string;
+  configured_bps: number;
+  observed_bps: number | null;
+  streaming: boolean;
+  recommended_bps: number;
+}
+
+export interface BudgetReport {
+  name: string;
+  capacity_bps: number;
+  usable_bps: number;
+  planned_bps: number;
+  active_bps: number;
+  observed_bps: number;
+  headroom_bps: number; // Negative when over budget
+  over_budget: boolean;
+  cameras: CameraBandwidth[];
 }
 
 export const DEFAULT_APP_SETTINGS: AppSettings = {
@@ -46,4 +85,14 @@ export const DEFAULT_APP_SETTINGS: AppSettings = {
       batteryCriticalThreshold: 10,
     },
   },
+  metrics: {
+    enabled: false,
+    port: 9464,
+  },
+  mainsFrequency: null,
+  sharedProfilesDir: null,
+  linkBudgets: [],
+  budgetPolicy: 'warn',
+  showEndTime: null,
+  latencyThresholdMs: 250,
 };
@@ -8,6 +8,7 @@ export interface Telemetry {
   queue_ms?: number;
   dropped_frames?: number;
   charging_state?: 'charging' | 'full' | 'unplugged';
+  timestamp?: string;  // Camera clock (NTP time-stamping), when sent
 }
 
 export type NdiState = 'streaming' | 'idle' | 'unknown';
@@ -17,6 +18,9 @@ export type ShutterMode = 'auto' | 'manual';
 export type TorchMode = 'auto' | 'manual';
 export type CameraPosition = 'front' | 'back';
 export type LensType = 'ultra_wide' | 'wide' | 'telephoto';
+export type MainsFrequency = '50hz' | '60hz';
+export type CameraScheme = 'http' | 'https';
+export type FocusMode = 'auto' | 'manual';
 
 export interface CurrentSettings {
   resolution?: string;
@@ -42,12 +46,43 @@ export interface CameraStatus {
   telemetry: Telemetry;
 }
 
+// Numbered framing preset (slots 1-99), recalled like a PTZ preset
+export interface LensPreset {
+  name?: string;
+  zoom_factor: number;
+  lens: LensType;
+  camera_position: CameraPosition;
+  focus_mode: FocusMode;
+  saved_at: string;
+}
+
 export interface Camera {
   id: string;
   alias: string;
   ip: string;
   port: number;
+  scheme?: CameraScheme;
+  cert_fingerprint?: string | null;  // SHA-256 of the pinned certificate (https)
   status: CameraStatus | null;
+  mains_frequency?: MainsFrequency | null;  // Anti-banding override of the app setting
+  lens_presets?: Record<string, LensPreset>;  // Keyed by slot number
+  battery_runtime?: BatteryRuntime | null;
+}
+
+export type BatteryRegime =
+  | 'streaming_unplugged'
+  | 'streaming_charging'
+  | 'idle_unplugged'
+  | 'idle_charging';
+
+export interface BatteryRuntime {
+  level: number;
+  charging_state: 'charging' | 'full' | 'unplugged' | null;
+  regime: BatteryRegime;
+  drain_per_hour: number | null;  // Level (0-1) lost per hour
+  minutes_left: number | null;  // null while not draining or still learning
+  predicted_empty_at: string | null;  // ISO 8601
+  dies_before_show_end: boolean;
 }
 
 export interface DiscoveredCamera {
@@ -56,3 +91,68 @@ export interface DiscoveredCamera {
   port: number;
   txt_records?: { [key: string]: string };
 }
+
+// GET /api/v1/diag
+export interface TemperatureSample {
+  timestamp: string;
+  temp_c: number;
+}
+
+export interface Diagnostics {
+  dropped_frames: number;
+  queue_depth: number;
+  temperature_timeline: TemperatureSample[];  // Oldest first
+}
+
+export interface LogDownloadResult {
+  camera_id: string;
+  path?: string | null;
+  bytes: number;
+  error?: string | null;
+}
+
+// Timestamped folder holding the archives of a group log download
+export interface LogBundle {
+  folder: string;
+  results: LogDownloadResult[];
+}
+
+// Round trips per channel; histogram counts are per bucket, the last one is above every bound
+export interface RttSeries {
+  last_ms: number | null;
+  smoothed_ms: number | null;
+  min_ms: number | null;
+  max_ms: number | null;
+  samples: number;
+  last_at: string | null;
+  histogram: { bounds_ms: number[]; counts: number[] };
+}
+
+export interface CameraLatency {
+  camera_id: string;
+  http: RttSeries;
+  websocket: RttSeries;
+  clock_offset: {
+    offset_ms: number;  // Positive when the camera clock is ahead
+    jitter_ms: number;
+    samples: number;
+    measured_at: string;
+  } | null;
+  high_latency: boolean;
+}
+
+export interface TokenStorageStatus {
+  source: 'key_file' | 'passphrase';
+  locked: boolean;  // Saved cameras wait for the passphrase
+  locked_cameras: number;
+}
+
+export type PairingMethod = 'pin' | 'qr';
+
+export interface PairingSession {
+  pairing_id: string;
+  camera_id: string;
+  method: PairingMethod;
+  qr_payload: string | null;  // Render as a QR code for the phone to scan (QR pairing)
+  expires_at: string;
+}
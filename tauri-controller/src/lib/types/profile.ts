@@ -1,4 +1,5 @@
 import type { WhiteBalanceMode, IsoMode, ShutterMode, LensType } from './camera';
+import type { FieldIssue, ShutterAdjustment, StreamSettings } from './settings';
 
 export interface ProfileSettings {
   wb_mode: WhiteBalanceMode;
@@ -12,13 +13,56 @@ export interface ProfileSettings {
   lens: LensType;
 }
 
+// Profile field mask entry: a single field or a group
+// (white_balance, exposure, framing, stream)
+export type ProfileField =
+  | 'wb_mode' | 'wb_kelvin' | 'wb_tint'
+  | 'iso_mode' | 'iso' | 'shutter_mode' | 'shutter_s' | 'shutter_angle_deg'
+  | 'focus_mode' | 'zoom_factor' | 'lens' | 'camera_position' | 'orientation_lock' | 'torch_level'
+  | 'resolution' | 'framerate' | 'bitrate' | 'codec'
+  | 'white_balance' | 'exposure' | 'framing' | 'stream';
+
+export interface ProfileMetadata {
+  author?: string;
+  description?: string;
+  device_model?: string;  // Device the look was built for
+  created_at?: string;
+  updated_at?: string;
+}
+
 export interface Profile {
   name: string;
   settings: ProfileSettings;
+  stream?: StreamSettings;  // Sent (in place) only when these actually change
+  fields?: ProfileField[];  // Empty or missing applies every field set above
+  metadata?: ProfileMetadata;
+  shared?: boolean;  // From the shared profiles folder (read-only)
+}
+
+// Dry-run result of a profile on one camera (nothing is sent)
+export interface ProfilePreview {
+  camera_id: string;
+  changes: { field: string; from: unknown; to: unknown }[];
+  lens_switch: boolean;
+  camera_switch: boolean;
+  stream_restart: boolean;
+  issues: FieldIssue[];
+  error?: string;
+}
+
+export type ConflictResolution = 'rename' | 'overwrite' | 'skip';
+
+export interface ProfileImportReport {
+  imported: string[];
+  renamed: { from: string; to: string }[];
+  overwritten: string[];
+  skipped: string[];
 }
 
 export interface GroupOperationResult {
   camera_id: string;
   success: boolean;
   error?: string;
+  shutter_adjustment?: ShutterAdjustment;
+  issues?: FieldIssue[];  // Validation errors (skipped) or warnings (clamped)
 }
@@ -4,7 +4,8 @@ import type {
   ShutterMode,
   TorchMode,
   LensType,
-  CameraPosition
+  CameraPosition,
+  MainsFrequency
 } from './camera';
 
 // Stream Settings (for starting NDI stream)
@@ -29,6 +30,40 @@ export interface CameraSettings {
   camera_position: CameraPosition;
   torch_mode: TorchMode;
   torch_level: number;  // NDI tally torch brightness (0.01-1.0)
+  shutter_angle_deg?: number;  // Converted to shutter_s for the current fps by the backend
+}
+
+// Part of update_camera_settings' outcome (and per camera in group results)
+// when a manual shutter was converted or snapped for anti-banding
+export interface ShutterAdjustment {
+  requested_shutter_s: number | null;
+  requested_angle_deg: number | null;
+  fps: number;
+  mains_frequency: MainsFrequency | null;
+  applied_shutter_s: number;
+  snapped: boolean;
+  flicker_safe: boolean | null;
+}
+
+// Settings validation against camera capabilities
+export type IssueSeverity = 'error' | 'warning';
+export type ValidationPolicy = 'skip' | 'clamp';  // Skip the camera or clamp out-of-range values
+
+export interface FieldIssue {
+  field: string;
+  severity: IssueSeverity;
+  message: string;
+}
+
+export interface SettingsValidation {
+  camera_id: string;
+  issues: FieldIssue[];
+}
+
+// Returned by update_camera_settings
+export interface SettingsOutcome {
+  shutter_adjustment: ShutterAdjustment | null;
+  warnings: FieldIssue[];
 }
 
 // White Balance Measurement Result
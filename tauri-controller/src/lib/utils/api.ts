@@ -1,7 +1,21 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { Camera, DiscoveredCamera } from '../types/camera';
-import type { StreamSettings, CameraSettings, WhiteBalanceResult } from '../types/settings';
-import type { Profile, GroupOperationResult } from '../types/profile';
+import type { Camera, DiscoveredCamera, LensPreset, Diagnostics, LogBundle, CameraLatency, CameraScheme, TokenStorageStatus, PairingMethod, PairingSession } from '../types/camera';
+import type {
+  StreamSettings,
+  CameraSettings,
+  WhiteBalanceResult,
+  SettingsOutcome,
+  SettingsValidation,
+  ValidationPolicy
+} from '../types/settings';
+import type {
+  Profile,
+  GroupOperationResult,
+  ConflictResolution,
+  ProfileImportReport,
+  ProfilePreview
+} from '../types/profile';
+import type { BudgetReport } from '../types/app-settings';
 
 // Camera Management
 export async function discoverCameras(): Promise<DiscoveredCamera[]> {
@@ -12,8 +26,53 @@ export async function getCameras(): Promise<Camera[]> {
   return invoke('get_cameras');
 }
 
-export async function addCameraManual(ip: string, port: number, token: string): Promise<void> {
-  return invoke('add_camera_manual', { ip, port, token });
+export async function addCameraManual(
+  ip: string,
+  port: number,
+  token: string,
+  scheme: CameraScheme = 'http'
+): Promise<void> {
+  return invoke('add_camera_manual', { ip, port, token, scheme });
+}
+
+// Pairing (replaces typing the token; the camera issues one per controller)
+export async function startPairing(
+  ip: string,
+  port: number,
+  method: PairingMethod,
+  scheme: CameraScheme = 'http'
+): Promise<PairingSession> {
+  return invoke('start_pairing', { ip, port, scheme, method });
+}
+
+// pin is the code shown on the phone (not needed for QR pairing); returns the camera id
+export async function confirmPairing(pairingId: string, pin?: string): Promise<string> {
+  return invoke('confirm_pairing', { pairingId, pin: pin ?? null });
+}
+
+// The camera invalidates this controller's token, then the camera is removed
+export async function revokePairing(cameraId: string): Promise<void> {
+  return invoke('revoke_pairing', { cameraId });
+}
+
+// Token storage (tokens are encrypted at rest and only returned masked)
+export async function getTokenStorageStatus(): Promise<TokenStorageStatus> {
+  return invoke('get_token_storage_status');
+}
+
+// Connects the saved cameras; returns how many connected
+export async function unlockTokens(passphrase: string): Promise<number> {
+  return invoke('unlock_tokens', { passphrase });
+}
+
+// null switches back to the machine-local key file
+export async function setTokenPassphrase(passphrase: string | null): Promise<void> {
+  return invoke('set_token_passphrase', { passphrase });
+}
+
+// Pin the certificate an https camera presents now; returns its fingerprint
+export async function retrustCamera(cameraId: string): Promise<string> {
+  return invoke('retrust_camera', { cameraId });
 }
 
 export async function removeCamera(cameraId: string): Promise<void> {
@@ -42,10 +101,38 @@ export async function stopStream(cameraId: string): Promise<void> {
 export async function updateCameraSettings(
   cameraId: string,
   settings: Partial<CameraSettings>
-): Promise<void> {
+): Promise<SettingsOutcome> {
   return invoke('update_camera_settings', { cameraId, settings });
 }
 
+// Lens Presets
+export async function saveLensPreset(
+  cameraId: string,
+  slot: number,
+  name?: string
+): Promise<LensPreset> {
+  return invoke('save_lens_preset', { cameraId, slot, name });
+}
+
+export async function recallLensPreset(cameraId: string, slot: number): Promise<SettingsOutcome> {
+  return invoke('recall_lens_preset', { cameraId, slot });
+}
+
+export async function deleteLensPreset(cameraId: string, slot: number): Promise<void> {
+  return invoke('delete_lens_preset', { cameraId, slot });
+}
+
+export async function getLensPresets(cameraId: string): Promise<Record<string, LensPreset>> {
+  return invoke('get_lens_presets', { cameraId });
+}
+
+export async function groupRecallLensPreset(
+  cameraIds: string[],
+  slot: number
+): Promise<GroupOperationResult[]> {
+  return invoke('group_recall_lens_preset', { cameraIds, slot });
+}
+
 export async function updateStreamSettings(
   cameraId: string,
   settings: StreamSettings
@@ -63,10 +150,28 @@ export async function measureWhiteBalance(cameraId: string): Promise<WhiteBalanc
   return invoke('measure_white_balance', { cameraId });
 }
 
+// Diagnostics
+export async function getDiagnostics(cameraId: string): Promise<Diagnostics> {
+  return invoke('get_diagnostics', { cameraId });
+}
+
+export async function downloadLogs(cameraId: string, directory: string): Promise<string> {
+  return invoke('download_logs', { cameraId, directory });
+}
+
+export async function groupDownloadLogs(cameraIds: string[], directory: string): Promise<LogBundle> {
+  return invoke('group_download_logs', { cameraIds, directory });
+}
+
+export async function getLatencyStats(): Promise<CameraLatency[]> {
+  return invoke('get_latency_stats');
+}
+
 // Group Operations
 export async function groupStartStream(
   cameraIds: string[],
-  settings: StreamSettings
+  settings: StreamSettings,
+  policy?: ValidationPolicy
 ): Promise<GroupOperationResult[]> {
   return invoke('group_start_stream', {
     cameraIds,
@@ -74,6 +179,7 @@ export async function groupStartStream(
     framerate: settings.framerate,
     bitrate: settings.bitrate,
     codec: settings.codec,
+    policy,
   });
 }
 
@@ -83,9 +189,32 @@ export async function groupStopStream(cameraIds: string[]): Promise<void> {
 
 export async function groupUpdateSettings(
   cameraIds: string[],
-  settings: Partial<CameraSettings>
+  settings: Partial<CameraSettings>,
+  policy?: ValidationPolicy
 ): Promise<GroupOperationResult[]> {
-  return invoke('group_update_settings', { cameraIds, settings });
+  return invoke('group_update_settings', { cameraIds, settings, policy });
+}
+
+// Validation (dry run, nothing is sent to the cameras)
+export async function validateCameraSettings(
+  cameraIds: string[],
+  settings: Partial<CameraSettings>,
+  policy?: ValidationPolicy
+): Promise<SettingsValidation[]> {
+  return invoke('validate_camera_settings', { cameraIds, settings, policy });
+}
+
+export async function validateStreamSettings(
+  cameraIds: string[],
+  request: StreamSettings,
+  policy?: ValidationPolicy
+): Promise<SettingsValidation[]> {
+  return invoke('validate_stream_settings', { cameraIds, request, policy });
+}
+
+// Bandwidth budgets (links declared in the app settings)
+export async function planBandwidth(): Promise<BudgetReport[]> {
+  return invoke('plan_bandwidth');
 }
 
 export async function startAllCameras(): Promise<GroupOperationResult[]> {
@@ -105,6 +234,9 @@ export async function saveProfile(profile: Profile): Promise<void> {
   return invoke('save_profile', {
     name: profile.name,
     settings: profile.settings,
+    stream: profile.stream,
+    fields: profile.fields,
+    metadata: profile.metadata,
   });
 }
 
@@ -112,6 +244,28 @@ export async function deleteProfile(name: string): Promise<void> {
   return invoke('delete_profile', { name });
 }
 
+export async function previewProfile(
+  profileName: string,
+  cameraIds: string[]
+): Promise<ProfilePreview[]> {
+  return invoke('preview_profile', { profileName, cameraIds });
+}
+
+export async function exportProfiles(
+  names: string[],
+  destination: string,
+  exportedBy?: string
+): Promise<number> {
+  return invoke('export_profiles', { names, destination, exportedBy });
+}
+
+export async function importProfiles(
+  source: string,
+  resolution: ConflictResolution = 'rename'
+): Promise<ProfileImportReport> {
+  return invoke('import_profiles', { source, resolution });
+}
+
 export async function applyProfile(
   profileName: string,
   cameraIds: string[]